@@ -0,0 +1,82 @@
+//! Reads a cookies DB where 80% of the rows expired, with and without `include_expired`.
+//! Run with `cargo bench --bench expired`.
+
+use rookie::common::enums::{ BrowserConfig, ReadOptions };
+use rookie::testing::{ self, ChromiumSchema, FixtureCookie };
+use std::time::{ Duration, Instant };
+
+const ROWS: usize = 20_000;
+const ROUNDS: u32 = 5;
+
+fn fixture_cookies() -> Vec<FixtureCookie> {
+    (0..ROWS)
+        .map(|i| {
+            let mut cookie = FixtureCookie::new(&format!("host{}.example.com", i % 500), &format!("c{}", i), "v4lue");
+            if i % 5 != 0 {
+                cookie.expires = Some(1_000_000_000);
+            }
+            cookie
+        })
+        .collect()
+}
+
+fn time(rounds: u32, mut read: impl FnMut() -> usize) -> (Duration, usize) {
+    let mut rows = 0;
+    let start = Instant::now();
+    for _ in 0..rounds {
+        rows = read();
+    }
+    (start.elapsed() / rounds, rows)
+}
+
+fn main() {
+    let dir = tempfile::tempdir().unwrap();
+    let cookies = fixture_cookies();
+    let fixture = testing::write_chromium_profile(dir.path(), ChromiumSchema::V21, &cookies).unwrap();
+    let mozilla_db = testing::write_mozilla_profile(dir.path(), &cookies).unwrap();
+
+    let data_paths = testing::chromium_data_paths(dir.path());
+    let data_paths: Vec<&str> = data_paths.iter().map(|p| p.as_str()).collect();
+    let config = BrowserConfig {
+        data_paths: &data_paths,
+        channels: None,
+        os_crypt_name: Some("chromium"),
+        osx_key_service: None,
+        osx_key_user: None,
+    };
+
+    for include_expired in [true, false] {
+        let mut options = ReadOptions::default();
+        options.include_expired = include_expired;
+        let (elapsed, rows) = time(ROUNDS, || {
+            #[cfg(target_os = "windows")]
+            let read = rookie::browser::chromium::chromium_read(
+                fixture.key_path.clone(),
+                fixture.db_path.clone(),
+                None::<Vec<&str>>,
+                options.clone()
+            );
+            #[cfg(unix)]
+            let read = rookie::browser::chromium::chromium_read_with(
+                &config,
+                fixture.db_path.clone(),
+                None::<Vec<&str>>,
+                &testing::CountingSecrets::default(),
+                options.clone()
+            );
+            read.unwrap().cookies.len()
+        });
+        println!("chromium include_expired={:<5} {:>6} rows {:?}", include_expired, rows, elapsed);
+
+        let (elapsed, rows) = time(ROUNDS, || {
+            rookie::browser::mozilla
+                ::firefox_read(mozilla_db.clone(), None::<Vec<&str>>, options.clone())
+                .unwrap()
+                .cookies
+                .len()
+        });
+        println!("firefox  include_expired={:<5} {:>6} rows {:?}", include_expired, rows, elapsed);
+    }
+    #[cfg(target_os = "windows")]
+    let _ = &config;
+}
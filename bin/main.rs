@@ -1,7 +1,111 @@
-use rookie;
+use std::process::ExitCode;
 
-fn main() {
-    let domains = vec!["facebook.com"];
-    let cookies = rookie::chrome_v2(Some(domains)).unwrap();
-    println!("{:?}", cookies);
+fn dry_run(args: &[String]) -> ExitCode {
+    let browser = match args {
+        [] => "all",
+        [flag, browser] if flag == "--browser" => browser.as_str(),
+        _ => {
+            eprintln!("usage: rookie dry-run [--browser <name|all>]");
+            return ExitCode::from(2);
+        }
+    };
+    match rookie::dry_run(browser) {
+        Ok(report) => {
+            print!("{}", report);
+            if report.passed() { ExitCode::SUCCESS } else { ExitCode::FAILURE }
+        }
+        Err(err) => {
+            eprintln!("{:#}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn ytdlp(args: &[String]) -> ExitCode {
+    let [spec] = args else {
+        eprintln!("usage: rookie ytdlp <BROWSER[+KEYRING][:PROFILE][::CONTAINER]>");
+        return ExitCode::from(2);
+    };
+    match rookie::ytdlp::cookies_txt(spec) {
+        Ok(cookies_txt) => {
+            print!("{}", cookies_txt);
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{:#}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn export(args: &[String]) -> ExitCode {
+    const USAGE: &str = "usage: rookie export <dir> [--jsonl] [--chunk-size <cookies per file>]";
+    let mut dir = None;
+    let mut jsonl = false;
+    let mut chunk_size = usize::MAX;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--jsonl" => {
+                jsonl = true;
+            }
+            "--chunk-size" => {
+                match args.next().and_then(|size| size.parse().ok()) {
+                    Some(size) if size > 0 => {
+                        chunk_size = size;
+                    }
+                    _ => {
+                        eprintln!("{}", USAGE);
+                        return ExitCode::from(2);
+                    }
+                }
+            }
+            _ if dir.is_none() && !arg.starts_with("--") => {
+                dir = Some(std::path::PathBuf::from(arg));
+            }
+            _ => {
+                eprintln!("{}", USAGE);
+                return ExitCode::from(2);
+            }
+        }
+    }
+    let Some(dir) = dir else {
+        eprintln!("{}", USAGE);
+        return ExitCode::from(2);
+    };
+
+    let written = rookie::load(None).and_then(|cookies| {
+        if jsonl {
+            rookie::export::write_jsonl_chunked(cookies, &dir, chunk_size)
+        } else {
+            rookie::export::write_netscape_chunked(cookies, &dir, chunk_size)
+        }
+    });
+    match written {
+        Ok(manifest) => {
+            println!("{} cookies in {} files", manifest.cookies, manifest.chunks.len());
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{:#}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("dry-run") => dry_run(&args[1..]),
+        Some("ytdlp") => ytdlp(&args[1..]),
+        Some("export") => export(&args[1..]),
+        _ => {
+            let domains = vec!["facebook.com"];
+            let cookies = rookie
+                ::chromium_profiles(&rookie::config::CHROME_CONFIG, Some(domains), &Default::default())
+                .unwrap();
+            println!("{:?}", cookies);
+            ExitCode::SUCCESS
+        }
+    }
 }
@@ -0,0 +1,92 @@
+//! Prints the cookies of every browser grouped by domain, then what couldn't be read.
+//!
+//! ```sh
+//! cargo run --example dump_all -- [--fixtures <dir>] [domain...]
+//! ```
+//!
+//! `--fixtures` reads the browsers under `<dir>` instead of the user profile.
+
+use anyhow::{ bail, Result };
+use rookie::common::enums::Cookie;
+use rookie::LoadOptions;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+
+fn split_fixtures(args: &[String]) -> Result<(Option<PathBuf>, Vec<String>)> {
+    let mut fixtures = None;
+    let mut rest = vec![];
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--fixtures" => {
+                let Some(dir) = args.next() else {
+                    bail!("--fixtures needs a directory");
+                };
+                fixtures = Some(PathBuf::from(dir));
+            }
+            _ => rest.push(arg.clone()),
+        }
+    }
+    Ok((fixtures, rest))
+}
+
+pub fn run(args: &[String], out: &mut dyn Write) -> Result<()> {
+    let (fixtures, domains) = split_fixtures(args)?;
+    let domains = rookie::Domains::new((!domains.is_empty()).then_some(domains));
+    // a dump shouldn't stop at the first keyring prompt
+    let mut options = LoadOptions::default();
+    options.max_prompts = Some(0);
+    let outcome = match &fixtures {
+        Some(root) => rookie::with_user_profile_root(root, || rookie::load_with(domains, &options))?,
+        None => rookie::load_with(domains, &options)?,
+    };
+
+    let mut by_domain: BTreeMap<&str, Vec<&Cookie>> = BTreeMap::new();
+    for cookie in &outcome.cookies {
+        by_domain.entry(cookie.domain.trim_start_matches('.')).or_default().push(cookie);
+    }
+    for (domain, mut cookies) in by_domain {
+        cookies.sort_by(|a, b| (&a.path, &a.name).cmp(&(&b.path, &b.name)));
+        writeln!(out, "{} ({})", domain, cookies.len())?;
+        for cookie in cookies {
+            let expires = cookie.expires.map_or("session".to_string(), |expires| expires.to_string());
+            let mut flags = vec![];
+            if cookie.secure {
+                flags.push("secure");
+            }
+            if cookie.http_only {
+                flags.push("httponly");
+            }
+            let line = format!(
+                "  {}={} path={} expires={} {}",
+                cookie.name,
+                cookie.value,
+                cookie.path,
+                expires,
+                flags.join(",")
+            );
+            writeln!(out, "{}", line.trim_end())?;
+        }
+    }
+
+    writeln!(out, "{} cookies", outcome.cookies.len())?;
+    for browser in &outcome.requires_interaction {
+        writeln!(out, "skipped {}: needs a keyring/keychain prompt", browser)?;
+    }
+    for browser in &outcome.unsupported_schemas {
+        writeln!(out, "skipped {}: cookies DB schema is newer than supported", browser)?;
+    }
+    for browser in &outcome.ports_ignored {
+        writeln!(out, "{}: ports ignored, the store doesn't record them", browser)?;
+    }
+    if outcome.duplicates > 0 {
+        writeln!(out, "dropped {} duplicate rows", outcome.duplicates)?;
+    }
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    run(&args, &mut std::io::stdout().lock())
+}
@@ -0,0 +1,46 @@
+//! Prints the cookies of every browser as a Netscape `cookies.txt`, for curl and wget.
+//!
+//! ```sh
+//! cargo run --example export_netscape -- [--fixtures <dir>] [domain...] > cookies.txt
+//! ```
+//!
+//! `--fixtures` reads the browsers under `<dir>` instead of the user profile.
+
+use anyhow::{ bail, Result };
+use rookie::common::utils::dedup_cookies;
+use std::io::Write;
+use std::path::PathBuf;
+
+fn split_fixtures(args: &[String]) -> Result<(Option<PathBuf>, Vec<String>)> {
+    let mut fixtures = None;
+    let mut rest = vec![];
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--fixtures" => {
+                let Some(dir) = args.next() else {
+                    bail!("--fixtures needs a directory");
+                };
+                fixtures = Some(PathBuf::from(dir));
+            }
+            _ => rest.push(arg.clone()),
+        }
+    }
+    Ok((fixtures, rest))
+}
+
+pub fn run(args: &[String], out: &mut dyn Write) -> Result<()> {
+    let (fixtures, domains) = split_fixtures(args)?;
+    let domains = rookie::Domains::new((!domains.is_empty()).then_some(domains));
+    let cookies = match &fixtures {
+        Some(root) => rookie::with_user_profile_root(root, || rookie::load(domains))?,
+        None => rookie::load(domains)?,
+    };
+    // the same cookie from two browsers would be two lines
+    rookie::export::to_netscape(&dedup_cookies(cookies), out)
+}
+
+fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    run(&args, &mut std::io::stdout().lock())
+}
@@ -0,0 +1,60 @@
+//! Prints the `Cookie` header a browser would send to a URL, from the cookies of
+//! its registrable domain.
+//!
+//! ```sh
+//! cargo run --example session_header -- [--fixtures <dir>] <url>
+//! ```
+//!
+//! `--fixtures` reads the browsers under `<dir>` instead of the user profile.
+
+use anyhow::{ anyhow, bail, Result };
+use rookie::common::request::registrable_domain;
+use rookie::{ header_for_request, RequestContext };
+use std::io::Write;
+use std::path::PathBuf;
+
+fn split_fixtures(args: &[String]) -> Result<(Option<PathBuf>, Vec<String>)> {
+    let mut fixtures = None;
+    let mut rest = vec![];
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--fixtures" => {
+                let Some(dir) = args.next() else {
+                    bail!("--fixtures needs a directory");
+                };
+                fixtures = Some(PathBuf::from(dir));
+            }
+            _ => rest.push(arg.clone()),
+        }
+    }
+    Ok((fixtures, rest))
+}
+
+pub fn run(args: &[String], out: &mut dyn Write) -> Result<()> {
+    let (fixtures, rest) = split_fixtures(args)?;
+    let [url] = rest.as_slice() else {
+        bail!("usage: session_header [--fixtures <dir>] <url>");
+    };
+    let ctx = RequestContext::new(url)?;
+    let host = ctx.url.host_str().ok_or_else(|| anyhow!("{} has no host", url))?;
+    // subdomains share cookies set on the registrable domain
+    let domains = vec![registrable_domain(host).unwrap_or_else(|| host.to_string())];
+    let cookies = match &fixtures {
+        Some(root) => rookie::with_user_profile_root(root, || rookie::load(domains))?,
+        None => rookie::load(domains)?,
+    };
+
+    let header = header_for_request(&cookies, &ctx)?;
+    if header.is_empty() {
+        writeln!(out, "no cookies for {}", url)?;
+    } else {
+        writeln!(out, "Cookie: {}", header)?;
+    }
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    run(&args, &mut std::io::stdout().lock())
+}
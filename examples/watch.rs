@@ -0,0 +1,99 @@
+//! Polls the browsers and prints the cookies that were added (`+`), removed (`-`)
+//! or changed (`~`) since the previous round. The first round lists every cookie.
+//!
+//! ```sh
+//! cargo run --example watch -- [--fixtures <dir>] [--interval <secs>] [--rounds <n>] [domain...]
+//! ```
+//!
+//! `--fixtures` reads the browsers under `<dir>` instead of the user profile.
+//! Without `--rounds` it polls until interrupted.
+
+use anyhow::{ bail, Result };
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+fn split_fixtures(args: &[String]) -> Result<(Option<PathBuf>, Vec<String>)> {
+    let mut fixtures = None;
+    let mut rest = vec![];
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--fixtures" => {
+                let Some(dir) = args.next() else {
+                    bail!("--fixtures needs a directory");
+                };
+                fixtures = Some(PathBuf::from(dir));
+            }
+            _ => rest.push(arg.clone()),
+        }
+    }
+    Ok((fixtures, rest))
+}
+
+/// Value and expiry of each (domain, path, name)
+type Snapshot = BTreeMap<(String, String, String), (String, Option<u64>)>;
+
+fn snapshot(fixtures: Option<&PathBuf>, domains: &rookie::Domains) -> Result<Snapshot> {
+    let cookies = match fixtures {
+        Some(root) => rookie::with_user_profile_root(root, || rookie::load(domains))?,
+        None => rookie::load(domains)?,
+    };
+    Ok(
+        cookies
+            .into_iter()
+            .map(|cookie| ((cookie.domain, cookie.path, cookie.name), (cookie.value, cookie.expires)))
+            .collect()
+    )
+}
+
+pub fn run(args: &[String], out: &mut dyn Write) -> Result<()> {
+    let (fixtures, rest) = split_fixtures(args)?;
+    let mut interval = Duration::from_secs(5);
+    let mut rounds = None;
+    let mut domains = vec![];
+    let mut rest = rest.into_iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--interval" => {
+                interval = Duration::from_secs(rest.next().unwrap_or_default().parse()?);
+            }
+            "--rounds" => {
+                rounds = Some(rest.next().unwrap_or_default().parse::<usize>()?);
+            }
+            _ => domains.push(arg),
+        }
+    }
+    let domains = rookie::Domains::new((!domains.is_empty()).then_some(domains));
+
+    let mut previous = Snapshot::new();
+    let mut round = 0;
+    while rounds.map_or(true, |rounds| round < rounds) {
+        if round > 0 {
+            std::thread::sleep(interval);
+        }
+        let current = snapshot(fixtures.as_ref(), &domains)?;
+        for (key, state) in &current {
+            let (domain, path, name) = key;
+            let value = &state.0;
+            match previous.get(key) {
+                None => writeln!(out, "+ {} {} {}={}", domain, path, name, value)?,
+                Some(old) if old != state => writeln!(out, "~ {} {} {}={}", domain, path, name, value)?,
+                Some(_) => {}
+            }
+        }
+        for (domain, path, name) in previous.keys().filter(|key| !current.contains_key(*key)) {
+            writeln!(out, "- {} {} {}", domain, path, name)?;
+        }
+        out.flush()?;
+        previous = current;
+        round += 1;
+    }
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    run(&args, &mut std::io::stdout().lock())
+}
@@ -0,0 +1,222 @@
+use crate::common::{ date, enums::*, provenance, request, utils };
+use crate::defaults;
+use anyhow::{ anyhow, bail, Result };
+use byteorder::{ BigEndian, ByteOrder, LittleEndian };
+use std::fs::File;
+use std::io::Read;
+use std::path::{ Path, PathBuf };
+use std::vec::Vec;
+
+fn parse_page(bs: &[u8]) -> Result<Vec<Cookie>> {
+    if slice(bs, 0, 4)? != [0x00, 0x00, 0x01, 0x00] {
+        bail!("bad page header");
+    }
+
+    let count = slice(bs, 4, 4).map(LittleEndian::read_u32)? as usize;
+    let parsed_table = parse_table::<LittleEndian>(&bs[8..], count)?;
+    let mut cookies: Vec<Cookie> = vec![];
+    for off in parsed_table {
+        let slice_result = slice(bs, off, 4);
+
+        // Read a little-endian unsigned 32-bit integer from the slice
+        let u32_value = slice_result.map(LittleEndian::read_u32);
+
+        // If 'u32_value' is Some(len), create a new slice of length 'len'
+        let parsed_slice = u32_value.and_then(|len| slice(bs, off, len as usize));
+
+        // Parse the sliced data into a Cookie struct using LittleEndian encoding
+        let cookie = parsed_slice.and_then(parse_cookie::<LittleEndian>)?;
+        cookies.push(cookie);
+
+        // Return the parsed Cookie struct, or propagate an error if any step fails
+    }
+
+    if slice(bs, count * 4 + 8, 4)? != [0x00, 0x00, 0x00, 0x00] {
+        bail!("bad page trailer");
+    }
+    Ok(cookies)
+}
+
+fn parse_cookie<T: ByteOrder>(bs: &[u8]) -> Result<Cookie> {
+    if bs.len() < 0x30 {
+        bail!("cookie data underflow");
+    }
+    let flags = T::read_u32(&bs[0x08..0x0c]);
+
+    let url_off = T::read_u32(&bs[0x10..0x14]) as usize;
+    let name_off = T::read_u32(&bs[0x14..0x18]) as usize;
+    let path_off = T::read_u32(&bs[0x18..0x1c]) as usize;
+    let value_off = T::read_u32(&bs[0x1c..0x20]) as usize;
+
+    // i/OS/X to Unix timestamp +(1 Jan 2001 epoch seconds).
+    let expires = T::read_f64(&bs[0x28..0x30]);
+    let expires = date::safari_timestamp(expires);
+    let creation = bs.get(0x30..0x38).and_then(|creation| date::safari_timestamp(T::read_f64(creation)));
+
+    let url = slice_to(bs, url_off, name_off).and_then(&c_str)?;
+    let name = slice_to(bs, name_off, path_off).and_then(&c_str)?;
+    let path = slice_to(bs, path_off, value_off).and_then(&c_str)?;
+    let value = slice_to(bs, value_off, bs.len()).and_then(&c_str)?;
+
+    let is_secure = (flags & 0x01) == 0x01;
+    let is_http_only = (flags & 0x04) == 0x04;
+
+    let cookie = Cookie {
+        expires,
+        // the flags only hold secure and httpOnly, domain cookies have a leading dot
+        host_only: !url.starts_with('.'),
+        domain: url,
+        http_only: is_http_only,
+        name,
+        path,
+        value,
+        same_site: SameSite::Unspecified,
+        same_site_raw: None,
+        creation,
+        last_accessed: None,
+        partition_key: None,
+        secure: is_secure,
+        port: None,
+        source_scheme: SourceScheme::Unset,
+        extension: None,
+        browser: String::new(),
+        profile: None,
+    };
+    Ok(cookie)
+}
+
+pub fn parse_content(bs: &[u8]) -> Result<Vec<Cookie>> {
+    // Magic bytes: "COOK" = 0x636F6F6B
+    if slice(bs, 0, 4)? != [0x63, 0x6f, 0x6f, 0x6b] {
+        bail!("not a cookie file");
+    }
+
+    let count = slice(bs, 4, 4).map(BigEndian::read_u32)? as usize;
+    let table = parse_table::<BigEndian>(&bs[8..], count)?;
+    let table = table.iter();
+    let mut pages = Vec::new();
+    let mut off = count * 4 + 8;
+
+    for &len in table {
+        let page_slice = match slice(bs, off, len) {
+            Ok(slice) => slice,
+            Err(_) => {
+                // Handle the error here, e.g., by returning the error.
+                bail!("cant get slice from page");
+            }
+        };
+
+        pages.push(page_slice.to_vec());
+        off += len;
+    }
+
+    let mut cookies: Vec<Cookie> = vec![];
+    for page in pages {
+        let cookie = parse_page(page.as_slice())?;
+        cookies.extend(cookie);
+    }
+    Ok(cookies)
+}
+
+fn slice(bs: &[u8], off: usize, len: usize) -> Result<&[u8]> {
+    // offsets come from the file, they can be anything
+    match off.checked_add(len) {
+        Some(end) if end <= bs.len() => Ok(&bs[off..end]),
+        Some(end) => bail!("data underflow: {}", end - bs.len()),
+        None => bail!("data offset overflow: {} + {}", off, len),
+    }
+}
+
+fn parse_table<T: ByteOrder>(bs: &[u8], count: usize) -> Result<Vec<usize>> {
+    let end = count * 4;
+    if end > bs.len() {
+        bail!("table data underflow");
+    }
+    let data = bs[..end]
+        .chunks(4)
+        .map(|u| T::read_u32(u) as usize)
+        .collect();
+    Ok(data)
+}
+
+fn slice_to(bs: &[u8], off: usize, to: usize) -> Result<&[u8]> {
+    if to < off { bail!("negative data length: -{}", off - to) } else { slice(bs, off, to - off) }
+}
+
+fn c_str(bs: &[u8]) -> Result<String> {
+    bs.split_last()
+        .ok_or_else(|| anyhow!("null c string"))
+        .and_then(|(&last, elements)| {
+            if last == 0x00 { Ok(elements) } else { bail!("c string non null terminator") }
+        })
+        .and_then(|elements| {
+            String::from_utf8(elements.to_vec()).map_err(|err| anyhow!(err.to_string()))
+        })
+}
+
+/// Parses a `Cookies.binarycookies` file as written by Safari on macOS and by
+/// iOS apps, on any OS
+pub fn binarycookies_based(
+    db_path: PathBuf,
+    domains: impl Into<Domains>
+) -> Result<Vec<Cookie>> {
+    Ok(binarycookies_read(db_path, domains, defaults::global_defaults().read)?.cookies)
+}
+
+/// Same as [`binarycookies_based`] with the given options, also reporting the
+/// provenance of the file when asked to. The file has no schema version
+pub fn binarycookies_read(
+    db_path: PathBuf,
+    domains: impl Into<Domains>,
+    options: ReadOptions
+) -> Result<CookiesRead> {
+    // 1. open cookies file
+    // 2. parse headers
+    // 3. parse pages (total from headers)
+    // 4. get N cookies from each page, iterate
+    // 5. parse each cookie
+    // 6. add each cookie based on domain filter
+    let (bs, provenance) = if options.provenance {
+        let (bs, provenance) = provenance::read(&db_path)?;
+        (bs, Some(provenance))
+    } else {
+        let mut file = File::open(db_path)?;
+        let mut bs: Vec<u8> = Vec::new();
+        file.read_to_end(&mut bs)?;
+        (bs, None)
+    };
+    let mut cookies = parse_content(&bs)?;
+    let cutoff = options.expiry_cutoff();
+    let ReadOptions { persistence, path, secure_only, http_only, .. } = &options;
+    cookies.retain(|cookie| {
+        persistence.matches(cookie) &&
+            request::path_in_scope(&cookie.path, path.as_deref()) &&
+            cookie.has_flags(*secure_only, *http_only) &&
+            cookie.expires_by(cutoff)
+    });
+
+    // Filter cookies by domain if domains are specified
+    let domains: Domains = domains.into();
+    let ports_ignored = utils::has_port(domains.as_deref());
+    if let Some(domain_filters) = utils::without_ports(domains.as_deref()) {
+        cookies.retain(|cookie| utils::some_domain_matches(Some(&domain_filters), &cookie.domain, options.domain_match));
+    }
+    utils::sort_and_page(&mut cookies, options.sort_by, options.offset, options.limit);
+    Ok(CookiesRead { cookies, ports_ignored, provenance, ..Default::default() })
+}
+
+/// Counts the unexpired cookies of `domain` and its subdomains, the file doesn't
+/// keep last access times
+pub fn domain_stats(path: &Path, domain: &str) -> Result<DomainStats> {
+    let cookies = parse_content(&std::fs::read(path)?)?;
+    let now = date::now();
+    let cookies = cookies
+        .iter()
+        .filter(|cookie| {
+            let host = cookie.domain.trim_start_matches('.');
+            host == domain || host.ends_with(&format!(".{}", domain))
+        })
+        .filter(|cookie| cookie.expires.map_or(true, |expires| expires > now))
+        .count();
+    Ok(DomainStats { cookies, last_access: None })
+}
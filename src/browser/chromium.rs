@@ -1,22 +1,30 @@
-use crate::common::{ date, enums::*, sqlite };
-use anyhow::{ anyhow, bail, Result };
+use crate::common::{ date, enums::*, paths, provenance, sqlite, utils };
+use crate::defaults;
+use anyhow::{ bail, Result };
 use log::{ info, warn };
-use std::path::PathBuf;
+use rusqlite::types::Value;
+use sha2::{ Digest, Sha256 };
+use std::collections::HashMap;
+use std::fs;
+use std::path::{ Path, PathBuf };
 
 cfg_if::cfg_if! {
     if #[cfg(target_os = "windows")] {
         use aes_gcm::{ Aes256Gcm, Key, aead::{ Aead, KeyInit, generic_array::GenericArray } };
         use serde_json;
         use base64::{ Engine as _, engine::general_purpose };
-        use crate::winapi;
+        use crate::common::winapi;
     } else if #[cfg(unix)] {
-        use crate::common::secrets;
+        use crate::common::secrets::{ self, SecretsProvider };
     }
 }
 
 #[cfg(target_os = "windows")]
 fn get_keys(key64: &str) -> Result<Vec<Vec<u8>>> {
     let mut keydpapi: Vec<u8> = general_purpose::STANDARD.decode(&key64)?;
+    if !keydpapi.starts_with(b"DPAPI") {
+        bail!("encrypted_key in Local State is missing the DPAPI prefix");
+    }
     let keydpapi = &mut keydpapi[5..];
     let v10_key = winapi::decrypt(keydpapi)?;
     let mut keys: Vec<Vec<u8>> = vec![];
@@ -24,189 +32,522 @@ fn get_keys(key64: &str) -> Result<Vec<Vec<u8>>> {
     Ok(keys)
 }
 
+#[cfg(target_os = "linux")]
+pub(crate) const PBKDF2_ITERATIONS: u32 = 1;
+
+#[cfg(target_os = "macos")]
+pub(crate) const PBKDF2_ITERATIONS: u32 = 1003;
+
 #[cfg(unix)]
-fn create_pbkdf2_key(password: &str, salt: &[u8; 9], iterations: u32) -> Vec<u8> {
+pub(crate) fn create_pbkdf2_key(password: &str, salt: &[u8; 9], iterations: u32) -> Vec<u8> {
     use pbkdf2::pbkdf2_hmac;
     use sha1::Sha1;
     let mut output = [0u8; 16];
     pbkdf2_hmac::<Sha1>(password.as_bytes(), salt, iterations, &mut output);
-    return output.to_vec();
+    let key = output.to_vec();
+    wipe(&mut output);
+    key
 }
 
+/// Keys derived from the keyring / keychain passwords, without the built-in fallbacks.
+/// The passwords are wiped once their key is derived.
 #[cfg(unix)]
-fn get_keys(config: &BrowserConfig) -> Result<Vec<Vec<u8>>> {
+pub(crate) fn keystore_keys(config: &BrowserConfig, secrets: &dyn SecretsProvider) -> Result<Vec<Vec<u8>>> {
     // AES CBC key
 
     let salt = b"saltysalt";
-    let iterations: u32;
-    #[cfg(target_os = "linux")]
-    {
-        iterations = 1;
-    }
-
-    #[cfg(target_os = "macos")]
-    {
-        iterations = 1003;
-    }
+    let iterations = PBKDF2_ITERATIONS;
 
     let mut keys: Vec<Vec<u8>> = vec![];
 
-    cfg_if::cfg_if! {
-        if #[cfg(target_os = "linux")] {
-            if let Ok(passwords) = secrets::get_passwords(config.os_crypt_name.unwrap_or("")) {
-                for password in passwords {
-                    let key = create_pbkdf2_key(password.as_str(), salt, iterations);
-                    keys.push(key);
-                }
-            }
-            // default keys
-            let key = create_pbkdf2_key("peanuts", salt, iterations);
-            keys.push(key);
-            let key = create_pbkdf2_key("", salt, iterations);
-            keys.push(key);
-        } else if #[cfg(target_os = "macos")] {
-            let key_service = config.osx_key_service.ok_or(anyhow!("missing osx_key_service"))?;
-            let key_user = config.osx_key_user.ok_or(anyhow!("missing osx_key_user"))?;
-            let password = secrets
-                ::get_osx_keychain_password(key_service, key_user)
-                .unwrap_or("peanuts".to_string());
-
-            let key = create_pbkdf2_key(password.as_str(), salt, iterations);
-            keys.push(key);
-
-            let key = create_pbkdf2_key("peanuts", salt, iterations);
-            keys.push(key);
-            let key = create_pbkdf2_key("", salt, iterations);
-            keys.push(key);
+    // keyring / keychain keys
+    let passwords = match secrets.passwords(config) {
+        Ok(passwords) => passwords,
+        Err(err) if err.is::<secrets::InteractionRequired>() => {
+            return Err(err);
         }
+        Err(_) => vec![],
+    };
+    for password in passwords {
+        let key = create_pbkdf2_key(password.as_str(), salt, iterations);
+        keys.push(key);
+        wipe(&mut password.into_bytes());
+    }
+    Ok(keys)
+}
 
-        // keychain key
+/// Keys chromium uses when the keyring / keychain has no password for it
+#[cfg(unix)]
+pub(crate) fn fallback_keys() -> Vec<Vec<u8>> {
+    ["peanuts", ""]
+        .into_iter()
+        .map(|password| create_pbkdf2_key(password, b"saltysalt", PBKDF2_ITERATIONS))
+        .collect()
+}
 
-        // default keys
-    }
+#[cfg(unix)]
+pub(crate) fn get_keys(config: &BrowserConfig, secrets: &dyn SecretsProvider) -> Result<Vec<Vec<u8>>> {
+    let mut keys = keystore_keys(config, secrets)?;
+    keys.extend(fallback_keys());
     Ok(keys)
 }
 
+/// Version and provider GUID that start every DPAPI blob
+#[cfg(target_os = "windows")]
+const DPAPI_BLOB_HEADER: &[u8] = &[
+    0x01, 0x00, 0x00, 0x00, 0xd0, 0x8c, 0x9d, 0xdf, 0x01, 0x15, 0xd1, 0x11, 0x8c, 0x7a, 0x00, 0xc0,
+    0x4f, 0xc2, 0x97, 0xeb,
+];
+
+/// From this `meta.version` on chromium puts the SHA256 of `host_key` in front of the
+/// plaintext before encrypting it, so a value can't be moved to another host
+const HOST_HASH_SCHEMA: i64 = 24;
+
+/// Plaintext of a decrypted value as text. With a `host_key` its hash has to come
+/// first and is dropped. `None` when the value isn't what chromium writes, the row
+/// is then skipped and counted in [`CookiesRead::undecodable`].
+fn decode_plaintext(plaintext: Vec<u8>, host_key: Option<&str>) -> Option<String> {
+    let plaintext = match host_key {
+        Some(host_key) => {
+            let hash = Sha256::digest(host_key.as_bytes());
+            let Some(value) = plaintext.strip_prefix(hash.as_slice()) else {
+                warn!("Decrypted value of {} doesn't start with the hash of its host", host_key);
+                return None;
+            };
+            value.to_vec()
+        }
+        None => plaintext,
+    };
+    match String::from_utf8(plaintext) {
+        Ok(decoded) => Some(decoded),
+        Err(_) => {
+            warn!("Error in decode decrypt value with utf8");
+            None
+        }
+    }
+}
+
+/// Some builds (360 Secure Browser) keep the value unencrypted in `encrypted_value`,
+/// with neither a `v10`/`v11` nor a DPAPI prefix. It's passed through when it's text.
+fn unprefixed_plaintext(value: String, encrypted_value: &[u8]) -> String {
+    match std::str::from_utf8(encrypted_value) {
+        Ok(plaintext) => plaintext.to_string(),
+        Err(_) => {
+            warn!("Unknown encrypted value prefix {:02x?}", encrypted_value.get(..3).unwrap_or_default());
+            value
+        }
+    }
+}
+
+/// Value of a cookie row, `host_key` is given when the plaintext starts with its
+/// hash (see [`HOST_HASH_SCHEMA`])
 #[cfg(target_os = "windows")]
 fn decrypt_encrypted_value(
     value: String,
     encrypted_value: &[u8],
-    keys: Vec<Vec<u8>>
-) -> Result<String> {
+    keys: &[Vec<u8>],
+    host_key: Option<&str>
+) -> Result<Option<String>> {
+    if !value.is_empty() {
+        // value isn't encrypted
+        return Ok(Some(value));
+    }
+    if encrypted_value.starts_with(DPAPI_BLOB_HEADER) {
+        // legacy builds and some forks (360 Extreme Explorer) store a bare DPAPI blob
+        let plaintext = winapi::decrypt(&mut encrypted_value.to_vec())?;
+        return Ok(decode_plaintext(plaintext, host_key));
+    }
     // gcm
-    let key_type = &encrypted_value[..3];
-    if !value.is_empty() || !(key_type == b"v11" || key_type == b"v10") {
-        // unknown key_type or value isn't encrypted
-        return Ok(value);
+    let key_type = encrypted_value.get(..3).unwrap_or_default();
+    if !(key_type == b"v11" || key_type == b"v10") {
+        return Ok(Some(unprefixed_plaintext(value, encrypted_value)));
+    }
+    if encrypted_value.len() < 15 {
+        return Ok(Some(value));
+    }
+    let plaintext = decrypt_blob(encrypted_value, keys)?;
+    Ok(decode_plaintext(plaintext, host_key))
+}
+
+/// Decrypts a `v10`/`v11` AES-256-GCM blob with the first key that fits
+#[cfg(target_os = "windows")]
+pub(crate) fn decrypt_blob(encrypted_value: &[u8], keys: &[Vec<u8>]) -> Result<Vec<u8>> {
+    if encrypted_value.len() < 15 {
+        bail!("encrypted value is too short");
     }
     let encrypted_value = &encrypted_value[3..];
-    let nonce = &encrypted_value[..12];
+    let nonce = GenericArray::from_slice(&encrypted_value[..12]); // 96-bits; unique per message
     let ciphertext = &encrypted_value[12..];
 
-    // Create a new AES block cipher.
-    for key in keys {
+    for key in keys.iter().filter(|key| key.len() == 32) {
         let key = Key::<Aes256Gcm>::from_slice(key.as_slice());
         let cipher = Aes256Gcm::new(&key);
-        let nonce = GenericArray::from_slice(nonce); // 96-bits; unique per message
-        let plaintext = cipher
-            .decrypt(nonce, ciphertext.as_ref())
-            .or(Err(anyhow!("cant decrypt using key")))?;
-        let plaintext = String::from_utf8(plaintext).or(
-            Err(anyhow!("cant decode encrypted value"))
-        )?;
-        return Ok(plaintext);
+        if let Ok(plaintext) = cipher.decrypt(nonce, ciphertext.as_ref()) {
+            return Ok(plaintext);
+        }
     }
     bail!("decrypt_encrypted_value failed")
 }
 
+/// Value of a cookie row, `host_key` is given when the plaintext starts with its
+/// hash (see [`HOST_HASH_SCHEMA`])
 #[cfg(unix)]
 fn decrypt_encrypted_value(
     value: String,
     encrypted_value: &[u8],
-    keys: Vec<Vec<u8>>
-) -> Result<String> {
+    keys: &[Vec<u8>],
+    host_key: Option<&str>
+) -> Result<Option<String>> {
     // cbc
     if !value.is_empty() {
         // unknown key_type or value isn't encrypted
-        return Ok(value);
+        return Ok(Some(value));
     }
-    if encrypted_value.len() <= 0 {
-        return Ok("".into());
+    if encrypted_value.is_empty() {
+        return Ok(Some("".into()));
     }
-    let key_type = &encrypted_value[..3];
+    let key_type = encrypted_value.get(..3).unwrap_or_default();
     if !(key_type == b"v11" || key_type == b"v10") {
-        return Ok(value);
+        return Ok(Some(unprefixed_plaintext(value, encrypted_value)));
     }
+    let plaintext = decrypt_blob(encrypted_value, keys)?;
+    Ok(decode_plaintext(plaintext, host_key))
+}
+
+/// Decrypts a `v10`/`v11` AES-128-CBC blob with the first key that fits
+#[cfg(unix)]
+pub(crate) fn decrypt_blob(encrypted_value: &[u8], keys: &[Vec<u8>]) -> Result<Vec<u8>> {
     use aes::cipher::{ block_padding::Pkcs7, BlockDecryptMut, KeyIvInit };
 
     type Aes128CbcDec = cbc::Decryptor<aes::Aes128>;
 
     // Create an AES-128 cipher with the provided key.
 
-    let encrypted_value = &mut encrypted_value.to_owned()[3..];
+    let encrypted_value = encrypted_value.get(3..).unwrap_or_default();
     let iv: [u8; 16] = [b' '; 16];
 
-    for key in &keys {
+    for key in keys.iter().filter(|key| key.len() >= 16) {
         let mut key_array: [u8; 16] = [0; 16];
         key_array.copy_from_slice(&key[..16]);
         let cipher = Aes128CbcDec::new(&key_array.into(), &iv.into());
+        wipe(&mut key_array);
         let mut cloned_encrypted_value: Vec<u8> = encrypted_value.to_vec();
 
         if let Ok(plaintext) = cipher.decrypt_padded_mut::<Pkcs7>(&mut cloned_encrypted_value) {
-            let decoded = String::from_utf8(plaintext.to_vec());
-            match decoded {
-                Ok(decoded) => {
-                    return Ok(decoded);
-                }
-                Err(_) => {
-                    warn!("Error in decode decrypt value with utf8");
-                    return Ok("".into());
-                }
-            }
+            return Ok(plaintext.to_vec());
         }
     }
     bail!("decrypt_encrypted_value failed")
 }
 
-fn query_cookies(
+/// Keys Opera sets in `Preferences` or `Local State` when "protect browsing data
+/// with a password" is on
+const PASSWORD_PROTECTION_MARKERS: &[&str] = &[
+    "/opera/browsing_data_protection/enabled",
+    "/os_crypt/password_protection_enabled",
+];
+
+/// Returned when the cookie values are wrapped with an extra key derived from the
+/// Opera sync password, they can't be decrypted with the usual key alone and
+/// unwrapping that key isn't supported
+#[derive(Debug)]
+pub struct AdditionalPasswordRequired(pub PathBuf);
+
+impl std::fmt::Display for AdditionalPasswordRequired {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "browsing data of {} is protected with a password, which isn't supported",
+            self.0.display()
+        )
+    }
+}
+
+impl std::error::Error for AdditionalPasswordRequired {}
+
+/// Returned by [`crate::opera_with_password`] for a protected profile, the extra key
+/// Opera derives from the sync password isn't known so the password can't unwrap it
+#[derive(Debug)]
+pub struct PasswordUnwrapUnsupported(pub PathBuf);
+
+impl std::fmt::Display for PasswordUnwrapUnsupported {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "browsing data of {} is protected with a password, unwrapping it with the supplied password isn't supported",
+            self.0.display()
+        )
+    }
+}
+
+impl std::error::Error for PasswordUnwrapUnsupported {}
+
+/// Returned when the key in a `Local State` of another user (see
+/// [`crate::with_user_profile_root`]) can't be decrypted, DPAPI only gives it out in
+/// that user's logon session
+#[derive(Debug)]
+pub struct NeedsUserContext(pub PathBuf);
+
+impl std::fmt::Display for NeedsUserContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "key in {} has to be decrypted in the session of its user", self.0.display())
+    }
+}
+
+impl std::error::Error for NeedsUserContext {}
+
+/// Whether the profile holding `db_path` has Opera's browsing data password protection on
+pub fn password_protected(db_path: &Path) -> bool {
+    let Some(profile_dir) = paths::chrome_profile_path(db_path) else {
+        return false;
+    };
+    // Opera keeps Local State inside the profile dir, other forks one level up
+    let mut files = vec![profile_dir.join("Preferences"), profile_dir.join("Local State")];
+    if let Some(user_data) = profile_dir.parent() {
+        files.push(user_data.join("Local State"));
+    }
+    files
+        .iter()
+        .filter_map(|path| read_json(path))
+        .any(|json| {
+            PASSWORD_PROTECTION_MARKERS.iter().any(|marker| {
+                json.pointer(marker).and_then(|value| value.as_bool()) == Some(true)
+            })
+        })
+}
+
+fn check_password_protection(db_path: &Path) -> Result<()> {
+    if password_protected(db_path) {
+        let profile_dir = paths::chrome_profile_path(db_path).unwrap_or(db_path);
+        bail!(AdditionalPasswordRequired(profile_dir.to_path_buf()));
+    }
+    Ok(())
+}
+
+/// Overwrites key material with zeros
+pub(crate) fn wipe(bytes: &mut [u8]) {
+    for byte in bytes.iter_mut() {
+        // volatile so the writes to memory about to be freed aren't optimized out
+        unsafe {
+            std::ptr::write_volatile(byte, 0);
+        }
+    }
+    std::sync::atomic::compiler_fence(std::sync::atomic::Ordering::SeqCst);
+}
+
+/// Keys that are overwritten with zeros when dropped
+pub(crate) struct WipedKeys(pub Vec<Vec<u8>>);
+
+impl Drop for WipedKeys {
+    fn drop(&mut self) {
+        for key in &mut self.0 {
+            wipe(key);
+        }
+    }
+}
+
+/// Decrypts one encrypted value to check the keys, the plaintext is dropped right away.
+/// Returns false when no value is encrypted.
+pub(crate) fn decrypt_sample(db_path: &Path, keys: &[Vec<u8>]) -> Result<bool> {
+    let connection = sqlite::connect(db_path.to_path_buf())?;
+    let hashed_host = meta_version(&connection).is_some_and(|version| version >= HOST_HASH_SCHEMA);
+    let mut stmt = connection.prepare(
+        "SELECT value, encrypted_value, host_key FROM cookies WHERE length(encrypted_value) > 0 LIMIT 1"
+    )?;
+    let mut rows = stmt.query([])?;
+    let Some(row) = rows.next()? else {
+        return Ok(false);
+    };
+    let encrypted_value: Vec<u8> = row.get(1)?;
+    let host_key: String = row.get(2)?;
+    decrypt_encrypted_value(row.get(0)?, &encrypted_value, keys, hashed_host.then_some(host_key.as_str()))?;
+    Ok(true)
+}
+
+/// Columns of Chromium's UNIQUE index on `cookies`, the ones missing in older schemas are left out
+const KEY_COLUMNS: [&str; 7] = [
+    "host_key",
+    "top_frame_site_key",
+    "has_cross_site_ancestor",
+    "name",
+    "path",
+    "source_scheme",
+    "source_port",
+];
+
+/// Newest cookies DB `meta.version` the parsing was tested with
+pub const MAX_TESTED_SCHEMA: i64 = 24;
+
+fn meta_version(connection: &rusqlite::Connection) -> Option<i64> {
+    connection
+        .query_row("SELECT CAST(value AS INTEGER) FROM meta WHERE key = 'version'", [], |row| row.get(0))
+        .ok()
+}
+
+/// `meta.version` of a chromium cookies DB, `None` when it has no meta table
+pub fn schema_version(db_path: &Path) -> Result<Option<i64>> {
+    Ok(meta_version(&sqlite::connect(db_path.to_path_buf())?))
+}
+
+pub(crate) fn query_cookies(
     keys: Vec<Vec<u8>>,
     db_path: PathBuf,
-    domains: Option<Vec<&str>>
-) -> Result<Vec<Cookie>> {
+    domains: Option<&[String]>,
+    names: &NameFilter,
+    options: ReadOptions
+) -> Result<CookiesRead> {
     cfg_if::cfg_if! {
         if #[cfg(target_os = "windows")] {
-            let db_path_str = db_path.to_str().ok_or(anyhow!("Cant convert db path to str"))?;
             warn!("Unlocking chrome database, it may take a while (sometimes up to minute)");
             unsafe {
-                winapi::release_file_lock(db_path_str);
+                winapi::release_file_lock(&db_path);
             }
         }
     }
 
-    info!("Creating sqlite connection to {}", db_path.to_str().unwrap_or(""));
-    let connection = sqlite::connect(db_path)?;
-    let mut query =
-        "SELECT host_key, path, is_secure, expires_utc, name, value, encrypted_value, is_httponly, samesite FROM cookies ".to_string();
-
+    // the profile, its extensions and the messages below still refer to the live DB
+    let snapshot = provenance::snapshot_if(options.provenance, &db_path)?;
+    let source = snapshot.as_ref().map_or(db_path.clone(), |snapshot| snapshot.path().to_path_buf());
+    info!("Creating sqlite connection to {}", source.display());
+    let connection = sqlite::connect(source)?;
+    let schema_version = meta_version(&connection);
+    sqlite::check_schema(&db_path, schema_version, MAX_TESTED_SCHEMA, options.strict_schema)?;
+    let hashed_host = schema_version.is_some_and(|version| version >= HOST_HASH_SCHEMA);
+    let columns: Vec<String> = connection
+        .prepare("SELECT name FROM pragma_table_info('cookies')")?
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    let tables: i64 = connection.query_row("SELECT count(*) FROM sqlite_master", [], |row| row.get(0))?;
+    if tables == 0 {
+        // truncated rather than emptied, Epic does this on exit
+        info!("{} is empty", db_path.display());
+        return Ok(CookiesRead {
+            schema_version,
+            provenance: snapshot.as_ref().map(|snapshot| snapshot.provenance.clone()),
+            ..Default::default()
+        });
+    }
+    let has_column = |column: &str| columns.iter().any(|c| c == column);
+    // crashed browsers can leave rows that break the UNIQUE index, so the key is
+    // checked here too and the most recently updated row wins
+    let key = KEY_COLUMNS.iter()
+        .filter(|column| has_column(column))
+        .map(|column| format!("quote({})", column))
+        .collect::<Vec<_>>()
+        .join(" || '|' || ");
+    let last_update = if has_column("last_update_utc") { "last_update_utc" } else { "0" };
+    // older schemas don't record the port, -1 is Chromium's "unspecified"
+    let has_port = has_column("source_port");
+    let source_port = if has_port { "source_port" } else { "-1" };
+    let partition = if has_column("top_frame_site_key") { "top_frame_site_key" } else { "''" };
+    let last_access = if has_column("last_access_utc") { "last_access_utc" } else { "0" };
+    let source_scheme = if has_column("source_scheme") { "source_scheme" } else { "0" };
+    let mut query = format!(
+        "SELECT host_key, path, is_secure, expires_utc, name, value, encrypted_value, is_httponly, samesite, {}, {}, creation_utc, {}, {}, {}, {} FROM cookies ",
+        key,
+        last_update,
+        source_port,
+        partition,
+        last_access,
+        source_scheme
+    );
+
+    let mut read = CookiesRead { schema_version, ..Default::default() };
+    let mut conditions: Vec<String> = vec![];
+    let mut params: Vec<Value> = vec![];
     if let Some(domains) = domains {
         let domain_queries: Vec<String> = domains
             .iter()
-            .map(|domain| format!("host_key LIKE '%{}%'", domain))
+            .map(|domain| {
+                let (host, port) = utils::split_port(domain);
+                let (condition, host_params) = sqlite::domain_condition("host_key", host, options.domain_match);
+                params.extend(host_params);
+                match port {
+                    Some(port) if has_port => format!("({} AND source_port = {})", condition, port),
+                    port => {
+                        read.ports_ignored |= port.is_some();
+                        condition
+                    }
+                }
+            })
             .collect();
+        if read.ports_ignored {
+            warn!("{} doesn't record ports, matching domains on the host only", db_path.display());
+        }
 
         if !domain_queries.is_empty() {
-            let joined_queries = domain_queries.join(" OR ");
-            query += &format!("WHERE ({})", joined_queries);
+            conditions.push(format!("({})", domain_queries.join(" OR ")));
         }
     }
+    if let Some((condition, names)) = sqlite::name_condition("name", names) {
+        conditions.push(condition);
+        params.extend(names);
+    }
+    if let Some((condition, path)) = sqlite::path_condition("path", options.path.as_deref()) {
+        conditions.push(condition);
+        params.extend(path);
+    }
+    conditions.extend(sqlite::flag_conditions("is_secure", "is_httponly", options.secure_only, options.http_only));
+    let session = if has_column("has_expires") { "has_expires = 0" } else { "expires_utc = 0" };
+    conditions.extend(sqlite::persistence_condition(session, options.persistence));
+    if let Some(cutoff) = options.expiry_cutoff() {
+        conditions.push(format!("(NOT ({}) AND expires_utc < ?)", session));
+        params.push(Value::Integer(date::to_chromium_timestamp(cutoff) as i64));
+    }
+    if !options.include_expired {
+        // expired rows are only purged lazily, skip them before decrypting anything
+        conditions.push(format!("({} OR expires_utc > ?)", session));
+        params.push(Value::Integer(date::to_chromium_timestamp(date::now()) as i64));
+    }
+    if options.paginates() {
+        // rows skipped below would still take up room in the page
+        if !options.include_partitioned {
+            conditions.push(format!("{} = ''", partition));
+        }
+        if !options.include_extensions {
+            conditions.push(
+                format!("NOT (host_key GLOB 'chrome-extension://[^/]*' OR {} GLOB 'chrome-extension://[^/]*')", partition)
+            );
+        }
+    }
+    if !conditions.is_empty() {
+        query += &format!("WHERE {}", conditions.join(" AND "));
+    }
+    if let Some(sort_by) = options.order() {
+        let first = match sort_by {
+            SortBy::Domain => String::new(),
+            SortBy::Expiry => "expires_utc, ".to_string(),
+            SortBy::LastAccessed => format!("{}, ", last_access),
+        };
+        query += &format!(" ORDER BY {}host_key, path, name, rowid", first);
+    }
+    query += &sqlite::limit_clause(options.limit, options.offset);
     query += ";";
 
-    let mut cookies: Vec<Cookie> = vec![];
+    let profile = paths::chrome_profile_dir(&db_path);
+    let mut seen: HashMap<String, (usize, (i64, i64))> = HashMap::new();
+    let mut installed_extensions: Option<HashMap<String, String>> = None;
     let mut stmt = connection.prepare(query.as_str())?;
-    let mut rows = stmt.query([])?;
+    let mut rows = stmt.query(rusqlite::params_from_iter(params))?;
 
     while let Some(row) = rows.next()? {
         let host_key: String = row.get(0)?;
+        let partition: String = row.get(13)?;
+        if !options.include_partitioned && !partition.is_empty() {
+            continue;
+        }
+        let extension = match extension_id(&host_key).or_else(|| extension_id(&partition)) {
+            Some(_) if !options.include_extensions => {
+                continue;
+            }
+            Some(id) => {
+                let names = installed_extensions.get_or_insert_with(|| {
+                    paths::chrome_profile_path(&db_path).map(extension_names).unwrap_or_default()
+                });
+                Some(ExtensionInfo { id: id.to_string(), name: names.get(id).cloned() })
+            }
+            None => None,
+        };
         let path: String = row.get(1)?;
         let is_secure: bool = row.get(2)?;
         let expires: u64 = row.get(3)?;
@@ -215,56 +556,360 @@ fn query_cookies(
 
         let value: String = row.get(5)?;
         let encrypted_value: Vec<u8> = row.get(6)?;
-        let decrypted_value = decrypt_encrypted_value(value, &encrypted_value, keys.to_owned())?;
+        let host = hashed_host.then_some(host_key.as_str());
+        let Some(decrypted_value) = decrypt_encrypted_value(value, &encrypted_value, &keys, host)? else {
+            read.undecodable += 1;
+            continue;
+        };
         let http_only: bool = row.get(7)?;
 
         let same_site: i64 = row.get(8)?;
+        let port: i64 = row.get(12)?;
         let cookie = Cookie {
             domain: host_key.to_string(),
+            host_only: !host_key.starts_with('.'),
             path: path.to_string(),
             secure: is_secure,
             expires,
             name: name.to_string(),
             value: decrypted_value,
             http_only,
-            same_site,
+            same_site: SameSite::from_chromium(same_site),
+            same_site_raw: Some(same_site),
+            creation: date::chromium_timestamp(row.get::<_, i64>(11)?.max(0) as u64),
+            last_accessed: date::chromium_timestamp(row.get::<_, i64>(14)?.max(0) as u64),
+            partition_key: Some(partition).filter(|partition| !partition.is_empty()),
+            port: u16::try_from(port).ok().filter(|port| *port > 0),
+            source_scheme: SourceScheme::from(row.get::<_, i64>(15)?),
+            extension,
+            browser: String::new(),
+            profile: profile.clone(),
         };
-        cookies.push(cookie);
+
+        let key: String = row.get(9)?;
+        let updated: (i64, i64) = (row.get(10)?, row.get(11)?);
+        match seen.get_mut(&key) {
+            Some((index, newest)) => {
+                read.duplicates += 1;
+                if updated > *newest {
+                    *newest = updated;
+                    read.cookies[*index] = cookie;
+                }
+            }
+            None => {
+                seen.insert(key, (read.cookies.len(), updated));
+                read.cookies.push(cookie);
+            }
+        }
+    }
+    if read.undecodable > 0 {
+        warn!("{} has {} cookie values that couldn't be decoded", db_path.display(), read.undecodable);
+    }
+    if read.duplicates > 0 {
+        warn!(
+            "{} has {} duplicate cookie rows, it may be corrupted",
+            db_path.display(),
+            read.duplicates
+        );
+    }
+    read.provenance = snapshot.as_ref().map(|snapshot| snapshot.provenance.clone());
+    Ok(read)
+}
+
+/// Counts the unexpired cookies of `domain` and its subdomains without decrypting
+/// anything, so no key (and no keyring prompt) is needed
+pub fn domain_stats(db_path: &Path, domain: &str) -> Result<DomainStats> {
+    let connection = sqlite::connect(db_path.to_path_buf())?;
+    let (condition, mut params) = sqlite::domain_condition("host_key", domain, DomainMatch::Suffix);
+    params.push(Value::Integer(date::to_chromium_timestamp(date::now()) as i64));
+    let (cookies, last_access): (i64, Option<i64>) = connection.query_row(
+        &format!("SELECT COUNT(*), MAX(last_access_utc) FROM cookies WHERE {} AND (expires_utc = 0 OR expires_utc > ?)", condition),
+        rusqlite::params_from_iter(params),
+        |row| Ok((row.get(0)?, row.get(1)?))
+    )?;
+    Ok(DomainStats {
+        cookies: cookies as usize,
+        last_access: last_access.and_then(|last_access| date::chromium_timestamp(last_access as u64)),
+    })
+}
+
+fn read_json(path: &Path) -> Option<serde_json::Value> {
+    utils::parse_json_lenient(&fs::read(path).ok()?).ok()
+}
+
+/// `os_crypt.encrypted_key` of a `Local State`, see [`utils::parse_json_lenient`] for
+/// the variants forks write. When the file doesn't parse at all, e.g. cut off by a
+/// crash, the raw text is scanned for the key instead.
+pub fn local_state_encrypted_key(local_state: &[u8]) -> Result<Option<String>> {
+    let err = match utils::parse_json_lenient(local_state) {
+        Ok(json) => {
+            return Ok(json_str(&json, &["/os_crypt/encrypted_key"]));
+        }
+        Err(err) => err,
+    };
+    let re = regex::Regex::new(r#""encrypted_key"\s*:\s*"([A-Za-z0-9+/=]+)""#)?;
+    let text = utils::decode_text(local_state);
+    let Some(key) = re.captures(&text).and_then(|captures| captures.get(1)) else {
+        bail!("Cant read Local State: {}", err);
+    };
+    warn!("Local State is malformed ({}), using the encrypted_key found in its text", err);
+    Ok(Some(key.as_str().to_string()))
+}
+
+fn json_str(json: &serde_json::Value, pointers: &[&str]) -> Option<String> {
+    pointers
+        .iter()
+        .filter_map(|pointer| json.pointer(pointer)?.as_str())
+        .find(|value| !value.is_empty())
+        .map(String::from)
+}
+
+/// Extension id of a `chrome-extension://<id>` origin, as used for host keys and partitions
+fn extension_id(origin: &str) -> Option<&str> {
+    let id = origin.strip_prefix("chrome-extension://")?.trim_end_matches('/');
+    (!id.is_empty()).then_some(id)
+}
+
+/// Names of the extensions installed in a chromium profile by id, from the
+/// `extensions.settings` of its `Secure Preferences` and `Preferences`
+pub fn extension_names(profile_dir: &Path) -> HashMap<String, String> {
+    let mut names = HashMap::new();
+    for file in ["Secure Preferences", "Preferences"] {
+        let settings = read_json(&profile_dir.join(file))
+            .and_then(|json| json.pointer("/extensions/settings")?.as_object().cloned())
+            .unwrap_or_default();
+        for (id, setting) in settings {
+            if names.contains_key(&id) {
+                continue;
+            }
+            if let Some(name) = extension_name(profile_dir, &setting) {
+                names.insert(id, name);
+            }
+        }
+    }
+    names
+}
+
+/// `manifest.name` of an extension, looked up in its `_locales` when it's a `__MSG_name__` placeholder
+fn extension_name(profile_dir: &Path, setting: &serde_json::Value) -> Option<String> {
+    let name = json_str(setting, &["/manifest/name"])?;
+    let Some(message) = name.strip_prefix("__MSG_").and_then(|name| name.strip_suffix("__")) else {
+        return Some(name);
+    };
+    // `path` is relative to the profile's Extensions dir, unpacked extensions have an absolute one
+    let extension_dir = profile_dir.join("Extensions").join(json_str(setting, &["/path"])?);
+    let locale = json_str(setting, &["/manifest/default_locale"])?;
+    let messages = read_json(&extension_dir.join("_locales").join(locale).join("messages.json"))?;
+    // message names are case insensitive
+    messages
+        .as_object()?
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(message))
+        .and_then(|(_, entry)| json_str(entry, &["/message"]))
+}
+
+/// Reads the account and installation ids of a chromium profile from its `Preferences`
+/// and the `Local State` one level up. Keys missing in a fork are left as `None`.
+pub fn profile_identity(profile_dir: &Path) -> Result<ProfileIdentity> {
+    let preferences = read_json(&profile_dir.join("Preferences"));
+    let local_state = profile_dir.parent().and_then(|dir| read_json(&dir.join("Local State")));
+    if preferences.is_none() && local_state.is_none() {
+        bail!("no Preferences or Local State for {}", profile_dir.display());
+    }
+
+    let mut identity = ProfileIdentity::default();
+    if let Some(preferences) = preferences {
+        identity.gaia_id = json_str(
+            &preferences,
+            &["/account_info/0/gaia", "/google/services/last_gaia_id"]
+        );
+        identity.account_email = json_str(
+            &preferences,
+            &["/account_info/0/email", "/google/services/last_username"]
+        );
+    }
+    if let Some(local_state) = local_state {
+        identity.client_id = json_str(
+            &local_state,
+            &["/user_experience_metrics/client_id2", "/user_experience_metrics/client_id"]
+        );
+    }
+    Ok(identity)
+}
+
+/// Display name of a chromium profile, from the `profile.info_cache` of the `Local State`
+/// one level up, or else from `profile.name` in its `Preferences`
+pub fn profile_name(profile_dir: &Path) -> Option<String> {
+    let dir_name = profile_dir.file_name()?.to_string_lossy().to_string();
+    profile_dir
+        .parent()
+        .and_then(|dir| read_json(&dir.join("Local State")))
+        .and_then(|local_state| {
+            let info = local_state.pointer("/profile/info_cache")?.get(&dir_name)?.clone();
+            json_str(&info, &["/name"])
+        })
+        .or_else(|| {
+            let preferences = read_json(&profile_dir.join("Preferences"))?;
+            json_str(&preferences, &["/profile/name"])
+        })
+}
+
+/// Executable registered under `App Paths` for a browser name as in [`crate::load`].
+/// Browsers that share their executable name with another one have none.
+pub fn executable_name(browser: &str) -> Option<&'static str> {
+    match browser {
+        "chrome" => Some("chrome.exe"),
+        "edge" => Some("msedge.exe"),
+        "brave" => Some("brave.exe"),
+        "vivaldi" => Some("vivaldi.exe"),
+        "opera" => Some("opera.exe"),
+        "whale" => Some("whale.exe"),
+        "qq_browser" => Some("QQBrowser.exe"),
+        _ => None,
+    }
+}
+
+/// Detects the version of a chromium based browser from its `Local State` path:
+/// the `Last Version` file next to it, then `browser.last_known_version` in it, then
+/// on Windows the version resource of `executable` found through the registry.
+/// Per-machine installs have no `Last Version` file.
+pub fn browser_version(local_state: &Path, executable: Option<&str>) -> Option<BrowserVersion> {
+    let last_version = local_state
+        .parent()
+        .and_then(|dir| fs::read_to_string(dir.join("Last Version")).ok())
+        .and_then(|content| BrowserVersion::parse(&content, VersionSource::LastVersionFile));
+    if last_version.is_some() {
+        return last_version;
+    }
+    let known_version = read_json(local_state)
+        .and_then(|local_state| json_str(&local_state, &["/browser/last_known_version"]))
+        .and_then(|version| BrowserVersion::parse(&version, VersionSource::LocalState));
+    if known_version.is_some() {
+        return known_version;
+    }
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let [major, minor, build, patch] = winapi::file_version(&winapi::app_path(executable?)?)?;
+            Some(BrowserVersion { major, minor, build, patch, source: VersionSource::Executable })
+        } else {
+            let _ = executable;
+            None
+        }
     }
-    Ok(cookies)
 }
 
 #[cfg(target_os = "windows")]
 pub fn chromium_based(
     key: PathBuf,
     db_path: PathBuf,
-    domains: Option<Vec<&str>>
+    domains: impl Into<Domains>
 ) -> Result<Vec<Cookie>> {
-    // Use DPAPI
-    let content = std::fs::read_to_string(&key)?;
-    let key_dict: serde_json::Value = serde_json
-        ::from_str(content.as_str())
-        .or(Err(anyhow!("Cant read json file")))?;
+    Ok(chromium_read(key, db_path, domains, defaults::global_defaults().read)?.cookies)
+}
 
-    let os_crypt = key_dict.get("os_crypt").ok_or(anyhow!("can't get os crypt"))?;
+/// Same as [`chromium_based`], also reporting the duplicate rows that were dropped
+/// and the schema version
+#[cfg(target_os = "windows")]
+pub fn chromium_read(
+    key: PathBuf,
+    db_path: PathBuf,
+    domains: impl Into<Domains>,
+    options: ReadOptions
+) -> Result<CookiesRead> {
+    chromium_read_filtered(key, db_path, domains, &NameFilter::default(), options)
+}
 
-    let key64 = os_crypt
-        .get("encrypted_key")
-        .ok_or(anyhow!("cant get encrypted_key"))?
-        .as_str()
-        .ok_or(anyhow!("Cant convert encrypted_key to str"))?;
+/// Same as [`chromium_read`] for the cookies matching `names` only, the others
+/// aren't decrypted
+#[cfg(target_os = "windows")]
+pub fn chromium_read_filtered(
+    key: PathBuf,
+    db_path: PathBuf,
+    domains: impl Into<Domains>,
+    names: &NameFilter,
+    options: ReadOptions
+) -> Result<CookiesRead> {
+    // every value would fail to decrypt
+    check_password_protection(&db_path)?;
+    let keys = local_state_keys(&key)?;
+    let domains: Domains = domains.into();
+    query_cookies(keys, db_path, domains.as_deref(), names, options)
+}
 
-    let keys = get_keys(key64)?;
-    query_cookies(keys, db_path, domains)
+/// Decrypts the AES key in `Local State` with DPAPI
+#[cfg(target_os = "windows")]
+pub(crate) fn local_state_keys(key: &Path) -> Result<Vec<Vec<u8>>> {
+    // Use DPAPI
+    let content = std::fs::read(paths::extended_length_path(key))?;
+    let keys = match local_state_encrypted_key(&content)? {
+        Some(key64) =>
+            match get_keys(&key64) {
+                Ok(keys) => keys,
+                Err(err) if paths::user_profile_root().is_some() => {
+                    warn!("cant decrypt the key of {}: {}", key.display(), err);
+                    bail!(NeedsUserContext(key.to_path_buf()));
+                }
+                Err(err) => {
+                    return Err(err);
+                }
+            }
+        None => {
+            // bare DPAPI values (360 Extreme Explorer) don't need the AES key
+            warn!("cant get encrypted_key, only DPAPI values can be decrypted");
+            vec![]
+        }
+    };
+    Ok(keys)
 }
 
 #[cfg(unix)]
 pub fn chromium_based(
     config: &BrowserConfig,
     db_path: PathBuf,
-    domains: Option<Vec<&str>>
+    domains: impl Into<Domains>
+) -> Result<Vec<Cookie>> {
+    chromium_based_with(config, db_path, domains, &secrets::OsSecrets)
+}
+
+/// Same as [`chromium_based`] with the keyring/keychain passwords taken from `secrets`
+#[cfg(unix)]
+pub fn chromium_based_with(
+    config: &BrowserConfig,
+    db_path: PathBuf,
+    domains: impl Into<Domains>,
+    secrets: &dyn SecretsProvider
 ) -> Result<Vec<Cookie>> {
+    Ok(chromium_read_with(config, db_path, domains, secrets, defaults::global_defaults().read)?.cookies)
+}
+
+/// Same as [`chromium_based_with`], also reporting the duplicate rows that were dropped
+/// and the schema version
+#[cfg(unix)]
+pub fn chromium_read_with(
+    config: &BrowserConfig,
+    db_path: PathBuf,
+    domains: impl Into<Domains>,
+    secrets: &dyn SecretsProvider,
+    options: ReadOptions
+) -> Result<CookiesRead> {
+    chromium_read_filtered(config, db_path, domains, &NameFilter::default(), secrets, options)
+}
+
+/// Same as [`chromium_read_with`] for the cookies matching `names` only, the others
+/// aren't decrypted
+#[cfg(unix)]
+pub fn chromium_read_filtered(
+    config: &BrowserConfig,
+    db_path: PathBuf,
+    domains: impl Into<Domains>,
+    names: &NameFilter,
+    secrets: &dyn SecretsProvider,
+    options: ReadOptions
+) -> Result<CookiesRead> {
+    // every value would fail to decrypt
+    check_password_protection(&db_path)?;
     // Simple AES
-    let keys = get_keys(&config)?;
-    query_cookies(keys, db_path, domains)
+    let keys = get_keys(config, secrets)?;
+    let domains: Domains = domains.into();
+    query_cookies(keys, db_path, domains.as_deref(), names, options)
 }
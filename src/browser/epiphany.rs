@@ -0,0 +1,100 @@
+use crate::common::{ date, enums::*, sqlite, utils };
+use crate::defaults;
+use anyhow::Result;
+use log::warn;
+use rusqlite::types::Value;
+use std::path::PathBuf;
+
+/// Reads the libsoup `cookies.sqlite` of Epiphany (GNOME Web) and other WebKitGTK browsers.
+/// Values are stored in plaintext, the table is a trimmed down `moz_cookies` without
+/// `originAttributes` and, before libsoup 2.70, without `sameSite`.
+pub fn epiphany_based(
+    db_path: PathBuf,
+    domains: impl Into<Domains>
+) -> Result<Vec<Cookie>> {
+    let domains: Domains = domains.into();
+    // libsoup doesn't record the port a cookie was set from
+    let domains = utils::without_ports(domains.as_deref());
+    let options = defaults::global_defaults().read;
+    let connection = sqlite::connect(db_path)?;
+
+    let has_same_site = connection
+        .prepare("SELECT name FROM pragma_table_info('moz_cookies') WHERE name = 'sameSite'")?
+        .exists([])?;
+    let mut query = format!(
+        "SELECT host, path, isSecure, expiry, name, value, isHttpOnly, {} FROM moz_cookies ",
+        if has_same_site { "sameSite" } else { "NULL" }
+    );
+
+    let mut conditions: Vec<String> = vec![];
+    let mut params: Vec<Value> = vec![];
+    if let Some(domains) = domains.as_deref() {
+        let domain_queries: Vec<String> = domains
+            .iter()
+            .map(|domain| {
+                let (condition, host_params) = sqlite::domain_condition("host", domain, options.domain_match);
+                params.extend(host_params);
+                condition
+            })
+            .collect();
+
+        if !domain_queries.is_empty() {
+            conditions.push(format!("({})", domain_queries.join(" OR ")));
+        }
+    }
+    if let Some((condition, path)) = sqlite::path_condition("path", options.path.as_deref()) {
+        conditions.push(condition);
+        params.extend(path);
+    }
+    conditions.extend(sqlite::flag_conditions("isSecure", "isHttpOnly", options.secure_only, options.http_only));
+    conditions.extend(sqlite::persistence_condition("expiry = 0", options.persistence));
+    if let Some(cutoff) = options.expiry_cutoff() {
+        conditions.push("(expiry != 0 AND expiry < ?)".to_string());
+        params.push(Value::Integer(cutoff as i64));
+    }
+    if !options.include_expired {
+        conditions.push("(expiry = 0 OR expiry > ?)".to_string());
+        params.push(Value::Integer(date::now() as i64));
+    }
+    if !conditions.is_empty() {
+        query += &format!("WHERE {}", conditions.join(" AND "));
+    }
+
+    let mut cookies = vec![];
+    let mut stmt = connection.prepare(query.as_str())?;
+    let mut rows = stmt.query(rusqlite::params_from_iter(params))?;
+    while let Some(row) = rows.next()? {
+        let host: Option<String> = row.get(0)?;
+        let Some(host) = host else {
+            warn!("host is NULL in row");
+            continue;
+        };
+        let expires: i64 = row.get::<_, Option<i64>>(3)?.unwrap_or(0);
+        // libsoup uses firefox's encoding
+        let same_site: Option<i64> = row.get(7)?;
+        cookies.push(Cookie {
+            host_only: !host.starts_with('.'),
+            domain: host,
+            path: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+            secure: row.get(2)?,
+            // expiry is in unix seconds, 0 for session cookies
+            expires: date::mozilla_timestamp(expires.max(0) as u64),
+            name: row.get::<_, Option<String>>(4)?.unwrap_or_default(),
+            value: row.get::<_, Option<String>>(5)?.unwrap_or_default(),
+            http_only: row.get(6)?,
+            same_site: same_site.map_or(SameSite::Unspecified, SameSite::from_mozilla),
+            same_site_raw: same_site,
+            // libsoup leaves lastAccessed empty and has no creation time
+            creation: None,
+            last_accessed: None,
+            partition_key: None,
+            port: None,
+            source_scheme: SourceScheme::Unset,
+            extension: None,
+            browser: String::new(),
+            profile: None,
+        });
+    }
+    utils::sort_and_page(&mut cookies, options.sort_by, options.offset, options.limit);
+    Ok(cookies)
+}
@@ -1,20 +1,27 @@
 use crate::common::date;
-use crate::common::enums::Cookie;
-use crate::winapi;
+use crate::common::paths;
+use crate::common::request;
+use crate::common::enums::{ Cookie, Domains, ReadOptions, SameSite, SourceScheme };
+use crate::common::utils;
+use crate::common::winapi;
+use crate::defaults;
 use anyhow::Result;
 use libesedb::EseDb;
 use std::path::PathBuf;
 
 pub fn internet_explorer_based(
     db_path: PathBuf,
-    domains: Option<Vec<&str>>
+    domains: impl Into<Domains>
 ) -> Result<Vec<Cookie>> {
+    let domains: Domains = domains.into();
+    let domains = utils::without_ports(domains.as_deref());
+    let options = defaults::global_defaults().read;
+    let cutoff = options.expiry_cutoff();
+    let ReadOptions { domain_match, persistence, path, secure_only, http_only, .. } = options;
     unsafe {
-        if let Some(path) = db_path.to_str() {
-            winapi::release_file_lock(path);
-        }
+        winapi::release_file_lock(&db_path);
     }
-    let db = EseDb::open(db_path)?;
+    let db = EseDb::open(paths::extended_length_path(&db_path))?;
     let mut cookies: Vec<Cookie> = vec![];
 
     for table in db.iter_tables()? {
@@ -34,7 +41,6 @@ pub fn internet_explorer_based(
                     .trim_matches('\0')
                     .to_string();
                 let value = rec.value(11)?;
-                let same_site = 0;
                 let value = String::from_utf8(value.as_bytes().unwrap_or(&[]).to_vec())
                     .unwrap_or("".to_string())
                     .trim_matches('\0')
@@ -44,21 +50,39 @@ pub fn internet_explorer_based(
                 let expires = date::internet_explorer_timestamp(expires);
                 let http_only = false;
 
-                let should_append = domains.is_none() || domains.iter().any(|d| d.contains(&host));
+                let should_append =
+                    domains.is_none() || utils::some_domain_matches(domains.as_deref(), host, domain_match);
                 if should_append {
                     cookies.push(Cookie {
                         domain: host.to_string(),
+                        host_only: !host.starts_with('.'),
                         path: path.to_string(),
                         secure,
                         expires,
                         name,
                         value,
                         http_only,
-                        same_site,
+                        same_site: SameSite::Unspecified,
+                        same_site_raw: None,
+                        creation: None,
+                        last_accessed: None,
+                        partition_key: None,
+                        port: None,
+                        source_scheme: SourceScheme::Unset,
+                        extension: None,
+                        browser: String::new(),
+                        profile: None,
                     });
                 }
             }
         }
     }
+    cookies.retain(|cookie| {
+        persistence.matches(cookie) &&
+            request::path_in_scope(&cookie.path, path.as_deref()) &&
+            cookie.has_flags(secure_only, http_only) &&
+            cookie.expires_by(cutoff)
+    });
+    utils::sort_and_page(&mut cookies, options.sort_by, options.offset, options.limit);
     Ok(cookies)
 }
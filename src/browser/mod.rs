@@ -1,5 +1,8 @@
+pub mod binarycookies;
 pub mod chromium;
+pub mod epiphany;
 pub mod mozilla;
+pub mod yandex;
 
 #[cfg(target_os = "windows")]
 pub mod internet_explorer;
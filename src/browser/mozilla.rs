@@ -1,4 +1,5 @@
-use crate::common::{ date, enums::*, sqlite, utils };
+use crate::common::{ date, enums::*, paths, provenance, request, sqlite, utils };
+use crate::defaults;
 use anyhow::bail;
 use anyhow::{ anyhow, Result };
 use ini::Ini;
@@ -9,30 +10,312 @@ use std::fs;
 use std::path::Path;
 use std::path::PathBuf;
 
-pub fn firefox_based(db_path: PathBuf, domains: Option<Vec<&str>>) -> Result<Vec<Cookie>> {
-    let connection = sqlite::connect(db_path.clone())?;
+pub fn firefox_based(
+    db_path: PathBuf,
+    domains: impl Into<Domains>
+) -> Result<Vec<Cookie>> {
+    Ok(firefox_read(db_path, domains, defaults::global_defaults().read)?.cookies)
+}
+
+/// Same as [`firefox_based`], also reporting the schema version. Session store
+/// cookies never expire and are kept even without `include_expired`.
+pub fn firefox_read(
+    db_path: PathBuf,
+    domains: impl Into<Domains>,
+    options: ReadOptions
+) -> Result<CookiesRead> {
+    firefox_read_filtered(db_path, domains, &NameFilter::default(), options)
+}
+
+/// Same as [`firefox_read`] for the cookies matching `names` only
+pub fn firefox_read_filtered(
+    db_path: PathBuf,
+    domains: impl Into<Domains>,
+    names: &NameFilter,
+    options: ReadOptions
+) -> Result<CookiesRead> {
+    let domains: Domains = domains.into();
+    let ports_ignored = utils::has_port(domains.as_deref());
+    // firefox doesn't record the port a cookie was set from
+    let domains = utils::without_ports(domains.as_deref());
+    let domains = domains.as_deref();
+    // the session store is merged in below, so the DB gives every cookie up to the
+    // end of the page and the page is cut after sorting them together
+    let db_options = ReadOptions { limit: options.limit.map(|limit| limit.saturating_add(options.offset)), offset: 0, ..options.clone() };
+    let mut read = query_cookies(&db_path, domains, names, None, db_options)?;
+    read.ports_ignored = ports_ignored;
+
+    let parent_path = db_path.parent().unwrap_or(&PathBuf::from("")).to_path_buf();
+    let cutoff = options.expiry_cutoff();
+    let keep = |cookie: &Cookie| {
+        names.matches(&cookie.name) &&
+            options.persistence.matches(cookie) &&
+            request::path_in_scope(&cookie.path, options.path.as_deref()) &&
+            cookie.has_flags(options.secure_only, options.http_only) &&
+            cookie.expires_by(cutoff)
+    };
+    if let Ok(session) = get_session_cookies_lz4(domains, parent_path.to_owned(), options.clone()) {
+        read.cookies.extend(session.cookies.into_iter().filter(keep));
+        read.private_excluded += session.private_excluded;
+    }
+
+    if let Ok(session) = get_session_cookies(domains, parent_path, options.clone()) {
+        read.cookies.extend(session.cookies.into_iter().filter(keep));
+        read.private_excluded += session.private_excluded;
+    }
+    if read.private_excluded > 0 {
+        warn!("{} private browsing cookies left out of {}", read.private_excluded, db_path.display());
+    }
+    utils::sort_and_page(&mut read.cookies, options.sort_by, options.offset, options.limit);
+    set_profile(&mut read.cookies, &db_path);
+    Ok(read)
+}
+
+/// Sets [`Cookie::profile`] to the profile directory holding `db_path`
+fn set_profile(cookies: &mut [Cookie], db_path: &Path) {
+    let profile = db_path
+        .parent()
+        .and_then(Path::file_name)
+        .map(|name| name.to_string_lossy().to_string());
+    for cookie in cookies {
+        cookie.profile = profile.clone();
+    }
+}
+
+/// Firefox container (contextual identity) to read cookies from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Container {
+    /// Cookies outside of every container
+    None,
+    /// `userContextId` of the container
+    Id(u64),
+}
+
+/// Looks up a container of a firefox profile by its name in `containers.json`.
+/// The built in containers are named by their label id ("Personal", "Work", ...),
+/// `"none"` selects the cookies outside of every container.
+pub fn find_container(profile_dir: &Path, name: &str) -> Result<Container> {
+    if name == "none" {
+        return Ok(Container::None);
+    }
+    let containers_path = profile_dir.join("containers.json");
+    let json: Value = serde_json::from_str(&fs::read_to_string(&containers_path)?)?;
+    let identities = json
+        .get("identities")
+        .and_then(|identities| identities.as_array())
+        .ok_or(anyhow!("no identities in {}", containers_path.display()))?;
+    for identity in identities {
+        let label = identity
+            .get("l10nID")
+            .and_then(|id| id.as_str())
+            .and_then(|id| id.strip_prefix("userContext"))
+            .and_then(|id| id.strip_suffix(".label"));
+        let custom_name = identity.get("name").and_then(|name| name.as_str());
+        if custom_name == Some(name) || label == Some(name) {
+            if let Some(id) = identity.get("userContextId").and_then(|id| id.as_u64()) {
+                return Ok(Container::Id(id));
+            }
+        }
+    }
+    bail!("no container named {} in {}", name, containers_path.display())
+}
+
+/// Same as [`firefox_based`] for the cookies of one container. Session store
+/// cookies don't record their container and are left out.
+pub fn firefox_based_in_container(
+    db_path: PathBuf,
+    domains: impl Into<Domains>,
+    container: Container
+) -> Result<Vec<Cookie>> {
+    let domains: Domains = domains.into();
+    let domains = utils::without_ports(domains.as_deref());
+    let mut read = query_cookies(
+        &db_path,
+        domains.as_deref(),
+        &NameFilter::default(),
+        Some(container),
+        defaults::global_defaults().read
+    )?;
+    set_profile(&mut read.cookies, &db_path);
+    Ok(read.cookies)
+}
+
+/// Newest `moz_cookies` schema (`PRAGMA user_version`) the parsing was tested with,
+/// the one the test fixtures write
+pub const MAX_TESTED_SCHEMA: i64 = 12;
+
+fn user_version(connection: &rusqlite::Connection) -> Result<Option<i64>> {
+    let version: i64 = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    // 0 is an unset version, not an old one
+    Ok(Some(version).filter(|version| *version > 0))
+}
+
+/// `PRAGMA user_version` of a firefox cookies DB, `None` when it isn't set
+pub fn schema_version(db_path: &Path) -> Result<Option<i64>> {
+    user_version(&sqlite::connect(db_path.to_path_buf())?)
+}
+
+/// Counts the unexpired cookies of `domain` and its subdomains without reading their values
+pub fn domain_stats(db_path: &Path, domain: &str) -> Result<DomainStats> {
+    let connection = sqlite::connect(db_path.to_path_buf())?;
+    let (condition, mut params) = sqlite::domain_condition("host", domain, DomainMatch::Suffix);
+    params.push(rusqlite::types::Value::Integer(date::to_mozilla_timestamp(date::now()) as i64));
+    let (cookies, last_access): (i64, Option<i64>) = connection.query_row(
+        &format!("SELECT COUNT(*), MAX(lastAccessed) FROM moz_cookies WHERE {} AND (expiry = 0 OR expiry > ?)", condition),
+        rusqlite::params_from_iter(params),
+        |row| Ok((row.get(0)?, row.get(1)?))
+    )?;
+    Ok(DomainStats {
+        cookies: cookies as usize,
+        // lastAccessed is in microseconds
+        last_access: last_access.filter(|last_access| *last_access > 0).map(|last_access| (last_access / 1_000_000) as u64),
+    })
+}
+
+/// Whether `originAttributes` (`^privateBrowsingId=1&userContextId=2`) mark a
+/// private browsing cookie. Default attributes aren't written, so any non zero id is private.
+pub fn is_private_origin(origin_attributes: &str) -> bool {
+    origin_attributes
+        .trim_start_matches('^')
+        .split('&')
+        .any(|attribute| {
+            attribute
+                .strip_prefix("privateBrowsingId=")
+                .is_some_and(|id| id != "0")
+        })
+}
+
+/// Top level site of the `partitionKey` in `originAttributes`
+/// (`^partitionKey=%28https%2Cexample.com%29`), written like chromium's
+/// `top_frame_site_key` (`"https://example.com"`)
+pub fn origin_partition_key(origin_attributes: &str) -> Option<String> {
+    url::form_urlencoded
+        ::parse(origin_attributes.trim_start_matches('^').as_bytes())
+        .find(|(key, _)| key == "partitionKey")
+        .and_then(|(_, partition_key)| partition_site(&partition_key))
+}
+
+/// "(https,example.com,8443,f)" to "https://example.com:8443", the port and the
+/// cross site ancestor flag being optional
+fn partition_site(partition_key: &str) -> Option<String> {
+    let mut parts = partition_key.strip_prefix('(')?.strip_suffix(')')?.split(',');
+    let scheme = parts.next().filter(|scheme| !scheme.is_empty())?;
+    let site = parts.next().filter(|site| !site.is_empty())?;
+    match parts.next().and_then(|port| port.parse::<u16>().ok()) {
+        Some(port) => Some(format!("{}://{}:{}", scheme, site, port)),
+        None => Some(format!("{}://{}", scheme, site)),
+    }
+}
+
+/// Same as [`is_private_origin`] for the `originAttributes` object of a session store cookie
+fn is_private_session_cookie(json_cookie: &Value) -> bool {
+    json_cookie
+        .pointer("/originAttributes/privateBrowsingId")
+        .and_then(|id| id.as_u64())
+        .is_some_and(|id| id != 0)
+}
+
+fn query_cookies(
+    db_path: &Path,
+    domains: Option<&[String]>,
+    names: &NameFilter,
+    container: Option<Container>,
+    options: ReadOptions
+) -> Result<CookiesRead> {
+    let snapshot = provenance::snapshot_if(options.provenance, db_path)?;
+    let source = snapshot.as_ref().map_or(db_path, |snapshot| snapshot.path());
+    let connection = sqlite::connect(source.to_path_buf())?;
+    let schema_version = user_version(&connection)?;
+    sqlite::check_schema(db_path, schema_version, MAX_TESTED_SCHEMA, options.strict_schema)?;
     let mut query =
         "
-        SELECT host, path, isSecure, expiry, name, value, isHttpOnly, sameSite from moz_cookies 
+        SELECT host, path, isSecure, expiry, name, value, isHttpOnly, sameSite, originAttributes, creationTime, lastAccessed from moz_cookies 
     ".to_string();
 
-    if let Some(domains) = domains.clone() {
+    let mut conditions: Vec<String> = vec![];
+    let mut params: Vec<rusqlite::types::Value> = vec![];
+    if let Some(domains) = domains {
         let domain_queries: Vec<String> = domains
             .iter()
-            .map(|domain| format!("host LIKE '%{}%'", domain))
+            .map(|domain| {
+                let (condition, host_params) = sqlite::domain_condition("host", domain, options.domain_match);
+                params.extend(host_params);
+                condition
+            })
             .collect();
 
         if !domain_queries.is_empty() {
-            let joined_queries = domain_queries.join(" OR ");
-            query += &format!("WHERE ({})", joined_queries);
+            conditions.push(format!("({})", domain_queries.join(" OR ")));
+        }
+    }
+    match container {
+        Some(Container::None) => {
+            conditions.push("originAttributes NOT LIKE '%userContextId=%'".to_string());
         }
+        Some(Container::Id(id)) => {
+            // "^a=1&userContextId=2", anchored on the separators so 2 doesn't match 12
+            conditions.push(
+                format!(
+                    "('&' || ltrim(originAttributes, '^') || '&') LIKE '%&userContextId={}&%'",
+                    id
+                )
+            );
+        }
+        None => {}
+    }
+    if let Some((condition, names)) = sqlite::name_condition("name", names) {
+        conditions.push(condition);
+        params.extend(names);
+    }
+    if let Some((condition, path)) = sqlite::path_condition("path", options.path.as_deref()) {
+        conditions.push(condition);
+        params.extend(path);
+    }
+    conditions.extend(sqlite::flag_conditions("isSecure", "isHttpOnly", options.secure_only, options.http_only));
+    conditions.extend(sqlite::persistence_condition("expiry = 0", options.persistence));
+    if let Some(cutoff) = options.expiry_cutoff() {
+        conditions.push("(expiry != 0 AND expiry < ?)".to_string());
+        params.push(rusqlite::types::Value::Integer(date::to_mozilla_timestamp(cutoff) as i64));
     }
+    if !options.include_expired {
+        conditions.push("(expiry = 0 OR expiry > ?)".to_string());
+        params.push(rusqlite::types::Value::Integer(date::to_mozilla_timestamp(date::now()) as i64));
+    }
+    if options.paginates() {
+        // rows skipped below would still take up room in the page
+        let attributes = "('&' || ltrim(originAttributes, '^') || '&')";
+        if !options.include_private_leftovers {
+            conditions.push(
+                format!("({0} NOT LIKE '%&privateBrowsingId=%' OR {0} LIKE '%&privateBrowsingId=0&%')", attributes)
+            );
+        }
+        if !options.include_partitioned {
+            conditions.push(format!("{} NOT LIKE '%&partitionKey=%'", attributes));
+        }
+    }
+    if !conditions.is_empty() {
+        query += &format!("WHERE {}", conditions.join(" AND "));
+    }
+    if let Some(sort_by) = options.order() {
+        let first = match sort_by {
+            SortBy::Domain => "",
+            SortBy::Expiry => "expiry, ",
+            // whole seconds like Cookie::last_accessed
+            SortBy::LastAccessed => "lastAccessed / 1000000, ",
+        };
+        query += &format!(" ORDER BY {}host, path, name, id", first);
+    }
+    query += &sqlite::limit_clause(options.limit, options.offset);
 
     query += ";";
 
-    let mut cookies: Vec<Cookie> = vec![];
+    let mut read = CookiesRead {
+        schema_version,
+        provenance: snapshot.as_ref().map(|snapshot| snapshot.provenance.clone()),
+        ..Default::default()
+    };
     let mut stmt = connection.prepare(query.as_str())?;
-    let mut rows = stmt.query([])?;
+    let mut rows = stmt.query(rusqlite::params_from_iter(params))?;
 
     while let Some(row) = rows.next()? {
         let host: Result<String, _> = row.get(0);
@@ -42,6 +325,15 @@ pub fn firefox_based(db_path: PathBuf, domains: Option<Vec<&str>>) -> Result<Vec
             continue;
         }
         let host = host?;
+        let origin_attributes: String = row.get(8)?;
+        if !options.include_private_leftovers && is_private_origin(&origin_attributes) {
+            read.private_excluded += 1;
+            continue;
+        }
+        let partition_key = origin_partition_key(&origin_attributes);
+        if !options.include_partitioned && partition_key.is_some() {
+            continue;
+        }
         let path: String = row.get(1)?;
         let is_secure: bool = row.get(2)?;
         let expires: u64 = row.get(3)?;
@@ -55,38 +347,37 @@ pub fn firefox_based(db_path: PathBuf, domains: Option<Vec<&str>>) -> Result<Vec
         let same_site: i64 = row.get(7)?;
         let cookie = Cookie {
             domain: host.to_string(),
+            host_only: !host.starts_with('.'),
             path: path.to_string(),
             secure: is_secure,
             expires,
             name: name.to_string(),
             value,
             http_only,
-            same_site,
+            same_site: SameSite::from_mozilla(same_site),
+            same_site_raw: Some(same_site),
+            creation: row.get::<_, Option<i64>>(9)?.and_then(|time| date::mozilla_prtime(time.max(0) as u64)),
+            last_accessed: row.get::<_, Option<i64>>(10)?.and_then(|time| date::mozilla_prtime(time.max(0) as u64)),
+            partition_key,
+            port: None,
+            source_scheme: SourceScheme::Unset,
+            extension: None,
+            browser: String::new(),
+            profile: None,
         };
-        cookies.push(cookie);
+        read.cookies.push(cookie);
     }
-
-    let parent_path = db_path.parent().unwrap_or(&PathBuf::from("")).to_path_buf();
-    if
-        let Ok(session_cookies) = get_session_cookies_lz4(
-            domains.to_owned(),
-            parent_path.to_owned()
-        )
-    {
-        cookies.extend(session_cookies);
-    }
-
-    if let Ok(session_cookies) = get_session_cookies(domains, parent_path) {
-        cookies.extend(session_cookies);
-    }
-    Ok(cookies)
+    Ok(read)
 }
 
+/// Cookies of `sessionstore.js`, leaving out private browsing ones unless
+/// `options.include_private_leftovers` is set
 pub fn get_session_cookies(
-    domains: Option<Vec<&str>>,
-    cookies_dir: PathBuf
-) -> Result<Vec<Cookie>> {
-    let mut cookies: Vec<Cookie> = vec![];
+    domains: Option<&[String]>,
+    cookies_dir: PathBuf,
+    options: ReadOptions
+) -> Result<CookiesRead> {
+    let mut read = CookiesRead::default();
     let session_file = cookies_dir.join("sessionstore.js");
     let plain = fs::read_to_string(session_file)?;
     let json: Value = serde_json::from_str(&plain)?;
@@ -107,29 +398,39 @@ pub fn get_session_cookies(
                         .unwrap_or("");
                     let should_add =
                         domains.is_none() || // add every domain
-                        (domains.is_some() &&
-                            utils::some_domain_in_host(domains.to_owned(), domain)); // only if some domain in host
+                        utils::some_domain_matches(domains, domain, options.domain_match);
                     if !should_add {
                         continue;
                     }
+                    if !options.include_private_leftovers && is_private_session_cookie(json_cookie) {
+                        read.private_excluded += 1;
+                        continue;
+                    }
                     if let Ok(cookie) = create_cookie(json_cookie) {
-                        cookies.push(cookie);
+                        if options.include_partitioned || cookie.partition_key.is_none() {
+                            read.cookies.push(cookie);
+                        }
                     }
                 }
             }
         }
     }
-    Ok(cookies)
+    Ok(read)
 }
 
+/// Same as [`get_session_cookies`] for `sessionstore-backups/recovery.jsonlz4`
 pub fn get_session_cookies_lz4(
-    domains: Option<Vec<&str>>,
-    cookies_dir: PathBuf
-) -> Result<Vec<Cookie>> {
-    let mut cookies: Vec<Cookie> = vec![];
+    domains: Option<&[String]>,
+    cookies_dir: PathBuf,
+    options: ReadOptions
+) -> Result<CookiesRead> {
+    let mut read = CookiesRead::default();
     let session_file_lz4 = cookies_dir.join("sessionstore-backups/recovery.jsonlz4");
-    let compressed = fs::read(session_file_lz4)?;
-    let compressed = compressed[8..].to_vec();
+    let compressed = fs::read(&session_file_lz4)?;
+    let compressed = compressed
+        .get(8..)
+        .ok_or_else(|| anyhow!("{} is missing the mozLz40 header", session_file_lz4.display()))?
+        .to_vec();
     let decompressed = decompress_size_prepended(&compressed)?;
     let plain = String::from_utf8(decompressed)?;
     let json: Value = serde_json::from_str(&plain)?;
@@ -142,15 +443,21 @@ pub fn get_session_cookies_lz4(
             .unwrap_or("");
         let should_add =
             domains.is_none() || // add every domain
-            utils::some_domain_in_host(domains.to_owned(), domain); // only if some domain in host
+            utils::some_domain_matches(domains, domain, options.domain_match);
         if !should_add {
             continue;
         }
+        if !options.include_private_leftovers && is_private_session_cookie(json_cookie) {
+            read.private_excluded += 1;
+            continue;
+        }
         if let Ok(cookie) = create_cookie(json_cookie) {
-            cookies.push(cookie);
+            if options.include_partitioned || cookie.partition_key.is_none() {
+                read.cookies.push(cookie);
+            }
         }
     }
-    Ok(cookies)
+    Ok(read)
 }
 
 pub fn create_cookie(json_cookie: &Value) -> Result<Cookie> {
@@ -184,46 +491,65 @@ pub fn create_cookie(json_cookie: &Value) -> Result<Cookie> {
         .unwrap_or(0);
     let expires = date::mozilla_timestamp(expires);
 
-    let same_site = json_cookie
-        .get("sameSite")
-        .and_then(|v| v.as_i64())
-        .unwrap_or(0);
+    let same_site = json_cookie.get("sameSite").and_then(|v| v.as_i64());
+    let partition_key = json_cookie
+        .pointer("/originAttributes/partitionKey")
+        .and_then(|v| v.as_str())
+        .and_then(partition_site);
 
     let cookie = Cookie {
         domain: host.to_string(),
+        host_only: !host.starts_with('.'),
         expires,
         http_only,
         name: name.to_string(),
         value: value.to_string(),
         path: path.to_string(),
-        same_site,
+        same_site: same_site.map_or(SameSite::Unspecified, SameSite::from_mozilla),
+        same_site_raw: same_site,
+        // the session store doesn't keep them
+        creation: None,
+        last_accessed: None,
+        partition_key,
         secure,
+        port: None,
+        source_scheme: SourceScheme::Unset,
+        extension: None,
+        browser: String::new(),
+        profile: None,
     };
     Ok(cookie)
 }
 
 pub fn get_default_profile(profiles_path: &Path) -> Result<String> {
-    let conf = Ini::load_from_file(profiles_path)?;
-    let installs: Vec<_> = conf
-        .iter()
+    default_entry(&Ini::load_from_file_noescape(profiles_path)?)
+}
+
+fn profile_sections(conf: &Ini) -> impl Iterator<Item = &ini::Properties> {
+    conf.iter()
+        .filter(|(name_option, _)| name_option.unwrap_or_default().starts_with("Profile"))
+        .map(|(_, props)| props)
+}
+
+fn install_sections(conf: &Ini) -> impl Iterator<Item = &ini::Properties> {
+    conf.iter()
         .filter(|(name_option, _)| name_option.unwrap_or_default().starts_with("Install"))
-        .collect();
-    if !installs.is_empty() {
-        let (_, props) = installs.first().unwrap();
+        .map(|(_, props)| props)
+}
+
+fn default_entry(conf: &Ini) -> Result<String> {
+    let installs: Vec<_> = install_sections(conf).collect();
+    if let Some(props) = installs.first() {
         return Ok(props.get("Default").unwrap_or_default().into());
     } else {
-        let profiles: Vec<_> = conf
-            .iter()
-            .filter(|(name_option, _)| name_option.unwrap_or_default().starts_with("Profile"))
-            .collect();
-        for (_, props) in &profiles {
+        for props in profile_sections(conf) {
             if props.get("Default").unwrap_or_default() == "1" {
                 return Ok(props.get("Path").unwrap_or_default().into());
             }
         }
 
         // still not found? last time try to get any Profile with Path.
-        for (_, props) in &profiles {
+        for props in profile_sections(conf) {
             if let Some(path) = props.get("Path") {
                 return Ok(path.into());
             }
@@ -231,3 +557,177 @@ pub fn get_default_profile(profiles_path: &Path) -> Result<String> {
     }
     bail!("Cant find any profile")
 }
+
+/// Returned when a profiles.ini entry points at a network share that can't be reached,
+/// usually a roaming profile while the machine is off the network
+#[derive(Debug)]
+pub struct ShareUnreachable(pub PathBuf);
+
+impl std::fmt::Display for ShareUnreachable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "profile share {} is unreachable", self.0.display())
+    }
+}
+
+impl std::error::Error for ShareUnreachable {}
+
+/// Checked on the text so Windows drive and UNC entries are recognized on every platform
+fn is_absolute_entry(path: &str) -> bool {
+    path.starts_with('/') || path.starts_with("\\\\") || path.as_bytes().get(1) == Some(&b':')
+}
+
+/// `\\server\share` part of a UNC path
+fn unc_share(path: &str) -> Option<&str> {
+    let rest = path.strip_prefix("\\\\")?;
+    let mut parts = rest.splitn(3, '\\');
+    let server = parts.next().filter(|server| !server.is_empty())?;
+    let share = parts.next().filter(|share| !share.is_empty())?;
+    Some(&path[..2 + server.len() + 1 + share.len()])
+}
+
+/// `Default` of every install, from the `[Install*]` sections of profiles.ini and
+/// from installs.ini (which firefox keeps on its own), in that order
+fn install_defaults(profiles_dir: &Path, conf: &Ini) -> Vec<String> {
+    let installs_ini = Ini::load_from_file_noescape(profiles_dir.join("installs.ini")).ok();
+    let mut entries: Vec<String> = vec![];
+    let sections = install_sections(conf).chain(
+        installs_ini.iter().flat_map(|installs| installs.iter().map(|(_, props)| props))
+    );
+    for entry in sections.filter_map(|props| props.get("Default")) {
+        if !entry.is_empty() && !entries.iter().any(|known| known == entry) {
+            entries.push(entry.into());
+        }
+    }
+    entries
+}
+
+/// Resolves the default profile directory from the profiles.ini in `profiles_dir`:
+/// the default of the install (profiles.ini or installs.ini), else the profile marked
+/// `Default=1`, else any listed profile. When that leaves several candidates (e.g.
+/// a release and an ESR install) the most recently used one wins.
+/// `IsRelative=0` entries (enterprise roaming profiles) are used as is, either an
+/// absolute local path or a UNC path, which fails with [`ShareUnreachable`] when the
+/// share can't be reached.
+pub fn default_profile_dir(profiles_dir: &Path) -> Result<PathBuf> {
+    let conf = Ini::load_from_file_noescape(profiles_dir.join("profiles.ini"))?;
+    let mut entries = install_defaults(profiles_dir, &conf);
+    if entries.is_empty() {
+        entries = profile_sections(&conf)
+            .filter(|props| props.get("Default") == Some("1"))
+            .filter_map(|props| props.get("Path"))
+            .take(1)
+            .map(String::from)
+            .collect();
+    }
+    if entries.is_empty() {
+        entries = profile_sections(&conf)
+            .filter_map(|props| props.get("Path"))
+            .map(String::from)
+            .collect();
+    }
+    if let [entry] = entries.as_slice() {
+        return resolve_entry(profiles_dir, &conf, entry);
+    }
+
+    let mut dirs = vec![];
+    let mut first_err = None;
+    for entry in &entries {
+        match resolve_entry(profiles_dir, &conf, entry) {
+            Ok(dir) => dirs.push(dir),
+            Err(err) => {
+                first_err.get_or_insert(err);
+            }
+        }
+    }
+    // reversed so max_by_key keeps the earliest of equally recent profiles
+    let most_recent = dirs
+        .into_iter()
+        .rev()
+        .max_by_key(|dir| paths::last_modified(&dir.join("cookies.sqlite")));
+    if let Some(dir) = most_recent {
+        return Ok(dir);
+    }
+    Err(first_err.unwrap_or_else(|| anyhow!("Cant find any profile")))
+}
+
+fn resolve_entry(profiles_dir: &Path, conf: &Ini, entry: &str) -> Result<PathBuf> {
+    let is_relative = profile_sections(conf)
+        .find(|props| props.get("Path") == Some(entry))
+        .and_then(|props| props.get("IsRelative"))
+        .map_or(!is_absolute_entry(entry), |is_relative| is_relative != "0");
+    if is_relative {
+        return Ok(profiles_dir.join(entry));
+    }
+    if let Some(share) = unc_share(entry) {
+        if !Path::new(share).exists() {
+            bail!(ShareUnreachable(PathBuf::from(share)));
+        }
+    }
+    Ok(PathBuf::from(entry))
+}
+
+/// Every profile directory listed in the profiles.ini in `profiles_dir`, in file order.
+/// Entries on an unreachable share are left out.
+pub fn profile_dirs(profiles_dir: &Path) -> Result<Vec<PathBuf>> {
+    Ok(
+        named_profile_dirs(profiles_dir)?
+            .into_iter()
+            .map(|(_, dir)| dir)
+            .collect()
+    )
+}
+
+/// Same as [`profile_dirs`], paired with the `Name` profiles.ini gives each profile
+pub fn named_profile_dirs(profiles_dir: &Path) -> Result<Vec<(Option<String>, PathBuf)>> {
+    let conf = Ini::load_from_file_noescape(profiles_dir.join("profiles.ini"))?;
+    let mut dirs: Vec<(Option<String>, PathBuf)> = vec![];
+    for props in profile_sections(&conf) {
+        let Some(entry) = props.get("Path") else {
+            continue;
+        };
+        match resolve_entry(profiles_dir, &conf, entry) {
+            Ok(dir) if !dirs.iter().any(|(_, known)| *known == dir) => {
+                dirs.push((props.get("Name").map(String::from), dir));
+            }
+            Ok(_) => {}
+            Err(err) => warn!("Skipping profile {}: {}", entry, err),
+        }
+    }
+    Ok(dirs)
+}
+
+/// Channel a profile was created for, from the suffix firefox gives the name of
+/// a channel's dedicated profile ("abcd1234.dev-edition-default")
+pub fn profile_channel(profile_dir: &str) -> Option<FirefoxChannel> {
+    let suffix = profile_dir.rsplit(['/', '\\']).next()?.split_once('.')?.1;
+    match suffix {
+        "default-release" => Some(FirefoxChannel::Release),
+        "default-beta" => Some(FirefoxChannel::Beta),
+        "dev-edition-default" => Some(FirefoxChannel::DeveloperEdition),
+        "default-nightly" => Some(FirefoxChannel::Nightly),
+        _ if suffix.starts_with("default-esr") => Some(FirefoxChannel::Esr),
+        _ => None,
+    }
+}
+
+/// Profile directories of every installed channel from the profiles.ini in
+/// `profiles_dir`: the default of each `[Install*]` section, then the channel
+/// profiles (see [`profile_channel`]) no installation points at anymore.
+/// Entries on an unreachable share are left out.
+pub fn channel_profile_dirs(profiles_dir: &Path) -> Result<Vec<PathBuf>> {
+    let conf = Ini::load_from_file_noescape(profiles_dir.join("profiles.ini"))?;
+    let installs = install_sections(&conf).filter_map(|props| props.get("Default"));
+    let channels = profile_sections(&conf)
+        .filter_map(|props| props.get("Path"))
+        .filter(|entry| profile_channel(entry).is_some());
+
+    let mut dirs: Vec<PathBuf> = vec![];
+    for entry in installs.chain(channels) {
+        match resolve_entry(profiles_dir, &conf, entry) {
+            Ok(dir) if !dirs.contains(&dir) => dirs.push(dir),
+            Ok(_) => {}
+            Err(err) => warn!("Skipping profile {}: {}", entry, err),
+        }
+    }
+    Ok(dirs)
+}
@@ -0,0 +1,132 @@
+//! Yandex Browser. Its cookies DB is Chromium's, but the profile holds a second
+//! key: `local_encryptor_data` in the `meta` table of `Ya Passman Data` is a data
+//! key wrapped with the usual os_crypt key. Values encrypted with it can't be
+//! read by [`crate::browser::chromium`] alone.
+
+use crate::common::{ enums::*, paths, sqlite };
+use crate::browser::chromium::{ self, WipedKeys };
+use crate::defaults;
+use anyhow::{ bail, Result };
+use std::path::{ Path, PathBuf };
+
+cfg_if::cfg_if! {
+    if #[cfg(unix)] {
+        use crate::common::secrets::{ self, SecretsProvider };
+    }
+}
+
+/// Signature in front of the unwrapped data key
+const DATA_KEY_SIGNATURE: &[u8] = &[0x08, 0x01, 0x12, 0x20];
+
+/// `v10`, nonce and ciphertext of the wrapped data key, the rest of the blob is protobuf framing
+const WRAPPED_KEY_LEN: usize = 3 + 96;
+
+/// Returned when the Yandex profile is locked with a master password. The data key
+/// is then sealed with a key derived from that password, which isn't supported.
+#[derive(Debug)]
+pub struct MasterPasswordRequired(pub PathBuf);
+
+impl std::fmt::Display for MasterPasswordRequired {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Yandex profile {} is locked with a master password, its cookies can't be decrypted",
+            self.0.display()
+        )
+    }
+}
+
+impl std::error::Error for MasterPasswordRequired {}
+
+/// Opens `Ya Passman Data` of the profile holding `db_path`, `None` when there's none
+fn passman_data(db_path: &Path) -> Result<Option<(PathBuf, rusqlite::Connection)>> {
+    let Some(profile_dir) = paths::chrome_profile_path(db_path) else {
+        return Ok(None);
+    };
+    let path = profile_dir.join("Ya Passman Data");
+    if !path.exists() {
+        return Ok(None);
+    }
+    Ok(Some((profile_dir.to_path_buf(), sqlite::connect(path)?)))
+}
+
+fn meta_blob(connection: &rusqlite::Connection, key: &str) -> Option<Vec<u8>> {
+    connection
+        .query_row("SELECT value FROM meta WHERE key = ?1", [key], |row| row.get::<_, Vec<u8>>(0))
+        .ok()
+        .filter(|value| !value.is_empty())
+}
+
+/// Whether the profile holding `db_path` is locked with a master password (`sealed_key` is set)
+pub fn master_password_set(db_path: &Path) -> bool {
+    matches!(passman_data(db_path), Ok(Some((_, connection))) if meta_blob(&connection, "sealed_key").is_some())
+}
+
+/// Unwraps the data key of the profile holding `db_path` with the os_crypt `keys`,
+/// `None` when the profile has no `Ya Passman Data` or no local encryptor
+pub fn data_key(db_path: &Path, keys: &[Vec<u8>]) -> Result<Option<Vec<u8>>> {
+    let Some((profile_dir, connection)) = passman_data(db_path)? else {
+        return Ok(None);
+    };
+    if meta_blob(&connection, "sealed_key").is_some() {
+        bail!(MasterPasswordRequired(profile_dir));
+    }
+    let Some(blob) = meta_blob(&connection, "local_encryptor_data") else {
+        return Ok(None);
+    };
+    let Some(start) = blob.windows(3).position(|window| window == b"v10") else {
+        bail!("local_encryptor_data of {} has no v10 key", profile_dir.display());
+    };
+    let end = blob.len().min(start + WRAPPED_KEY_LEN);
+    let plaintext = WipedKeys(vec![chromium::decrypt_blob(&blob[start..end], keys)?]);
+    let Some(data_key) = plaintext.0[0].strip_prefix(DATA_KEY_SIGNATURE).and_then(|key| key.get(..32)) else {
+        bail!("local_encryptor_data of {} doesn't hold a data key", profile_dir.display());
+    };
+    Ok(Some(data_key.to_vec()))
+}
+
+fn read(mut keys: Vec<Vec<u8>>, db_path: PathBuf, domains: Option<&[String]>) -> Result<Vec<Cookie>> {
+    if let Some(data_key) = data_key(&db_path, &keys)? {
+        // after the os_crypt keys, values written before the data key existed use those
+        keys.push(data_key);
+    }
+    Ok(chromium::query_cookies(keys, db_path, domains, &NameFilter::default(), defaults::global_defaults().read)?.cookies)
+}
+
+#[cfg(target_os = "windows")]
+pub fn yandex_based(
+    key: PathBuf,
+    db_path: PathBuf,
+    domains: impl Into<Domains>
+) -> Result<Vec<Cookie>> {
+    let keys = chromium::local_state_keys(&key)?;
+    let domains: Domains = domains.into();
+    read(keys, db_path, domains.as_deref())
+}
+
+#[cfg(unix)]
+pub fn yandex_based(
+    config: &BrowserConfig,
+    db_path: PathBuf,
+    domains: impl Into<Domains>
+) -> Result<Vec<Cookie>> {
+    yandex_based_with(config, db_path, domains, &secrets::OsSecrets)
+}
+
+/// Same as [`yandex_based`] with the keyring/keychain passwords taken from `secrets`
+#[cfg(unix)]
+pub fn yandex_based_with(
+    config: &BrowserConfig,
+    db_path: PathBuf,
+    domains: impl Into<Domains>,
+    secrets: &dyn SecretsProvider
+) -> Result<Vec<Cookie>> {
+    // no prompt for a profile that can't be read anyway
+    if master_password_set(&db_path) {
+        let profile_dir = paths::chrome_profile_path(&db_path).unwrap_or(&db_path);
+        bail!(MasterPasswordRequired(profile_dir.to_path_buf()));
+    }
+    let keys = chromium::get_keys(config, secrets)?;
+    let domains: Domains = domains.into();
+    read(keys, db_path, domains.as_deref())
+}
@@ -1,9 +1,12 @@
+use std::time::{ Duration, SystemTime, UNIX_EPOCH };
+
 pub fn chromium_timestamp(timestamp: u64) -> Option<u64> {
-    if timestamp <= 0 {
+    if timestamp == 0 {
         return None;
     }
-    let mut timestamp = timestamp - 11_644_473_600_000_000;
-    timestamp /= 1000000; // milliseconds to seconds
+    // corrupted or pre-1970 values would underflow, there's no unix time for them
+    let mut timestamp = timestamp.checked_sub(11_644_473_600_000_000)?;
+    timestamp /= 1000000; // microseconds to seconds
     unix_timestamp(timestamp)
 }
 
@@ -11,29 +14,113 @@ pub fn mozilla_timestamp(timestamp: u64) -> Option<u64> {
     unix_timestamp(timestamp)
 }
 
+/// Firefox PRTime (microseconds since 1970), as in `creationTime` and `lastAccessed`
+pub fn mozilla_prtime(timestamp: u64) -> Option<u64> {
+    unix_timestamp(timestamp / 1_000_000)
+}
+
+/// Inverse of [`chromium_timestamp`], unix seconds to microseconds since 1601
+pub fn to_chromium_timestamp(timestamp: u64) -> u64 {
+    (timestamp + 11_644_473_600) * 1_000_000
+}
+
+/// Inverse of [`mozilla_timestamp`]
+pub fn to_mozilla_timestamp(timestamp: u64) -> u64 {
+    timestamp
+}
+
+/// Current unix seconds
+pub fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+/// Unix time before which cookies have to expire for `expires_before` and
+/// `expires_within`, the earlier of both. Capped at year 9999 so it converts
+/// to any browser timestamp.
+pub(crate) fn expiry_cutoff(before: Option<SystemTime>, within: Option<Duration>) -> Option<u64> {
+    const MAX: u64 = 253_402_300_800;
+    let before = before.map(|time| time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()));
+    let within = within.map(|duration| now().saturating_add(duration.as_secs()));
+    before.into_iter().chain(within).min().map(|cutoff| cutoff.min(MAX))
+}
+
 #[cfg(target_os = "windows")]
 pub fn internet_explorer_timestamp(timestamp: u64) -> Option<u64> {
-    if timestamp <= 0 {
+    if timestamp == 0 {
         return None;
     }
-    let mut timestamp = timestamp - 116_444_736_000_000_000;
+    let mut timestamp = timestamp.checked_sub(116_444_736_000_000_000)?;
     timestamp /= 10_000_000;
     unix_timestamp(timestamp)
 }
 
-pub fn safari_timestamp(timestamp: u64) -> Option<u64> {
-    if timestamp <= 0 {
+/// Seconds since 2001-01-01 (Mac absolute time) as stored in binarycookies
+pub fn safari_timestamp(timestamp: f64) -> Option<u64> {
+    // corrupted files can hold anything, NaN and huge values included
+    if timestamp.is_nan() || timestamp <= 0.0 {
         return None;
     }
-    let unix_timestamp = timestamp + 978_307_200;
-    // nanoseconds to seconds
-    let unix_timestamp = unix_timestamp / 1_000_000_000;
-    Some(unix_timestamp)
+    Some((timestamp as u64).saturating_add(978_307_200))
+}
+
+/// Year, month and day of days since 1970-01-01 (Howard Hinnant's algorithm)
+fn civil_date(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Formats unix seconds as ISO 8601 in UTC the way `Date.toISOString` does,
+/// e.g. `2100-01-01T00:00:00.000Z`
+pub fn iso_8601(timestamp: u64) -> String {
+    let seconds = timestamp % 86_400;
+    let (year, month, day) = civil_date(timestamp / 86_400);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.000Z",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        (seconds % 3600) / 60,
+        seconds % 60
+    )
+}
+
+/// Formats unix seconds as an HTTP date, e.g. `Fri, 01 Jan 2100 00:00:00 GMT`
+pub fn http_date(timestamp: u64) -> String {
+    const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    let days = timestamp / 86_400;
+    let seconds = timestamp % 86_400;
+    // 1970-01-01 was a Thursday
+    let weekday = WEEKDAYS[((days + 4) % 7) as usize];
+    let (year, month, day) = civil_date(days);
+
+    format!(
+        "{}, {:02} {} {} {:02}:{:02}:{:02} GMT",
+        weekday,
+        day,
+        MONTHS[(month - 1) as usize],
+        year,
+        seconds / 3600,
+        (seconds % 3600) / 60,
+        seconds % 60
+    )
 }
 
 fn unix_timestamp(timestamp: u64) -> Option<u64> {
-    if timestamp <= 0 {
+    if timestamp == 0 {
         return None;
     }
-    return Some(timestamp);
+    Some(timestamp)
 }
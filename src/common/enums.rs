@@ -1,30 +1,1062 @@
+use crate::common::date;
+use crate::common::provenance::Provenance;
 use serde::{ Deserialize, Serialize };
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{ Duration, SystemTime, UNIX_EPOCH };
 
-#[derive(Serialize, Deserialize, Debug)]
+/// A cookie read from a browser. With the `serde` feature it serializes with camelCase
+/// field names (`httpOnly`, `sameSite`), the shape [`crate::export::to_json`] writes,
+/// and unset optional fields are left out. The snake_case names of older exports
+/// still deserialize.
+///
+/// `{:?}` hides the value so cookies can be logged without leaking sessions, see
+/// [`Cookie::debug_full`]
+///
+/// New fields can be added in a minor release, build cookies with [`Cookie::new`]
+/// or `Default::default()` and set the fields needed.
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+#[non_exhaustive]
 pub struct Cookie {
     pub domain: String,
+    /// Only sent to `domain` itself and not its subdomains, the cookie was set
+    /// without a `Domain` attribute. Stores mark domain cookies with a leading dot.
+    #[cfg_attr(feature = "serde", serde(default, alias = "host_only"))]
+    pub host_only: bool,
     pub path: String,
     pub secure: bool,
+    /// Unix seconds whichever browser the cookie comes from, `None` for session cookies
     pub expires: Option<u64>,
+    /// Unix seconds the cookie was created at, `None` when the store doesn't keep it
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub creation: Option<u64>,
+    /// Unix seconds the cookie was last sent at, `None` when the store doesn't keep it
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "last_accessed"))]
+    pub last_accessed: Option<u64>,
+    /// Top level site a partitioned (CHIPS) cookie is keyed on, e.g. `"https://example.com"`,
+    /// `None` for unpartitioned cookies
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "partition_key"))]
+    pub partition_key: Option<String>,
     pub name: String,
     pub value: String,
+    #[cfg_attr(feature = "serde", serde(alias = "http_only"))]
     pub http_only: bool,
-    pub same_site: i64,
+    #[cfg_attr(feature = "serde", serde(alias = "same_site"))]
+    pub same_site: SameSite,
+    /// `same_site` as the store recorded it, in that browser's own encoding. `None`
+    /// for stores that don't keep it (Safari, Internet Explorer)
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing))]
+    pub same_site_raw: Option<i64>,
+    /// Port the cookie was set from, only known for chromium (`source_port`)
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub port: Option<u16>,
+    /// Scheme the cookie was set over, only known for chromium (`source_scheme`)
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "SourceScheme::is_unset", alias = "source_scheme"))]
+    pub source_scheme: SourceScheme,
+    /// Extension that owns the cookie, only set for chromium extension origins read
+    /// with [`ReadOptions::include_extensions`]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub extension: Option<ExtensionInfo>,
+    /// Browser the cookie was read from, named as in [`crate::load`] ("chrome",
+    /// "librewolf", ...). Empty when the store was given by path, e.g. [`crate::any_browser`]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "String::is_empty"))]
+    pub browser: String,
+    /// Profile directory the cookie was read from ("Default", "abcd1234.default-release"),
+    /// `None` for stores without profiles
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub profile: Option<String>,
+}
+
+/// `SameSite` attribute of a cookie, decoded from the value each browser stores.
+/// Serialized as Chromium's integer (-1 unspecified, 0 none, 1 lax, 2 strict), which
+/// is what `same_site` held before it was typed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(from = "i64", into = "i64"))]
+pub enum SameSite {
+    /// `SameSite=None`, sent with cross-site requests too
+    None,
+    /// `SameSite=Lax`
+    Lax,
+    /// `SameSite=Strict`
+    Strict,
+    /// No attribute, browsers treat it as Lax with a few exceptions
+    #[default]
+    Unspecified,
+}
+
+impl SameSite {
+    /// Chromium's `samesite` column: -1 unspecified, 0 no restriction, 1 lax, 2 strict
+    pub fn from_chromium(raw: i64) -> Self {
+        match raw {
+            0 => SameSite::None,
+            1 => SameSite::Lax,
+            2 => SameSite::Strict,
+            _ => SameSite::Unspecified,
+        }
+    }
+
+    /// Firefox's `sameSite` column and session store, also used by libsoup (Epiphany):
+    /// 0 none, 1 lax, 2 strict. Firefox stores cookies without the attribute as none
+    /// unless `network.cookie.sameSite.laxByDefault` is set, they can't be told apart
+    pub fn from_mozilla(raw: i64) -> Self {
+        match raw {
+            0 => SameSite::None,
+            1 => SameSite::Lax,
+            2 => SameSite::Strict,
+            _ => SameSite::Unspecified,
+        }
+    }
+}
+
+impl From<i64> for SameSite {
+    fn from(raw: i64) -> Self {
+        SameSite::from_chromium(raw)
+    }
+}
+
+impl From<SameSite> for i64 {
+    fn from(same_site: SameSite) -> Self {
+        match same_site {
+            SameSite::None => 0,
+            SameSite::Lax => 1,
+            SameSite::Strict => 2,
+            SameSite::Unspecified => -1,
+        }
+    }
+}
+
+/// Scheme of the page that set a cookie, Chromium's `source_scheme` column.
+/// Serialized as that integer (0 unset, 1 non secure, 2 secure)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(from = "i64", into = "i64"))]
+pub enum SourceScheme {
+    /// Not recorded, by older Chromium releases and every other browser
+    #[default]
+    Unset,
+    /// Set over http
+    NonSecure,
+    /// Set over https
+    Secure,
+}
+
+impl SourceScheme {
+    pub fn is_unset(&self) -> bool {
+        *self == SourceScheme::Unset
+    }
+}
+
+impl From<i64> for SourceScheme {
+    fn from(raw: i64) -> Self {
+        match raw {
+            1 => SourceScheme::NonSecure,
+            2 => SourceScheme::Secure,
+            _ => SourceScheme::Unset,
+        }
+    }
+}
+
+impl From<SourceScheme> for i64 {
+    fn from(source_scheme: SourceScheme) -> Self {
+        match source_scheme {
+            SourceScheme::Unset => 0,
+            SourceScheme::NonSecure => 1,
+            SourceScheme::Secure => 2,
+        }
+    }
+}
+
+/// Chromium extension behind a `chrome-extension://<id>` host key or partition
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ExtensionInfo {
+    /// The 32 letter extension id
+    pub id: String,
+    /// `manifest.name` from the profile's `Preferences` or `Secure Preferences`,
+    /// `None` when the extension isn't installed in that profile anymore
+    pub name: Option<String>,
+}
+
+/// The classic cookie fields, kept stable while [`Cookie`] grows new ones.
+/// Code that only needs these should take a `MinimalCookie`, no field will be
+/// added or removed without a major version.
+///
+/// # Examples
+///
+/// ```
+/// use rookie::common::enums::MinimalCookie;
+///
+/// fn main() {
+///     let cookie = MinimalCookie {
+///         domain: ".example.com".to_string(),
+///         host_only: false,
+///         name: "session".to_string(),
+///         value: "s3ss10n".to_string(),
+///         path: "/".to_string(),
+///         expires: None,
+///         secure: true,
+///         http_only: true,
+///     };
+///     assert_eq!(cookie.header_pair(), "session=s3ss10n");
+/// }
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MinimalCookie {
+    pub domain: String,
+    /// See [`Cookie::host_only`]
+    #[serde(default)]
+    pub host_only: bool,
+    pub name: String,
+    pub value: String,
+    pub path: String,
+    pub expires: Option<u64>,
+    pub secure: bool,
+    pub http_only: bool,
+}
+
+impl MinimalCookie {
+    /// `name=value` as sent in a `Cookie` header
+    pub fn header_pair(&self) -> String {
+        // browsers send nameless cookies as the bare value
+        if self.name.is_empty() {
+            self.value.clone()
+        } else {
+            format!("{}={}", self.name, self.value)
+        }
+    }
+}
+
+impl From<&Cookie> for MinimalCookie {
+    fn from(cookie: &Cookie) -> Self {
+        MinimalCookie {
+            domain: cookie.domain.clone(),
+            host_only: cookie.host_only,
+            name: cookie.name.clone(),
+            value: cookie.value.clone(),
+            path: cookie.path.clone(),
+            expires: cookie.expires,
+            secure: cookie.secure,
+            http_only: cookie.http_only,
+        }
+    }
+}
+
+impl Cookie {
+    /// A session cookie for `/`, a domain cookie when `domain` starts with a dot
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rookie::common::enums::Cookie;
+    ///
+    /// fn main() {
+    ///     let mut cookie = Cookie::new(".example.com", "session", "s3ss10n");
+    ///     cookie.secure = true;
+    ///     assert!(!cookie.host_only);
+    /// }
+    /// ```
+    pub fn new(domain: &str, name: &str, value: &str) -> Self {
+        Cookie {
+            domain: domain.to_string(),
+            host_only: !domain.starts_with('.'),
+            path: "/".to_string(),
+            name: name.to_string(),
+            value: value.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn to_minimal(&self) -> MinimalCookie {
+        MinimalCookie::from(self)
+    }
+
+    /// The domain with punycode labels (`xn--bcher-kva.de`) decoded for display
+    /// (`bücher.de`), keeping the leading dot of domain cookies. Browsers store and
+    /// match the punycode form, which `domain` holds.
+    pub fn domain_unicode(&self) -> String {
+        let domain = self.domain.trim_start_matches('.');
+        let dot = &self.domain[..self.domain.len() - domain.len()];
+        // empty for IP addresses and hosts that aren't valid domains, kept as stored
+        let unicode = url::quirks::domain_to_unicode(domain);
+        if unicode.is_empty() {
+            return self.domain.clone();
+        }
+        format!("{}{}", dot, unicode)
+    }
+
+    /// Whether the cookie expires before `cutoff`, a unix time from
+    /// [`ReadOptions::expiry_cutoff`]. Session cookies never do.
+    pub(crate) fn expires_by(&self, cutoff: Option<u64>) -> bool {
+        cutoff.map_or(true, |cutoff| self.expires.is_some_and(|expires| expires < cutoff))
+    }
+
+    /// Whether the cookie passes the `secure_only` and `http_only` of [`ReadOptions`]
+    pub(crate) fn has_flags(&self, secure_only: bool, http_only: Option<bool>) -> bool {
+        (self.secure || !secure_only) && http_only.map_or(true, |http_only| self.http_only == http_only)
+    }
+
+    /// Whether the cookie expired already, session cookies never do
+    pub fn is_expired(&self) -> bool {
+        self.expires.is_some_and(|expires| expires <= date::now())
+    }
+
+    /// [`Cookie::expires`] as a [`SystemTime`], `None` for session cookies
+    pub fn expires_datetime(&self) -> Option<SystemTime> {
+        self.expires.map(|expires| UNIX_EPOCH + Duration::from_secs(expires))
+    }
+
+    /// The cookie value, same as the `value` field
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Same as `{:?}` with the value shown, for when it's really needed
+    pub fn debug_full(&self) -> String {
+        format!("{:?}", DebugCookie { cookie: self, redact: false })
+    }
+
+    fn identity(&self) -> (&str, &str, &str, Option<&str>) {
+        (&self.domain, &self.path, &self.name, self.partition_key.as_deref())
+    }
+}
+
+struct Redacted(usize);
+
+impl std::fmt::Debug for Redacted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<redacted, {} bytes>", self.0)
+    }
+}
+
+struct DebugCookie<'a> {
+    cookie: &'a Cookie,
+    redact: bool,
+}
+
+impl std::fmt::Debug for DebugCookie<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cookie = self.cookie;
+        let redacted = Redacted(cookie.value.len());
+        let value: &dyn std::fmt::Debug = if self.redact { &redacted } else { &cookie.value };
+        f.debug_struct("Cookie")
+            .field("domain", &cookie.domain)
+            .field("host_only", &cookie.host_only)
+            .field("path", &cookie.path)
+            .field("secure", &cookie.secure)
+            .field("expires", &cookie.expires)
+            .field("creation", &cookie.creation)
+            .field("last_accessed", &cookie.last_accessed)
+            .field("partition_key", &cookie.partition_key)
+            .field("name", &cookie.name)
+            .field("value", value)
+            .field("http_only", &cookie.http_only)
+            .field("same_site", &cookie.same_site)
+            .field("same_site_raw", &cookie.same_site_raw)
+            .field("port", &cookie.port)
+            .field("source_scheme", &cookie.source_scheme)
+            .field("extension", &cookie.extension)
+            .field("browser", &cookie.browser)
+            .field("profile", &cookie.profile)
+            .finish()
+    }
+}
+
+impl std::fmt::Debug for Cookie {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        DebugCookie { cookie: self, redact: true }.fmt(f)
+    }
+}
+
+/// Cookies are equal when they have the same identity, the domain, name, path and
+/// partition key a browser keys them on. The value, flags and timestamps don't take
+/// part, so the same cookie read from two browsers or at two times compares equal.
+impl PartialEq for Cookie {
+    fn eq(&self, other: &Self) -> bool {
+        self.identity() == other.identity()
+    }
+}
+
+impl Eq for Cookie {}
+
+impl std::hash::Hash for Cookie {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.identity().hash(state);
+    }
+}
+
+/// Sorts by domain, then path, then name, then partition key. Ties are equal
+/// cookies, so `sort()` followed by `dedup()` keeps one per identity.
+impl Ord for Cookie {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.identity().cmp(&other.identity())
+    }
+}
+
+impl PartialOrd for Cookie {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 pub trait CookieToString {
     fn to_string(&self) -> String;
 }
 
-impl CookieToString for Vec<Cookie> {
+impl CookieToString for Vec<MinimalCookie> {
     fn to_string(&self) -> String {
         self.iter()
-            .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+            .map(MinimalCookie::header_pair)
             .collect::<Vec<String>>()
             .join(";")
     }
 }
 
+impl CookieToString for Vec<Cookie> {
+    fn to_string(&self) -> String {
+        self.iter().map(MinimalCookie::from).collect::<Vec<_>>().to_string()
+    }
+}
+
+/// Which special Chromium profiles the multi-profile readers should return.
+/// The default skips all of them.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EnumerationOptions {
+    /// Include the `Guest Profile` directory
+    pub include_guest: bool,
+    /// Include the `System Profile` directory used by the profile picker
+    pub include_system: bool,
+    /// Include profiles marked `is_ephemeral` in `Local State`
+    pub include_ephemeral: bool,
+    /// Include component stores kept inside a profile (Vivaldi mail/calendar, Opera VPN)
+    pub include_components: bool,
+    /// Include Brave's `Tor Profile` used by private windows with Tor
+    pub include_tor: bool,
+    /// Attach [`ProfileIdentity`] to each profile. Off by default since the ids are sensitive
+    pub include_identity: bool,
+    /// Parse a private copy of each cookies DB and record its hash, size and mtime,
+    /// see [`ReadOptions::provenance`]
+    pub provenance: bool,
+}
+
+/// Where a [`BrowserVersion`] was read from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionSource {
+    /// `Last Version` file next to `Local State`
+    LastVersionFile,
+    /// `browser.last_known_version` in `Local State`
+    LocalState,
+    /// Version resource of the installed executable (Windows only)
+    Executable,
+}
+
+/// Version of a chromium based browser, e.g. 120.0.6099.109
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BrowserVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub build: u32,
+    pub patch: u32,
+    pub source: VersionSource,
+}
+
+impl BrowserVersion {
+    /// Parses a dotted version, missing trailing parts are 0
+    pub fn parse(version: &str, source: VersionSource) -> Option<Self> {
+        let mut parts = [0_u32; 4];
+        for (index, part) in version.trim().split('.').enumerate() {
+            *parts.get_mut(index)? = part.parse().ok()?;
+        }
+        let [major, minor, build, patch] = parts;
+        Some(BrowserVersion { major, minor, build, patch, source })
+    }
+
+    /// `(major, minor, build, patch)`, to compare versions regardless of their source
+    pub fn numbers(&self) -> (u32, u32, u32, u32) {
+        (self.major, self.minor, self.build, self.patch)
+    }
+}
+
+impl std::fmt::Display for BrowserVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}.{}", self.major, self.minor, self.build, self.patch)
+    }
+}
+
+/// Account and installation identifiers of a chromium profile, each one only when present
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProfileIdentity {
+    /// Google account id of the signed in user (`Preferences`)
+    pub gaia_id: Option<String>,
+    /// Email of the signed in user (`Preferences`)
+    pub account_email: Option<String>,
+    /// Metrics client id of the installation (`Local State`)
+    pub client_id: Option<String>,
+}
+
+/// Cookies read from a single chromium profile, or one of its component stores
+#[derive(Debug)]
+pub struct ProfileCookies {
+    pub cookies: Vec<Cookie>,
+    /// Profile directory name, e.g. "Default" or "Profile 1"
+    pub profile_dir: String,
+    /// Name shown in the profile picker, from `Local State` (`profile.info_cache`)
+    pub display_name: Option<String>,
+    /// Component store the cookies came from (e.g. "Mail"), `None` for the profile itself
+    pub component: Option<String>,
+    /// Content of `Last Version` next to `Local State`, or the version found instead of it
+    pub last_version: Option<String>,
+    /// Browser version, see [`crate::browser::chromium::browser_version`]
+    pub version: Option<BrowserVersion>,
+    /// Only filled when `EnumerationOptions::include_identity` is set
+    pub identity: Option<ProfileIdentity>,
+    /// Only filled when `EnumerationOptions::provenance` is set
+    pub provenance: Option<Provenance>,
+    /// Duplicate rows dropped from the cookies DB, non zero means it's corrupted
+    pub duplicates: usize,
+    /// `meta.version` of the cookies DB
+    pub schema_version: Option<i64>,
+}
+
+/// Options for [`crate::load_with`], start from `Default::default()` and set the
+/// fields needed. New fields can be added in a minor release.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct LoadOptions {
+    /// Keyring/keychain items to ask for before skipping the remaining chromium
+    /// browsers, `None` for no limit. Each item is asked for once per read.
+    pub max_prompts: Option<usize>,
+    /// Return cookies that already expired but weren't purged by the browser yet.
+    /// When unset the chromium and firefox DBs skip them in the query itself.
+    pub include_expired: bool,
+    /// Skip cookies DBs with a schema version newer than the tested ones, see [`ReadOptions`]
+    pub strict_schema: bool,
+    /// Return rows marked as private browsing, see [`ReadOptions`]
+    pub include_private_leftovers: bool,
+    /// Return chromium extension cookies, see [`ReadOptions`]
+    pub include_extensions: bool,
+    /// Return partitioned cookies, see [`ReadOptions`]
+    pub include_partitioned: bool,
+    /// How the `domains` filter matches cookie domains, see [`ReadOptions`]
+    pub domain_match: DomainMatch,
+    /// Read only session or only persistent cookies, see [`ReadOptions`]
+    pub persistence: Persistence,
+    /// Read only the cookies scoped to this path or below it, see [`ReadOptions`]
+    pub path: Option<String>,
+    /// Read only Secure cookies, see [`ReadOptions`]
+    pub secure_only: bool,
+    /// Read only HttpOnly (`Some(true)`) or only script visible (`Some(false)`) cookies,
+    /// see [`ReadOptions`]
+    pub http_only: Option<bool>,
+    /// Read only the cookies expiring before this time, see [`ReadOptions`]
+    pub expires_before: Option<SystemTime>,
+    /// Read only the cookies expiring within this long from now, see [`ReadOptions`]
+    pub expires_within: Option<Duration>,
+    /// Order of the cookies of all browsers together, see [`ReadOptions`]
+    pub sort_by: Option<SortBy>,
+    /// Return at most this many cookies of all browsers together, after skipping
+    /// `offset`. Each browser is still read in full, see [`ReadOptions`] to page a single DB
+    pub limit: Option<usize>,
+    /// Cookies to skip before `limit`
+    pub offset: usize,
+    /// Record the provenance of each cookies DB read in [`LoadOutcome::provenance`],
+    /// see [`ReadOptions`]
+    pub provenance: bool,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        LoadOptions {
+            max_prompts: None,
+            include_expired: true,
+            strict_schema: false,
+            include_private_leftovers: false,
+            include_extensions: false,
+            include_partitioned: true,
+            domain_match: DomainMatch::Suffix,
+            persistence: Persistence::All,
+            path: None,
+            secure_only: false,
+            http_only: None,
+            expires_before: None,
+            expires_within: None,
+            sort_by: None,
+            limit: None,
+            offset: 0,
+            provenance: false,
+        }
+    }
+}
+
+impl LoadOptions {
+    /// Options for reading each cookies DB. Sorting and paging only apply to the
+    /// cookies of all browsers together, so they aren't passed on
+    pub fn read_options(&self) -> ReadOptions {
+        ReadOptions {
+            include_expired: self.include_expired,
+            strict_schema: self.strict_schema,
+            include_private_leftovers: self.include_private_leftovers,
+            include_extensions: self.include_extensions,
+            include_partitioned: self.include_partitioned,
+            domain_match: self.domain_match,
+            persistence: self.persistence,
+            path: self.path.clone(),
+            secure_only: self.secure_only,
+            http_only: self.http_only,
+            expires_before: self.expires_before,
+            expires_within: self.expires_within,
+            sort_by: None,
+            limit: None,
+            offset: 0,
+            provenance: self.provenance,
+        }
+    }
+}
+
+/// How a `domains` filter entry matches the domain of a cookie. Both sides are compared
+/// without the leading dot of domain cookies, case insensitively and IDN hosts in their
+/// punycode form (`bücher.de` is `xn--bcher-kva.de`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DomainMatch {
+    /// The domain and its subdomains on a label boundary: `google.com` matches
+    /// `google.com`, `.google.com` and `accounts.google.com`, not `notgoogle.com`
+    #[default]
+    Suffix,
+    /// The domain only: `google.com` matches `google.com` and `.google.com`
+    Exact,
+}
+
+/// Order of the cookies read, ascending. Ties are broken by domain, path and name,
+/// so pages of [`ReadOptions::limit`] stay the same while the DB doesn't change.
+/// Session cookies have no expiry and cookies never accessed no access time, they go first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortBy {
+    Domain,
+    Expiry,
+    LastAccessed,
+}
+
+impl SortBy {
+    /// Sorts `cookies` the way the cookies DBs order them
+    pub(crate) fn sort(&self, cookies: &mut [Cookie]) {
+        let ties = |cookie: &Cookie| (cookie.domain.clone(), cookie.path.clone(), cookie.name.clone());
+        match self {
+            SortBy::Domain => cookies.sort_by_cached_key(ties),
+            SortBy::Expiry => cookies.sort_by_cached_key(|cookie| (cookie.expires, ties(cookie))),
+            SortBy::LastAccessed => cookies.sort_by_cached_key(|cookie| (cookie.last_accessed, ties(cookie))),
+        }
+    }
+}
+
+/// Which cookies to read by lifetime. Session cookies have no expiry and are neither
+/// persistent nor expired
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Persistence {
+    #[default]
+    All,
+    /// Cookies without an expiry, dropped when the browser closes
+    SessionOnly,
+    /// Cookies with an expiry, expired ones included unless `include_expired` is unset
+    PersistentOnly,
+}
+
+impl Persistence {
+    pub fn matches(&self, cookie: &Cookie) -> bool {
+        match self {
+            Persistence::All => true,
+            Persistence::SessionOnly => cookie.expires.is_none(),
+            Persistence::PersistentOnly => cookie.expires.is_some(),
+        }
+    }
+}
+
+/// How a single cookies DB is read, start from `Default::default()` and set the
+/// fields needed. New fields can be added in a minor release.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ReadOptions {
+    /// Return cookies that already expired but weren't purged by the browser yet. When
+    /// unset the chromium, firefox and epiphany DBs skip them in the query itself, before
+    /// any value is decrypted. Session cookies never count as expired.
+    pub include_expired: bool,
+    /// Refuse DBs with a schema version newer than the tested ones with
+    /// [`crate::common::sqlite::UnsupportedSchema`], instead of warning and reading them
+    pub strict_schema: bool,
+    /// Return rows marked as private browsing (Firefox `privateBrowsingId`) that a
+    /// crash left behind. Off by default, only meant for forensic use.
+    ///
+    /// Chromium never writes Incognito cookies to disk and its rows carry no
+    /// marker, so its stores hold no such rows to exclude.
+    pub include_private_leftovers: bool,
+    /// Return chromium cookies set by extensions, the ones with a `chrome-extension://`
+    /// host key or partition, labeled with [`Cookie::extension`]. Off by default
+    /// since they're never sent to a website.
+    pub include_extensions: bool,
+    /// Return partitioned (CHIPS) cookies, the ones with a [`Cookie::partition_key`].
+    /// On by default, they're only sent from within their top level site.
+    pub include_partitioned: bool,
+    /// How the `domains` filter matches cookie domains
+    pub domain_match: DomainMatch,
+    /// Read only session or only persistent cookies. The chromium, firefox and epiphany
+    /// DBs filter them in the query itself
+    pub persistence: Persistence,
+    /// Read only the cookies whose path is this one or below it, split on `/` like the
+    /// path-match of RFC 6265: `/api` keeps `/api`, `/api/` and `/api/v1` but not
+    /// `/apiv2` or `/`. The chromium, firefox and epiphany DBs filter them in the query itself
+    pub path: Option<String>,
+    /// Read only the cookies with the Secure attribute, the ones sent over https only
+    pub secure_only: bool,
+    /// Read only HttpOnly cookies with `Some(true)`, or only the ones scripts can see
+    /// through `document.cookie` with `Some(false)`. `None` reads both
+    pub http_only: Option<bool>,
+    /// Read only the persistent cookies expiring before this time. Session cookies have
+    /// no expiry and are left out. Expired cookies are still read unless `include_expired`
+    /// is unset. The chromium, firefox and epiphany DBs filter them in the query itself
+    pub expires_before: Option<SystemTime>,
+    /// Read only the persistent cookies expiring within this long from now, like
+    /// `expires_before`. With both set the earlier time applies
+    pub expires_within: Option<Duration>,
+    /// Order of the cookies read, `None` for the order of the DB. The chromium and
+    /// firefox DBs sort in the query itself
+    pub sort_by: Option<SortBy>,
+    /// Return at most this many cookies, after skipping `offset`. The chromium and
+    /// firefox DBs page in the query itself, so the other rows are never decrypted.
+    /// Cookies are sorted by `sort_by`, by domain when unset, before paging.
+    /// Duplicate rows of a corrupted chromium DB still take up room in a page
+    pub limit: Option<usize>,
+    /// Cookies to skip before `limit`
+    pub offset: usize,
+    /// Parse a private copy of the cookies DB and record its hash, size and mtime in
+    /// [`CookiesRead::provenance`]. The hash is taken from the bytes that were parsed
+    pub provenance: bool,
+}
+
+impl Default for ReadOptions {
+    fn default() -> Self {
+        ReadOptions {
+            include_expired: true,
+            strict_schema: false,
+            include_private_leftovers: false,
+            include_extensions: false,
+            include_partitioned: true,
+            domain_match: DomainMatch::Suffix,
+            persistence: Persistence::All,
+            path: None,
+            secure_only: false,
+            http_only: None,
+            expires_before: None,
+            expires_within: None,
+            sort_by: None,
+            limit: None,
+            offset: 0,
+            provenance: false,
+        }
+    }
+}
+
+impl ReadOptions {
+    /// Unix time of `expires_before` and `expires_within`
+    pub(crate) fn expiry_cutoff(&self) -> Option<u64> {
+        date::expiry_cutoff(self.expires_before, self.expires_within)
+    }
+
+    /// Whether `limit` or `offset` is set
+    pub(crate) fn paginates(&self) -> bool {
+        self.limit.is_some() || self.offset > 0
+    }
+
+    /// `sort_by`, by domain when paging without one
+    pub(crate) fn order(&self) -> Option<SortBy> {
+        self.sort_by.or(self.paginates().then_some(SortBy::Domain))
+    }
+}
+
+/// `domains` filter of the reading functions, `None` reads every domain. It converts
+/// from the `Option<Vec<&str>>` the readers always took and from lists of `&str` or
+/// `String`, [`Domains::new`] takes any optional list, e.g. an `Option<Vec<String>>`
+/// of a command line argument. The list is shared, passing `&domains` to several
+/// readers doesn't copy it.
+///
+/// # Examples
+///
+/// ```
+/// use rookie::Domains;
+///
+/// fn main() {
+///     let owned = vec!["google.com".to_string(), "github.com".to_string()];
+///     let cookies = rookie::chrome(&owned);
+///     let cookies = rookie::firefox(Some(vec!["google.com"]));
+///     let cookies = rookie::edge(None);
+///     let domains = Domains::new(std::env::args().nth(1).map(|domain| vec![domain]));
+///     let cookies = rookie::brave(&domains);
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Domains(Option<Arc<[String]>>);
+
+impl Domains {
+    pub fn new(domains: Option<impl IntoIterator<Item = impl AsRef<str>>>) -> Self {
+        Domains(
+            domains.map(|domains| {
+                domains
+                    .into_iter()
+                    .map(|domain| domain.as_ref().to_string())
+                    .collect()
+            })
+        )
+    }
+
+    /// The domains, `None` when every domain is read
+    pub fn as_deref(&self) -> Option<&[String]> {
+        self.0.as_deref()
+    }
+}
+
+impl<'a> From<Option<Vec<&'a str>>> for Domains {
+    fn from(domains: Option<Vec<&'a str>>) -> Self {
+        Domains::new(domains)
+    }
+}
+
+impl<'a> From<Vec<&'a str>> for Domains {
+    fn from(domains: Vec<&'a str>) -> Self {
+        Domains::new(Some(domains))
+    }
+}
+
+impl From<Vec<String>> for Domains {
+    fn from(domains: Vec<String>) -> Self {
+        Domains(Some(domains.into()))
+    }
+}
+
+impl<S: AsRef<str>> From<&Vec<S>> for Domains {
+    fn from(domains: &Vec<S>) -> Self {
+        Domains::new(Some(domains))
+    }
+}
+
+impl<S: AsRef<str>> From<&[S]> for Domains {
+    fn from(domains: &[S]) -> Self {
+        Domains::new(Some(domains))
+    }
+}
+
+impl<S: AsRef<str>, const N: usize> From<[S; N]> for Domains {
+    fn from(domains: [S; N]) -> Self {
+        Domains::new(Some(domains))
+    }
+}
+
+impl From<&Domains> for Domains {
+    fn from(domains: &Domains) -> Self {
+        domains.clone()
+    }
+}
+
+/// Cookie names to read. The chromium and firefox DBs match them in the query itself,
+/// so the values of the other cookies are never decrypted. No names matches every cookie.
+///
+/// # Examples
+///
+/// ```
+/// use rookie::NameFilter;
+///
+/// fn main() {
+///     let names = NameFilter::prefix(["__Secure-"]);
+///     assert!(names.matches("__Secure-3PSID"));
+///     assert!(!NameFilter::exact(["SID"]).matches("sid"));
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NameFilter {
+    pub names: Vec<String>,
+    /// Match the names starting with one of `names` instead of the ones equal to it
+    pub prefix: bool,
+}
+
+impl NameFilter {
+    /// Cookies named exactly like one of `names`, case sensitive
+    pub fn exact(names: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        NameFilter {
+            names: names
+                .into_iter()
+                .map(|name| name.as_ref().to_string())
+                .collect(),
+            prefix: false,
+        }
+    }
+
+    /// Cookies whose name starts with one of `prefixes` (`__Secure-`, `_ga`), case sensitive
+    pub fn prefix(prefixes: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        NameFilter { prefix: true, ..NameFilter::exact(prefixes) }
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        self.names.is_empty() ||
+            self.names.iter().any(|filter| if self.prefix { name.starts_with(filter.as_str()) } else { name == filter })
+    }
+}
+
+/// Regex patterns on the domain and name of cookies, applied to cookies already read,
+/// e.g. after the `domains` filter of the reading functions. Unset patterns match everything.
+///
+/// # Examples
+///
+/// ```
+/// use regex::Regex;
+/// use rookie::CookieFilter;
+///
+/// fn main() {
+///     let filter = CookieFilter {
+///         domain: Some(Regex::new(r"\.internal\.corp$").unwrap()),
+///         exclude_domain: Some(Regex::new(r"(^|\.)staging\.").unwrap()),
+///         ..Default::default()
+///     };
+///     let cookies = rookie::load_matching(Some(vec!["internal.corp"]), &filter);
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CookieFilter {
+    /// Keep the cookies whose domain matches
+    pub domain: Option<regex::Regex>,
+    /// Drop the cookies whose domain matches, the regex crate has no lookaround
+    pub exclude_domain: Option<regex::Regex>,
+    /// Keep the cookies whose name matches
+    pub name: Option<regex::Regex>,
+}
+
+impl CookieFilter {
+    pub fn matches(&self, cookie: &Cookie) -> bool {
+        self.domain.as_ref().map_or(true, |domain| domain.is_match(&cookie.domain)) &&
+            !self.exclude_domain.as_ref().is_some_and(|exclude| exclude.is_match(&cookie.domain)) &&
+            self.name.as_ref().map_or(true, |name| name.is_match(&cookie.name))
+    }
+
+    /// Keeps the cookies that match
+    pub fn apply(&self, cookies: Vec<Cookie>) -> Vec<Cookie> {
+        cookies
+            .into_iter()
+            .filter(|cookie| self.matches(cookie))
+            .collect()
+    }
+}
+
+/// Cookies from every browser that could be read
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct LoadOutcome {
+    pub cookies: Vec<Cookie>,
+    /// Browsers skipped because reading their key needed more prompts than `max_prompts`
+    pub requires_interaction: Vec<String>,
+    /// Browsers of another user whose key only opens in that user's session, see
+    /// [`crate::load_for_user`]
+    pub needs_user_context: Vec<String>,
+    /// Duplicate rows dropped from corrupted chromium cookies DBs
+    pub duplicates: usize,
+    /// Schema version of the cookies DB of each browser read, `meta.version` for
+    /// chromium and `user_version` for firefox
+    pub schema_versions: Vec<(String, i64)>,
+    /// Browsers skipped by `strict_schema` because their schema is too new
+    pub unsupported_schemas: Vec<String>,
+    /// Private browsing rows left out of `cookies`, see [`ReadOptions::include_private_leftovers`]
+    pub private_excluded: usize,
+    /// Browsers whose cookies matched `host:port` domain filters on the host only,
+    /// their stores don't record ports
+    pub ports_ignored: Vec<String>,
+    /// Provenance of each cookies DB read, by browser. Only filled when
+    /// `LoadOptions::provenance` is set
+    pub provenance: Vec<(String, Provenance)>,
+}
+
+/// Cookies a store holds for a domain, counted without reading any value
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DomainStats {
+    /// Cookies that didn't expire yet, session cookies included
+    pub cookies: usize,
+    /// Newest last access of those cookies in unix seconds, `None` when the store doesn't keep it
+    pub last_access: Option<u64>,
+}
+
+/// Cookies of one chromium, firefox or binarycookies cookie store
+#[derive(Debug, Default)]
+pub struct CookiesRead {
+    pub cookies: Vec<Cookie>,
+    /// Rows dropped because another row had the same key (host, name, path and the
+    /// partition columns), which Chromium never writes. Only the newest row is kept.
+    pub duplicates: usize,
+    /// `meta.version` for chromium, `PRAGMA user_version` for firefox
+    pub schema_version: Option<i64>,
+    /// Rows left out because they're marked as private browsing
+    pub private_excluded: usize,
+    /// Chromium rows left out because their decrypted value isn't UTF-8 or, from
+    /// `meta.version` 24 on, doesn't start with the SHA256 of its host
+    pub undecodable: usize,
+    /// A `host:port` domain filter was matched on the host only since the DB
+    /// doesn't record ports
+    pub ports_ignored: bool,
+    /// Only filled when `ReadOptions::provenance` is set
+    pub provenance: Option<Provenance>,
+}
+
+/// Outcome of a multi-profile read, a broken profile doesn't fail the others
+#[derive(Debug, Default)]
+pub struct ProfilesRead {
+    pub profiles: Vec<ProfileCookies>,
+    /// Cookies DB of each profile that couldn't be read, with the reason
+    pub failures: Vec<(PathBuf, anyhow::Error)>,
+}
+
+/// Release channel of a firefox installation, told from its profile name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FirefoxChannel {
+    /// `*.default-release`
+    Release,
+    /// `*.default-beta`
+    Beta,
+    /// `*.default-esr`
+    Esr,
+    /// `*.dev-edition-default`
+    DeveloperEdition,
+    /// `*.default-nightly`
+    Nightly,
+}
+
+/// Cookies read from the profile of one installed firefox channel
+#[derive(Debug)]
+pub struct ChannelCookies {
+    pub cookies: Vec<Cookie>,
+    /// `None` when the profile name doesn't tell, e.g. a profile created by hand
+    pub channel: Option<FirefoxChannel>,
+    /// Profile directory name, e.g. "abcd1234.dev-edition-default"
+    pub profile_dir: String,
+    /// `PRAGMA user_version` of the cookies DB
+    pub schema_version: Option<i64>,
+}
+
+/// Outcome of [`crate::firefox_channels`], a broken profile doesn't fail the others
+#[derive(Debug, Default)]
+pub struct ChannelsRead {
+    pub channels: Vec<ChannelCookies>,
+    /// Cookies DB of each profile that couldn't be read, with the reason
+    pub failures: Vec<(PathBuf, anyhow::Error)>,
+}
+
+/// Outcome of [`crate::load_users`], a user whose profile can't be read doesn't fail the others
+#[derive(Debug, Default)]
+pub struct UsersRead {
+    /// Cookies of each user, by user profile directory
+    pub users: Vec<(PathBuf, LoadOutcome)>,
+    /// User profile directory of each user that couldn't be read, with the reason
+    pub failures: Vec<(PathBuf, anyhow::Error)>,
+}
+
+impl ProfileCookies {
+    /// Whether the cookies come from Brave's `Tor Profile`
+    pub fn is_tor(&self) -> bool {
+        self.profile_dir == "Tor Profile"
+    }
+}
+
 #[derive(Debug)]
 pub struct BrowserConfig<'a> {
     pub data_paths: &'a [&'a str],
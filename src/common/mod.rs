@@ -1,6 +1,8 @@
 pub mod date;
 pub mod enums;
 pub mod paths;
+pub mod provenance;
+pub mod request;
 pub mod sqlite;
 pub mod utils;
 
@@ -1,8 +1,11 @@
-use crate::{ browser::mozilla::get_default_profile, common::enums::BrowserConfig };
+use crate::{
+    browser::mozilla::{ channel_profile_dirs, default_profile_dir, profile_dirs, ShareUnreachable },
+    common::{ enums::{ BrowserConfig, EnumerationOptions }, sqlite, utils },
+};
 use anyhow::{ anyhow, bail, Result };
 use glob;
 use log::debug;
-use std::{ env, path::PathBuf };
+use std::{ cell::RefCell, env, fs, path::{ Path, PathBuf } };
 
 fn expand_glob_paths(path: PathBuf) -> Result<Vec<PathBuf>> {
     let mut data_paths: Vec<PathBuf> = vec![];
@@ -16,37 +19,185 @@ fn expand_glob_paths(path: PathBuf) -> Result<Vec<PathBuf>> {
     Ok(data_paths)
 }
 
+thread_local! {
+    static USER_PROFILE_ROOT: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+}
+
+/// Runs `f` with browser discovery rebased onto another user profile root, e.g.
+/// `D:\Users\alice` of a mounted Windows installation or another home directory
+/// on unix. Only the calling thread is affected, the previous root is restored after.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// fn main() {
+///     let cookies = rookie::with_user_profile_root(Path::new(r"D:\Users\alice"), || {
+///         rookie::chrome(None)
+///     });
+/// }
+/// ```
+pub fn with_user_profile_root<T>(root: &Path, f: impl FnOnce() -> T) -> T {
+    struct Restore(Option<PathBuf>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            USER_PROFILE_ROOT.with(|root| root.replace(self.0.take()));
+        }
+    }
+    let previous = USER_PROFILE_ROOT.with(|current| current.replace(Some(root.to_path_buf())));
+    // restored on panic too
+    let _restore = Restore(previous);
+    f()
+}
+
+/// Profile directories of the users under `users_dir` (`C:\Users`, `/home`), leaving out
+/// the shared `Public` and `Default` ones and links to other directories
+pub fn user_profile_dirs(users_dir: &Path) -> Result<Vec<PathBuf>> {
+    const SHARED: &[&str] = &["public", "default", "default user", "all users"];
+    let mut dirs = vec![];
+    for entry in fs::read_dir(users_dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_lowercase();
+        // "All Users" and "Default User" are junctions
+        if !entry.file_type()?.is_dir() || SHARED.contains(&name.as_str()) {
+            continue;
+        }
+        dirs.push(entry.path());
+    }
+    dirs.sort();
+    Ok(dirs)
+}
+
+/// Root set by [`with_user_profile_root`] on this thread
+pub fn user_profile_root() -> Option<PathBuf> {
+    USER_PROFILE_ROOT.with(|root| root.borrow().clone())
+}
+
+/// Folder a `%VAR%` placeholder of the Windows configs stands for under the user
+/// profile `root`, the layout every Windows version since Vista uses
+pub fn rebased_folder(root: &str, placeholder: &str) -> Option<String> {
+    let folder = match placeholder.to_ascii_uppercase().as_str() {
+        "USERPROFILE" => "",
+        "APPDATA" => "AppData\\Roaming",
+        "LOCALAPPDATA" => "AppData\\Local",
+        _ => {
+            return None;
+        }
+    };
+    let root = root.trim_end_matches(['\\', '/']);
+    Some(if folder.is_empty() { root.to_string() } else { format!("{}\\{}", root, folder) })
+}
+
+/// Replaces the `%VAR%` placeholders of `path` with `resolve`, unresolved ones are kept
+pub fn expand_placeholders(path: &str, resolve: impl Fn(&str) -> Option<String>) -> Result<String> {
+    let re = regex::Regex::new(r"%([^%]+)%")?;
+    let mut expanded_path = path.to_owned();
+    for capture in re.captures_iter(path) {
+        // e.g. "APPDATA" from "%APPDATA%"
+        if let Some(value) = resolve(&capture[1]) {
+            expanded_path = expanded_path.replace(&capture[0], &value);
+        }
+    }
+    Ok(expanded_path)
+}
+
+/// Expands the `%VAR%` placeholders of a config path from, in order, the root set by
+/// [`with_user_profile_root`], the environment and the known folder APIs. No drive
+/// letter is assumed, so profiles moved off `C:` are found.
 #[cfg(target_os = "windows")]
 pub fn expand_path(path: &str) -> Result<PathBuf> {
-    use regex::Regex;
-    // Define a regex pattern to match placeholders like %SOMETHING%
-    let re = Regex::new(r"%([^%]+)%")?;
+    let root = user_profile_root();
+    let expanded_path = expand_placeholders(path, |placeholder| {
+        if let Some(root) = &root {
+            return rebased_folder(&root.to_string_lossy(), placeholder);
+        }
+        env::var(placeholder)
+            .ok()
+            .filter(|value| !value.is_empty())
+            .or_else(|| {
+                crate::common::winapi
+                    ::known_folder(placeholder)
+                    .map(|folder| folder.to_string_lossy().to_string())
+            })
+    })?;
+    Ok(PathBuf::from(expanded_path))
+}
 
-    // Clone the input path for modification
-    let mut expanded_path = path.to_owned();
+/// Rewrites an absolute path with the `\\?\` prefix when Win32 path normalization would
+/// break it: longer than MAX_PATH, a component with trailing dots or spaces, or a
+/// reserved device name (`CON`, `AUX`, ...) used as a folder name.
+/// `..` components are resolved since verbatim paths are passed to the filesystem as is.
+#[cfg(target_os = "windows")]
+pub fn extended_length_path(path: &Path) -> PathBuf {
+    use std::ffi::{ OsStr, OsString };
+    use std::path::{ Component, Prefix };
 
-    // Iterate over all matches of the regex pattern in the input path
-    for capture in re.captures_iter(&path) {
-        // Get the matched placeholder (e.g., "APPDATA" from "%APPDATA%")
-        let placeholder = &capture[1];
+    const MAX_PATH: usize = 260;
+    const RESERVED_NAMES: &[&str] = &[
+        "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7",
+        "COM8", "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+    ];
 
-        // Try to get the corresponding environment variable value
-        if let Ok(var_value) = env::var(placeholder) {
-            // Replace the placeholder with the environment variable value
-            expanded_path = expanded_path.replace(&capture[0], &var_value);
-        }
+    let is_pathological = |name: &OsStr| {
+        let name = name.to_string_lossy();
+        let stem = name.split('.').next().unwrap_or_default().trim_end();
+        name.ends_with(['.', ' ']) || RESERVED_NAMES.iter().any(|r| r.eq_ignore_ascii_case(stem))
+    };
+    let needs_prefix =
+        path.as_os_str().len() >= MAX_PATH ||
+        path.components().any(|c| matches!(c, Component::Normal(name) if is_pathological(name)));
+    if !needs_prefix {
+        return path.to_path_buf();
     }
 
-    // Convert the expanded path to a PathBuf
-    let path_buf = PathBuf::from(expanded_path);
+    let mut components = path.components();
+    let mut extended = match components.next() {
+        Some(Component::Prefix(prefix)) =>
+            match prefix.kind() {
+                Prefix::Disk(letter) => OsString::from(format!(r"\\?\{}:", letter as char)),
+                Prefix::UNC(server, share) => {
+                    let mut unc = OsString::from(r"\\?\UNC\");
+                    unc.push(server);
+                    unc.push(r"\");
+                    unc.push(share);
+                    unc
+                }
+                // already verbatim or a device path
+                _ => {
+                    return path.to_path_buf();
+                }
+            }
+        // relative paths can't be extended
+        _ => {
+            return path.to_path_buf();
+        }
+    };
 
-    Ok(path_buf)
+    let mut names: Vec<&OsStr> = vec![];
+    for component in components {
+        match component {
+            Component::Normal(name) => names.push(name),
+            Component::ParentDir => {
+                names.pop();
+            }
+            _ => {}
+        }
+    }
+    for name in names {
+        extended.push(r"\");
+        extended.push(name);
+    }
+    PathBuf::from(extended)
 }
 
 #[cfg(unix)]
 pub fn expand_path(path: &str) -> Result<PathBuf> {
-    // Get the value of the HOME environment variable
-    let home = env::var("HOME")?;
+    // Get the value of the HOME environment variable, or the root set by with_user_profile_root
+    let home = match user_profile_root() {
+        Some(root) => root.to_string_lossy().to_string(),
+        None => env::var("HOME")?,
+    };
 
     // Replace ~ or $HOME with the actual home directory path
     let expanded_path = path.replace("~", &home).replace("$HOME", &home);
@@ -55,10 +206,27 @@ pub fn expand_path(path: &str) -> Result<PathBuf> {
     Ok(PathBuf::from(expanded_path))
 }
 
+/// Locates `Local State` for a cookies DB, which lives in the `User Data` root
+/// for most browsers and one level above it for some forks (UC Browser).
+pub fn find_key_path(db_path: &Path) -> Option<PathBuf> {
+    let parent = db_path.parent()?;
+    let key_path = [
+        "../../Local State",
+        "../Local State",
+        "Local State",
+        "../../../Local State",
+    ]
+        .iter()
+        .map(|p| parent.join(p))
+        .find(|p| p.exists())
+        .unwrap_or_else(|| parent.join("Local State"));
+    Some(key_path)
+}
+
 pub fn find_chrome_based_paths(browser_config: &BrowserConfig) -> Result<(PathBuf, PathBuf)> {
     for path in browser_config.data_paths {
         // base paths
-        let channels: &[&str] = &browser_config.channels.as_deref().unwrap_or(&[""]);
+        let channels: &[&str] = browser_config.channels.unwrap_or(&[""]);
         for channel in channels {
             // channels
             let path = path.replace("{channel}", channel);
@@ -67,12 +235,7 @@ pub fn find_chrome_based_paths(browser_config: &BrowserConfig) -> Result<(PathBu
             for db_path in glob_db_paths {
                 // glob expanded paths
                 if db_path.exists() {
-                    if let Some(parent) = db_path.parent() {
-                        let key_path = ["../../Local State", "../Local State", "Local State"]
-                            .iter()
-                            .map(|p| parent.join(p))
-                            .find(|p| p.exists())
-                            .unwrap_or_else(|| parent.join("Local State"));
+                    if let Some(key_path) = find_key_path(&db_path) {
                         debug!("Found chrome path {}, {}", db_path.display(), key_path.display());
                         return Ok((key_path, db_path));
                     }
@@ -83,26 +246,102 @@ pub fn find_chrome_based_paths(browser_config: &BrowserConfig) -> Result<(PathBu
     Err(anyhow!("can't find any cookies file"))
 }
 
+/// Component stores that keep their own cookies DB inside a chromium profile
+pub const CHROME_COMPONENTS: &[&str] = &["Mail", "Calendar", "VPN"];
+
+fn chrome_store_dir(db_path: &Path) -> Option<&Path> {
+    let parent = db_path.parent()?;
+    if parent.file_name()? == "Network" { parent.parent() } else { Some(parent) }
+}
+
+/// Returns the component name ("Mail", ...) when the cookies DB belongs to a component store
+pub fn chrome_component(db_path: &Path) -> Option<String> {
+    let name = chrome_store_dir(db_path)?.file_name()?.to_string_lossy().to_string();
+    CHROME_COMPONENTS.contains(&name.as_str()).then_some(name)
+}
+
+/// Returns the profile directory holding a chromium cookies DB, skipping `Network` and component stores
+pub fn chrome_profile_path(db_path: &Path) -> Option<&Path> {
+    let store_dir = chrome_store_dir(db_path)?;
+    if chrome_component(db_path).is_some() { store_dir.parent() } else { Some(store_dir) }
+}
+
+/// Returns the profile directory name ("Default", "Profile 1", ...) of a chromium cookies DB
+pub fn chrome_profile_dir(db_path: &Path) -> Option<String> {
+    Some(chrome_profile_path(db_path)?.file_name()?.to_string_lossy().to_string())
+}
+
+fn find_component_paths(db_path: &Path) -> Vec<PathBuf> {
+    let Some(profile_dir) = chrome_store_dir(db_path) else {
+        return vec![];
+    };
+    CHROME_COMPONENTS.iter()
+        .flat_map(|component| {
+            let component_dir = profile_dir.join(component);
+            [component_dir.join("Network/Cookies"), component_dir.join("Cookies")]
+        })
+        .filter(|p| p.exists())
+        .collect()
+}
+
+fn is_ephemeral_profile(key_path: &Path, profile_dir: &str) -> bool {
+    let Ok(content) = fs::read(key_path) else {
+        return false;
+    };
+    let Ok(local_state) = utils::parse_json_lenient(&content) else {
+        return false;
+    };
+    local_state
+        .pointer("/profile/info_cache")
+        .and_then(|info_cache| info_cache.get(profile_dir))
+        .and_then(|info| info.get("is_ephemeral"))
+        .and_then(|is_ephemeral| is_ephemeral.as_bool())
+        .unwrap_or(false)
+}
+
 pub fn find_chrome_based_paths_v2(
-    browser_config: &BrowserConfig
+    browser_config: &BrowserConfig,
+    options: &EnumerationOptions
 ) -> Result<Vec<(PathBuf, PathBuf)>> {
     let mut paths: Vec<(PathBuf, PathBuf)> = vec![];
 
+    let mut data_paths: Vec<String> = vec![];
     for path in browser_config.data_paths {
-        let channels: &[&str] = &browser_config.channels.as_deref().unwrap_or(&[""]);
+        data_paths.push(path.to_string());
+        if path.contains("/Default/") {
+            if options.include_guest {
+                data_paths.push(path.replace("/Default/", "/Guest Profile/"));
+            }
+            if options.include_system {
+                data_paths.push(path.replace("/Default/", "/System Profile/"));
+            }
+            if options.include_tor {
+                data_paths.push(path.replace("/Default/", "/Tor Profile/"));
+            }
+        }
+    }
+
+    for path in &data_paths {
+        let channels: &[&str] = browser_config.channels.unwrap_or(&[""]);
         for channel in channels {
             let path = path.replace("{channel}", channel);
             let db_path = expand_path(path.as_str())?;
             let glob_db_paths = expand_glob_paths(db_path)?;
             for db_path in glob_db_paths {
                 if db_path.exists() {
-                    if let Some(parent) = db_path.parent() {
-                        let key_path = ["../../Local State", "../Local State", "Local State"]
-                            .iter()
-                            .map(|p| parent.join(p))
-                            .find(|p| p.exists())
-                            .unwrap_or_else(|| parent.join("Local State"));
-
+                    if let Some(key_path) = find_key_path(&db_path) {
+                        if !options.include_ephemeral {
+                            let profile_dir = chrome_profile_dir(&db_path).unwrap_or_default();
+                            if is_ephemeral_profile(&key_path, &profile_dir) {
+                                debug!("Skipping ephemeral profile {}", db_path.display());
+                                continue;
+                            }
+                        }
+                        if options.include_components {
+                            for component_path in find_component_paths(&db_path) {
+                                paths.push((key_path.clone(), component_path));
+                            }
+                        }
                         paths.push((key_path, db_path));
                     }
                 }
@@ -117,41 +356,300 @@ pub fn find_chrome_based_paths_v2(
     Ok(paths)
 }
 
+/// Last write to a sqlite DB, its WAL included
+pub(crate) fn last_modified(db_path: &Path) -> Option<std::time::SystemTime> {
+    let mut wal_path = db_path.as_os_str().to_owned();
+    wal_path.push("-wal");
+    [db_path, Path::new(&wal_path)]
+        .iter()
+        .filter_map(|path| fs::metadata(path).and_then(|metadata| metadata.modified()).ok())
+        .max()
+}
+
+/// Locates `cookies.sqlite` of the default profile. When several locations (e.g. the
+/// classic, snap and flatpak installs) have one in profiles.ini, the most recently
+/// written wins, ties go to the earlier location
 pub fn find_mozilla_based_paths(browser_config: &BrowserConfig) -> Result<PathBuf> {
+    let mut unreachable = None;
+    let mut defaults: Vec<PathBuf> = vec![];
+    let mut fallback = None;
     for path in browser_config.data_paths {
         // base paths
-        let channels: &[&str] = &browser_config.channels.as_deref().unwrap_or(&[""]);
+        let channels: &[&str] = browser_config.channels.unwrap_or(&[""]);
         for channel in channels {
             // channels
-            let path = path.replace("{channel}", &channel);
+            let path = path.replace("{channel}", channel);
             let firefox_path = expand_path(path.as_str())?;
             let glob_paths = expand_glob_paths(firefox_path)?;
             for path in glob_paths {
                 // expanded glob paths
-                let profiles_path = path.join("profiles.ini");
-                let default_profile = get_default_profile(profiles_path.as_path()).unwrap_or(
-                    "".to_string()
-                );
-                let db_path = path.join(default_profile).join("cookies.sqlite");
+                let profile_dir = match default_profile_dir(&path) {
+                    Ok(profile_dir) => {
+                        let db_path = profile_dir.join("cookies.sqlite");
+                        if db_path.exists() {
+                            debug!("Found mozilla path {}", db_path.display());
+                            defaults.push(db_path);
+                            continue;
+                        }
+                        profile_dir
+                    }
+                    Err(err) if err.is::<ShareUnreachable>() => {
+                        unreachable = Some(err);
+                        continue;
+                    }
+                    Err(_) => path.clone(),
+                };
+                if fallback.is_some() {
+                    continue;
+                }
+                let db_path = profile_dir.join("cookies.sqlite");
                 if db_path.exists() {
-                    debug!("Found mozilla path {}", db_path.display());
-                    return Ok(db_path);
+                    fallback = Some(db_path);
+                    continue;
                 }
+                // forks don't always have a profiles.ini or a *.default-release profile
+                fallback = any_mozilla_profile(&path)?;
             }
         }
     }
 
+    // reversed so max_by_key keeps the earliest of equally recent DBs
+    if let Some(db_path) = defaults.into_iter().rev().max_by_key(|db_path| last_modified(db_path)) {
+        return Ok(db_path);
+    }
+    if let Some(db_path) = fallback {
+        debug!("Found mozilla path {}", db_path.display());
+        return Ok(db_path);
+    }
+    if let Some(err) = unreachable {
+        return Err(err);
+    }
     bail!("cant find any brave cookies file")
 }
 
+/// Locates `cookies.sqlite` of every installed channel (release, beta, ESR, developer
+/// edition, nightly) through profiles.ini, they all share one profiles root
+pub fn find_mozilla_channel_paths(browser_config: &BrowserConfig) -> Result<Vec<PathBuf>> {
+    let mut db_paths = vec![];
+    for path in browser_config.data_paths {
+        let channels: &[&str] = browser_config.channels.unwrap_or(&[""]);
+        for channel in channels {
+            let path = path.replace("{channel}", channel);
+            for root in expand_glob_paths(expand_path(path.as_str())?)? {
+                let Ok(profile_dirs) = channel_profile_dirs(&root) else {
+                    continue;
+                };
+                for profile_dir in profile_dirs {
+                    let db_path = profile_dir.join("cookies.sqlite");
+                    if db_path.exists() && !db_paths.contains(&db_path) {
+                        debug!("Found mozilla channel path {}", db_path.display());
+                        db_paths.push(db_path);
+                    }
+                }
+            }
+        }
+    }
+
+    if db_paths.is_empty() {
+        bail!("cant find any firefox profile in profiles.ini");
+    }
+    Ok(db_paths)
+}
+
+/// Locates `cookies.sqlite` of every profile, the ones profiles.ini lists and any
+/// other profile directory holding one, for browsers that spread a user's data over
+/// several profiles (Zen)
+pub fn find_mozilla_profile_paths(browser_config: &BrowserConfig) -> Result<Vec<PathBuf>> {
+    let mut db_paths: Vec<PathBuf> = vec![];
+    for path in browser_config.data_paths {
+        let channels: &[&str] = browser_config.channels.unwrap_or(&[""]);
+        for channel in channels {
+            let path = path.replace("{channel}", channel);
+            for root in expand_glob_paths(expand_path(path.as_str())?)? {
+                let listed = profile_dirs(&root)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|profile_dir| profile_dir.join("cookies.sqlite"))
+                    .filter(|db_path| db_path.exists());
+                let mut found = expand_glob_paths(root.join("*/cookies.sqlite"))?;
+                found.extend(expand_glob_paths(root.join("Profiles/*/cookies.sqlite"))?);
+                for db_path in listed.chain(found) {
+                    if !db_paths.contains(&db_path) {
+                        debug!("Found mozilla profile path {}", db_path.display());
+                        db_paths.push(db_path);
+                    }
+                }
+            }
+        }
+    }
+
+    if db_paths.is_empty() {
+        bail!("cant find any mozilla profile");
+    }
+    Ok(db_paths)
+}
+
+/// `cookies.sqlite` of any profile under `profiles_dir` or its `Profiles` dir, a
+/// `*.default-release` one first, then `*.default` (Thunderbird and older firefox),
+/// then the first by name
+fn any_mozilla_profile(profiles_dir: &Path) -> Result<Option<PathBuf>> {
+    let mut db_paths = expand_glob_paths(profiles_dir.join("*/cookies.sqlite"))?;
+    db_paths.extend(expand_glob_paths(profiles_dir.join("Profiles/*/cookies.sqlite"))?);
+    let with_suffix = |suffix: &str| {
+        db_paths.iter().position(|db_path| {
+            db_path
+                .parent()
+                .and_then(Path::file_name)
+                .is_some_and(|name| name.to_string_lossy().ends_with(suffix))
+        })
+    };
+    let preferred = [".default-release", ".default-default", ".default"]
+        .iter()
+        .find_map(|suffix| with_suffix(suffix));
+    match preferred {
+        Some(index) => Ok(db_paths.into_iter().nth(index)),
+        // LibreWolf and hand made profiles have no known suffix, take the one in use
+        None => Ok(db_paths.into_iter().rev().max_by_key(|db_path| last_modified(db_path))),
+    }
+}
+
+/// Locates `cookies.sqlite` under a directory given by the user, either a profile
+/// directory (as passed to `-profile`) or a profiles root
+pub fn find_mozilla_dir_path(dir: &Path) -> Result<PathBuf> {
+    let db_path = dir.join("cookies.sqlite");
+    if db_path.exists() {
+        return Ok(db_path);
+    }
+    if let Ok(profile_dir) = default_profile_dir(dir) {
+        let db_path = profile_dir.join("cookies.sqlite");
+        if db_path.exists() {
+            return Ok(db_path);
+        }
+    }
+    any_mozilla_profile(dir)?.ok_or_else(|| anyhow!("no mozilla profile in {}", dir.display()))
+}
+
+/// Locates `cookies.sqlite` of a firefox profile given as a directory path, or as the
+/// name of a profile directory ("abcd1234.default-release") under the profiles root
+pub fn find_mozilla_profile_path(browser_config: &BrowserConfig, profile: &str) -> Result<PathBuf> {
+    let profile_path = Path::new(profile);
+    if profile_path.is_dir() {
+        let db_path = profile_path.join("cookies.sqlite");
+        if !db_path.exists() {
+            bail!("no cookies.sqlite in {}", profile_path.display());
+        }
+        return Ok(db_path);
+    }
+    for path in browser_config.data_paths {
+        let channels: &[&str] = browser_config.channels.unwrap_or(&[""]);
+        for channel in channels {
+            let path = path.replace("{channel}", channel);
+            for root in expand_glob_paths(expand_path(path.as_str())?)? {
+                // profiles sit in a Profiles dir on Windows and macOS
+                for db_path in [
+                    root.join(profile).join("cookies.sqlite"),
+                    root.join("Profiles").join(profile).join("cookies.sqlite"),
+                ] {
+                    if db_path.exists() {
+                        debug!("Found mozilla profile path {}", db_path.display());
+                        return Ok(db_path);
+                    }
+                }
+            }
+        }
+    }
+
+    bail!("cant find firefox profile {}", profile)
+}
+
+/// Locates `cookies.sqlite` inside Firefox for Android app data pulled from a device,
+/// either the app directory itself (`files/mozilla`), an `adb backup` extraction
+/// (`apps/<package>/f/mozilla`) or the `mozilla` directory directly
+pub fn find_mozilla_android_path(app_root: &Path) -> Result<PathBuf> {
+    let mut mozilla_dirs = vec![
+        app_root.join("files/mozilla"),
+        app_root.join("f/mozilla"),
+        app_root.to_path_buf(),
+    ];
+    mozilla_dirs.extend(expand_glob_paths(app_root.join("apps/*/f/mozilla"))?);
+
+    for mozilla_dir in mozilla_dirs {
+        if let Ok(profile_dir) = default_profile_dir(&mozilla_dir) {
+            let db_path = profile_dir.join("cookies.sqlite");
+            if db_path.exists() {
+                debug!("Found mozilla android path {}", db_path.display());
+                return Ok(db_path);
+            }
+        }
+        // profiles.ini isn't always part of the pulled data
+        if let Some(db_path) = expand_glob_paths(mozilla_dir.join("*/cookies.sqlite"))?.pop() {
+            debug!("Found mozilla android path {}", db_path.display());
+            return Ok(db_path);
+        }
+    }
+
+    bail!("cant find any firefox android cookies file in {}", app_root.display())
+}
+
+/// Locates the `.binarycookies` files of an app in an unencrypted iTunes/Finder
+/// backup of an iOS device, through the hashed names listed in its `Manifest.db`.
+/// `bundle_id` is an app bundle id (e.g. "com.apple.mobilesafari") or a backup
+/// domain as is (e.g. "HomeDomain").
+pub fn find_ios_backup_cookies(backup_dir: &Path, bundle_id: &str) -> Result<Vec<PathBuf>> {
+    let manifest_path = backup_dir.join("Manifest.db");
+    if !manifest_path.exists() {
+        bail!("no Manifest.db in {}", backup_dir.display());
+    }
+    let connection = sqlite::connect(manifest_path.clone())?;
+    let mut stmt = connection
+        .prepare(
+            "SELECT fileID, relativePath FROM Files WHERE (domain = ?1 OR domain = 'AppDomain-' || ?1) AND relativePath LIKE 'Library/Cookies/%.binarycookies' ORDER BY relativePath"
+        )
+        .map_err(|err| {
+            anyhow!("cant read {} (encrypted backups aren't supported): {}", manifest_path.display(), err)
+        })?;
+    let file_ids: Vec<(String, String)> = stmt
+        .query_map([bundle_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+
+    let mut paths: Vec<PathBuf> = vec![];
+    for (file_id, relative_path) in file_ids {
+        // iOS 10+ backups shard the files by the first two hex digits of their id
+        let sharded = file_id.get(..2).map(|shard| backup_dir.join(shard).join(&file_id));
+        match sharded.into_iter().chain([backup_dir.join(&file_id)]).find(|path| path.exists()) {
+            Some(path) => {
+                debug!("Found {} of {} at {}", relative_path, bundle_id, path.display());
+                paths.push(path);
+            }
+            None => debug!("{} of {} is listed but missing from the backup", relative_path, bundle_id),
+        }
+    }
+    if paths.is_empty() {
+        bail!("no binarycookies for {} in {}", bundle_id, backup_dir.display());
+    }
+    Ok(paths)
+}
+
+#[cfg(target_os = "linux")]
+pub fn find_epiphany_based_paths(browser_config: &BrowserConfig) -> Result<PathBuf> {
+    for path in browser_config.data_paths {
+        let db_path = expand_path(path)?;
+        if db_path.exists() {
+            debug!("Found epiphany path {}", db_path.display());
+            return Ok(db_path);
+        }
+    }
+    bail!("cant find any epiphany cookies file")
+}
+
 #[cfg(target_os = "macos")]
 pub fn find_safari_based_paths(browser_config: &BrowserConfig) -> Result<PathBuf> {
     for path in browser_config.data_paths {
         // base paths
-        let channels: &[&str] = &browser_config.channels.as_deref().unwrap_or(&[""]);
+        let channels: &[&str] = browser_config.channels.unwrap_or(&[""]);
         for channel in channels {
             // channels
-            let path = path.replace("{channel}", &channel);
+            let path = path.replace("{channel}", channel);
             let safari_path = expand_path(path.as_str())?;
             let glob_paths = expand_glob_paths(safari_path)?;
             for path in glob_paths {
@@ -170,11 +668,11 @@ pub fn find_safari_based_paths(browser_config: &BrowserConfig) -> Result<PathBuf
 pub fn find_ie_based_paths(browser_config: &BrowserConfig) -> Result<PathBuf> {
     for path in browser_config.data_paths {
         // base paths
-        let channels: &[&str] = &browser_config.channels.as_deref().unwrap_or(&[""]);
+        let channels: &[&str] = browser_config.channels.unwrap_or(&[""]);
         for channel in channels {
             // channels
 
-            let path = path.replace("{channel}", &channel);
+            let path = path.replace("{channel}", channel);
             let path = expand_path(path.as_str())?;
             let glob_paths = expand_glob_paths(path)?;
             for path in glob_paths {
@@ -0,0 +1,88 @@
+use anyhow::Result;
+use serde::Serialize;
+use sha2::{ Digest, Sha256 };
+use std::fs;
+use std::io::Write;
+use std::path::{ Path, PathBuf };
+use std::time::{ SystemTime, UNIX_EPOCH };
+use tempfile::NamedTempFile;
+
+/// Which file state produced a read, for chain-of-custody records
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Provenance {
+    pub source_path: PathBuf,
+    /// Size in bytes of the copy that was parsed
+    pub size: u64,
+    /// Unix seconds of the source file's last modification
+    pub modified: Option<u64>,
+    /// Hex encoded SHA-256 of the copy that was parsed
+    pub sha256: String,
+    /// Unix seconds when the copy was taken
+    pub extracted_at: u64,
+}
+
+/// Private copy of a cookies DB, removed when dropped
+#[derive(Debug)]
+pub struct Snapshot {
+    file: NamedTempFile,
+    pub provenance: Provenance,
+}
+
+impl Snapshot {
+    pub fn path(&self) -> &Path {
+        self.file.path()
+    }
+}
+
+fn unix_seconds(time: SystemTime) -> Option<u64> {
+    time.duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|duration| duration.as_secs())
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Reads `source` and records the provenance of the bytes read, for stores
+/// that are parsed from memory
+pub fn read(source: &Path) -> Result<(Vec<u8>, Provenance)> {
+    let modified = fs
+        ::metadata(source)?
+        .modified()
+        .ok()
+        .and_then(unix_seconds);
+    let bytes = fs::read(source)?;
+    let provenance = Provenance {
+        source_path: source.to_path_buf(),
+        size: bytes.len() as u64,
+        modified,
+        sha256: sha256_hex(&bytes),
+        extracted_at: unix_seconds(SystemTime::now()).unwrap_or_default(),
+    };
+    Ok((bytes, provenance))
+}
+
+/// Copies `source` to a temp file and records its provenance.
+/// The hash is taken from the same bytes that are written to the copy,
+/// so it always matches what gets parsed even if the live file changes.
+/// The copy holds decryptable cookies: it gets a random name, is created
+/// exclusively (a planted file or symlink fails the copy) and on unix is
+/// readable by the current user only.
+pub fn snapshot(source: &Path) -> Result<Snapshot> {
+    let (bytes, provenance) = read(source)?;
+
+    let suffix = format!("-{}", source.file_name().unwrap_or_default().to_string_lossy());
+    let mut file = tempfile::Builder::new().prefix("rookie-").suffix(&suffix).tempfile()?;
+    file.write_all(&bytes)?;
+    file.flush()?;
+    Ok(Snapshot { file, provenance })
+}
+
+/// Snapshot of `db_path` when `provenance` is set, the readers parse it in place of the live DB
+pub(crate) fn snapshot_if(provenance: bool, db_path: &Path) -> Result<Option<Snapshot>> {
+    provenance.then(|| snapshot(db_path)).transpose()
+}
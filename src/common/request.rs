@@ -0,0 +1,229 @@
+use crate::common::enums::{ Cookie, SameSite };
+use anyhow::{ anyhow, Result };
+use std::time::{ SystemTime, UNIX_EPOCH };
+use url::Url;
+
+/// Second level labels that are registered under like a TLD (`example.co.uk`)
+const SECOND_LEVEL_SUFFIXES: &[&str] = &["co", "com", "net", "org", "ac", "edu", "gov", "ne", "or"];
+
+/// Where a request goes and what started it, to decide which cookies go with it.
+/// Built with [`RequestContext::new`], new fields can be added in a minor release.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct RequestContext {
+    pub url: Url,
+    /// Page that started the request, `None` when the user did (address bar, bookmark)
+    pub initiator: Option<Url>,
+    /// Whether the request loads the top-level document rather than a subresource
+    pub top_level_navigation: bool,
+    /// HTTP method, only safe methods (GET, HEAD, ...) carry `SameSite=Lax` cookies cross-site
+    pub method: String,
+    /// Top-level page the request is made from, `None` when `url` is the top-level
+    /// document itself. Partitioned cookies only go with requests under their top level site
+    pub top_frame: Option<Url>,
+}
+
+impl RequestContext {
+    /// A user initiated top-level GET navigation to `url`
+    pub fn new(url: &str) -> Result<Self> {
+        Ok(RequestContext {
+            url: Url::parse(url)?,
+            initiator: None,
+            top_level_navigation: true,
+            method: "GET".to_string(),
+            top_frame: None,
+        })
+    }
+
+    fn is_safe_method(&self) -> bool {
+        ["GET", "HEAD", "OPTIONS", "TRACE"].iter().any(|m| m.eq_ignore_ascii_case(&self.method))
+    }
+
+    /// Whether a cookie partitioned on `partition_key` ("https://example.com") belongs
+    /// to the top level site of the request
+    fn in_partition(&self, partition_key: &str) -> bool {
+        let top_frame = self.top_frame.as_ref().unwrap_or(&self.url);
+        match Url::parse(partition_key) {
+            Ok(partition) =>
+                partition.scheme() == top_frame.scheme() &&
+                    partition.host_str().and_then(registrable_domain) ==
+                        top_frame.host_str().and_then(registrable_domain),
+            Err(_) => false,
+        }
+    }
+
+    /// Schemeful same-site: same scheme and registrable domain as the initiator
+    pub fn is_same_site(&self) -> bool {
+        match &self.initiator {
+            None => true,
+            Some(initiator) =>
+                initiator.scheme() == self.url.scheme() &&
+                    initiator.host_str().and_then(registrable_domain) ==
+                        self.url.host_str().and_then(registrable_domain),
+        }
+    }
+}
+
+/// Registrable domain of a host, approximated without the public suffix list
+/// as the last two labels, or three below common second level suffixes (`co.uk`)
+pub fn registrable_domain(host: &str) -> Option<String> {
+    let host = host.trim_start_matches('.').trim_end_matches('.').to_lowercase();
+    if host.is_empty() {
+        return None;
+    }
+    if host.parse::<std::net::IpAddr>().is_ok() {
+        return Some(host);
+    }
+    let labels: Vec<&str> = host.split('.').collect();
+    let mut keep = 2;
+    if labels.len() >= 3 {
+        let tld = labels[labels.len() - 1];
+        let second = labels[labels.len() - 2];
+        if tld.len() == 2 && SECOND_LEVEL_SUFFIXES.contains(&second) {
+            keep = 3;
+        }
+    }
+    Some(labels[labels.len().saturating_sub(keep)..].join("."))
+}
+
+/// Registrable domain of a host by the public suffix list, e.g. `user.github.io` for
+/// `www.user.github.io`. Hosts without one (a suffix itself, `localhost`) are kept as is.
+#[cfg(feature = "publicsuffix")]
+pub fn registrable_domain_psl(host: &str, list: &publicsuffix::List) -> Option<String> {
+    use publicsuffix::Psl;
+    let host = host.trim_start_matches('.').trim_end_matches('.').to_lowercase();
+    if host.is_empty() {
+        return None;
+    }
+    if host.parse::<std::net::IpAddr>().is_ok() {
+        return Some(host);
+    }
+    match list.domain(host.as_bytes()) {
+        Some(domain) => Some(String::from_utf8_lossy(domain.as_bytes()).to_string()),
+        None => Some(host),
+    }
+}
+
+fn domain_matches(cookie_domain: &str, host_only: bool, host: &str) -> bool {
+    let domain = cookie_domain.trim_start_matches('.');
+    host == domain || (!host_only && host.ends_with(&format!(".{}", domain)))
+}
+
+/// Whether a cookie set from `cookie_port` goes to a request on `request_port`.
+/// An unknown port on either side matches, and the default ports 80 and 443 match
+/// each other since a site moving to https keeps its cookies. Any other port only
+/// matches itself, so `localhost:3000` and `localhost:8080` are kept apart.
+pub fn port_matches(cookie_port: Option<u16>, request_port: Option<u16>) -> bool {
+    const DEFAULT_PORTS: [u16; 2] = [80, 443];
+    match (cookie_port, request_port) {
+        (Some(cookie_port), Some(request_port)) =>
+            cookie_port == request_port ||
+                (DEFAULT_PORTS.contains(&cookie_port) && DEFAULT_PORTS.contains(&request_port)),
+        _ => true,
+    }
+}
+
+/// Path-match of RFC 6265 section 5.1.4, case sensitive: `/foo` matches `/foo`,
+/// `/foo/` and `/foo/bar` but not `/foobar`. A query or fragment on `request_path`
+/// is ignored and an empty path on either side counts as `/`.
+pub fn path_matches(cookie_path: &str, request_path: &str) -> bool {
+    let request_path = request_path.split(['?', '#']).next().unwrap_or_default();
+    let cookie_path = if cookie_path.is_empty() { "/" } else { cookie_path };
+    let request_path = if request_path.is_empty() { "/" } else { request_path };
+    match request_path.strip_prefix(cookie_path) {
+        Some(rest) => rest.is_empty() || cookie_path.ends_with('/') || rest.starts_with('/'),
+        None => false,
+    }
+}
+
+/// Whether `cookie_path` is `scope` or below it, for the `path` of [`crate::ReadOptions`].
+/// No scope keeps every path
+pub(crate) fn path_in_scope(cookie_path: &str, scope: Option<&str>) -> bool {
+    scope.map_or(true, |scope| path_matches(scope, cookie_path))
+}
+
+impl Cookie {
+    /// Whether the cookie applies to `request_path`, see [`path_matches`]
+    pub fn path_matches(&self, request_path: &str) -> bool {
+        path_matches(&self.path, request_path)
+    }
+
+    /// Whether a browser would attach this cookie to the request described by `ctx`,
+    /// following the domain, path, Secure and SameSite rules and [`port_matches`].
+    /// Partitioned cookies also need `ctx` to be under their top level site.
+    ///
+    /// Cookies without a SameSite attribute are treated as Lax with Chromium's
+    /// "Lax-allowing-unsafe" exception, which also sends them on cross-site top-level
+    /// POST navigations. Chromium limits that to cookies younger than two minutes,
+    /// the cookie age isn't known here so it applies to all of them.
+    pub fn sent_in(&self, ctx: &RequestContext) -> bool {
+        let Some(host) = ctx.url.host_str() else {
+            return false;
+        };
+        if !domain_matches(&self.domain, self.host_only, host) || !self.path_matches(ctx.url.path()) {
+            return false;
+        }
+        if !port_matches(self.port, ctx.url.port_or_known_default()) {
+            return false;
+        }
+        if self.partition_key.as_ref().is_some_and(|partition_key| !ctx.in_partition(partition_key)) {
+            return false;
+        }
+        if self.secure && ctx.url.scheme() != "https" && host != "localhost" {
+            return false;
+        }
+        if let Some(expires) = self.expires {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+            if expires <= now {
+                return false;
+            }
+        }
+        if ctx.is_same_site() {
+            return true;
+        }
+        match self.same_site {
+            SameSite::None => true,
+            SameSite::Lax => ctx.top_level_navigation && ctx.is_safe_method(),
+            SameSite::Strict => false,
+            // Lax-allowing-unsafe
+            SameSite::Unspecified => ctx.top_level_navigation,
+        }
+    }
+}
+
+/// Builds the `Cookie` header value a browser would send for the request, longer
+/// paths first and then older cookies first as RFC 6265 recommends. Cookies without
+/// a creation time go after the others of the same path length. Values are sent as stored.
+pub fn header_for_request(cookies: &[Cookie], ctx: &RequestContext) -> Result<String> {
+    if ctx.url.host_str().is_none() {
+        return Err(anyhow!("{} has no host", ctx.url));
+    }
+    let mut sent: Vec<&Cookie> = cookies
+        .iter()
+        .filter(|cookie| cookie.sent_in(ctx))
+        .collect();
+    sent.sort_by_key(|cookie| (std::cmp::Reverse(cookie.path.len()), cookie.creation.unwrap_or(u64::MAX)));
+    Ok(
+        sent
+            .iter()
+            .map(|cookie| cookie.to_minimal().header_pair())
+            .collect::<Vec<_>>()
+            .join("; ")
+    )
+}
+
+/// `Cookie` header value for a user initiated top-level GET navigation to `url`,
+/// see [`header_for_request`] for other requests
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let cookies = rookie::firefox(Some(vec!["example.com"])).unwrap_or_default();
+///     let header = rookie::to_cookie_header(&cookies, "https://www.example.com/");
+/// }
+/// ```
+pub fn to_cookie_header(cookies: &[Cookie], url: &str) -> Result<String> {
+    header_for_request(cookies, &RequestContext::new(url)?)
+}
@@ -1,14 +1,18 @@
 #[cfg(unix)]
+use crate::common::enums::BrowserConfig;
+#[cfg(unix)]
 use anyhow::{ anyhow, bail, Result };
+#[cfg(unix)]
+use std::{ collections::HashMap, sync::Mutex };
 
 cfg_if::cfg_if! {
     if #[cfg(target_os = "linux")] {
         use crate::common::utils;
         use crate::config;
-        use std::{ collections::HashMap, sync::Arc };
+        use std::sync::Arc;
         use zbus::{ blocking::Connection, zvariant::Value, zvariant::ObjectPath, Message };
 
-        fn libsecret_call<'a, T>(
+        fn libsecret_call<T>(
             connection: &Connection,
             method: &str,
             args: T
@@ -24,16 +28,18 @@ cfg_if::cfg_if! {
             )
         }
 
-        fn kwallet_call<'a, T>(
+        /// `service` is the wallet daemon, `kwalletd5` or `kwalletd6`
+        fn kwallet_call<T>(
             connection: &Connection,
+            service: &str,
             method: &str,
             args: T
         ) -> zbus::Result<Arc<Message>>
             where T: serde::ser::Serialize + zvariant::DynamicType
         {
             connection.call_method(
-                Some("org.kde.kwalletd5"),
-                "/modules/kwalletd5",
+                Some(format!("org.kde.{}", service).as_str()),
+                format!("/modules/{}", service).as_str(),
                 Some("org.kde.KWallet"),
                 method,
                 &args
@@ -41,22 +47,52 @@ cfg_if::cfg_if! {
         }
 
         pub fn get_passwords(os_crypt_name: &str) -> Result<Vec<String>> {
-            // Attempt to get the password from libsecret
-            let mut passwords: Vec<String> = vec![];
-            for schema in [
-                "chrome_libsecret_os_crypt_password_v2",
-                "chrome_libsecret_os_crypt_password_v1",
-            ] {
-                if let Ok(libsecret_pass) = get_password_libsecret(schema, os_crypt_name) {
-                    passwords.push(libsecret_pass);
+            LinuxSecrets { libsecret: true, kwallet: true, kwallet6: true }.get_passwords(os_crypt_name)
+        }
+
+        /// Reads passwords from the selected keyring backends only,
+        /// none of them for chromium's basic text storage
+        #[derive(Debug, Clone, Copy, Default)]
+        pub struct LinuxSecrets {
+            pub libsecret: bool,
+            /// KWallet of Plasma 5, `kwalletd5`
+            pub kwallet: bool,
+            /// KWallet of Plasma 6, `kwalletd6`
+            pub kwallet6: bool,
+        }
+
+        impl LinuxSecrets {
+            fn get_passwords(&self, os_crypt_name: &str) -> Result<Vec<String>> {
+                let mut passwords: Vec<String> = vec![];
+                // Attempt to get the password from libsecret
+                if self.libsecret {
+                    for schema in [
+                        "chrome_libsecret_os_crypt_password_v2",
+                        "chrome_libsecret_os_crypt_password_v1",
+                    ] {
+                        if let Ok(libsecret_pass) = get_password_libsecret(schema, os_crypt_name) {
+                            passwords.push(libsecret_pass);
+                        }
+                    }
                 }
+                // Attempt to get the password from kdewallet
+                for (enabled, service) in [(self.kwallet, "kwalletd5"), (self.kwallet6, "kwalletd6")] {
+                    if !enabled {
+                        continue;
+                    }
+                    if let Ok(password) = get_password_kdewallet(service, os_crypt_name) {
+                        passwords.push(password);
+                    }
+                }
+
+                Ok(passwords)
             }
-            // Attempt to get the password from kdewallet
-            if let Ok(password) = get_password_kdewallet(os_crypt_name) {
-                passwords.push(password);
-            }
+        }
 
-            Ok(passwords)
+        impl SecretsProvider for LinuxSecrets {
+            fn passwords(&self, config: &BrowserConfig) -> Result<Vec<String>> {
+                self.get_passwords(config.os_crypt_name.unwrap_or(""))
+            }
         }
 
         fn get_password_libsecret(schema: &str, crypt_name: &str) -> Result<String> {
@@ -88,28 +124,28 @@ cfg_if::cfg_if! {
             Ok(String::from_utf8(secret.clone())?)
         }
 
-        fn get_password_kdewallet(crypt_name: &str) -> Result<String> {
+        fn get_password_kdewallet(service: &str, crypt_name: &str) -> Result<String> {
             let connection = Connection::session()?;
             let folder = format!("{} Keys", utils::capitalize(crypt_name));
             let key = format!("{} Safe Storage", utils::capitalize(crypt_name));
 
-            let m = kwallet_call(&connection, "networkWallet", ())?;
+            let m = kwallet_call(&connection, service, "networkWallet", ())?;
             let network_wallet: String = m.body()?;
 
-            let m = kwallet_call(&connection, "open", (
+            let m = kwallet_call(&connection, service, "open", (
                 network_wallet.clone(),
-                0 as i64,
+                0_i64,
                 config::APP_ID,
             ))?;
             let handle: i32 = m.body()?;
-            let m = kwallet_call(&connection, "readPassword", (
+            let m = kwallet_call(&connection, service, "readPassword", (
                 handle,
                 folder,
                 key,
                 config::APP_ID,
             ))?;
             let password: String = m.body()?;
-            let m = kwallet_call(&connection, "close", (network_wallet, false))?;
+            let m = kwallet_call(&connection, service, "close", (network_wallet, false))?;
             let close_ok: i32 = m.body()?;
             if close_ok != 1 {
                 bail!("Close failed");
@@ -124,17 +160,15 @@ cfg_if::cfg_if! {
             osx_key_user: &str
         ) -> Result<String> {
             let cmd = Command::new("/usr/bin/security")
-                .args(
-                    &[
-                        "-q",
-                        "find-generic-password",
-                        "-w",
-                        "-a",
-                        osx_key_user,
-                        "-s",
-                        osx_key_service,
-                    ]
-                )
+                .args([
+                    "-q",
+                    "find-generic-password",
+                    "-w",
+                    "-a",
+                    osx_key_user,
+                    "-s",
+                    osx_key_service,
+                ])
                 .output();
 
             match cmd {
@@ -151,3 +185,80 @@ cfg_if::cfg_if! {
         }
     }
 }
+
+/// Source of the passwords chromium cookie keys are derived from
+/// (libsecret/kwallet on Linux, the keychain on macOS), swappable for tests
+pub trait SecretsProvider: std::fmt::Debug + Send + Sync {
+    fn passwords(&self, config: &BrowserConfig) -> Result<Vec<String>>;
+}
+
+/// Reads passwords from the OS keystores, which may show a prompt
+#[derive(Debug, Default)]
+pub struct OsSecrets;
+
+impl SecretsProvider for OsSecrets {
+    fn passwords(&self, config: &BrowserConfig) -> Result<Vec<String>> {
+        cfg_if::cfg_if! {
+            if #[cfg(target_os = "linux")] {
+                get_passwords(config.os_crypt_name.unwrap_or(""))
+            } else {
+                let key_service = config.osx_key_service.ok_or(anyhow!("missing osx_key_service"))?;
+                let key_user = config.osx_key_user.ok_or(anyhow!("missing osx_key_user"))?;
+                Ok(vec![get_osx_keychain_password(key_service, key_user)?])
+            }
+        }
+    }
+}
+
+/// Returned instead of prompting once the prompt budget of a read is used up
+#[derive(Debug)]
+pub struct InteractionRequired(pub String);
+
+impl std::fmt::Display for InteractionRequired {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "reading the {} key requires user interaction", self.0)
+    }
+}
+
+impl std::error::Error for InteractionRequired {}
+
+/// Asks the inner provider at most once per keystore item, so forks sharing an item
+/// and every profile of a browser reuse one answer, and stops asking after `max_prompts`
+#[derive(Debug)]
+pub struct CachedSecrets<'a> {
+    inner: &'a dyn SecretsProvider,
+    max_prompts: Option<usize>,
+    cache: Mutex<HashMap<String, Vec<String>>>,
+}
+
+impl<'a> CachedSecrets<'a> {
+    pub fn new(inner: &'a dyn SecretsProvider, max_prompts: Option<usize>) -> Self {
+        CachedSecrets { inner, max_prompts, cache: Mutex::default() }
+    }
+
+    fn item(config: &BrowserConfig) -> String {
+        format!(
+            "{}/{}/{}",
+            config.os_crypt_name.unwrap_or_default(),
+            config.osx_key_service.unwrap_or_default(),
+            config.osx_key_user.unwrap_or_default()
+        )
+    }
+}
+
+impl SecretsProvider for CachedSecrets<'_> {
+    fn passwords(&self, config: &BrowserConfig) -> Result<Vec<String>> {
+        let item = Self::item(config);
+        let mut cache = self.cache.lock().unwrap_or_else(|err| err.into_inner());
+        if let Some(passwords) = cache.get(&item) {
+            return Ok(passwords.clone());
+        }
+        if self.max_prompts.is_some_and(|max| cache.len() >= max) {
+            bail!(InteractionRequired(config.os_crypt_name.unwrap_or_default().to_string()));
+        }
+        // a denied or failed lookup is cached too, it would only prompt again
+        let passwords = self.inner.passwords(config).unwrap_or_default();
+        cache.insert(item, passwords.clone());
+        Ok(passwords)
+    }
+}
@@ -1,14 +1,196 @@
-use anyhow::{ anyhow, Result };
-use rusqlite::{ self, Connection, OpenFlags };
-use std::path::PathBuf;
-use url::Url;
+use crate::common::enums::{ DomainMatch, NameFilter, Persistence };
+use crate::common::utils;
+use anyhow::Result;
+use log::warn;
+use rusqlite::{ self, types::Value, Connection, OpenFlags };
+use std::path::{ Path, PathBuf };
+
+cfg_if::cfg_if! {
+    if #[cfg(target_os = "windows")] {
+        use crate::common::paths;
+        use std::ffi::OsStr;
+        use std::os::windows::ffi::OsStrExt;
+    } else {
+        use anyhow::anyhow;
+        use url::Url;
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn file_uri(path: &Path) -> Result<String> {
+    // canonical paths are verbatim (`\\?\`) on Windows, which sqlite hands to CreateFileW
+    // untouched while a file:/// url would go through path normalization again
+    let path = paths::extended_length_path(path).canonicalize()?;
+    let escaped: String = wtf8_bytes(path.as_os_str())
+        .into_iter()
+        .map(|b| {
+            if b.is_ascii_alphanumeric() || b"-._~/\\:".contains(&b) {
+                (b as char).to_string()
+            } else {
+                format!("%{:02X}", b)
+            }
+        })
+        .collect();
+    Ok(format!("file:{}", escaped))
+}
+
+/// UTF-8 bytes of a Windows path, with unpaired surrogates encoded the same way
+/// instead of failing or being replaced by U+FFFD
+#[cfg(target_os = "windows")]
+fn wtf8_bytes(path: &OsStr) -> Vec<u8> {
+    let mut bytes = vec![];
+    for unit in char::decode_utf16(path.encode_wide()) {
+        match unit {
+            Ok(c) => bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+            Err(err) => {
+                let surrogate = err.unpaired_surrogate();
+                bytes.extend_from_slice(
+                    &[
+                        0xe0 | ((surrogate >> 12) as u8),
+                        0x80 | (((surrogate >> 6) & 0x3f) as u8),
+                        0x80 | ((surrogate & 0x3f) as u8),
+                    ]
+                );
+            }
+        }
+    }
+    bytes
+}
+
+#[cfg(not(target_os = "windows"))]
+fn file_uri(path: &Path) -> Result<String> {
+    let url = Url::from_file_path(path.canonicalize()?).or(Err(anyhow!("Error opening connection")))?;
+    Ok(url.to_string())
+}
+
+/// `WHERE` condition on `column` for `names` and its parameters, `None` when it
+/// matches every cookie. `instr` rather than `LIKE` since names can hold `%` and `_`
+pub(crate) fn name_condition(column: &str, names: &NameFilter) -> Option<(String, Vec<Value>)> {
+    if names.names.is_empty() {
+        return None;
+    }
+    let condition = if names.prefix {
+        let prefixes = vec![format!("instr({}, ?) = 1", column); names.names.len()];
+        format!("({})", prefixes.join(" OR "))
+    } else {
+        format!("{} IN ({})", column, vec!["?"; names.names.len()].join(", "))
+    };
+    let params = names.names
+        .iter()
+        .map(|name| Value::Text(name.clone()))
+        .collect();
+    Some((condition, params))
+}
+
+/// `WHERE` condition on the host `column` for a `domains` filter entry and its
+/// parameters, matching on label boundaries like [`utils::domain_matches`]. The GLOB
+/// keeps IPv4 hosts (digits and dots only) to exact matches
+pub(crate) fn domain_condition(column: &str, domain: &str, mode: DomainMatch) -> (String, Vec<Value>) {
+    let domain = utils::normalize_domain(domain);
+    let dotted = format!(".{}", domain);
+    match mode {
+        DomainMatch::Suffix if utils::is_ip_address(&domain) =>
+            (format!("{} = ?", column), vec![Value::Text(domain)]),
+        DomainMatch::Exact =>
+            (format!("({0} = ? OR {0} = ?)", column), vec![Value::Text(domain), Value::Text(dotted)]),
+        DomainMatch::Suffix =>
+            (
+                format!("({0} = ? OR (substr({0}, -?) = ? AND {0} GLOB '*[^0-9.]*'))", column),
+                vec![Value::Text(domain), Value::Integer(dotted.len() as i64), Value::Text(dotted)],
+            ),
+    }
+}
+
+/// `WHERE` condition on the path `column` for the `path` of [`crate::ReadOptions`] and its
+/// parameters, like [`crate::common::request::path_matches`] with `scope` as the cookie
+/// path. `None` when every path is in scope. `substr` rather than `LIKE` since paths can
+/// hold `%` and `_`
+pub(crate) fn path_condition(column: &str, scope: Option<&str>) -> Option<(String, Vec<Value>)> {
+    let scope = scope.filter(|scope| !scope.is_empty() && *scope != "/")?;
+    let prefix = if scope.ends_with('/') { scope.to_string() } else { format!("{}/", scope) };
+    Some((
+        format!("({0} = ? OR substr({0}, 1, ?) = ?)", column),
+        vec![Value::Text(scope.to_string()), Value::Integer(prefix.chars().count() as i64), Value::Text(prefix)],
+    ))
+}
+
+/// `WHERE` conditions on the Secure and HttpOnly flag columns for the `secure_only` and
+/// `http_only` of [`crate::ReadOptions`]
+pub(crate) fn flag_conditions(secure: &str, http_only: &str, secure_only: bool, only_http: Option<bool>) -> Vec<String> {
+    let mut conditions = vec![];
+    if secure_only {
+        conditions.push(format!("{} != 0", secure));
+    }
+    if let Some(only_http) = only_http {
+        conditions.push(format!("{} {} 0", http_only, if only_http { "!=" } else { "=" }));
+    }
+    conditions
+}
+
+/// `LIMIT` and `OFFSET` clause for the `limit` and `offset` of [`crate::ReadOptions`],
+/// empty when not paging
+pub(crate) fn limit_clause(limit: Option<usize>, offset: usize) -> String {
+    match (limit, offset) {
+        (None, 0) => String::new(),
+        // -1 is no limit
+        (limit, offset) => format!(" LIMIT {} OFFSET {}", limit.map_or(-1, |limit| i64::try_from(limit).unwrap_or(i64::MAX)), offset),
+    }
+}
+
+/// `WHERE` condition for `persistence` given the condition that holds for session
+/// cookies in that DB, `None` when every cookie is read
+pub(crate) fn persistence_condition(session: &str, persistence: Persistence) -> Option<String> {
+    match persistence {
+        Persistence::All => None,
+        Persistence::SessionOnly => Some(format!("({})", session)),
+        Persistence::PersistentOnly => Some(format!("NOT ({})", session)),
+    }
+}
 
 pub fn connect(path: PathBuf) -> Result<Connection> {
     let flags = OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_URI;
-    let conn_str = format!(
-        "{}?mode=ro&immutable=1",
-        Url::from_file_path(&path.canonicalize()?).or(Err(anyhow!("Error opening connection")))?
-    );
+    let conn_str = format!("{}?mode=ro&immutable=1", file_uri(&path)?);
     let connection = rusqlite::Connection::open_with_flags(&conn_str, flags)?;
     Ok(connection)
 }
+
+/// Returned in strict mode when a cookies DB has a schema version newer than the
+/// ones rookie was tested with, instead of risking misparsed cookies
+#[derive(Debug)]
+pub struct UnsupportedSchema {
+    pub path: PathBuf,
+    pub version: i64,
+    /// Newest version rookie was tested with
+    pub max: i64,
+}
+
+impl std::fmt::Display for UnsupportedSchema {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} has schema version {}, newer than the tested {}",
+            self.path.display(),
+            self.version,
+            self.max
+        )
+    }
+}
+
+impl std::error::Error for UnsupportedSchema {}
+
+/// Fails with [`UnsupportedSchema`] in strict mode when `version` is newer than `max`,
+/// otherwise only warns
+pub fn check_schema(path: &Path, version: Option<i64>, max: i64, strict: bool) -> Result<()> {
+    let Some(version) = version else {
+        return Ok(());
+    };
+    if version <= max {
+        return Ok(());
+    }
+    let unsupported = UnsupportedSchema { path: path.to_path_buf(), version, max };
+    if strict {
+        return Err(unsupported.into());
+    }
+    warn!("{}, reading it anyway", unsupported);
+    Ok(())
+}
@@ -1,12 +1,122 @@
-pub fn some_domain_in_host(domains: Option<Vec<&str>>, host: &str) -> bool {
-    if let Some(strings) = domains {
-        for d in strings {
-            if host.contains(d) {
-                return true;
-            }
-        }
+use anyhow::{ anyhow, Result };
+use crate::common::enums::{ Cookie, DomainMatch, Domains, SortBy };
+use std::collections::HashSet;
+
+/// Splits a `host:port` domain filter, e.g. `localhost:3000` or `[::1]:8080`.
+/// IPv6 literals keep their brackets like Chromium's `host_key`, a bare IPv6
+/// literal (`::1`) has no port.
+pub fn split_port(domain: &str) -> (&str, Option<u16>) {
+    let split = match domain.rfind(':') {
+        // `[::1]:3000`, the colon has to follow the closing bracket
+        Some(index) if domain.starts_with('[') => domain[..index].ends_with(']').then_some(index),
+        // more than one colon is a bare IPv6 literal
+        Some(index) if !domain[..index].contains(':') => Some(index),
+        _ => None,
+    };
+    match split.and_then(|index| Some((index, domain[index + 1..].parse().ok()?))) {
+        Some((index, port)) => (&domain[..index], Some(port)),
+        None => (domain, None),
+    }
+}
+
+/// Whether any domain filter has a port
+pub fn has_port(domains: Option<&[String]>) -> bool {
+    domains.into_iter().flatten().any(|domain| split_port(domain).1.is_some())
+}
+
+/// Domain filters with their ports dropped, for stores that don't record the port
+pub fn without_ports(domains: Option<&[String]>) -> Option<Vec<String>> {
+    domains.map(|domains| {
+        domains
+            .iter()
+            .map(|domain| split_port(domain).0.to_string())
+            .collect()
+    })
+}
+
+/// Drops cookies with the same domain, port, path, name and partition key as an
+/// earlier one, an empty name is a key like any other. A partitioned (CHIPS) cookie
+/// is a different cookie in every top-level site it's set under
+pub fn dedup_cookies(cookies: Vec<Cookie>) -> Vec<Cookie> {
+    let mut seen = HashSet::new();
+    cookies
+        .into_iter()
+        .filter(|cookie| {
+            seen.insert((
+                cookie.domain.clone(),
+                cookie.port,
+                cookie.path.clone(),
+                cookie.name.clone(),
+                cookie.partition_key.clone(),
+            ))
+        })
+        .collect()
+}
+
+/// Longest prefix of `s` that fits in `max` bytes without splitting a character,
+/// to shorten values and domains for display
+pub fn truncate_utf8(s: &str, max: usize) -> &str {
+    if s.len() <= max {
+        return s;
     }
-    false
+    let end = (0..=max).rev().find(|index| s.is_char_boundary(*index)).unwrap_or(0);
+    &s[..end]
+}
+
+/// Same as [`truncate_utf8`] for bytes that may not be UTF-8, invalid sequences
+/// become U+FFFD before truncating
+pub fn truncate_bytes_lossy(bytes: &[u8], max: usize) -> String {
+    truncate_utf8(&String::from_utf8_lossy(bytes), max).to_string()
+}
+
+/// Lowercase punycode form of a domain, without the leading dot of domain cookies
+/// or a trailing dot. IP addresses are only lowercased.
+pub fn normalize_domain(domain: &str) -> String {
+    let domain = domain.trim_start_matches('.').trim_end_matches('.');
+    match url::Host::parse(domain) {
+        Ok(url::Host::Domain(ascii)) => ascii,
+        _ => domain.to_lowercase(),
+    }
+}
+
+/// Sorts `cookies` by `sort_by` and keeps the `limit` ones after `offset`, by domain
+/// when paging without an order, for the stores that can't do it while reading
+pub(crate) fn sort_and_page(cookies: &mut Vec<Cookie>, sort_by: Option<SortBy>, offset: usize, limit: Option<usize>) {
+    let paginates = limit.is_some() || offset > 0;
+    if let Some(sort_by) = sort_by.or(paginates.then_some(SortBy::Domain)) {
+        sort_by.sort(cookies);
+    }
+    cookies.drain(..offset.min(cookies.len()));
+    if let Some(limit) = limit {
+        cookies.truncate(limit);
+    }
+}
+
+/// Whether a host is an IPv4 or IPv6 address, with or without brackets
+pub fn is_ip_address(host: &str) -> bool {
+    host.trim_start_matches('[').trim_end_matches(']').parse::<std::net::IpAddr>().is_ok()
+}
+
+/// Whether the domain of a cookie matches a `domains` filter entry, see [`DomainMatch`].
+/// IP addresses have no subdomains and only match exactly.
+pub fn domain_matches(cookie_domain: &str, filter: &str, mode: DomainMatch) -> bool {
+    let host = normalize_domain(cookie_domain);
+    let filter = normalize_domain(filter);
+    match mode {
+        DomainMatch::Suffix if !is_ip_address(&host) && !is_ip_address(&filter) =>
+            host == filter || host.ends_with(&format!(".{}", filter)),
+        _ => host == filter,
+    }
+}
+
+/// Whether the domain of a cookie matches any entry of a `domains` filter
+pub fn some_domain_matches(domains: Option<&[String]>, host: &str, mode: DomainMatch) -> bool {
+    domains.into_iter().flatten().any(|domain| domain_matches(host, domain, mode))
+}
+
+/// Same as [`some_domain_matches`] with [`DomainMatch::Suffix`]
+pub fn some_domain_in_host(domains: impl Into<Domains>, host: &str) -> bool {
+    some_domain_matches(domains.into().as_deref(), host, DomainMatch::Suffix)
 }
 
 #[cfg(target_os = "linux")]
@@ -17,3 +127,82 @@ pub fn capitalize(s: &str) -> String {
         Some(f) => f.to_uppercase().collect::<String>() + c.as_str(),
     }
 }
+
+/// Decodes a text file written by any chromium fork: UTF-8 with or without a BOM,
+/// or UTF-16 with a BOM (also little endian without one, detected from the NUL bytes).
+/// Invalid sequences become U+FFFD.
+pub fn decode_text(bytes: &[u8]) -> String {
+    let utf16 = |bytes: &[u8], from_bytes: fn([u8; 2]) -> u16| {
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|pair| from_bytes([pair[0], pair[1]]))
+            .collect();
+        String::from_utf16_lossy(&units)
+    };
+    match bytes {
+        [0xef, 0xbb, 0xbf, rest @ ..] => String::from_utf8_lossy(rest).into_owned(),
+        [0xff, 0xfe, rest @ ..] => utf16(rest, u16::from_le_bytes),
+        [0xfe, 0xff, rest @ ..] => utf16(rest, u16::from_be_bytes),
+        [first, 0, ..] if *first != 0 => utf16(bytes, u16::from_le_bytes),
+        _ => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+/// Removes `//` and `/* */` comments outside of JSON strings
+pub fn strip_json_comments(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            stripped.push(c);
+            match c {
+                '\\' => stripped.extend(chars.next()),
+                '"' => {
+                    in_string = false;
+                }
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                stripped.push(c);
+            }
+            ('/', Some('/')) => {
+                while chars.next_if(|c| *c != '\n').is_some() {}
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+                // keeps tokens on both sides apart
+                stripped.push(' ');
+            }
+            _ => stripped.push(c),
+        }
+    }
+    stripped
+}
+
+/// Parses a JSON file leniently: see [`decode_text`] for the encodings, comments are
+/// ignored and so is anything after the top-level value, which crash recovery can leave
+pub fn parse_json_lenient(bytes: &[u8]) -> Result<serde_json::Value> {
+    let text = decode_text(bytes);
+    if let Ok(json) = serde_json::from_str(&text) {
+        return Ok(json);
+    }
+    let text = strip_json_comments(&text);
+    serde_json::Deserializer
+        ::from_str(text.trim_start())
+        .into_iter::<serde_json::Value>()
+        .next()
+        .ok_or_else(|| anyhow!("empty JSON document"))?
+        .map_err(|err| anyhow!("invalid JSON: {}", err))
+}
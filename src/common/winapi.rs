@@ -1,4 +1,4 @@
-use std::{ ffi::c_void, ptr };
+use std::{ ffi::c_void, path::{ Path, PathBuf }, ptr };
 
 use anyhow::{ anyhow, bail, Result };
 use windows::Win32::{ Foundation, Security::Cryptography };
@@ -6,6 +6,14 @@ use windows::{
     core::{ HSTRING, PCWSTR, PWSTR },
     Win32::{
         Foundation::{ ERROR_MORE_DATA, ERROR_SUCCESS, WIN32_ERROR },
+        Storage::FileSystem::{
+            GetFileVersionInfoSizeW,
+            GetFileVersionInfoW,
+            VerQueryValueW,
+            VS_FIXEDFILEINFO,
+        },
+        System::Com::CoTaskMemFree,
+        System::Registry::{ RegGetValueW, HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE, RRF_RT_REG_SZ },
         System::RestartManager::{
             RmEndSession,
             RmForceShutdown,
@@ -16,6 +24,14 @@ use windows::{
             CCH_RM_SESSION_KEY,
             RM_PROCESS_INFO,
         },
+        UI::Shell::{
+            SHGetKnownFolderPath,
+            FOLDERID_LocalAppData,
+            FOLDERID_Profile,
+            FOLDERID_RoamingAppData,
+            FOLDERID_UserProfiles,
+            KF_FLAG_DEFAULT,
+        },
     },
 };
 
@@ -67,8 +83,38 @@ pub fn decrypt(keydpapi: &mut [u8]) -> Result<Vec<u8>> {
     Ok(decrypted_data)
 }
 
-pub unsafe fn release_file_lock(file_path: &str) -> bool {
-    let file_path = HSTRING::from(file_path);
+#[cfg(feature = "testing")]
+pub fn encrypt(data: &mut [u8]) -> Result<Vec<u8>> {
+    // https://learn.microsoft.com/en-us/windows/win32/api/dpapi/nf-dpapi-cryptprotectdata
+
+    let data_in = Cryptography::CRYPT_INTEGER_BLOB {
+        cbData: data.len() as u32,
+        pbData: data.as_mut_ptr(),
+    };
+    let mut data_out = Cryptography::CRYPT_INTEGER_BLOB {
+        cbData: 0,
+        pbData: ptr::null_mut(),
+    };
+
+    unsafe {
+        Cryptography::CryptProtectData(&data_in, PCWSTR::null(), None, None, None, 0, &mut data_out)
+            .or(Err(anyhow!("CryptProtectData failed")))?;
+    }
+    if data_out.pbData.is_null() {
+        bail!("CryptProtectData returned a null pointer");
+    }
+
+    let encrypted_data = unsafe {
+        std::slice::from_raw_parts(data_out.pbData, data_out.cbData as usize).to_vec()
+    };
+    unsafe {
+        let _ = Foundation::LocalFree(Foundation::HLOCAL(data_out.pbData as *mut c_void));
+    }
+    Ok(encrypted_data)
+}
+
+pub unsafe fn release_file_lock(file_path: &Path) -> bool {
+    let file_path = HSTRING::from(file_path.as_os_str());
     let mut session: u32 = 0;
     let mut session_key_buffer = [0_u16; (CCH_RM_SESSION_KEY as usize) + 1];
     let session_key = PWSTR(session_key_buffer.as_mut_ptr());
@@ -113,3 +159,95 @@ pub unsafe fn release_file_lock(file_path: &str) -> bool {
     }
     return false;
 }
+
+/// Path of an executable registered under `App Paths`, per-user first then per-machine
+pub fn app_path(executable: &str) -> Option<PathBuf> {
+    let subkey = HSTRING::from(
+        format!("SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\App Paths\\{}", executable)
+    );
+    for hkey in [HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE] {
+        let mut size: u32 = 0;
+        unsafe {
+            if RegGetValueW(hkey, &subkey, PCWSTR::null(), RRF_RT_REG_SZ, None, None, Some(&mut size)).is_err() {
+                continue;
+            }
+            let mut buffer = vec![0_u16; (size as usize) / 2 + 1];
+            if
+                RegGetValueW(
+                    hkey,
+                    &subkey,
+                    PCWSTR::null(),
+                    RRF_RT_REG_SZ,
+                    None,
+                    Some(buffer.as_mut_ptr() as *mut c_void),
+                    Some(&mut size)
+                ).is_err()
+            {
+                continue;
+            }
+            let len = buffer.iter().position(|c| *c == 0).unwrap_or(buffer.len());
+            let path = String::from_utf16_lossy(&buffer[..len]);
+            // some installers quote the path
+            return Some(PathBuf::from(path.trim_matches('"')));
+        }
+    }
+    None
+}
+
+/// Known folder a `%VAR%` placeholder of the configs stands for, used when the
+/// variable isn't set (services, scheduled tasks, stripped environments)
+pub fn known_folder(placeholder: &str) -> Option<PathBuf> {
+    let id = match placeholder.to_ascii_uppercase().as_str() {
+        "APPDATA" => FOLDERID_RoamingAppData,
+        "LOCALAPPDATA" => FOLDERID_LocalAppData,
+        "USERPROFILE" => FOLDERID_Profile,
+        _ => {
+            return None;
+        }
+    };
+    known_folder_path(&id)
+}
+
+/// Folder holding the profile of every user (`C:\Users`), also right when running as
+/// SYSTEM whose own profile is elsewhere
+pub fn user_profiles_folder() -> Option<PathBuf> {
+    known_folder_path(&FOLDERID_UserProfiles)
+}
+
+fn known_folder_path(id: &windows::core::GUID) -> Option<PathBuf> {
+    unsafe {
+        let path = SHGetKnownFolderPath(id, KF_FLAG_DEFAULT, None).ok()?;
+        let folder = path.to_string().ok();
+        CoTaskMemFree(Some(path.0 as *const c_void));
+        folder.map(PathBuf::from)
+    }
+}
+
+/// Product version from the version resource of a file, as `[major, minor, build, patch]`
+pub fn file_version(path: &Path) -> Option<[u32; 4]> {
+    let path = HSTRING::from(path.as_os_str());
+    unsafe {
+        let size = GetFileVersionInfoSizeW(&path, None);
+        if size == 0 {
+            return None;
+        }
+        let mut data = vec![0_u8; size as usize];
+        GetFileVersionInfoW(&path, 0, size, data.as_mut_ptr() as *mut c_void).ok()?;
+        let mut info: *mut c_void = ptr::null_mut();
+        let mut len: u32 = 0;
+        let root = HSTRING::from("\\");
+        if !VerQueryValueW(data.as_ptr() as *const c_void, &root, &mut info, &mut len).as_bool() {
+            return None;
+        }
+        if info.is_null() || (len as usize) < std::mem::size_of::<VS_FIXEDFILEINFO>() {
+            return None;
+        }
+        let info = &*(info as *const VS_FIXEDFILEINFO);
+        Some([
+            info.dwProductVersionMS >> 16,
+            info.dwProductVersionMS & 0xffff,
+            info.dwProductVersionLS >> 16,
+            info.dwProductVersionLS & 0xffff,
+        ])
+    }
+}
@@ -5,57 +5,126 @@ pub const APP_ID: &str = "rookie";
 
 cfg_if::cfg_if! {
     if #[cfg(target_os = "windows")] {
-        // Initialize the CHROME_CONFIG as a static variable with specific values
+        // every Chrome channel has the same layout under its own directory
+        const CHROME_DATA_PATHS: &[&str] = &[
+            "%LOCALAPPDATA%/Google/Chrome{channel}/User Data/Default/Cookies",
+            "%LOCALAPPDATA%/Google/Chrome{channel}/User Data/Default/Network/Cookies",
+            "%LOCALAPPDATA%/Google/Chrome{channel}/User Data/Profile */Cookies",
+            "%LOCALAPPDATA%/Google/Chrome{channel}/User Data/Profile */Network/Cookies",
+
+            "%APPDATA%/Google/Chrome{channel}/User Data/Default/Cookies",
+            "%APPDATA%/Google/Chrome{channel}/User Data/Default/Network/Cookies",
+            "%APPDATA%/Google/Chrome{channel}/User Data/Profile */Cookies",
+            "%APPDATA%/Google/Chrome{channel}/User Data/Profile */Network/Cookies",
+        ];
 
         pub static CHROME_CONFIG: BrowserConfig<'static> = BrowserConfig {
-            data_paths: &[
-                "%LOCALAPPDATA%/Google/Chrome{channel}/User Data/Default/Cookies",
-                "%LOCALAPPDATA%/Google/Chrome{channel}/User Data/Default/Network/Cookies",
-                "%LOCALAPPDATA%/Google/Chrome{channel}/User Data/Profile */Cookies",
-                "%LOCALAPPDATA%/Google/Chrome{channel}/User Data/Profile */Network/Cookies",
-
-                "%APPDATA%/Google/Chrome{channel}/User Data/Default/Cookies",
-                "%APPDATA%/Google/Chrome{channel}/User Data/Default/Network/Cookies",
-                "%APPDATA%/Google/Chrome{channel}/User Data/Profile */Cookies",
-                "%APPDATA%/Google/Chrome{channel}/User Data/Profile */Network/Cookies",
-            ],
-            channels: Some(&["", "-Beta", "-Dev", "-Nightly"]),
+            data_paths: CHROME_DATA_PATHS,
+            channels: None,
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static CHROME_BETA_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: CHROME_DATA_PATHS,
+            channels: Some(&[" Beta"]),
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static CHROME_DEV_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: CHROME_DATA_PATHS,
+            channels: Some(&[" Dev"]),
             os_crypt_name: None,
             osx_key_service: None,
             osx_key_user: None,
         };
 
+        pub static CHROME_CANARY_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: CHROME_DATA_PATHS,
+            channels: Some(&[" SxS"]),
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        const BRAVE_DATA_PATHS: &[&str] = &[
+            "%LOCALAPPDATA%/BraveSoftware/Brave-Browser{channel}/User Data/Default/Cookies",
+            "%LOCALAPPDATA%/BraveSoftware/Brave-Browser{channel}/User Data/Default/Network/Cookies",
+            "%LOCALAPPDATA%/BraveSoftware/Brave-Browser{channel}/User Data/Profile */Cookies",
+            "%LOCALAPPDATA%/BraveSoftware/Brave-Browser{channel}/User Data/Profile */Network/Cookies",
+
+            "%APPDATA%/BraveSoftware/Brave-Browser{channel}/User Data/Default/Cookies",
+            "%APPDATA%/BraveSoftware/Brave-Browser{channel}/User Data/Default/Network/Cookies",
+            "%APPDATA%/BraveSoftware/Brave-Browser{channel}/User Data/Profile */Cookies",
+            "%APPDATA%/BraveSoftware/Brave-Browser{channel}/User Data/Profile */Network/Cookies",
+        ];
+
         pub static BRAVE_CONFIG: BrowserConfig<'static> = BrowserConfig {
-            data_paths: &[
-                "%LOCALAPPDATA%/BraveSoftware/Brave-Browser{channel}/User Data/Default/Cookies",
-                "%LOCALAPPDATA%/BraveSoftware/Brave-Browser{channel}/User Data/Default/Network/Cookies",
-                "%LOCALAPPDATA%/BraveSoftware/Brave-Browser{channel}/User Data/Profile */Cookies",
-                "%LOCALAPPDATA%/BraveSoftware/Brave-Browser{channel}/User Data/Profile */Network/Cookies",
-
-                "%APPDATA%/BraveSoftware/Brave-Browser{channel}/User Data/Default/Cookies",
-                "%APPDATA%/BraveSoftware/Brave-Browser{channel}/User Data/Default/Network/Cookies",
-                "%APPDATA%/BraveSoftware/Brave-Browser{channel}/User Data/Profile */Cookies",
-                "%APPDATA%/BraveSoftware/Brave-Browser{channel}/User Data/Profile */Network/Cookies",
-            ],
-            channels: Some(&["", "-Beta", "-Dev", "-Nightly"]),
+            data_paths: BRAVE_DATA_PATHS,
+            channels: None,
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static BRAVE_BETA_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: BRAVE_DATA_PATHS,
+            channels: Some(&["-Beta"]),
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static BRAVE_NIGHTLY_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: BRAVE_DATA_PATHS,
+            channels: Some(&["-Nightly"]),
             os_crypt_name: None,
             osx_key_service: None,
             osx_key_user: None,
         };
 
+        const EDGE_DATA_PATHS: &[&str] = &[
+            "%LOCALAPPDATA%/Microsoft/Edge{channel}/User Data/Default/Cookies",
+            "%LOCALAPPDATA%/Microsoft/Edge{channel}/User Data/Default/Network/Cookies",
+            "%LOCALAPPDATA%/Microsoft/Edge{channel}/User Data/Profile */Cookies",
+            "%LOCALAPPDATA%/Microsoft/Edge{channel}/User Data/Profile */Network/Cookies",
+
+            "%APPDATA%/Microsoft/Edge{channel}/User Data/Default/Cookies",
+            "%APPDATA%/Microsoft/Edge{channel}/User Data/Default/Network/Cookies",
+            "%APPDATA%/Microsoft/Edge{channel}/User Data/Profile */Cookies",
+            "%APPDATA%/Microsoft/Edge{channel}/User Data/Profile */Network/Cookies",
+        ];
+
         pub static EDGE_CONFIG: BrowserConfig<'static> = BrowserConfig {
-            data_paths: &[
-                "%LOCALAPPDATA%/Microsoft/Edge{channel}/User Data/Default/Cookies",
-                "%LOCALAPPDATA%/Microsoft/Edge{channel}/User Data/Default/Network/Cookies",
-                "%LOCALAPPDATA%/Microsoft/Edge{channel}/User Data/Profile */Cookies",
-                "%LOCALAPPDATA%/Microsoft/Edge{channel}/User Data/Profile */Network/Cookies",
-
-                "%APPDATA%/Microsoft/Edge{channel}/User Data/Default/Cookies",
-                "%APPDATA%/Microsoft/Edge{channel}/User Data/Default/Network/Cookies",
-                "%APPDATA%/Microsoft/Edge{channel}/User Data/Profile */Cookies",
-                "%APPDATA%/Microsoft/Edge{channel}/User Data/Profile */Network/Cookies",
-            ],
-            channels: Some(&["", "-Beta", "-Dev", "-Nightly"]),
+            data_paths: EDGE_DATA_PATHS,
+            channels: None,
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static EDGE_BETA_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: EDGE_DATA_PATHS,
+            channels: Some(&[" Beta"]),
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static EDGE_DEV_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: EDGE_DATA_PATHS,
+            channels: Some(&[" Dev"]),
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static EDGE_CANARY_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: EDGE_DATA_PATHS,
+            channels: Some(&[" SxS"]),
             os_crypt_name: None,
             osx_key_service: None,
             osx_key_user: None,
@@ -69,33 +138,62 @@ cfg_if::cfg_if! {
             osx_key_user: None,
         };
 
+        const VIVALDI_DATA_PATHS: &[&str] = &[
+            "%LOCALAPPDATA%/Vivaldi{channel}/User Data/Default/Cookies",
+            "%LOCALAPPDATA%/Vivaldi{channel}/User Data/Default/Network/Cookies",
+            "%LOCALAPPDATA%/Vivaldi{channel}/User Data/Profile */Cookies",
+            "%LOCALAPPDATA%/Vivaldi{channel}/User Data/Profile */Network/Cookies",
+
+            "%APPDATA%/Vivaldi{channel}/User Data/Default/Cookies",
+            "%APPDATA%/Vivaldi{channel}/User Data/Default/Network/Cookies",
+            "%APPDATA%/Vivaldi{channel}/User Data/Profile */Cookies",
+            "%APPDATA%/Vivaldi{channel}/User Data/Profile */Network/Cookies",
+        ];
+
         pub static VIVALDI_CONFIG: BrowserConfig<'static> = BrowserConfig {
-            data_paths: &[
-                "%LOCALAPPDATA%/Vivaldi/User Data/Default/Cookies",
-                "%LOCALAPPDATA%/Vivaldi/User Data/Default/Network/Cookies",
-                "%LOCALAPPDATA%/Vivaldi/User Data/Profile */Cookies",
-                "%LOCALAPPDATA%/Vivaldi/User Data/Profile */Network/Cookies",
-
-                "%APPDATA%/Vivaldi/User Data/Default/Cookies",
-                "%APPDATA%/Vivaldi/User Data/Default/Network/Cookies",
-                "%APPDATA%/Vivaldi/User Data/Profile */Cookies",
-                "%APPDATA%/Vivaldi/User Data/Profile */Network/Cookies",
-            ],
+            data_paths: VIVALDI_DATA_PATHS,
             channels: None,
             os_crypt_name: None,
             osx_key_service: None,
             osx_key_user: None,
         };
 
+        pub static VIVALDI_SNAPSHOT_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: VIVALDI_DATA_PATHS,
+            channels: Some(&[" Snapshot"]),
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        const OPERA_DATA_PATHS: &[&str] = &[
+            "%LOCALAPPDATA%/Opera Software/Opera {channel}/Cookies",
+            "%LOCALAPPDATA%/Opera Software/Opera {channel}/Network/Cookies",
+
+            "%APPDATA%/Opera Software/Opera {channel}/Cookies",
+            "%APPDATA%/Opera Software/Opera {channel}/Network/Cookies",
+        ];
+
         pub static OPERA_CONFIG: BrowserConfig<'static> = BrowserConfig {
-            data_paths: &[
-                "%LOCALAPPDATA%/Opera Software/Opera {channel}/Cookies",
-                "%LOCALAPPDATA%/Opera Software/Opera {channel}/Network/Cookies",
+            data_paths: OPERA_DATA_PATHS,
+            channels: Some(&["Stable"]),
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
 
-                "%APPDATA%/Opera Software/Opera {channel}/Cookies",
-                "%APPDATA%/Opera Software/Opera {channel}/Network/Cookies",
-            ],
-            channels: Some(&["Stable", "Next", "Developer"]),
+        // older installs of the beta stream still use the "Opera Next" directory
+        pub static OPERA_BETA_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: OPERA_DATA_PATHS,
+            channels: Some(&["Beta", "Next"]),
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static OPERA_DEVELOPER_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: OPERA_DATA_PATHS,
+            channels: Some(&["Developer"]),
             os_crypt_name: None,
             osx_key_service: None,
             osx_key_user: None,
@@ -133,12 +231,116 @@ cfg_if::cfg_if! {
             osx_key_user: None,
         };
 
+        pub static WHALE_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: &[
+                "%LOCALAPPDATA%/Naver/Naver Whale/User Data/Default/Cookies",
+                "%LOCALAPPDATA%/Naver/Naver Whale/User Data/Default/Network/Cookies",
+                "%LOCALAPPDATA%/Naver/Naver Whale/User Data/Profile */Cookies",
+                "%LOCALAPPDATA%/Naver/Naver Whale/User Data/Profile */Network/Cookies",
+            ],
+            channels: None,
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static EPIC_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: &[
+                "%LOCALAPPDATA%/Epic Privacy Browser/User Data/Default/Cookies",
+                "%LOCALAPPDATA%/Epic Privacy Browser/User Data/Default/Network/Cookies",
+                "%LOCALAPPDATA%/Epic Privacy Browser/User Data/Profile */Cookies",
+                "%LOCALAPPDATA%/Epic Privacy Browser/User Data/Profile */Network/Cookies",
+            ],
+            channels: None,
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static IRIDIUM_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: &[
+                "%LOCALAPPDATA%/Iridium/User Data/Default/Cookies",
+                "%LOCALAPPDATA%/Iridium/User Data/Default/Network/Cookies",
+                "%LOCALAPPDATA%/Iridium/User Data/Profile */Cookies",
+                "%LOCALAPPDATA%/Iridium/User Data/Profile */Network/Cookies",
+            ],
+            channels: None,
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
         pub static OCTO_BROWSER_CONFIG: BrowserConfig<'static> = BrowserConfig {
             data_paths: &[
                 "%LOCALAPPDATA%/Octo Browser/tmp/*/Default/Network/Cookies",
                 "%APPDATA%/Octo Browser/tmp/*/Default/Network/Cookies",
             ],
-            channels: Some(&["Stable", ""]),
+            channels: None,
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static MAXTHON_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: &[
+                "%LOCALAPPDATA%/Maxthon/Application/User Data/Default/Cookies",
+                "%LOCALAPPDATA%/Maxthon/Application/User Data/Default/Network/Cookies",
+                "%LOCALAPPDATA%/Maxthon/Application/User Data/Profile */Cookies",
+                "%LOCALAPPDATA%/Maxthon/Application/User Data/Profile */Network/Cookies",
+            ],
+            channels: None,
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static UC_BROWSER_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: &[
+                "%LOCALAPPDATA%/UCBrowser/User Data/Default/Cookies",
+                "%LOCALAPPDATA%/UCBrowser/User Data/Default/Network/Cookies",
+                "%LOCALAPPDATA%/UCBrowser/User Data/Profile */Cookies",
+                "%LOCALAPPDATA%/UCBrowser/User Data/Profile */Network/Cookies",
+            ],
+            channels: None,
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static EXTREME_EXPLORER_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: &[
+                "%APPDATA%/360ChromeX/Chrome/User Data/Default/Cookies",
+                "%APPDATA%/360ChromeX/Chrome/User Data/Default/Network/Cookies",
+                "%APPDATA%/360ChromeX/Chrome/User Data/Profile */Cookies",
+                "%APPDATA%/360ChromeX/Chrome/User Data/Profile */Network/Cookies",
+            ],
+            channels: None,
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static QIHOO_360_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: &[
+                "%LOCALAPPDATA%/360Chrome/Chrome/User Data/Default/Cookies",
+                "%LOCALAPPDATA%/360Chrome/Chrome/User Data/Default/Network/Cookies",
+                "%LOCALAPPDATA%/360Chrome/Chrome/User Data/Profile */Cookies",
+                "%LOCALAPPDATA%/360Chrome/Chrome/User Data/Profile */Network/Cookies",
+            ],
+            channels: None,
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static QQ_BROWSER_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: &[
+                "%LOCALAPPDATA%/Tencent/QQBrowser/User Data/Default/Cookies",
+                "%LOCALAPPDATA%/Tencent/QQBrowser/User Data/Default/Network/Cookies",
+                "%LOCALAPPDATA%/Tencent/QQBrowser/User Data/Profile */Cookies",
+                "%LOCALAPPDATA%/Tencent/QQBrowser/User Data/Profile */Network/Cookies",
+            ],
+            channels: None,
             os_crypt_name: None,
             osx_key_service: None,
             osx_key_user: None,
@@ -152,6 +354,39 @@ cfg_if::cfg_if! {
             osx_key_user: None,
         };
 
+        pub static WATERFOX_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            // "Waterfox Classic" is the legacy (pre-Quantum) edition, installable side by side
+            data_paths: &["%APPDATA%/Waterfox{channel}"],
+            channels: Some(&["", " Classic"]),
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static MULLVAD_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: &["%APPDATA%/Mullvad/MullvadBrowser/Profiles"],
+            channels: None,
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static THUNDERBIRD_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: &["%APPDATA%/Thunderbird"],
+            channels: None,
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static ZEN_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: &["%APPDATA%/zen"],
+            channels: None,
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
         pub static IE_CONFIG: BrowserConfig<'static> = BrowserConfig {
             data_paths: &[
                 "%APPDATA%/Microsoft/Windows/WebCache/WebCacheV01.dat",
@@ -162,73 +397,182 @@ cfg_if::cfg_if! {
             osx_key_service: None,
             osx_key_user: None,
         };
-    } else if #[cfg(target_os = "linux")] {
-        pub static CHROME_CONFIG: BrowserConfig<'static> = BrowserConfig {
+
+        pub static YANDEX_CONFIG: BrowserConfig<'static> = BrowserConfig {
             data_paths: &[
-                "~/.config/google-chrome{channel}/Default/Cookies",
-                "~/.config/google-chrome{channel}/Profile */Cookies",
-                "~/.var/app/com.google.Chrome/config/google-chrome{channel}/Default/Cookies",
-                "~/.var/app/com.google.Chrome/config/google-chrome{channel}/Profile */Cookies",
+                "%LOCALAPPDATA%/Yandex/YandexBrowser/User Data/Default/Cookies",
+                "%LOCALAPPDATA%/Yandex/YandexBrowser/User Data/Default/Network/Cookies",
+                "%LOCALAPPDATA%/Yandex/YandexBrowser/User Data/Profile */Cookies",
+                "%LOCALAPPDATA%/Yandex/YandexBrowser/User Data/Profile */Network/Cookies",
             ],
-            channels: Some(&["", "-Beta", "-Dev", "-Nightly"]),
+            channels: None,
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+    } else if #[cfg(target_os = "linux")] {
+        const CHROME_DATA_PATHS: &[&str] = &[
+            "~/.config/google-chrome{channel}/Default/Cookies",
+            "~/.config/google-chrome{channel}/Profile */Cookies",
+            "~/.var/app/com.google.Chrome/config/google-chrome{channel}/Default/Cookies",
+            "~/.var/app/com.google.Chrome/config/google-chrome{channel}/Profile */Cookies",
+        ];
+
+        // all channels share the "Chrome Safe Storage" secret
+        pub static CHROME_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: CHROME_DATA_PATHS,
+            channels: None,
+            os_crypt_name: Some("chrome"),
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static CHROME_BETA_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: CHROME_DATA_PATHS,
+            channels: Some(&["-beta"]),
+            os_crypt_name: Some("chrome"),
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static CHROME_DEV_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: CHROME_DATA_PATHS,
+            channels: Some(&["-unstable"]),
             os_crypt_name: Some("chrome"),
             osx_key_service: None,
             osx_key_user: None,
         };
 
+        pub static CHROME_CANARY_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: CHROME_DATA_PATHS,
+            channels: Some(&["-canary"]),
+            os_crypt_name: Some("chrome"),
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        const BRAVE_DATA_PATHS: &[&str] = &[
+            "~/snap/brave/*/.config/BraveSoftware/Brave-Browser{channel}/Default/Cookies",
+            "~/.config/BraveSoftware/Brave-Browser{channel}/Default/Cookies",
+            "~/.config/BraveSoftware/Brave-Browser{channel}/Profile */Cookies",
+            "~/.var/app/com.brave.Browser/config/BraveSoftware/Brave-Browser{channel}/Default/Cookies",
+            "~/.var/app/com.brave.Browser/config/BraveSoftware/Brave-Browser{channel}/Profile */Cookies",
+        ];
+
         pub static BRAVE_CONFIG: BrowserConfig<'static> = BrowserConfig {
-            data_paths: &[
-                "~/snap/brave/*/.config/BraveSoftware/Brave-Browser/Default/Cookies",
-                "~/.config/BraveSoftware/Brave-Browser{channel}/Default/Cookies",
-                "~/.config/BraveSoftware/Brave-Browser{channel}/Profile */Cookies",
-                "~/.var/app/com.brave.Browser/config/BraveSoftware/Brave-Browser{channel}/Default/Cookies",
-                "~/.var/app/com.brave.Browser/config/BraveSoftware/Brave-Browser{channel}/Profile */Cookies",
-            ],
-            channels: Some(&["", "-Beta", "-Dev", "-Nightly"]),
+            data_paths: BRAVE_DATA_PATHS,
+            channels: None,
+            os_crypt_name: Some("brave"),
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static BRAVE_BETA_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: BRAVE_DATA_PATHS,
+            channels: Some(&["-Beta"]),
             os_crypt_name: Some("brave"),
             osx_key_service: None,
             osx_key_user: None,
         };
 
+        pub static BRAVE_NIGHTLY_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: BRAVE_DATA_PATHS,
+            channels: Some(&["-Nightly"]),
+            os_crypt_name: Some("brave"),
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        const EDGE_DATA_PATHS: &[&str] = &[
+            "~/.config/microsoft-edge{channel}/Default/Cookies",
+            "~/.config/microsoft-edge{channel}/Profile */Cookies",
+            "~/.var/app/com.microsoft.Edge/config/microsoft-edge{channel}/Default/Cookies",
+            "~/.var/app/com.microsoft.Edge/config/microsoft-edge{channel}/Profile */Cookies",
+        ];
+
+        // every channel stores its key under the "chromium" application, Canary isn't
+        // released for Linux and is kept so the channel configs are the same on every OS
         pub static EDGE_CONFIG: BrowserConfig<'static> = BrowserConfig {
-            data_paths: &[
-                "~/.config/microsoft-edge{channel}/Default/Cookies",
-                "~/.config/microsoft-edge{channel}/Profile */Cookies",
-                "~/.var/app/com.microsoft.Edge/config/microsoft-edge{channel}/Default/Cookies",
-                "~/.var/app/com.microsoft.Edge/config/microsoft-edge{channel}/Profile */Cookies",
-            ],
-            channels: Some(&["", "-Beta", "-Dev", "-Nightly"]),
+            data_paths: EDGE_DATA_PATHS,
+            channels: None,
+            os_crypt_name: Some("chromium"),
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static EDGE_BETA_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: EDGE_DATA_PATHS,
+            channels: Some(&["-beta"]),
+            os_crypt_name: Some("chromium"),
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static EDGE_DEV_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: EDGE_DATA_PATHS,
+            channels: Some(&["-dev"]),
             os_crypt_name: Some("chromium"),
             osx_key_service: None,
             osx_key_user: None,
         };
 
+        pub static EDGE_CANARY_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: EDGE_DATA_PATHS,
+            channels: Some(&["-canary"]),
+            os_crypt_name: Some("chromium"),
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        const VIVALDI_DATA_PATHS: &[&str] = &[
+            "~/.config/vivaldi{channel}/Default/Cookies",
+            "~/.config/vivaldi{channel}/Profile */Cookies",
+            "~/.var/app/com.vivaldi.Vivaldi/config/vivaldi{channel}/Default/Cookies",
+            "~/.var/app/com.vivaldi.Vivaldi/config/vivaldi{channel}/Profile */Cookies",
+        ];
+
         pub static VIVALDI_CONFIG: BrowserConfig<'static> = BrowserConfig {
-            data_paths: &[
-                "~/.config/vivaldi/Default/Cookies",
-                "~/.config/vivaldi/Profile */Cookies",
-                "~/.config/vivaldi-snapshot/Default/Cookies",
-                "~/.config/vivaldi-snapshot/Profile */Cookies",
-                "~/.var/app/com.vivaldi.Vivaldi/config/vivaldi/Default/Cookies",
-                "~/.var/app/com.vivaldi.Vivaldi/config/vivaldi/Profile */Cookies",
-            ],
+            data_paths: VIVALDI_DATA_PATHS,
             channels: None,
             os_crypt_name: Some("chrome"),
             osx_key_service: None,
             osx_key_user: None,
         };
 
+        pub static VIVALDI_SNAPSHOT_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: VIVALDI_DATA_PATHS,
+            channels: Some(&["-snapshot"]),
+            os_crypt_name: Some("chrome"),
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        const OPERA_DATA_PATHS: &[&str] = &[
+            "~/snap/opera{channel}/*/.config/opera{channel}/Cookies",
+            "~/.config/opera{channel}/Cookies",
+            "~/.var/app/com.opera.Opera/config/opera{channel}/Cookies",
+        ];
+
+        // every channel stores its key under the "chromium" application
         pub static OPERA_CONFIG: BrowserConfig<'static> = BrowserConfig {
-            data_paths: &[
-                "~/snap/opera/*/.config/opera/Cookies",
-                "~/.config/opera/Cookies",
-                "~/.config/opera-beta/Cookies",
-                "~/.config/opera-developer/Cookies",
-                "~/.var/app/com.opera.Opera/config/opera/Cookies",
-                "~/.var/app/com.opera.Opera/config/opera-beta/Cookies",
-                "~/.var/app/com.opera.Opera/config/opera-developer/Cookies",
-            ],
-            channels: Some(&["Stable", "Next", "Developer"]),
+            data_paths: OPERA_DATA_PATHS,
+            channels: None,
+            os_crypt_name: Some("chromium"),
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static OPERA_BETA_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: OPERA_DATA_PATHS,
+            channels: Some(&["-beta"]),
+            os_crypt_name: Some("chromium"),
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static OPERA_DEVELOPER_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: OPERA_DATA_PATHS,
+            channels: Some(&["-developer"]),
             os_crypt_name: Some("chromium"),
             osx_key_service: None,
             osx_key_user: None,
@@ -236,11 +580,13 @@ cfg_if::cfg_if! {
 
         pub static CHROMIUM_CONFIG: BrowserConfig<'static> = BrowserConfig {
             data_paths: &[
-                "~/snap/chromium/common/chromium/Default/Cookies",
                 "~/.config/chromium/Default/Cookies",
                 "~/.config/chromium/Profile */Cookies",
                 "~/.var/app/org.chromium.Chromium/config/chromium/Default/Cookies",
                 "~/.var/app/org.chromium.Chromium/config/chromium/Profile */Cookies",
+                // the Ubuntu snap, only used when there's no regular install
+                "~/snap/chromium/common/chromium/Default/Cookies",
+                "~/snap/chromium/common/chromium/Profile */Cookies",
             ],
             channels: None,
             os_crypt_name: Some("chromium"),
@@ -250,8 +596,8 @@ cfg_if::cfg_if! {
 
         pub static FIREFOX_CONFIG: BrowserConfig<'static> = BrowserConfig {
             data_paths: &[
-                "~/snap/firefox/common/.mozilla/firefox",
                 "~/.mozilla/firefox",
+                "~/snap/firefox/common/.mozilla/firefox",
                 "~/.var/app/org.mozilla.firefox/.mozilla/firefox",
             ],
             channels: None,
@@ -261,49 +607,235 @@ cfg_if::cfg_if! {
         };
 
         pub static LIBRE_WOLF_CONFIG: BrowserConfig<'static> = BrowserConfig {
-            data_paths: &["~/snap/librewolf/common/.librewolf", "~/.librewolf"],
+            data_paths: &[
+                "~/.librewolf",
+                "~/snap/librewolf/common/.librewolf",
+                "~/.var/app/io.gitlab.librewolf-community/.librewolf",
+            ],
+            channels: None,
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static WATERFOX_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: &[
+                "~/.waterfox{channel}",
+                "~/.var/app/net.waterfox.waterfox/.waterfox",
+            ],
+            // "Waterfox Classic" is the legacy (pre-Quantum) edition, installable side by side
+            channels: Some(&["", "-classic"]),
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static MULLVAD_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: &["~/.mullvad-browser"],
+            channels: None,
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static THUNDERBIRD_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: &[
+                "~/.thunderbird",
+                "~/snap/thunderbird/common/.thunderbird",
+                "~/.var/app/org.mozilla.Thunderbird/.thunderbird",
+            ],
+            channels: None,
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static ZEN_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: &[
+                "~/.zen",
+                "~/.var/app/app.zen_browser.zen/.zen",
+            ],
+            channels: None,
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static EPIPHANY_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: &[
+                "~/.local/share/epiphany/cookies.sqlite",
+                "~/.var/app/org.gnome.Epiphany/data/epiphany/cookies.sqlite",
+            ],
             channels: None,
             os_crypt_name: None,
             osx_key_service: None,
             osx_key_user: None,
         };
 
+        // QtWebEngine keeps a chromium cookies DB per profile, without a keyring entry
+        pub static FALKON_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: &[
+                "~/.config/falkon/profiles/*/Cookies",
+                "~/.config/falkon/profiles/*/Network/Cookies",
+                "~/.var/app/org.kde.falkon/config/falkon/profiles/*/Cookies",
+                "~/.var/app/org.kde.falkon/config/falkon/profiles/*/Network/Cookies",
+            ],
+            channels: None,
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        // GX shares the keyring entry of opera
         pub static OPERA_GX_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: &[
+                "~/snap/opera-gx/*/.config/opera-gx/Cookies",
+                "~/.config/opera-gx/Cookies",
+                "~/.var/app/com.opera.OperaGX/config/opera-gx/Cookies",
+            ],
+            channels: None,
+            os_crypt_name: Some("chromium"),
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static WHALE_CONFIG: BrowserConfig<'static> = BrowserConfig {
             data_paths: &[],
-            channels: Some(&["", ""]),
+            channels: None,
             os_crypt_name: None,
             osx_key_service: None,
             osx_key_user: None,
         };
-    } else if #[cfg(target_os = "macos")] {
-        pub static CHROME_CONFIG: BrowserConfig<'static> = BrowserConfig {
+
+        pub static EPIC_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: &[],
+            channels: None,
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        // stock chromium, it shares the "chromium" keyring entry
+        pub static IRIDIUM_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: &["~/.config/iridium/Default/Cookies", "~/.config/iridium/Profile */Cookies"],
+            channels: None,
+            os_crypt_name: Some("chromium"),
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static YANDEX_CONFIG: BrowserConfig<'static> = BrowserConfig {
             data_paths: &[
-                "~/Library/Application Support/Google/Chrome{channel}/Default/Cookies",
-                "~/Library/Application Support/Google/Chrome{channel}/Profile */Cookies",
+                "~/.config/yandex-browser{channel}/Default/Cookies",
+                "~/.config/yandex-browser{channel}/Profile */Cookies",
             ],
-            channels: Some(&["", "-Beta", "-Dev", "-Nightly"]),
+            channels: Some(&["", "-beta"]),
+            os_crypt_name: Some("yandex"),
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+    } else if #[cfg(target_os = "macos")] {
+        const CHROME_DATA_PATHS: &[&str] = &[
+            "~/Library/Application Support/Google/Chrome{channel}/Default/Cookies",
+            "~/Library/Application Support/Google/Chrome{channel}/Profile */Cookies",
+        ];
+
+        // all channels share the "Chrome Safe Storage" keychain item
+        pub static CHROME_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: CHROME_DATA_PATHS,
+            channels: None,
             os_crypt_name: Some("chrome"),
             osx_key_service: Some("Chrome Safe Storage"),
             osx_key_user: Some("Chrome"),
         };
 
+        pub static CHROME_BETA_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: CHROME_DATA_PATHS,
+            channels: Some(&[" Beta"]),
+            os_crypt_name: Some("chrome"),
+            osx_key_service: Some("Chrome Safe Storage"),
+            osx_key_user: Some("Chrome"),
+        };
+
+        pub static CHROME_DEV_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: CHROME_DATA_PATHS,
+            channels: Some(&[" Dev"]),
+            os_crypt_name: Some("chrome"),
+            osx_key_service: Some("Chrome Safe Storage"),
+            osx_key_user: Some("Chrome"),
+        };
+
+        pub static CHROME_CANARY_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: CHROME_DATA_PATHS,
+            channels: Some(&[" Canary"]),
+            os_crypt_name: Some("chrome"),
+            osx_key_service: Some("Chrome Safe Storage"),
+            osx_key_user: Some("Chrome"),
+        };
+
+        const BRAVE_DATA_PATHS: &[&str] = &[
+            "~/Library/Application Support/BraveSoftware/Brave-Browser{channel}/Default/Cookies",
+            "~/Library/Application Support/BraveSoftware/Brave-Browser{channel}/Profile */Cookies",
+        ];
+
+        // all channels share the "Brave Safe Storage" keychain item
         pub static BRAVE_CONFIG: BrowserConfig<'static> = BrowserConfig {
-            data_paths: &[
-                "~/Library/Application Support/BraveSoftware/Brave-Browser{channel}/Default/Cookies",
-                "~/Library/Application Support/BraveSoftware/Brave-Browser{channel}/Profile */Cookies",
-            ],
-            channels: Some(&["", "-Beta", "-Dev", "-Nightly"]),
+            data_paths: BRAVE_DATA_PATHS,
+            channels: None,
+            os_crypt_name: Some("brave"),
+            osx_key_service: Some("Brave Safe Storage"),
+            osx_key_user: Some("Brave"),
+        };
+
+        pub static BRAVE_BETA_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: BRAVE_DATA_PATHS,
+            channels: Some(&["-Beta"]),
+            os_crypt_name: Some("brave"),
+            osx_key_service: Some("Brave Safe Storage"),
+            osx_key_user: Some("Brave"),
+        };
+
+        pub static BRAVE_NIGHTLY_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: BRAVE_DATA_PATHS,
+            channels: Some(&["-Nightly"]),
             os_crypt_name: Some("brave"),
             osx_key_service: Some("Brave Safe Storage"),
             osx_key_user: Some("Brave"),
         };
 
+        const EDGE_DATA_PATHS: &[&str] = &[
+            "~/Library/Application Support/Microsoft Edge{channel}/Default/Cookies",
+            "~/Library/Application Support/Microsoft Edge{channel}/Profile */Cookies",
+        ];
+
+        // all channels share the "Microsoft Edge Safe Storage" keychain item
         pub static EDGE_CONFIG: BrowserConfig<'static> = BrowserConfig {
-            data_paths: &[
-                "~/Library/Application Support/Microsoft Edge{channel}/Default/Cookies",
-                "~/Library/Application Support/Microsoft Edge{channel}/Profile */Cookies",
-            ],
-            channels: Some(&["", " Beta", " Dev", " Canary"]),
+            data_paths: EDGE_DATA_PATHS,
+            channels: None,
+            os_crypt_name: Some("chromium"),
+            osx_key_service: Some("Microsoft Edge Safe Storage"),
+            osx_key_user: Some("Microsoft Edge"),
+        };
+
+        pub static EDGE_BETA_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: EDGE_DATA_PATHS,
+            channels: Some(&[" Beta"]),
+            os_crypt_name: Some("chromium"),
+            osx_key_service: Some("Microsoft Edge Safe Storage"),
+            osx_key_user: Some("Microsoft Edge"),
+        };
+
+        pub static EDGE_DEV_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: EDGE_DATA_PATHS,
+            channels: Some(&[" Dev"]),
+            os_crypt_name: Some("chromium"),
+            osx_key_service: Some("Microsoft Edge Safe Storage"),
+            osx_key_user: Some("Microsoft Edge"),
+        };
+
+        pub static EDGE_CANARY_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: EDGE_DATA_PATHS,
+            channels: Some(&[" Canary"]),
             os_crypt_name: Some("chromium"),
             osx_key_service: Some("Microsoft Edge Safe Storage"),
             osx_key_user: Some("Microsoft Edge"),
@@ -325,29 +857,76 @@ cfg_if::cfg_if! {
             osx_key_user: None,
         };
 
+        pub static WATERFOX_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: &["~/Library/Application Support/Waterfox{channel}"],
+            // "Waterfox Classic" is the legacy (pre-Quantum) edition, installable side by side
+            channels: Some(&["", " Classic"]),
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static MULLVAD_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            // same layout as Tor Browser, which it's built from
+            data_paths: &["~/Library/Application Support/MullvadBrowser-Data/Browser"],
+            channels: None,
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        const VIVALDI_DATA_PATHS: &[&str] = &[
+            "~/Library/Application Support/Vivaldi{channel}/Default/Cookies",
+            "~/Library/Application Support/Vivaldi{channel}/Profile */Cookies",
+        ];
+
+        // Snapshot shares the "Vivaldi Safe Storage" keychain item
         pub static VIVALDI_CONFIG: BrowserConfig<'static> = BrowserConfig {
-            data_paths: &[
-                "~/Library/Application Support/Vivaldi/Default/Cookies",
-                "~/Library/Application Support/Vivaldi/Profile */Cookies",
-            ],
+            data_paths: VIVALDI_DATA_PATHS,
             channels: None,
             os_crypt_name: Some("chrome"),
             osx_key_service: Some("Vivaldi Safe Storage"),
             osx_key_user: Some("Vivaldi"),
         };
 
+        pub static VIVALDI_SNAPSHOT_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: VIVALDI_DATA_PATHS,
+            channels: Some(&[" Snapshot"]),
+            os_crypt_name: Some("chrome"),
+            osx_key_service: Some("Vivaldi Safe Storage"),
+            osx_key_user: Some("Vivaldi"),
+        };
+
+        // the beta stream keeps its data under the OperaNext bundle id
+        const OPERA_DATA_PATHS: &[&str] = &[
+            "~/Library/Application Support/com.operasoftware.Opera{channel}/Cookies",
+        ];
+
         pub static OPERA_CONFIG: BrowserConfig<'static> = BrowserConfig {
-            data_paths: &[
-                "~/Library/Application Support/com.operasoftware.Opera/Cookies",
-                "~/Library/Application Support/com.operasoftware.OperaNext/Cookies",
-                "~/Library/Application Support/com.operasoftware.OperaDeveloper/Cookies",
-            ],
-            channels: Some(&["Stable", "Next", "Developer"]),
+            data_paths: OPERA_DATA_PATHS,
+            channels: None,
             os_crypt_name: Some("chromium"),
             osx_key_service: Some("Opera Safe Storage"),
             osx_key_user: Some("Opera"),
         };
 
+        // every channel has a keychain item named after its app
+        pub static OPERA_BETA_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: OPERA_DATA_PATHS,
+            channels: Some(&["Next"]),
+            os_crypt_name: Some("chromium"),
+            osx_key_service: Some("Opera Beta Safe Storage"),
+            osx_key_user: Some("Opera Beta"),
+        };
+
+        pub static OPERA_DEVELOPER_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: OPERA_DATA_PATHS,
+            channels: Some(&["Developer"]),
+            os_crypt_name: Some("chromium"),
+            osx_key_service: Some("Opera Developer Safe Storage"),
+            osx_key_user: Some("Opera Developer"),
+        };
+
         pub static CHROMIUM_CONFIG: BrowserConfig<'static> = BrowserConfig {
             data_paths: &[
                 "~/Library/Application Support/Chromium/Default/Cookies",
@@ -367,6 +946,52 @@ cfg_if::cfg_if! {
             osx_key_user: Some("Opera"),
         };
 
+        pub static WHALE_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: &[
+                "~/Library/Application Support/Naver/Whale/Default/Cookies",
+                "~/Library/Application Support/Naver/Whale/Profile */Cookies",
+            ],
+            channels: None,
+            os_crypt_name: Some("whale"),
+            osx_key_service: Some("Whale Safe Storage"),
+            osx_key_user: Some("Whale"),
+        };
+
+        pub static EPIC_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: &[
+                "~/Library/Application Support/HiddenReflex/Epic/Default/Cookies",
+                "~/Library/Application Support/HiddenReflex/Epic/Profile */Cookies",
+            ],
+            channels: None,
+            os_crypt_name: Some("epic"),
+            osx_key_service: Some("Epic Safe Storage"),
+            osx_key_user: Some("Epic"),
+        };
+
+        pub static IRIDIUM_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: &[],
+            channels: None,
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static THUNDERBIRD_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: &["~/Library/Thunderbird"],
+            channels: None,
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
+        pub static ZEN_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: &["~/Library/Application Support/zen"],
+            channels: None,
+            os_crypt_name: None,
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+
         pub static SAFARI_CONFIG: BrowserConfig<'static> = BrowserConfig {
             data_paths: &[
                 "~/Library/Containers/com.apple.Safari/Data/Library/Cookies/Cookies.binarycookies",
@@ -377,5 +1002,16 @@ cfg_if::cfg_if! {
             osx_key_service: None,
             osx_key_user: None,
         };
+
+        pub static YANDEX_CONFIG: BrowserConfig<'static> = BrowserConfig {
+            data_paths: &[
+                "~/Library/Application Support/Yandex/YandexBrowser/Default/Cookies",
+                "~/Library/Application Support/Yandex/YandexBrowser/Profile */Cookies",
+            ],
+            channels: None,
+            os_crypt_name: Some("yandex"),
+            osx_key_service: Some("Yandex Safe Storage"),
+            osx_key_user: Some("Yandex"),
+        };
     } // not available on Linux
 }
@@ -0,0 +1,115 @@
+use crate::common::enums::{ Cookie, SameSite, SourceScheme };
+use ::cookie::time::{ Duration, OffsetDateTime };
+use ::cookie::Expiration;
+
+/// Returned when a cookie can't be converted to or from the `cookie` crate's type
+#[derive(Debug)]
+pub struct ConversionError {
+    pub name: String,
+    pub domain: String,
+    pub reason: String,
+}
+
+impl std::fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Can't convert cookie {:?} of {:?}: {}", self.name, self.domain, self.reason)
+    }
+}
+
+impl std::error::Error for ConversionError {}
+
+/// The `cookie` crate has no host-only flag, so the domain is always set and a
+/// host-only cookie comes back from [`TryFrom<&cookie::Cookie>`] as a domain cookie.
+/// Values are kept as they are, even the ones `cookie::Cookie::parse` would reject.
+impl TryFrom<&Cookie> for ::cookie::Cookie<'static> {
+    type Error = ConversionError;
+
+    fn try_from(cookie: &Cookie) -> Result<Self, Self::Error> {
+        let error = |reason: String| ConversionError {
+            name: cookie.name.clone(),
+            domain: cookie.domain.clone(),
+            reason,
+        };
+        let expires = match cookie.expires {
+            Some(expires) => {
+                let datetime = i64
+                    ::try_from(expires)
+                    .ok()
+                    .and_then(|expires| OffsetDateTime::from_unix_timestamp(expires).ok())
+                    .ok_or_else(|| error(format!("expiry {} is out of range", expires)))?;
+                Expiration::DateTime(datetime)
+            }
+            None => Expiration::Session,
+        };
+        let mut converted = ::cookie::Cookie
+            ::build(cookie.name.clone(), cookie.value.clone())
+            .domain(cookie.domain.clone())
+            .path(cookie.path.clone())
+            .secure(cookie.secure)
+            .http_only(cookie.http_only)
+            .expires(expires)
+            .finish();
+        converted.set_same_site(match cookie.same_site {
+            SameSite::Strict => Some(::cookie::SameSite::Strict),
+            SameSite::Lax => Some(::cookie::SameSite::Lax),
+            SameSite::None => Some(::cookie::SameSite::None),
+            SameSite::Unspecified => None,
+        });
+        Ok(converted)
+    }
+}
+
+/// Needs a domain. It's read as a domain cookie (see [`Cookie::host_only`]) since a
+/// `Domain` attribute makes one, and `Max-Age` counts from now when there's no `Expires`.
+impl TryFrom<&::cookie::Cookie<'_>> for Cookie {
+    type Error = ConversionError;
+
+    fn try_from(cookie: &::cookie::Cookie<'_>) -> Result<Self, Self::Error> {
+        let error = |reason: &str| ConversionError {
+            name: cookie.name().to_string(),
+            domain: cookie.domain().unwrap_or_default().to_string(),
+            reason: reason.to_string(),
+        };
+        let domain = match cookie.domain() {
+            Some(domain) if !domain.is_empty() => format!(".{}", domain),
+            _ => {
+                return Err(error("it has no domain"));
+            }
+        };
+        let expires = match (cookie.expires_datetime(), cookie.max_age()) {
+            (Some(datetime), _) => Some(datetime),
+            (None, Some(max_age)) => Some(OffsetDateTime::now_utc() + max_age.max(Duration::ZERO)),
+            (None, None) => None,
+        };
+        let expires = match expires {
+            Some(datetime) =>
+                Some(u64::try_from(datetime.unix_timestamp()).map_err(|_| error("it expires before 1970"))?),
+            None => None,
+        };
+        Ok(Cookie {
+            domain,
+            host_only: false,
+            path: cookie.path().unwrap_or("/").to_string(),
+            secure: cookie.secure().unwrap_or(false),
+            expires,
+            name: cookie.name().to_string(),
+            value: cookie.value().to_string(),
+            http_only: cookie.http_only().unwrap_or(false),
+            same_site: match cookie.same_site() {
+                Some(::cookie::SameSite::Strict) => SameSite::Strict,
+                Some(::cookie::SameSite::Lax) => SameSite::Lax,
+                Some(::cookie::SameSite::None) => SameSite::None,
+                None => SameSite::Unspecified,
+            },
+            same_site_raw: None,
+            creation: None,
+            last_accessed: None,
+            partition_key: None,
+            port: None,
+            source_scheme: SourceScheme::Unset,
+            extension: None,
+            browser: String::new(),
+            profile: None,
+        })
+    }
+}
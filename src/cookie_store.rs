@@ -0,0 +1,81 @@
+use crate::common::enums::Cookie;
+use crate::cookie::ConversionError;
+use ::cookie_store::{ CookieError, CookieStore };
+use url::Url;
+
+/// A [`CookieStore`] filled by [`to_cookie_store`] and the cookies it didn't take
+#[derive(Debug, Default)]
+pub struct StoreOutcome {
+    pub store: CookieStore,
+    /// Cookies left out, each with the reason
+    pub skipped: Vec<ConversionError>,
+}
+
+/// Inserts `cookies` into `store` as if each was received from its own site, the
+/// request URL `cookie_store` needs being made of its domain, path and Secure flag.
+/// Host-only cookies stay host-only, domain cookies come from a subdomain so one
+/// set on a public suffix is refused rather than made host-only. Expired cookies and the ones the store refuses,
+/// e.g. set on a public suffix like `.com` when `store` has a public suffix list,
+/// are returned instead of inserted.
+pub fn insert_cookies(store: &mut CookieStore, cookies: &[Cookie]) -> Vec<ConversionError> {
+    let mut skipped = vec![];
+    for cookie in cookies {
+        let error = |reason: String| ConversionError {
+            name: cookie.name.clone(),
+            domain: cookie.domain.clone(),
+            reason,
+        };
+        if cookie.is_expired() {
+            skipped.push(error("it expired".to_string()));
+            continue;
+        }
+        let mut raw = match ::cookie::Cookie::try_from(cookie) {
+            Ok(raw) => raw,
+            Err(err) => {
+                skipped.push(err);
+                continue;
+            }
+        };
+        if cookie.host_only {
+            raw.unset_domain();
+        }
+        let host = cookie.domain.trim_start_matches('.');
+        let scheme = if cookie.secure { "https" } else { "http" };
+        let path = if cookie.path.starts_with('/') { cookie.path.as_str() } else { "/" };
+        let sub = if cookie.host_only || host.parse::<std::net::IpAddr>().is_ok() { "" } else { "www." };
+        let url = match Url::parse(&format!("{}://{}{}{}", scheme, sub, host, path)) {
+            Ok(url) => url,
+            Err(err) => {
+                skipped.push(error(format!("{} isn't a valid host ({})", host, err)));
+                continue;
+            }
+        };
+        match store.insert_raw(&raw, &url) {
+            Ok(_) => {}
+            Err(CookieError::PublicSuffix) => skipped.push(error("its domain is a public suffix".to_string())),
+            Err(err) => skipped.push(error(err.to_string())),
+        }
+    }
+    skipped
+}
+
+/// A new [`CookieStore`] holding `cookies`, see [`insert_cookies`]. The store has no
+/// public suffix list, fill a `CookieStore::new(Some(list))` with [`insert_cookies`]
+/// to have cookies set on public suffixes refused.
+///
+/// # Examples
+///
+/// ```
+/// fn main() {
+///     let cookies = rookie::firefox(Some(vec!["github.com"])).unwrap_or_default();
+///     let outcome = rookie::to_cookie_store(&cookies);
+///     for skipped in &outcome.skipped {
+///         println!("{}", skipped);
+///     }
+/// }
+/// ```
+pub fn to_cookie_store(cookies: &[Cookie]) -> StoreOutcome {
+    let mut store = CookieStore::default();
+    let skipped = insert_cookies(&mut store, cookies);
+    StoreOutcome { store, skipped }
+}
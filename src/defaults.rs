@@ -0,0 +1,49 @@
+use crate::common::enums::{ LoadOptions, ReadOptions };
+use std::sync::{ OnceLock, RwLock };
+
+/// Options used by every entry point that isn't given its own.
+///
+/// Precedence is per-call > global > built-in: [`crate::load_with`] uses the
+/// `LoadOptions` it's called with, [`crate::load`] uses `load` from here, and the
+/// single browser readers ([`crate::chrome`], [`crate::firefox`], ...) use `read`.
+/// Until [`set_global_defaults`] is called these are the built-in defaults.
+#[derive(Debug, Clone, Default)]
+pub struct GlobalDefaults {
+    pub read: ReadOptions,
+    pub load: LoadOptions,
+}
+
+fn global() -> &'static RwLock<GlobalDefaults> {
+    static GLOBAL: OnceLock<RwLock<GlobalDefaults>> = OnceLock::new();
+    GLOBAL.get_or_init(Default::default)
+}
+
+/// Replaces the options used when a call doesn't supply its own, for the whole process
+///
+/// # Examples
+///
+/// ```
+/// use rookie::GlobalDefaults;
+///
+/// fn main() {
+///     // never ask the keyring/keychain, skip expired cookies everywhere
+///     let mut defaults = GlobalDefaults::default();
+///     defaults.read.include_expired = false;
+///     defaults.load.max_prompts = Some(0);
+///     defaults.load.include_expired = false;
+///     rookie::set_global_defaults(defaults);
+/// }
+/// ```
+pub fn set_global_defaults(defaults: GlobalDefaults) {
+    *global().write().unwrap_or_else(|err| err.into_inner()) = defaults;
+}
+
+/// Goes back to the built-in defaults
+pub fn reset_global_defaults() {
+    set_global_defaults(GlobalDefaults::default());
+}
+
+/// The options currently used when a call doesn't supply its own
+pub fn global_defaults() -> GlobalDefaults {
+    global().read().unwrap_or_else(|err| err.into_inner()).clone()
+}
@@ -0,0 +1,310 @@
+use crate::browser::chromium::{ self, WipedKeys };
+use crate::browser::mozilla;
+use crate::common::{ enums::{ BrowserConfig, BrowserVersion }, paths, sqlite };
+use anyhow::{ bail, Result };
+use std::fmt;
+use std::path::Path;
+
+#[cfg(target_os = "macos")]
+use crate::config;
+
+#[cfg(unix)]
+use crate::common::secrets::{ OsSecrets, SecretsProvider };
+
+/// Steps of a read checked by a dry run, in order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Stage {
+    /// Cookies DB (and `Local State`) found
+    Paths,
+    /// Key obtained from DPAPI or the keyring/keychain
+    Key,
+    /// Cookies DB opened and its rows counted
+    Database,
+    /// One value decrypted with the key
+    Decrypt,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StageStatus {
+    Passed,
+    Failed(String),
+    /// Ran, but its outcome doesn't prove anything, e.g. no keyring password so only
+    /// the built-in fallback keys could be tried
+    NotVerified(String),
+    /// Not needed for this browser, or an earlier stage failed
+    Skipped,
+}
+
+/// Dry run of a single browser
+#[derive(Debug)]
+pub struct BrowserDryRun {
+    pub browser: String,
+    pub stages: Vec<(Stage, StageStatus)>,
+    /// Rows in the cookies DB, when it could be opened
+    pub rows: Option<usize>,
+    /// Version of a chromium based browser, e.g. to tell whether it uses app-bound encryption
+    pub version: Option<BrowserVersion>,
+    /// Schema version of the cookies DB, `meta.version` for chromium and `user_version` for firefox
+    pub schema_version: Option<i64>,
+    /// Newest schema version rookie was tested with for this kind of browser
+    pub max_tested_schema: Option<i64>,
+}
+
+impl BrowserDryRun {
+    fn new(browser: &str) -> Self {
+        BrowserDryRun {
+            browser: browser.to_string(),
+            stages: vec![],
+            rows: None,
+            version: None,
+            schema_version: None,
+            max_tested_schema: None,
+        }
+    }
+
+    /// Records the stage and whether the next ones should run
+    fn record<T>(&mut self, stage: Stage, result: Result<T>) -> Option<T> {
+        match result {
+            Ok(value) => {
+                self.stages.push((stage, StageStatus::Passed));
+                Some(value)
+            }
+            Err(err) => {
+                self.stages.push((stage, StageStatus::Failed(format!("{:#}", err))));
+                None
+            }
+        }
+    }
+
+    /// Marks the stages that didn't run as skipped
+    fn finish(mut self) -> Self {
+        for stage in [Stage::Paths, Stage::Key, Stage::Database, Stage::Decrypt] {
+            if !self.stages.iter().any(|(recorded, _)| *recorded == stage) {
+                self.stages.push((stage, StageStatus::Skipped));
+            }
+        }
+        self.stages.sort_by_key(|(stage, _)| *stage);
+        self
+    }
+
+    /// Whether the cookies DB schema is newer than the tested one, reads warn or
+    /// fail in strict mode
+    pub fn schema_is_newer(&self) -> bool {
+        matches!((self.schema_version, self.max_tested_schema), (Some(version), Some(max)) if version > max)
+    }
+
+    pub fn passed(&self) -> bool {
+        !self.stages.iter().any(|(_, status)| matches!(status, StageStatus::Failed(_)))
+    }
+
+    /// Whether the cookies DB was found, i.e. the browser is installed
+    pub fn found(&self) -> bool {
+        self.stages.iter().any(|(stage, status)| *stage == Stage::Paths && *status == StageStatus::Passed)
+    }
+}
+
+/// Outcome of [`dry_run`], one entry per browser checked
+#[derive(Debug, Default)]
+pub struct DryRunReport {
+    pub browsers: Vec<BrowserDryRun>,
+}
+
+impl DryRunReport {
+    /// Whether at least one browser was found and none of the found ones failed a stage,
+    /// browsers that aren't installed are listed but don't fail an `"all"` run
+    pub fn passed(&self) -> bool {
+        let mut found = self.browsers.iter().filter(|run| run.found()).peekable();
+        found.peek().is_some() && found.all(BrowserDryRun::passed)
+    }
+}
+
+impl fmt::Display for DryRunReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.browsers.is_empty() {
+            writeln!(f, "no browser found")?;
+        }
+        for browser in &self.browsers {
+            match browser.version {
+                Some(version) => writeln!(f, "{} {} ({:?})", browser.browser, version, version.source)?,
+                None => writeln!(f, "{}", browser.browser)?,
+            }
+            for (stage, status) in &browser.stages {
+                let name = format!("{:?}", stage).to_lowercase();
+                match (status, browser.rows) {
+                    (StageStatus::Passed, Some(rows)) if *stage == Stage::Database => {
+                        write!(f, "  {}: ok ({} rows", name, rows)?;
+                        if let Some(version) = browser.schema_version {
+                            write!(f, ", schema {}", version)?;
+                        }
+                        if let (true, Some(max)) = (browser.schema_is_newer(), browser.max_tested_schema) {
+                            write!(f, ", newer than the tested {}", max)?;
+                        }
+                        writeln!(f, ")")?
+                    }
+                    (StageStatus::Passed, _) => writeln!(f, "  {}: ok", name)?,
+                    (StageStatus::Failed(reason), _) => writeln!(f, "  {}: failed, {}", name, reason)?,
+                    (StageStatus::NotVerified(reason), _) => writeln!(f, "  {}: not verified, {}", name, reason)?,
+                    (StageStatus::Skipped, _) => writeln!(f, "  {}: skipped", name)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn count_rows(db_path: &Path, table: &str) -> Result<usize> {
+    let connection = sqlite::connect(db_path.to_path_buf())?;
+    let rows: i64 = connection.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+        row.get(0)
+    })?;
+    Ok(rows as usize)
+}
+
+/// Checks every stage of reading a chromium based browser without returning any value.
+/// The key is wiped from memory once the sample value is decrypted.
+pub fn check_chromium(browser: &str, config: &BrowserConfig) -> BrowserDryRun {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            check_chromium_keys(browser, config)
+        } else {
+            check_chromium_with_secrets(browser, config, &OsSecrets)
+        }
+    }
+}
+
+/// Same as [`check_chromium`] with the keyring / keychain passwords taken from `secrets`.
+/// The Key stage is "not verified" when `secrets` has no password, chromium falls back
+/// to a built-in key then, so finding a key says nothing about the keystore.
+#[cfg(unix)]
+pub fn check_chromium_with_secrets(
+    browser: &str,
+    config: &BrowserConfig,
+    secrets: &dyn SecretsProvider
+) -> BrowserDryRun {
+    check_chromium_keys(browser, config, secrets)
+}
+
+fn check_chromium_keys(
+    browser: &str,
+    config: &BrowserConfig,
+    #[cfg(unix)] secrets: &dyn SecretsProvider
+) -> BrowserDryRun {
+    let mut run = BrowserDryRun::new(browser);
+    let Some((key_path, db_path)) = run.record(
+        Stage::Paths,
+        paths::find_chrome_based_paths(config)
+    ) else {
+        return run.finish();
+    };
+    run.version = chromium::browser_version(&key_path, chromium::executable_name(browser));
+
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let Some(keys) = run.record(Stage::Key, chromium::local_state_keys(&key_path).map(WipedKeys)) else {
+                return run.finish();
+            };
+        } else {
+            let _ = key_path;
+            let keys = match chromium::keystore_keys(config, secrets) {
+                Ok(keys) if keys.is_empty() => {
+                    let reason = "no keyring / keychain password, only the built-in fallback keys";
+                    run.stages.push((Stage::Key, StageStatus::NotVerified(reason.to_string())));
+                    Some(WipedKeys(keys))
+                }
+                keys => run.record(Stage::Key, keys.map(WipedKeys)),
+            };
+            let Some(mut keys) = keys else {
+                return run.finish();
+            };
+            keys.0.extend(chromium::fallback_keys());
+        }
+    }
+
+    run.rows = run.record(Stage::Database, count_rows(&db_path, "cookies"));
+    run.schema_version = chromium::schema_version(&db_path).ok().flatten();
+    run.max_tested_schema = Some(chromium::MAX_TESTED_SCHEMA);
+    if run.rows.is_some() {
+        let sample = chromium::decrypt_sample(&db_path, &keys.0).and_then(|decrypted| {
+            if !decrypted {
+                bail!("no encrypted values to check the key with");
+            }
+            Ok(())
+        });
+        run.record(Stage::Decrypt, sample);
+    }
+    drop(keys);
+    run.finish()
+}
+
+/// Checks that the cookies DB of a mozilla based browser is found and readable,
+/// its values aren't encrypted so there is no key to check
+pub fn check_mozilla(browser: &str, config: &BrowserConfig) -> BrowserDryRun {
+    let mut run = BrowserDryRun::new(browser);
+    if let Some(db_path) = run.record(Stage::Paths, paths::find_mozilla_based_paths(config)) {
+        run.rows = run.record(Stage::Database, count_rows(&db_path, "moz_cookies"));
+        run.schema_version = mozilla::schema_version(&db_path).ok().flatten();
+        run.max_tested_schema = Some(mozilla::MAX_TESTED_SCHEMA);
+    }
+    run.finish()
+}
+
+#[cfg(target_os = "macos")]
+fn check_safari() -> BrowserDryRun {
+    let mut run = BrowserDryRun::new("safari");
+    if let Some(db_path) = run.record(Stage::Paths, paths::find_safari_based_paths(&config::SAFARI_CONFIG)) {
+        let cookies = crate::browser::safari::safari_based(db_path, None);
+        run.rows = run.record(Stage::Database, cookies.map(|cookies| cookies.len()));
+    }
+    run.finish()
+}
+
+fn check(browser: &str) -> Option<BrowserDryRun> {
+    if let Some((name, config)) = crate::mozilla_browsers().into_iter().find(|(name, _)| *name == browser) {
+        return Some(check_mozilla(name, config));
+    }
+    match browser {
+        #[cfg(target_os = "macos")]
+        "safari" => Some(check_safari()),
+        _ =>
+            crate
+                ::chromium_browsers()
+                .into_iter()
+                .find(|(name, _)| *name == browser)
+                .map(|(name, config)| check_chromium(name, config)),
+    }
+}
+
+/// Validates everything a read of `browser` needs (paths, key, DB, decryption of one
+/// value) without returning or logging any cookie value, e.g. to check a CI machine.
+/// `"all"` checks every browser, the ones that aren't installed are listed with a
+/// failed Paths stage but don't make the report fail.
+///
+/// # Arguments
+///
+/// * `browser` - A browser name as in [`crate::load`] ("chrome", "firefox", ...) or "all"
+///
+/// # Examples
+///
+/// ```no_run
+/// fn main() {
+///     let report = rookie::dry_run("all").unwrap();
+///     println!("{}", report);
+/// }
+/// ```
+pub fn dry_run(browser: &str) -> Result<DryRunReport> {
+    let browser = browser.to_lowercase();
+    let mut report = DryRunReport::default();
+    if browser == "all" {
+        let mut names: Vec<_> = crate::mozilla_browsers().into_iter().map(|(name, _)| name).collect();
+        #[cfg(target_os = "macos")]
+        names.push("safari");
+        names.extend(crate::chromium_browsers().into_iter().map(|(name, _)| name));
+        report.browsers = names.into_iter().filter_map(check).collect();
+        return Ok(report);
+    }
+    match check(&browser) {
+        Some(run) => report.browsers.push(run),
+        None => bail!("unknown browser {}", browser),
+    }
+    Ok(report)
+}
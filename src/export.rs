@@ -0,0 +1,436 @@
+use crate::common::{ date, enums::{ Cookie, SameSite }, provenance::Provenance };
+use anyhow::{ anyhow, bail, Result };
+use log::warn;
+use serde::{ Deserialize, Serialize };
+use std::borrow::Borrow;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use url::Url;
+
+/// Script of `document.cookie` setters for one page
+#[derive(Debug)]
+pub struct JsExport {
+    pub script: String,
+    /// httpOnly cookies can't be set from a script and are left out
+    pub skipped_http_only: usize,
+    /// Cookies that don't apply to the page host and would be rejected by the browser
+    pub skipped_other_domain: usize,
+}
+
+fn same_site_attribute(cookie: &Cookie) -> Option<&'static str> {
+    match cookie.same_site {
+        SameSite::None if cookie.secure => Some("none"),
+        SameSite::Lax => Some("lax"),
+        SameSite::Strict => Some("strict"),
+        _ => None,
+    }
+}
+
+fn domain_matches(cookie: &Cookie, host: &str) -> bool {
+    let domain = cookie.domain.trim_start_matches('.');
+    host == domain || (!cookie.host_only && host.ends_with(&format!(".{}", domain)))
+}
+
+/// Builds a script to paste into the devtools console of `url`,
+/// restoring every cookie that a page script is allowed to set
+pub fn to_js(cookies: &[Cookie], url: &str) -> Result<JsExport> {
+    let url = Url::parse(url)?;
+    let host = url.host_str().ok_or_else(|| anyhow!("{} has no host", url))?;
+
+    let mut export = JsExport {
+        script: format!("// {}\n", url),
+        skipped_http_only: 0,
+        skipped_other_domain: 0,
+    };
+    for cookie in cookies {
+        if cookie.http_only {
+            export.skipped_http_only += 1;
+            continue;
+        }
+        if !domain_matches(cookie, host) {
+            export.skipped_other_domain += 1;
+            continue;
+        }
+
+        // a raw semicolon would end the cookie value
+        let mut attributes = vec![
+            format!("{}={}", cookie.name.replace(';', "%3B"), cookie.value.replace(';', "%3B"))
+        ];
+        if !cookie.host_only {
+            attributes.push(format!("domain={}", cookie.domain));
+        }
+        attributes.push(format!("path={}", cookie.path));
+        if let Some(expires) = cookie.expires {
+            attributes.push(format!("expires={}", date::http_date(expires)));
+        }
+        if cookie.secure {
+            attributes.push("secure".to_string());
+        }
+        if let Some(same_site) = same_site_attribute(cookie) {
+            attributes.push(format!("samesite={}", same_site));
+        }
+
+        // a JSON string is a valid JS string literal with quotes and backslashes escaped
+        let literal = serde_json::to_string(&attributes.join("; "))?;
+        export.script += &format!("document.cookie = {};\n", literal);
+    }
+    Ok(export)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CookieEditorCookie<'a> {
+    domain: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expiration_date: Option<u64>,
+    host_only: bool,
+    http_only: bool,
+    name: &'a str,
+    path: &'a str,
+    same_site: &'static str,
+    secure: bool,
+    session: bool,
+    store_id: Option<&'a str>,
+    value: &'a str,
+}
+
+/// Serializes cookies in the import format of the Cookie-Editor browser extension
+pub fn to_cookie_editor_json(cookies: &[Cookie]) -> Result<String> {
+    let cookies: Vec<_> = cookies
+        .iter()
+        .map(|cookie| CookieEditorCookie {
+            domain: &cookie.domain,
+            expiration_date: cookie.expires,
+            host_only: cookie.host_only,
+            http_only: cookie.http_only,
+            name: &cookie.name,
+            path: &cookie.path,
+            same_site: match cookie.same_site {
+                SameSite::None => "no_restriction",
+                SameSite::Lax => "lax",
+                SameSite::Strict => "strict",
+                SameSite::Unspecified => "unspecified",
+            },
+            secure: cookie.secure,
+            session: cookie.expires.is_none(),
+            store_id: None,
+            value: &cookie.value,
+        })
+        .collect();
+    Ok(serde_json::to_string_pretty(&cookies)?)
+}
+
+/// A [`Cookie`] in the shape of its `serde` derive, plus its timestamps as ISO 8601.
+/// Spelled out so the exports don't need the feature
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonCookie<'a> {
+    domain: &'a str,
+    host_only: bool,
+    path: &'a str,
+    secure: bool,
+    expires: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    creation: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_accessed: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    partition_key: Option<&'a str>,
+    name: &'a str,
+    value: &'a str,
+    http_only: bool,
+    same_site: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_scheme: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    extension: Option<JsonExtension<'a>>,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    browser: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    profile: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expires_iso: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    creation_iso: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_accessed_iso: Option<String>,
+    #[serde(rename = "_provenance", skip_serializing_if = "Option::is_none")]
+    provenance: Option<&'a Provenance>,
+}
+
+#[derive(Serialize)]
+struct JsonExtension<'a> {
+    id: &'a str,
+    name: Option<&'a str>,
+}
+
+impl<'a> JsonCookie<'a> {
+    fn new(cookie: &'a Cookie, provenance: Option<&'a Provenance>) -> Self {
+        JsonCookie {
+            domain: &cookie.domain,
+            host_only: cookie.host_only,
+            path: &cookie.path,
+            secure: cookie.secure,
+            expires: cookie.expires,
+            creation: cookie.creation,
+            last_accessed: cookie.last_accessed,
+            partition_key: cookie.partition_key.as_deref(),
+            name: &cookie.name,
+            value: &cookie.value,
+            http_only: cookie.http_only,
+            same_site: cookie.same_site.into(),
+            port: cookie.port,
+            source_scheme: (!cookie.source_scheme.is_unset()).then(|| cookie.source_scheme.into()),
+            extension: cookie.extension.as_ref().map(|extension| JsonExtension {
+                id: &extension.id,
+                name: extension.name.as_deref(),
+            }),
+            browser: &cookie.browser,
+            profile: cookie.profile.as_deref(),
+            expires_iso: cookie.expires.map(date::iso_8601),
+            creation_iso: cookie.creation.map(date::iso_8601),
+            last_accessed_iso: cookie.last_accessed.map(date::iso_8601),
+            provenance,
+        }
+    }
+}
+
+/// Serializes cookies as a JSON array. Each object has the camelCase fields of the
+/// `serde` derive of [`Cookie`], so it deserializes back into one with that feature,
+/// and `expiresIso`, `creationIso` and `lastAccessedIso` with the unix seconds of the
+/// timestamps as ISO 8601.
+pub fn to_json(cookies: &[Cookie]) -> Result<String> {
+    json_with(cookies, None)
+}
+
+/// Same as [`to_json`] with the [`Provenance`] of the read the cookies come from
+/// (see `ReadOptions::provenance`) under `_provenance` in each object
+pub fn to_json_with_provenance(cookies: &[Cookie], provenance: &Provenance) -> Result<String> {
+    json_with(cookies, Some(provenance))
+}
+
+fn json_with(cookies: &[Cookie], provenance: Option<&Provenance>) -> Result<String> {
+    let cookies: Vec<JsonCookie> = cookies
+        .iter()
+        .map(|cookie| JsonCookie::new(cookie, provenance))
+        .collect();
+    Ok(serde_json::to_string_pretty(&cookies)?)
+}
+
+/// Writes one cookie per line in the format of [`to_json`] to `writer`, flushing each
+/// line as it's written so a large store can be streamed without collecting it first
+pub fn to_ndjson(cookies: impl IntoIterator<Item = impl Borrow<Cookie>>, writer: impl Write) -> Result<()> {
+    ndjson_with(cookies, None, writer)
+}
+
+/// Same as [`to_ndjson`] with `provenance` under `_provenance` on every line, see
+/// [`to_json_with_provenance`]
+pub fn to_ndjson_with_provenance(
+    cookies: impl IntoIterator<Item = impl Borrow<Cookie>>,
+    provenance: &Provenance,
+    writer: impl Write
+) -> Result<()> {
+    ndjson_with(cookies, Some(provenance), writer)
+}
+
+fn ndjson_with(
+    cookies: impl IntoIterator<Item = impl Borrow<Cookie>>,
+    provenance: Option<&Provenance>,
+    mut writer: impl Write
+) -> Result<()> {
+    for cookie in cookies {
+        serde_json::to_writer(&mut writer, &JsonCookie::new(cookie.borrow(), provenance))?;
+        writer.write_all(b"\n")?;
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+/// Cookies as the `cookies` entries of a HAR 1.2 request, an array of objects with
+/// `name`, `value`, `path`, `domain`, `expires` (ISO 8601, left out for session
+/// cookies), `httpOnly`, `secure` and `sameSite` as in Chrome's HAR exports (`Lax`,
+/// `Strict`, `None`, left out when unspecified). Values are kept as stored by the browser.
+pub fn to_har(cookies: &[Cookie]) -> serde_json::Value {
+    cookies
+        .iter()
+        .map(|cookie| {
+            let mut entry = serde_json::json!({
+                "name": cookie.name,
+                "value": cookie.value,
+                "path": cookie.path,
+                "domain": cookie.domain,
+                "httpOnly": cookie.http_only,
+                "secure": cookie.secure,
+            });
+            if let Some(expires) = cookie.expires {
+                entry["expires"] = date::iso_8601(expires).into();
+            }
+            let same_site = match cookie.same_site {
+                SameSite::Lax => Some("Lax"),
+                SameSite::Strict => Some("Strict"),
+                SameSite::None => Some("None"),
+                SameSite::Unspecified => None,
+            };
+            if let Some(same_site) = same_site {
+                entry["sameSite"] = same_site.into();
+            }
+            entry
+        })
+        .collect()
+}
+
+/// Same as [`to_har`] with `provenance` under `_provenance` in each entry, the
+/// underscore prefix HAR reserves for custom fields
+pub fn to_har_with_provenance(cookies: &[Cookie], provenance: &Provenance) -> Result<serde_json::Value> {
+    let provenance = serde_json::to_value(provenance)?;
+    let mut har = to_har(cookies);
+    if let Some(entries) = har.as_array_mut() {
+        for entry in entries {
+            entry["_provenance"] = provenance.clone();
+        }
+    }
+    Ok(har)
+}
+
+/// Whether the domain, path, name and value of a cookie fit in their `cookies.txt`
+/// columns, warns when they don't
+fn netscape_writable(fields: [&str; 4]) -> bool {
+    if fields.iter().any(|field| field.contains(['\t', '\n', '\r'])) {
+        warn!("Skipping cookie {:?} of {} in cookies.txt, it has a tab or line break", fields[2], fields[0]);
+        return false;
+    }
+    true
+}
+
+/// Writes cookies in the Netscape `cookies.txt` format read by curl, wget and yt-dlp
+/// to `writer`, e.g. a file handed to `curl -b`, `wget --load-cookies` or `yt-dlp --cookies`.
+/// httpOnly cookies get the `#HttpOnly_` domain prefix and session cookies expire at 0.
+/// Cookies with a tab or line break in a field would break the 7 columns and are
+/// skipped with a warning.
+pub fn to_netscape(cookies: &[Cookie], mut writer: impl Write) -> Result<()> {
+    let bool_field = |value: bool| if value { "TRUE" } else { "FALSE" };
+    writer.write_all(b"# Netscape HTTP Cookie File\n")?;
+    for cookie in cookies {
+        if !netscape_writable([&cookie.domain, &cookie.path, &cookie.name, &cookie.value]) {
+            continue;
+        }
+        writeln!(
+            writer,
+            "{}{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            if cookie.http_only { "#HttpOnly_" } else { "" },
+            cookie.domain,
+            bool_field(!cookie.host_only),
+            cookie.path,
+            bool_field(cookie.secure),
+            cookie.expires.unwrap_or(0),
+            cookie.name,
+            cookie.value
+        )?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// One file written by [`write_netscape_chunked`] or [`write_jsonl_chunked`]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Chunk {
+    /// File name inside the output directory, e.g. `cookies-0001.txt`
+    pub file: String,
+    pub cookies: usize,
+    /// Smallest and largest cookie domain in the file, compared as strings
+    pub first_domain: String,
+    pub last_domain: String,
+}
+
+/// Index of a chunked export, written as `manifest.json` next to the chunks
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChunkManifest {
+    pub chunks: Vec<Chunk>,
+    /// Cookies in all chunks
+    pub cookies: usize,
+}
+
+fn write_chunk(
+    dir: &Path,
+    extension: &str,
+    chunk: &[Cookie],
+    render: fn(&[Cookie]) -> Result<String>,
+    manifest: &mut ChunkManifest
+) -> Result<()> {
+    let file = format!("cookies-{:04}.{}", manifest.chunks.len() + 1, extension);
+    fs::write(dir.join(&file), render(chunk)?)?;
+    let domains = chunk.iter().map(|cookie| &cookie.domain);
+    manifest.chunks.push(Chunk {
+        file,
+        cookies: chunk.len(),
+        first_domain: domains.clone().min().cloned().unwrap_or_default(),
+        last_domain: domains.max().cloned().unwrap_or_default(),
+    });
+    manifest.cookies += chunk.len();
+    Ok(())
+}
+
+fn write_chunked(
+    cookies: impl IntoIterator<Item = Cookie>,
+    dir: &Path,
+    max_cookies_per_file: usize,
+    extension: &str,
+    render: fn(&[Cookie]) -> Result<String>
+) -> Result<ChunkManifest> {
+    if max_cookies_per_file == 0 {
+        bail!("max_cookies_per_file must be at least 1");
+    }
+    fs::create_dir_all(dir)?;
+    let mut manifest = ChunkManifest::default();
+    // only one chunk is held in memory, a cookie is always written whole to the current one
+    let mut chunk: Vec<Cookie> = vec![];
+    for cookie in cookies {
+        chunk.push(cookie);
+        if chunk.len() == max_cookies_per_file {
+            write_chunk(dir, extension, &chunk, render, &mut manifest)?;
+            chunk.clear();
+        }
+    }
+    if !chunk.is_empty() {
+        write_chunk(dir, extension, &chunk, render, &mut manifest)?;
+    }
+    fs::write(dir.join("manifest.json"), serde_json::to_string_pretty(&manifest)?)?;
+    Ok(manifest)
+}
+
+/// Writes cookies in the Netscape format (see [`to_netscape`]) to `cookies-0001.txt`,
+/// `cookies-0002.txt`, ... in `dir`, at most `max_cookies_per_file` each, plus a
+/// `manifest.json` listing the chunks. Cookies are consumed one at a time so a
+/// large store doesn't have to be collected first.
+pub fn write_netscape_chunked(
+    cookies: impl IntoIterator<Item = Cookie>,
+    dir: &Path,
+    max_cookies_per_file: usize
+) -> Result<ChunkManifest> {
+    // skipped before chunking so the manifest counts what's written
+    let cookies = cookies
+        .into_iter()
+        .filter(|cookie| netscape_writable([&cookie.domain, &cookie.path, &cookie.name, &cookie.value]));
+    write_chunked(cookies, dir, max_cookies_per_file, "txt", |chunk| {
+        let mut txt = vec![];
+        to_netscape(chunk, &mut txt)?;
+        Ok(String::from_utf8(txt)?)
+    })
+}
+
+/// Same as [`write_netscape_chunked`] with one cookie per line as in [`to_ndjson`] in
+/// `cookies-0001.jsonl`, `cookies-0002.jsonl`, ...
+pub fn write_jsonl_chunked(
+    cookies: impl IntoIterator<Item = Cookie>,
+    dir: &Path,
+    max_cookies_per_file: usize
+) -> Result<ChunkManifest> {
+    write_chunked(cookies, dir, max_cookies_per_file, "jsonl", |chunk| {
+        let mut jsonl = vec![];
+        to_ndjson(chunk, &mut jsonl)?;
+        Ok(String::from_utf8(jsonl)?)
+    })
+}
@@ -0,0 +1,92 @@
+use crate::common::enums::{ Cookie, SameSite, SourceScheme };
+use anyhow::{ bail, Result };
+use log::warn;
+use std::io::BufRead;
+
+/// Reads a Netscape `cookies.txt` as written by curl, wget, yt-dlp and
+/// [`crate::export::to_netscape`]. `#HttpOnly_` domain prefixes mark httpOnly cookies,
+/// the flags are read case insensitively and a 0 or empty expiry is a session cookie,
+/// the latter as yt-dlp and Python's `http.cookiejar` save them. Comments
+/// and blank lines are skipped, and so are malformed lines with a warning.
+///
+/// Domain cookies get the leading dot browsers store them with, so the cookies work
+/// like browser ones with every other function.
+pub fn from_netscape(mut reader: impl BufRead) -> Result<Vec<Cookie>> {
+    let mut cookies = vec![];
+    let mut line = vec![];
+    let mut number = 0;
+    while reader.read_until(b'\n', &mut line)? > 0 {
+        number += 1;
+        match String::from_utf8(std::mem::take(&mut line)) {
+            Ok(text) =>
+                match parse_line(text.trim_end_matches(['\n', '\r'])) {
+                    Ok(Some(cookie)) => cookies.push(cookie),
+                    Ok(None) => {}
+                    Err(err) => warn!("Skipping line {} of cookies.txt: {}", number, err),
+                }
+            Err(_) => warn!("Skipping line {} of cookies.txt: not valid UTF-8", number),
+        }
+    }
+    Ok(cookies)
+}
+
+fn parse_line(line: &str) -> Result<Option<Cookie>> {
+    let (line, http_only) = match line.strip_prefix("#HttpOnly_") {
+        Some(line) => (line, true),
+        None => (line, false),
+    };
+    if line.trim().is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+    // curl writes an empty value as a missing column, a value can hold tabs
+    let fields: Vec<&str> = line.splitn(7, '\t').collect();
+    if fields.len() < 6 {
+        bail!("expected 7 tab separated fields, found {}", fields.len());
+    }
+    let flag = |field: &str| -> Result<bool> {
+        if field.eq_ignore_ascii_case("TRUE") {
+            Ok(true)
+        } else if field.eq_ignore_ascii_case("FALSE") {
+            Ok(false)
+        } else {
+            bail!("{:?} isn't TRUE or FALSE", field)
+        }
+    };
+    let include_subdomains = flag(fields[1])?;
+    let secure = flag(fields[3])?;
+    let expires = match fields[4] {
+        "" => 0,
+        expires =>
+            match expires.parse::<u64>() {
+                Ok(expires) => expires,
+                Err(_) => bail!("{:?} isn't a unix time", expires),
+            }
+    };
+    let domain = match fields[0] {
+        "" => bail!("the domain is empty"),
+        domain if include_subdomains && !domain.starts_with('.') => format!(".{}", domain),
+        domain => domain.to_string(),
+    };
+    Ok(
+        Some(Cookie {
+            host_only: !include_subdomains,
+            domain,
+            path: fields[2].to_string(),
+            secure,
+            expires: (expires != 0).then_some(expires),
+            name: fields[5].to_string(),
+            value: fields.get(6).copied().unwrap_or_default().to_string(),
+            http_only,
+            same_site: SameSite::Unspecified,
+            same_site_raw: None,
+            creation: None,
+            last_accessed: None,
+            partition_key: None,
+            port: None,
+            source_scheme: SourceScheme::Unset,
+            extension: None,
+            browser: String::new(),
+            profile: None,
+        })
+    )
+}
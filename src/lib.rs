@@ -1,28 +1,1279 @@
+#![allow(clippy::needless_doctest_main)]
+
 pub mod browser;
 pub mod common;
 pub mod config;
+pub mod defaults;
+pub mod dry_run;
+pub mod export;
+pub mod import;
+pub mod login;
+pub mod prelude;
+pub mod profiles;
+pub mod sites;
+pub mod ytdlp;
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
+#[cfg(feature = "cookie")]
+pub mod cookie;
+
+#[cfg(feature = "cookie_store")]
+pub mod cookie_store;
+
+#[cfg(feature = "reqwest")]
+pub mod reqwest;
+
+use anyhow::{ anyhow, bail, Context, Result };
+use browser::chromium::{ self, chromium_based };
+use browser::binarycookies::binarycookies_based;
+#[cfg(target_os = "linux")]
+use browser::epiphany::epiphany_based;
+use browser::mozilla::{ self, firefox_based, firefox_read, firefox_read_filtered };
+use browser::yandex::yandex_based;
+use common::enums::{
+    BrowserConfig,
+    ChannelCookies,
+    ChannelsRead,
+    Cookie,
+    CookiesRead,
+    EnumerationOptions,
+    ProfileCookies,
+    ProfileIdentity,
+    ProfilesRead,
+    UsersRead,
+};
+use common::{ date, paths, request, sqlite::UnsupportedSchema, utils };
+use log::warn;
+pub use common::enums::{ CookieFilter, Domains, LoadOptions, LoadOutcome, NameFilter, ReadOptions };
+pub use common::request::{ header_for_request, to_cookie_header, RequestContext };
+pub use defaults::{ reset_global_defaults, set_global_defaults, GlobalDefaults };
+#[cfg(feature = "cookie_store")]
+pub use crate::cookie_store::to_cookie_store;
+pub use common::paths::with_user_profile_root;
+pub use dry_run::{ dry_run, DryRunReport };
+pub use login::{ login_status, LoginStatus };
+pub use profiles::{ list_profiles, read_profile, ProfileInfo };
+pub use sites::register_site_profile;
+
+cfg_if::cfg_if! {
+    if #[cfg(target_os = "windows")] {
+        use std::path::PathBuf;
+        use browser::internet_explorer;
+        pub use internet_explorer::internet_explorer_based;
+    } else {
+        use browser::chromium::{ chromium_read_filtered, chromium_read_with };
+        use common::secrets::{ CachedSecrets, InteractionRequired, OsSecrets, SecretsProvider };
+        #[cfg(target_os = "macos")]
+        use browser::safari::{ safari_based, safari_read };
+    }
+}
+
+/// Returns cookies from firefox
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::firefox(Some(domains));
+/// }
+/// ```
+pub fn firefox(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    let db_path = paths::find_mozilla_based_paths(&config::FIREFOX_CONFIG)?;
+    labeled("firefox", firefox_based(db_path, domains))
+}
+
+/// Returns cookies from every firefox profile holding a `cookies.sqlite`, grouped by
+/// profile name (the profiles.ini `Name`, else the directory name). Empty or locked
+/// databases are skipped, an error is returned only when no profile could be read.
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let profiles = rookie::firefox_all_profiles(Some(domains));
+/// }
+/// ```
+pub fn firefox_all_profiles(domains: impl Into<Domains>) -> Result<Vec<(String, Vec<Cookie>)>> {
+    let domains: Domains = domains.into();
+    let infos = profiles::mozilla_profile_infos("firefox", &config::FIREFOX_CONFIG);
+    if infos.is_empty() {
+        bail!("cant find any firefox profile");
+    }
+    let mut profiles = vec![];
+    let mut failure = None;
+    for info in infos {
+        let db_path = info.db_path;
+        let profile = info.name.unwrap_or(info.profile_dir);
+        let read = match std::fs::metadata(&db_path) {
+            Ok(metadata) if metadata.len() == 0 => Err(anyhow!("empty database")),
+            _ => labeled("firefox", firefox_based(db_path.clone(), &domains)),
+        };
+        match read {
+            Ok(cookies) => profiles.push((profile, cookies)),
+            Err(err) => {
+                warn!("Skipping profile {}: {}", db_path.display(), err);
+                failure.get_or_insert(err.context(format!("cant read {}", db_path.display())));
+            }
+        }
+    }
+    match failure {
+        Some(err) if profiles.is_empty() => Err(err),
+        _ => Ok(profiles),
+    }
+}
+
+/// Returns cookies from Firefox for Android app data pulled from a device
+///
+/// # Arguments
+///
+/// * `profile_root` - Extracted app data directory (`org.mozilla.firefox`) or `adb backup` extraction
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::firefox_android(Path::new("org.mozilla.firefox"), Some(domains));
+/// }
+/// ```
+pub fn firefox_android(
+    profile_root: &std::path::Path,
+    domains: impl Into<Domains>
+) -> Result<Vec<Cookie>> {
+    let db_path = paths::find_mozilla_android_path(profile_root)?;
+    labeled("firefox", firefox_based(db_path, domains))
+}
+
+/// Returns cookies from libre wolf
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::libre_wolf(Some(domains));
+/// }
+/// ```
+pub fn libre_wolf(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    let db_path = paths::find_mozilla_based_paths(&config::LIBRE_WOLF_CONFIG)?;
+    labeled("librewolf", firefox_based(db_path, domains))
+}
+
+/// Returns cookies from a LibreWolf profile at a custom location, e.g. one started
+/// with `-profile` or a portable install
+///
+/// # Arguments
+///
+/// * `path` - Profile directory, or a profiles root holding profiles.ini
+/// * `domains` - An optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let profile = Path::new("LibreWolf-Portable/Profiles/Default");
+///     let cookies = rookie::libre_wolf_from_dir(profile, Some(domains));
+/// }
+/// ```
+pub fn libre_wolf_from_dir(
+    path: &std::path::Path,
+    domains: impl Into<Domains>
+) -> Result<Vec<Cookie>> {
+    let db_path = paths::find_mozilla_dir_path(path)?;
+    labeled("librewolf", firefox_based(db_path, domains))
+}
+
+/// Returns cookies from waterfox, the current edition or Waterfox Classic
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::waterfox(Some(domains));
+/// }
+/// ```
+pub fn waterfox(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    let db_path = paths::find_mozilla_based_paths(&config::WATERFOX_CONFIG)?;
+    labeled("waterfox", firefox_based(db_path, domains))
+}
+
+/// Returns cookies from mullvad browser
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::mullvad(Some(domains));
+/// }
+/// ```
+pub fn mullvad(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    let db_path = paths::find_mozilla_based_paths(&config::MULLVAD_CONFIG)?;
+    labeled("mullvad", firefox_based(db_path, domains))
+}
+
+/// Returns cookies from thunderbird. It isn't read by [`load`], webmail and
+/// calendar logins are only wanted when asked for
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["mail.google.com"];
+///     let cookies = rookie::thunderbird(Some(domains));
+/// }
+/// ```
+pub fn thunderbird(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    let db_path = paths::find_mozilla_based_paths(&config::THUNDERBIRD_CONFIG)?;
+    labeled("thunderbird", firefox_based(db_path, domains))
+}
+
+/// Returns cookies from zen, merged from all of its profiles
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::zen(Some(domains));
+/// }
+/// ```
+pub fn zen(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    let domains: Domains = domains.into();
+    let mut cookies = vec![];
+    let mut failure = None;
+    let mut read_any = false;
+    for db_path in paths::find_mozilla_profile_paths(&config::ZEN_CONFIG)? {
+        match labeled("zen", firefox_based(db_path.clone(), &domains)) {
+            Ok(profile_cookies) => {
+                cookies.extend(profile_cookies);
+                read_any = true;
+            }
+            Err(err) => {
+                warn!("Skipping profile {}: {}", db_path.display(), err);
+                failure.get_or_insert(err.context(format!("cant read {}", db_path.display())));
+            }
+        }
+    }
+    match failure {
+        Some(err) if !read_any => Err(err),
+        _ => Ok(cookies),
+    }
+}
+
+/// Returns cookies from chrome
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::chrome(Some(domains));
+/// }
+/// ```
+pub fn chrome(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let (key, db_path) = paths::find_chrome_based_paths(&config::CHROME_CONFIG)?;
+            labeled("chrome", chromium_based(key, db_path, domains))
+        } else {
+            let (_, db_path) = paths::find_chrome_based_paths(&config::CHROME_CONFIG)?;
+            labeled("chrome", chromium_based(&config::CHROME_CONFIG, db_path, domains))
+        }
+    }
+}
+
+/// Returns cookies from a Chrome profile, given by its directory ("Profile 2") or by
+/// the name Chrome shows for it. The error lists the available profiles when none matches
+///
+/// # Arguments
+///
+/// * `name` - Profile directory or display name
+/// * `domains` - An optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::chrome_profile("Work", Some(domains));
+/// }
+/// ```
+pub fn chrome_profile(
+    name: &str,
+    domains: impl Into<Domains>
+) -> Result<Vec<Cookie>> {
+    read_profile(&profiles::find_profile("chrome", name)?, domains)
+}
+
+/// Returns cookies from chrome beta
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::chrome_beta(Some(domains));
+/// }
+/// ```
+pub fn chrome_beta(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let (key, db_path) = paths::find_chrome_based_paths(&config::CHROME_BETA_CONFIG)?;
+            labeled("chrome_beta", chromium_based(key, db_path, domains))
+        } else {
+            let (_, db_path) = paths::find_chrome_based_paths(&config::CHROME_BETA_CONFIG)?;
+            labeled("chrome_beta", chromium_based(&config::CHROME_BETA_CONFIG, db_path, domains))
+        }
+    }
+}
+
+/// Returns cookies from chrome dev
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::chrome_dev(Some(domains));
+/// }
+/// ```
+pub fn chrome_dev(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let (key, db_path) = paths::find_chrome_based_paths(&config::CHROME_DEV_CONFIG)?;
+            labeled("chrome_dev", chromium_based(key, db_path, domains))
+        } else {
+            let (_, db_path) = paths::find_chrome_based_paths(&config::CHROME_DEV_CONFIG)?;
+            labeled("chrome_dev", chromium_based(&config::CHROME_DEV_CONFIG, db_path, domains))
+        }
+    }
+}
 
-use anyhow::{ bail, Result };
-use browser::chromium::chromium_based;
-use browser::mozilla::firefox_based;
-use common::enums::Cookie;
-use common::paths;
-use std::path::PathBuf;
+/// Returns cookies from chrome canary
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::chrome_canary(Some(domains));
+/// }
+/// ```
+pub fn chrome_canary(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let (key, db_path) = paths::find_chrome_based_paths(&config::CHROME_CANARY_CONFIG)?;
+            labeled("chrome_canary", chromium_based(key, db_path, domains))
+        } else {
+            let (_, db_path) = paths::find_chrome_based_paths(&config::CHROME_CANARY_CONFIG)?;
+            labeled("chrome_canary", chromium_based(&config::CHROME_CANARY_CONFIG, db_path, domains))
+        }
+    }
+}
+
+#[deprecated(note = "use `chromium_profiles(&config::CHROME_CONFIG, domains, &Default::default())`, which also reports profile dirs and failures")]
+pub fn chrome_v2(domains: Option<Vec<&str>>) -> Result<Vec<(Vec<Cookie>, Option<String>)>> {
+    chromium_v2(&config::CHROME_CONFIG, domains)
+}
+
+/// Returns cookies from chromium
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::chromium(Some(domains));
+/// }
+/// ```
+pub fn chromium(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let (key, db_path) = paths::find_chrome_based_paths(&config::CHROMIUM_CONFIG)?;
+            labeled("chromium", chromium_based(key, db_path, domains))
+        } else {
+            let (_, db_path) = paths::find_chrome_based_paths(&config::CHROMIUM_CONFIG)?;
+            labeled("chromium", chromium_based(&config::CHROMIUM_CONFIG, db_path, domains))
+        }
+    }
+}
+
+/// Returns cookies from brave
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::brave(Some(domains));
+/// }
+/// ```
+pub fn brave(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let (key, db_path) = paths::find_chrome_based_paths(&config::BRAVE_CONFIG)?;
+            labeled("brave", chromium_based(key, db_path, domains))
+        } else {
+            let (_, db_path) = paths::find_chrome_based_paths(&config::BRAVE_CONFIG)?;
+            labeled("brave", chromium_based(&config::BRAVE_CONFIG, db_path, domains))
+        }
+    }
+}
+
+/// Returns cookies from a Brave profile, given by its directory ("Profile 2") or by
+/// the name Brave shows for it. The error lists the available profiles when none matches
+///
+/// # Arguments
+///
+/// * `name` - Profile directory or display name
+/// * `domains` - An optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::brave_profile("Work", Some(domains));
+/// }
+/// ```
+pub fn brave_profile(
+    name: &str,
+    domains: impl Into<Domains>
+) -> Result<Vec<Cookie>> {
+    read_profile(&profiles::find_profile("brave", name)?, domains)
+}
+
+/// Returns cookies from brave beta, use [`chromium_profiles`] with `config::BRAVE_BETA_CONFIG` for every profile
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::brave_beta(Some(domains));
+/// }
+/// ```
+pub fn brave_beta(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let (key, db_path) = paths::find_chrome_based_paths(&config::BRAVE_BETA_CONFIG)?;
+            labeled("brave_beta", chromium_based(key, db_path, domains))
+        } else {
+            let (_, db_path) = paths::find_chrome_based_paths(&config::BRAVE_BETA_CONFIG)?;
+            labeled("brave_beta", chromium_based(&config::BRAVE_BETA_CONFIG, db_path, domains))
+        }
+    }
+}
+
+/// Returns cookies from brave nightly, use [`chromium_profiles`] with `config::BRAVE_NIGHTLY_CONFIG` for every profile
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::brave_nightly(Some(domains));
+/// }
+/// ```
+pub fn brave_nightly(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let (key, db_path) = paths::find_chrome_based_paths(&config::BRAVE_NIGHTLY_CONFIG)?;
+            labeled("brave_nightly", chromium_based(key, db_path, domains))
+        } else {
+            let (_, db_path) = paths::find_chrome_based_paths(&config::BRAVE_NIGHTLY_CONFIG)?;
+            labeled("brave_nightly", chromium_based(&config::BRAVE_NIGHTLY_CONFIG, db_path, domains))
+        }
+    }
+}
+
+/// Returns cookies from every brave profile, paired with the browser version.
+/// The `Tor Profile` is skipped, use [`chromium_profiles`] with `include_tor` to read it
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::brave_v2(Some(domains));
+/// }
+/// ```
+#[deprecated(note = "use `chromium_profiles(&config::BRAVE_CONFIG, domains, &Default::default())`, which also reports profile dirs and failures")]
+pub fn brave_v2(domains: Option<Vec<&str>>) -> Result<Vec<(Vec<Cookie>, Option<String>)>> {
+    chromium_v2(&config::BRAVE_CONFIG, domains)
+}
+
+/// Returns cookies from edge
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::edge(Some(domains));
+/// }
+/// ```
+pub fn edge(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let (key, db_path) = paths::find_chrome_based_paths(&config::EDGE_CONFIG)?;
+            labeled("edge", chromium_based(key, db_path, domains))
+        } else {
+            let (_, db_path) = paths::find_chrome_based_paths(&config::EDGE_CONFIG)?;
+            labeled("edge", chromium_based(&config::EDGE_CONFIG, db_path, domains))
+        }
+    }
+}
+
+/// Returns cookies from an Edge profile, given by its directory ("Profile 2") or by
+/// the name Edge shows for it. The error lists the available profiles when none matches
+///
+/// # Arguments
+///
+/// * `name` - Profile directory or display name
+/// * `domains` - An optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::edge_profile("Work", Some(domains));
+/// }
+/// ```
+pub fn edge_profile(
+    name: &str,
+    domains: impl Into<Domains>
+) -> Result<Vec<Cookie>> {
+    read_profile(&profiles::find_profile("edge", name)?, domains)
+}
+
+/// Returns cookies from edge beta
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::edge_beta(Some(domains));
+/// }
+/// ```
+pub fn edge_beta(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let (key, db_path) = paths::find_chrome_based_paths(&config::EDGE_BETA_CONFIG)?;
+            labeled("edge_beta", chromium_based(key, db_path, domains))
+        } else {
+            let (_, db_path) = paths::find_chrome_based_paths(&config::EDGE_BETA_CONFIG)?;
+            labeled("edge_beta", chromium_based(&config::EDGE_BETA_CONFIG, db_path, domains))
+        }
+    }
+}
+
+/// Returns cookies from edge dev
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::edge_dev(Some(domains));
+/// }
+/// ```
+pub fn edge_dev(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let (key, db_path) = paths::find_chrome_based_paths(&config::EDGE_DEV_CONFIG)?;
+            labeled("edge_dev", chromium_based(key, db_path, domains))
+        } else {
+            let (_, db_path) = paths::find_chrome_based_paths(&config::EDGE_DEV_CONFIG)?;
+            labeled("edge_dev", chromium_based(&config::EDGE_DEV_CONFIG, db_path, domains))
+        }
+    }
+}
+
+/// Returns cookies from edge canary
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::edge_canary(Some(domains));
+/// }
+/// ```
+pub fn edge_canary(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let (key, db_path) = paths::find_chrome_based_paths(&config::EDGE_CANARY_CONFIG)?;
+            labeled("edge_canary", chromium_based(key, db_path, domains))
+        } else {
+            let (_, db_path) = paths::find_chrome_based_paths(&config::EDGE_CANARY_CONFIG)?;
+            labeled("edge_canary", chromium_based(&config::EDGE_CANARY_CONFIG, db_path, domains))
+        }
+    }
+}
+
+#[deprecated(note = "use `chromium_profiles(&config::EDGE_CONFIG, domains, &Default::default())`, which also reports profile dirs and failures")]
+pub fn edge_v2(domains: Option<Vec<&str>>) -> Result<Vec<(Vec<Cookie>, Option<String>)>> {
+    chromium_v2(&config::EDGE_CONFIG, domains)
+}
+
+/// Returns cookies from vivaldi
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::vivaldi(Some(domains));
+/// }
+/// ```
+pub fn vivaldi(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let (key, db_path) = paths::find_chrome_based_paths(&config::VIVALDI_CONFIG)?;
+            labeled("vivaldi", chromium_based(key, db_path, domains))
+        } else {
+            let (_, db_path) = paths::find_chrome_based_paths(&config::VIVALDI_CONFIG)?;
+            labeled("vivaldi", chromium_based(&config::VIVALDI_CONFIG, db_path, domains))
+        }
+    }
+}
+
+/// Returns cookies from a Vivaldi profile, given by its directory ("Profile 2") or by
+/// the name Vivaldi shows for it. The error lists the available profiles when none matches
+///
+/// # Arguments
+///
+/// * `name` - Profile directory or display name
+/// * `domains` - An optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::vivaldi_profile("Work", Some(domains));
+/// }
+/// ```
+pub fn vivaldi_profile(
+    name: &str,
+    domains: impl Into<Domains>
+) -> Result<Vec<Cookie>> {
+    read_profile(&profiles::find_profile("vivaldi", name)?, domains)
+}
+
+/// Returns cookies from vivaldi snapshot
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::vivaldi_snapshot(Some(domains));
+/// }
+/// ```
+pub fn vivaldi_snapshot(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let (key, db_path) = paths::find_chrome_based_paths(&config::VIVALDI_SNAPSHOT_CONFIG)?;
+            labeled("vivaldi_snapshot", chromium_based(key, db_path, domains))
+        } else {
+            let (_, db_path) = paths::find_chrome_based_paths(&config::VIVALDI_SNAPSHOT_CONFIG)?;
+            labeled("vivaldi_snapshot", chromium_based(&config::VIVALDI_SNAPSHOT_CONFIG, db_path, domains))
+        }
+    }
+}
+
+/// Returns cookies from every vivaldi profile, paired with the browser version.
+/// Component stores are skipped, use [`chromium_profiles`] with `include_components` to read them
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::vivaldi_v2(Some(domains));
+/// }
+/// ```
+#[deprecated(note = "use `chromium_profiles(&config::VIVALDI_CONFIG, domains, &Default::default())`, which also reports profile dirs and failures")]
+pub fn vivaldi_v2(domains: Option<Vec<&str>>) -> Result<Vec<(Vec<Cookie>, Option<String>)>> {
+    chromium_v2(&config::VIVALDI_CONFIG, domains)
+}
+
+/// Returns cookies from opera. Fails with [`browser::chromium::AdditionalPasswordRequired`]
+/// when its browsing data is protected with the sync password
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::opera(Some(domains));
+/// }
+/// ```
+pub fn opera(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let (key, db_path) = paths::find_chrome_based_paths(&config::OPERA_CONFIG)?;
+            labeled("opera", chromium_based(key, db_path, domains))
+        } else {
+            let (_, db_path) = paths::find_chrome_based_paths(&config::OPERA_CONFIG)?;
+            labeled("opera", chromium_based(&config::OPERA_CONFIG, db_path, domains))
+        }
+    }
+}
+
+/// Returns cookies from opera beta
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::opera_beta(Some(domains));
+/// }
+/// ```
+pub fn opera_beta(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let (key, db_path) = paths::find_chrome_based_paths(&config::OPERA_BETA_CONFIG)?;
+            labeled("opera_beta", chromium_based(key, db_path, domains))
+        } else {
+            let (_, db_path) = paths::find_chrome_based_paths(&config::OPERA_BETA_CONFIG)?;
+            labeled("opera_beta", chromium_based(&config::OPERA_BETA_CONFIG, db_path, domains))
+        }
+    }
+}
+
+/// Returns cookies from opera developer
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::opera_developer(Some(domains));
+/// }
+/// ```
+pub fn opera_developer(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let (key, db_path) = paths::find_chrome_based_paths(&config::OPERA_DEVELOPER_CONFIG)?;
+            labeled("opera_developer", chromium_based(key, db_path, domains))
+        } else {
+            let (_, db_path) = paths::find_chrome_based_paths(&config::OPERA_DEVELOPER_CONFIG)?;
+            labeled("opera_developer", chromium_based(&config::OPERA_DEVELOPER_CONFIG, db_path, domains))
+        }
+    }
+}
+
+/// Returns cookies from every opera profile, paired with the browser version.
+/// Component stores are skipped, use [`chromium_profiles`] with `include_components` to read them
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::opera_v2(Some(domains));
+/// }
+/// ```
+#[deprecated(note = "use `chromium_profiles(&config::OPERA_CONFIG, domains, &Default::default())`, which also reports profile dirs and failures")]
+pub fn opera_v2(domains: Option<Vec<&str>>) -> Result<Vec<(Vec<Cookie>, Option<String>)>> {
+    chromium_v2(&config::OPERA_CONFIG, domains)
+}
+
+/// Returns cookies from opera gx. Fails with [`browser::chromium::AdditionalPasswordRequired`]
+/// when its browsing data is protected with the sync password
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::opera_gx(Some(domains));
+/// }
+/// ```
+pub fn opera_gx(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let (key, db_path) = paths::find_chrome_based_paths(&config::OPERA_GX_CONFIG)?;
+            labeled("opera_gx", chromium_based(key, db_path, domains))
+        } else {
+            let (_, db_path) = paths::find_chrome_based_paths(&config::OPERA_GX_CONFIG)?;
+            labeled("opera_gx", chromium_based(&config::OPERA_GX_CONFIG, db_path, domains))
+        }
+    }
+}
+
+/// Returns cookies from naver whale
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::whale(Some(domains));
+/// }
+/// ```
+pub fn whale(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let (key, db_path) = paths::find_chrome_based_paths(&config::WHALE_CONFIG)?;
+            labeled("whale", chromium_based(key, db_path, domains))
+        } else {
+            let (_, db_path) = paths::find_chrome_based_paths(&config::WHALE_CONFIG)?;
+            labeled("whale", chromium_based(&config::WHALE_CONFIG, db_path, domains))
+        }
+    }
+}
+
+/// Returns cookies from epic privacy browser. It clears cookies on exit, so an empty
+/// list is the usual result of reading it while it's closed
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::epic(Some(domains));
+/// }
+/// ```
+pub fn epic(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let (key, db_path) = paths::find_chrome_based_paths(&config::EPIC_CONFIG)?;
+            labeled("epic", chromium_based(key, db_path, domains))
+        } else {
+            let (_, db_path) = paths::find_chrome_based_paths(&config::EPIC_CONFIG)?;
+            labeled("epic", chromium_based(&config::EPIC_CONFIG, db_path, domains))
+        }
+    }
+}
+
+/// Returns cookies from iridium
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::iridium(Some(domains));
+/// }
+/// ```
+pub fn iridium(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let (key, db_path) = paths::find_chrome_based_paths(&config::IRIDIUM_CONFIG)?;
+            labeled("iridium", chromium_based(key, db_path, domains))
+        } else {
+            let (_, db_path) = paths::find_chrome_based_paths(&config::IRIDIUM_CONFIG)?;
+            labeled("iridium", chromium_based(&config::IRIDIUM_CONFIG, db_path, domains))
+        }
+    }
+}
+
+/// Returns cookies from opera, taking the sync password its browsing data may be
+/// protected with. Profiles without the protection are read as with [`opera`], protected
+/// ones fail with [`browser::chromium::PasswordUnwrapUnsupported`]
+///
+/// # Arguments
+///
+/// * `password` - The Opera sync password
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::opera_with_password("password", Some(domains));
+/// }
+/// ```
+pub fn opera_with_password(password: &str, domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    labeled("opera", opera_based_with_password(&config::OPERA_CONFIG, password, domains))
+}
+
+/// Returns cookies from opera gx, taking the sync password its browsing data may be
+/// protected with. Profiles without the protection are read as with [`opera_gx`], protected
+/// ones fail with [`browser::chromium::PasswordUnwrapUnsupported`]
+///
+/// # Arguments
+///
+/// * `password` - The Opera sync password
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::opera_gx_with_password("password", Some(domains));
+/// }
+/// ```
+pub fn opera_gx_with_password(password: &str, domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    labeled("opera_gx", opera_based_with_password(&config::OPERA_GX_CONFIG, password, domains))
+}
+
+fn opera_based_with_password(
+    config: &BrowserConfig,
+    password: &str,
+    domains: impl Into<Domains>
+) -> Result<Vec<Cookie>> {
+    let (key, db_path) = paths::find_chrome_based_paths(config)?;
+    if chromium::password_protected(&db_path) {
+        // how Opera derives the wrapping key from the password isn't known
+        let _ = password;
+        let profile_dir = paths::chrome_profile_path(&db_path).unwrap_or(&db_path);
+        bail!(chromium::PasswordUnwrapUnsupported(profile_dir.to_path_buf()));
+    }
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            chromium_based(key, db_path, domains)
+        } else {
+            let _ = key;
+            chromium_based(config, db_path, domains)
+        }
+    }
+}
+
+/// Returns cookies from yandex, unwrapping the data key its profiles keep in
+/// `Ya Passman Data`. Fails with [`browser::yandex::MasterPasswordRequired`] when
+/// the profile is locked with a master password.
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::yandex(Some(domains));
+/// }
+/// ```
+pub fn yandex(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let (key, db_path) = paths::find_chrome_based_paths(&config::YANDEX_CONFIG)?;
+            labeled("yandex", yandex_based(key, db_path, domains))
+        } else {
+            let (_, db_path) = paths::find_chrome_based_paths(&config::YANDEX_CONFIG)?;
+            labeled("yandex", yandex_based(&config::YANDEX_CONFIG, db_path, domains))
+        }
+    }
+}
+
+/// Returns cookies from every octo browser profile, keyed by the profile directory.
+/// Anti-detect profiles are kept apart instead of merged into one list
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     for (profile, cookies) in rookie::octo_browser(Some(domains)).unwrap() {
+///         println!("{}: {} cookies", profile, cookies.len());
+///     }
+/// }
+/// ```
+#[cfg(target_os = "windows")]
+pub fn octo_browser(domains: impl Into<Domains>) -> Result<Vec<(String, Vec<Cookie>)>> {
+    let domains: Domains = domains.into();
+    let paths = paths::find_chrome_based_paths_v2(
+        &config::OCTO_BROWSER_CONFIG,
+        &EnumerationOptions::default()
+    )?;
+    let mut profiles = vec![];
+    let mut failure = None;
+    for (key, db_path) in paths {
+        // each profile is a user data dir of its own, <profile>/Default/Network/Cookies
+        let profile = paths
+            ::chrome_profile_path(&db_path)
+            .and_then(|dir| dir.parent()?.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        match labeled("octo_browser", chromium_based(key, db_path.clone(), &domains)) {
+            Ok(cookies) => profiles.push((profile, cookies)),
+            Err(err) => {
+                warn!("Skipping profile {}: {}", db_path.display(), err);
+                failure.get_or_insert(err.context(format!("cant read {}", db_path.display())));
+            }
+        }
+    }
+    match failure {
+        Some(err) if profiles.is_empty() => Err(err),
+        _ => Ok(profiles),
+    }
+}
+
+/// Returns cookies from maxthon (Windows only)
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::maxthon(Some(domains));
+/// }
+/// ```
+#[cfg(target_os = "windows")]
+pub fn maxthon(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    let (key, db_path) = paths::find_chrome_based_paths(&config::MAXTHON_CONFIG)?;
+    labeled("maxthon", chromium_based(key, db_path, domains))
+}
+
+/// Returns cookies from uc browser (Windows only)
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::uc_browser(Some(domains));
+/// }
+/// ```
+#[cfg(target_os = "windows")]
+pub fn uc_browser(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    let (key, db_path) = paths::find_chrome_based_paths(&config::UC_BROWSER_CONFIG)?;
+    labeled("uc_browser", chromium_based(key, db_path, domains))
+}
+
+/// Returns cookies from 360 extreme explorer (Windows only, there are no macOS or Linux builds)
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::extreme_explorer(Some(domains));
+/// }
+/// ```
+#[cfg(target_os = "windows")]
+pub fn extreme_explorer(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    let (key, db_path) = paths::find_chrome_based_paths(&config::EXTREME_EXPLORER_CONFIG)?;
+    labeled("extreme_explorer", chromium_based(key, db_path, domains))
+}
 
-cfg_if::cfg_if! {
-    if #[cfg(target_os = "windows")] {
-        use common::winapi;
-        use browser::internet_explorer;
-        pub use internet_explorer::internet_explorer_based;
-    } else if #[cfg(target_os = "macos")] {
-        use browser::safari::safari_based;
-        use common::secrets;
-    } else if #[cfg(target_os = "linux")] {
-        use common::secrets;
-    }
+/// Returns cookies from 360 secure browser (Windows only, there are no macOS or Linux builds)
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::browser_360(Some(domains));
+/// }
+/// ```
+#[cfg(target_os = "windows")]
+pub fn browser_360(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    let (key, db_path) = paths::find_chrome_based_paths(&config::QIHOO_360_CONFIG)?;
+    labeled("browser_360", chromium_based(key, db_path, domains))
 }
 
-/// Returns cookies from firefox
+/// Returns cookies from qq browser (Windows only)
 ///
 /// # Arguments
 ///
@@ -34,15 +1285,16 @@ cfg_if::cfg_if! {
 ///
 /// fn main() {
 ///     let domains = vec!["google.com"];
-///     let cookies = rookie::firefox(Some(domains));
+///     let cookies = rookie::qq_browser(Some(domains));
 /// }
 /// ```
-pub fn firefox(domains: Option<Vec<&str>>) -> Result<Vec<Cookie>> {
-    let db_path = paths::find_mozilla_based_paths(&config::FIREFOX_CONFIG)?;
-    firefox_based(db_path, domains)
+#[cfg(target_os = "windows")]
+pub fn qq_browser(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    let (key, db_path) = paths::find_chrome_based_paths(&config::QQ_BROWSER_CONFIG)?;
+    labeled("qq_browser", chromium_based(key, db_path, domains))
 }
 
-/// Returns cookies from libre wolf
+/// Returns cookies from GNOME Web (Epiphany) (Linux only)
 ///
 /// # Arguments
 ///
@@ -54,15 +1306,16 @@ pub fn firefox(domains: Option<Vec<&str>>) -> Result<Vec<Cookie>> {
 ///
 /// fn main() {
 ///     let domains = vec!["google.com"];
-///     let cookies = rookie::libre_wolf(Some(domains));
+///     let cookies = rookie::epiphany(Some(domains));
 /// }
 /// ```
-pub fn libre_wolf(domains: Option<Vec<&str>>) -> Result<Vec<Cookie>> {
-    let db_path = paths::find_mozilla_based_paths(&config::LIBRE_WOLF_CONFIG)?;
-    firefox_based(db_path, domains)
+#[cfg(target_os = "linux")]
+pub fn epiphany(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    let db_path = paths::find_epiphany_based_paths(&config::EPIPHANY_CONFIG)?;
+    labeled("epiphany", epiphany_based(db_path, domains))
 }
 
-/// Returns cookies from chrome
+/// Returns cookies from every Falkon profile (Linux only)
 ///
 /// # Arguments
 ///
@@ -74,70 +1327,100 @@ pub fn libre_wolf(domains: Option<Vec<&str>>) -> Result<Vec<Cookie>> {
 ///
 /// fn main() {
 ///     let domains = vec!["google.com"];
-///     let cookies = rookie::chrome(Some(domains));
+///     let cookies = rookie::falkon(Some(domains));
 /// }
 /// ```
-pub fn chrome(domains: Option<Vec<&str>>) -> Result<Vec<Cookie>> {
-    cfg_if::cfg_if! {
-        if #[cfg(target_os = "windows")] {
-            let (key, db_path) = paths::find_chrome_based_paths(&config::CHROME_CONFIG)?;
-            chromium_based(PathBuf::from(key), db_path, domains)
-        } else {
-            let (_, db_path) = paths::find_chrome_based_paths(&config::CHROME_CONFIG)?;
-            chromium_based(&config::CHROME_CONFIG, db_path, domains)
+#[cfg(target_os = "linux")]
+pub fn falkon(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    let read = chromium_profiles(&config::FALKON_CONFIG, domains, &EnumerationOptions::default())?;
+    for (db_path, err) in &read.failures {
+        warn!("Skipping profile {}: {}", db_path.display(), err);
+    }
+    if read.profiles.is_empty() {
+        if let Some((db_path, err)) = read.failures.into_iter().next() {
+            return Err(err.context(format!("cant read {}", db_path.display())));
         }
     }
+    Ok(
+        read.profiles
+            .into_iter()
+            .flat_map(|profile| profile.cookies)
+            .collect()
+    )
 }
 
-pub fn chrome_v2(domains: Option<Vec<&str>>) -> Result<Vec<(Vec<Cookie>, Option<String>)>> {
-    let paths = paths::find_chrome_based_paths_v2(&config::CHROME_CONFIG)?;
-
-    let results: Vec<_> = paths
-        .into_iter()
-        .map(|(key_path, db_path)| {
-            let cookies = chromium_based(key_path.clone(), db_path, domains.clone())?;
-
-            let last_version_path = key_path.parent().unwrap().join("Last Version");
-            let last_version = match std::fs::read_to_string(&last_version_path) {
-                Ok(content) => Some(content.trim().to_string()),
-                Err(_) => None,
-            };
-
-            Ok((cookies, last_version))
-        })
-        .collect::<Result<_>>()?;
-
-    Ok(results)
+/// Returns cookies from safari (MacOS only)
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::safari(Some(domains));
+/// }
+/// ```
+#[cfg(target_os = "macos")]
+pub fn safari(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    let db_path = paths::find_safari_based_paths(&config::SAFARI_CONFIG)?;
+    labeled("safari", safari_based(db_path, domains))
 }
 
-/// Returns cookies from chromium
+/// Returns cookies from a `Cookies.binarycookies` file (Safari, iOS apps), on any OS
 ///
 /// # Arguments
 ///
+/// * `path` - Path of the `.binarycookies` file
 /// * `domains` - A optional list that for getting specific domains only
 ///
 /// # Examples
 ///
 /// ```
+/// use std::path::Path;
 ///
 /// fn main() {
 ///     let domains = vec!["google.com"];
-///     let cookies = rookie::chromium(Some(domains));
+///     let cookies = rookie::binarycookies(Path::new("Cookies.binarycookies"), Some(domains));
 /// }
 /// ```
-pub fn chromium(domains: Option<Vec<&str>>) -> Result<Vec<Cookie>> {
-    cfg_if::cfg_if! {
-        if #[cfg(target_os = "windows")] {
-            let (key, db_path) = paths::find_chrome_based_paths(&config::CHROMIUM_CONFIG)?;
-            chromium_based(PathBuf::from(key), db_path, domains)
-        } else {
-            let (_, db_path) = paths::find_chrome_based_paths(&config::CHROMIUM_CONFIG)?;
-            chromium_based(&config::CHROMIUM_CONFIG, db_path, domains)
-        }
+pub fn binarycookies(
+    path: &std::path::Path,
+    domains: impl Into<Domains>
+) -> Result<Vec<Cookie>> {
+    binarycookies_based(path.to_path_buf(), domains)
+}
+
+/// Returns the cookies of an iOS app from an unencrypted iTunes/Finder backup,
+/// locating its hashed `.binarycookies` files through `Manifest.db`
+///
+/// # Arguments
+///
+/// * `manifest_db_dir` - Backup directory holding `Manifest.db`
+/// * `bundle_id` - App bundle id, e.g. "com.apple.mobilesafari", or a backup domain such as "HomeDomain"
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// fn main() {
+///     let backup = Path::new("MobileSync/Backup/00008030-001A2B3C4D5E6F70");
+///     let cookies = rookie::from_ios_backup(backup, "com.apple.mobilesafari");
+/// }
+/// ```
+pub fn from_ios_backup(manifest_db_dir: &std::path::Path, bundle_id: &str) -> Result<Vec<Cookie>> {
+    let mut cookies = vec![];
+    for path in paths::find_ios_backup_cookies(manifest_db_dir, bundle_id)? {
+        cookies.extend(binarycookies_based(path, None)?);
     }
+    Ok(cookies)
 }
 
-/// Returns cookies from brave
+/// Returns cookies from internet explorer (Windows only)
 ///
 /// # Arguments
 ///
@@ -149,43 +1432,134 @@ pub fn chromium(domains: Option<Vec<&str>>) -> Result<Vec<Cookie>> {
 ///
 /// fn main() {
 ///     let domains = vec!["google.com"];
-///     let cookies = rookie::brave(Some(domains));
+///     let cookies = rookie::internet_explorer(Some(domains));
 /// }
 /// ```
-pub fn brave(domains: Option<Vec<&str>>) -> Result<Vec<Cookie>> {
-    cfg_if::cfg_if! {
-        if #[cfg(target_os = "windows")] {
-            let (key, db_path) = paths::find_chrome_based_paths(&config::BRAVE_CONFIG)?;
-            chromium_based(PathBuf::from(key), db_path, domains)
-        } else {
-            let (_, db_path) = paths::find_chrome_based_paths(&config::BRAVE_CONFIG)?;
-            chromium_based(&config::BRAVE_CONFIG, db_path, domains)
-        }
-    }
+#[cfg(target_os = "windows")]
+pub fn internet_explorer(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    let db_path = paths::find_ie_based_paths(&config::IE_CONFIG)?;
+    labeled("internet_explorer", internet_explorer_based(db_path, domains))
 }
 
-pub fn brave_v2(domains: Option<Vec<&str>>) -> Result<Vec<(Vec<Cookie>, Option<String>)>> {
-    let paths = paths::find_chrome_based_paths_v2(&config::BRAVE_CONFIG)?;
+/// Returns cookies from every profile of a chromium based browser,
+/// labeled with the profile directory, component store and browser version.
+/// Profiles that can't be read are listed in `failures` instead of failing the others.
+/// Takes any chromium based config, e.g. `CHROMIUM_CONFIG` or `OPERA_GX_CONFIG`, which
+/// have no `_v2` function of their own
+///
+/// # Arguments
+///
+/// * `config` - Browser config to discover profiles with
+/// * `domains` - A optional list that for getting specific domains only
+/// * `options` - Which special profiles (guest, system, ephemeral, tor) and component stores to include
+///
+/// # Examples
+///
+/// ```
+/// use rookie::common::enums::EnumerationOptions;
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let options = EnumerationOptions { include_guest: true, ..Default::default() };
+///     let profiles = rookie::chromium_profiles(&rookie::config::CHROME_CONFIG, Some(domains), &options);
+/// }
+/// ```
+pub fn chromium_profiles(
+    config: &BrowserConfig,
+    domains: impl Into<Domains>,
+    options: &EnumerationOptions
+) -> Result<ProfilesRead> {
+    let domains: Domains = domains.into();
+    let paths = paths::find_chrome_based_paths_v2(config, options)?;
 
-    let results: Vec<_> = paths
+    #[cfg(unix)]
+    let os_secrets = OsSecrets;
+    // every profile shares one keyring/keychain lookup
+    #[cfg(unix)]
+    let secrets = CachedSecrets::new(&os_secrets, None);
+
+    let browser = chromium_browsers()
         .into_iter()
-        .map(|(key_path, db_path)| {
-            let cookies = chromium_based(key_path.clone(), db_path, domains.clone())?;
+        .find(|(_, known)| std::ptr::eq(*known, config))
+        .map(|(name, _)| name);
+    let executable = browser.and_then(chromium::executable_name);
+
+    let mut read = ProfilesRead::default();
+    for (key_path, db_path) in paths {
+        let profile_dir = paths::chrome_profile_dir(&db_path).unwrap_or_default();
+        let component = paths::chrome_component(&db_path);
+        let display_name = paths::chrome_profile_path(&db_path).and_then(chromium::profile_name);
+        let identity = if options.include_identity {
+            paths
+                ::chrome_profile_path(&db_path)
+                .and_then(|dir| chromium::profile_identity(dir).ok())
+        } else {
+            None
+        };
+        let read_options = ReadOptions { provenance: options.provenance, ..defaults::global_defaults().read };
+        cfg_if::cfg_if! {
+            if #[cfg(target_os = "windows")] {
+                let cookies = chromium::chromium_read(key_path.clone(), db_path.clone(), &domains, read_options);
+            } else {
+                let cookies = chromium_read_with(config, db_path.clone(), &domains, &secrets, read_options);
+            }
+        }
+        let cookies = match cookies {
+            Ok(cookies) => cookies,
+            Err(err) => {
+                read.failures.push((db_path, err));
+                continue;
+            }
+        };
 
-            let last_version_path = key_path.parent().unwrap().join("Last Version");
-            let last_version = match std::fs::read_to_string(&last_version_path) {
-                Ok(content) => Some(content.trim().to_string()),
-                Err(_) => None,
-            };
+        let version = chromium::browser_version(&key_path, executable);
+        let last_version = key_path
+            .parent()
+            .and_then(|dir| std::fs::read_to_string(dir.join("Last Version")).ok())
+            .map(|content| content.trim().to_string())
+            .or(version.map(|version| version.to_string()));
 
-            Ok((cookies, last_version))
-        })
-        .collect::<Result<_>>()?;
+        read.profiles.push(ProfileCookies {
+            cookies: with_browser(browser.unwrap_or_default(), cookies.cookies),
+            profile_dir,
+            display_name,
+            component,
+            last_version,
+            version,
+            identity,
+            provenance: cookies.provenance,
+            duplicates: cookies.duplicates,
+            schema_version: cookies.schema_version,
+        });
+    }
 
-    Ok(results)
+    Ok(read)
 }
 
-/// Returns cookies from edge
+fn chromium_v2(
+    config: &BrowserConfig,
+    domains: impl Into<Domains>
+) -> Result<Vec<(Vec<Cookie>, Option<String>)>> {
+    let mut read = chromium_profiles(config, domains, &EnumerationOptions::default())?;
+    if read.profiles.is_empty() && !read.failures.is_empty() {
+        let (db_path, err) = read.failures.remove(0);
+        return Err(err.context(format!("cant read {}", db_path.display())));
+    }
+    for (db_path, err) in &read.failures {
+        warn!("Skipping profile {}: {}", db_path.display(), err);
+    }
+    Ok(
+        read.profiles
+            .into_iter()
+            .map(|profile| (profile.cookies, profile.last_version))
+            .collect()
+    )
+}
+
+/// Returns cookies from the profile of every installed firefox channel (release,
+/// beta, ESR, developer edition, nightly), labeled with the channel it came from.
+/// [`firefox`] only reads the default profile of one installation. Profiles that
+/// can't be read are listed in `failures` instead of failing the others
 ///
 /// # Arguments
 ///
@@ -194,169 +1568,458 @@ pub fn brave_v2(domains: Option<Vec<&str>>) -> Result<Vec<(Vec<Cookie>, Option<S
 /// # Examples
 ///
 /// ```
-///
 /// fn main() {
 ///     let domains = vec!["google.com"];
-///     let cookies = rookie::edge(Some(domains));
+///     if let Ok(read) = rookie::firefox_channels(Some(domains)) {
+///         for channel in read.channels {
+///             println!("{:?} {}: {} cookies", channel.channel, channel.profile_dir, channel.cookies.len());
+///         }
+///     }
 /// }
 /// ```
-pub fn edge(domains: Option<Vec<&str>>) -> Result<Vec<Cookie>> {
-    cfg_if::cfg_if! {
-        if #[cfg(target_os = "windows")] {
-            let (key, db_path) = paths::find_chrome_based_paths(&config::EDGE_CONFIG)?;
-            chromium_based(PathBuf::from(key), db_path, domains)
-        } else {
-            let (_, db_path) = paths::find_chrome_based_paths(&config::EDGE_CONFIG)?;
-            chromium_based(&config::EDGE_CONFIG, db_path, domains)
+pub fn firefox_channels(domains: impl Into<Domains>) -> Result<ChannelsRead> {
+    let domains: Domains = domains.into();
+    let mut read = ChannelsRead::default();
+    for db_path in paths::find_mozilla_channel_paths(&config::FIREFOX_CONFIG)? {
+        let profile_dir = db_path
+            .parent()
+            .and_then(|dir| dir.file_name())
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        match firefox_read(db_path.clone(), &domains, defaults::global_defaults().read) {
+            Ok(cookies) =>
+                read.channels.push(ChannelCookies {
+                    cookies: with_browser("firefox", cookies.cookies),
+                    channel: mozilla::profile_channel(&profile_dir),
+                    profile_dir,
+                    schema_version: cookies.schema_version,
+                }),
+            Err(err) => read.failures.push((db_path, err)),
         }
     }
+    Ok(read)
 }
 
-pub fn edge_v2(domains: Option<Vec<&str>>) -> Result<Vec<(Vec<Cookie>, Option<String>)>> {
-    let paths = paths::find_chrome_based_paths_v2(&config::EDGE_CONFIG)?;
+/// Returns the account and installation ids of a chromium profile directory
+///
+/// # Arguments
+///
+/// * `profile_dir` - Profile directory, e.g. `User Data/Default`
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+///
+/// fn main() {
+///     let identity = rookie::profile_identity(Path::new("User Data/Default"));
+/// }
+/// ```
+pub fn profile_identity(profile_dir: &std::path::Path) -> Result<ProfileIdentity> {
+    chromium::profile_identity(profile_dir)
+}
 
-    let results: Vec<_> = paths
-        .into_iter()
-        .map(|(key_path, db_path)| {
-            let cookies = chromium_based(key_path.clone(), db_path, domains.clone())?;
+/// Returns cookies from all browsers, with the options set by [`set_global_defaults`]
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+///
+/// fn main() {
+///     let domains = vec!["google.com"];
+///     let cookies = rookie::load(Some(domains));
+/// }
+/// ```
+pub fn load(domains: impl Into<Domains>) -> Result<Vec<Cookie>> {
+    Ok(load_with(domains, &defaults::global_defaults().load)?.cookies)
+}
 
-            let last_version_path = key_path.parent().unwrap().join("Last Version");
-            let last_version = match std::fs::read_to_string(&last_version_path) {
-                Ok(content) => Some(content.trim().to_string()),
-                Err(_) => None,
-            };
+/// Firefox based browsers read by [`load`], in that order
+pub(crate) fn mozilla_browsers() -> [(&'static str, &'static BrowserConfig<'static>); 5] {
+    [
+        ("firefox", &config::FIREFOX_CONFIG),
+        ("librewolf", &config::LIBRE_WOLF_CONFIG),
+        ("waterfox", &config::WATERFOX_CONFIG),
+        ("mullvad", &config::MULLVAD_CONFIG),
+        ("zen", &config::ZEN_CONFIG),
+    ]
+}
 
-            Ok((cookies, last_version))
-        })
-        .collect::<Result<_>>()?;
+/// Cookies DBs [`load`] reads for a firefox based browser, every profile for Zen
+pub(crate) fn mozilla_db_paths(config: &BrowserConfig) -> Vec<std::path::PathBuf> {
+    if std::ptr::eq(config, &config::ZEN_CONFIG) {
+        paths::find_mozilla_profile_paths(config).unwrap_or_default()
+    } else {
+        paths::find_mozilla_based_paths(config).into_iter().collect()
+    }
+}
 
-    Ok(results)
+/// Chromium based browsers read by [`load`], in reading order
+pub(crate) fn chromium_browsers() -> Vec<(&'static str, &'static BrowserConfig<'static>)> {
+    let mut browsers = vec![
+        ("opera", &config::OPERA_CONFIG),
+        ("opera_beta", &config::OPERA_BETA_CONFIG),
+        ("opera_developer", &config::OPERA_DEVELOPER_CONFIG),
+        ("edge", &config::EDGE_CONFIG),
+        ("edge_beta", &config::EDGE_BETA_CONFIG),
+        ("edge_dev", &config::EDGE_DEV_CONFIG),
+        ("edge_canary", &config::EDGE_CANARY_CONFIG),
+        ("chromium", &config::CHROMIUM_CONFIG),
+        ("brave", &config::BRAVE_CONFIG),
+        ("brave_beta", &config::BRAVE_BETA_CONFIG),
+        ("brave_nightly", &config::BRAVE_NIGHTLY_CONFIG),
+        ("vivaldi", &config::VIVALDI_CONFIG),
+        ("vivaldi_snapshot", &config::VIVALDI_SNAPSHOT_CONFIG),
+        ("iridium", &config::IRIDIUM_CONFIG)
+    ];
+    #[cfg(not(target_os = "windows"))]
+    browsers.push(("opera_gx", &config::OPERA_GX_CONFIG));
+    browsers.push(("chrome", &config::CHROME_CONFIG));
+    browsers.push(("chrome_beta", &config::CHROME_BETA_CONFIG));
+    browsers.push(("chrome_dev", &config::CHROME_DEV_CONFIG));
+    browsers.push(("chrome_canary", &config::CHROME_CANARY_CONFIG));
+    #[cfg(target_os = "windows")]
+    browsers.push(("opera_gx", &config::OPERA_GX_CONFIG));
+    #[cfg(target_os = "windows")]
+    browsers.push(("qq_browser", &config::QQ_BROWSER_CONFIG));
+    #[cfg(any(target_os = "windows", target_os = "macos"))]
+    browsers.push(("whale", &config::WHALE_CONFIG));
+    #[cfg(target_os = "linux")]
+    browsers.push(("falkon", &config::FALKON_CONFIG));
+    browsers
 }
 
-/// Returns cookies from vivaldi
+/// Sets [`Cookie::browser`] of every cookie
+pub(crate) fn with_browser(browser: &str, mut cookies: Vec<Cookie>) -> Vec<Cookie> {
+    for cookie in &mut cookies {
+        cookie.browser = browser.to_string();
+    }
+    cookies
+}
+
+/// [`with_browser`] for the result of a reader
+fn labeled(browser: &str, read: Result<Vec<Cookie>>) -> Result<Vec<Cookie>> {
+    read.map(|cookies| with_browser(browser, cookies))
+}
+
+fn record_read(outcome: &mut LoadOutcome, browser: &str, read: Result<CookiesRead>) {
+    match read {
+        Ok(read) => {
+            outcome.cookies.extend(with_browser(browser, read.cookies));
+            outcome.duplicates += read.duplicates;
+            outcome.private_excluded += read.private_excluded;
+            if let Some(version) = read.schema_version {
+                outcome.schema_versions.push((browser.to_string(), version));
+            }
+            if read.ports_ignored {
+                outcome.ports_ignored.push(browser.to_string());
+            }
+            if let Some(provenance) = read.provenance {
+                outcome.provenance.push((browser.to_string(), provenance));
+            }
+        }
+        #[cfg(unix)]
+        Err(err) if err.is::<InteractionRequired>() => {
+            outcome.requires_interaction.push(browser.to_string());
+        }
+        Err(err) if err.is::<chromium::NeedsUserContext>() => {
+            outcome.needs_user_context.push(browser.to_string());
+        }
+        Err(err) => {
+            if let Some(unsupported) = err.downcast_ref::<UnsupportedSchema>() {
+                outcome.schema_versions.push((browser.to_string(), unsupported.version));
+                outcome.unsupported_schemas.push(browser.to_string());
+            }
+        }
+    }
+}
+
+/// Returns cookies from all browsers, asking for every keyring/keychain item
+/// up front and at most once so users see one group of prompts
 ///
 /// # Arguments
 ///
-/// * `domains` - A optional list that for getting specific domains only
+/// * `domains` - A optional list that for getting specific domains only. A `host:port`
+///   entry (`localhost:3000`, `[::1]:8080`) also matches the port where the browser
+///   records it, see [`LoadOutcome::ports_ignored`]
+/// * `options` - Limits on how the browsers are read
 ///
 /// # Examples
 ///
 /// ```
+/// use rookie::LoadOptions;
 ///
 /// fn main() {
 ///     let domains = vec!["google.com"];
-///     let cookies = rookie::vivaldi(Some(domains));
+///     let mut options = LoadOptions::default();
+///     options.max_prompts = Some(1);
+///     options.include_expired = false;
+///     let outcome = rookie::load_with(Some(domains), &options);
 /// }
 /// ```
-pub fn vivaldi(domains: Option<Vec<&str>>) -> Result<Vec<Cookie>> {
+pub fn load_with(
+    domains: impl Into<Domains>,
+    options: &LoadOptions
+) -> Result<LoadOutcome> {
     cfg_if::cfg_if! {
         if #[cfg(target_os = "windows")] {
-            let (key, db_path) = paths::find_chrome_based_paths(&config::VIVALDI_CONFIG)?;
-            chromium_based(PathBuf::from(key), db_path, domains)
+            load_filtered_with(domains, &NameFilter::default(), options)
         } else {
-            let (_, db_path) = paths::find_chrome_based_paths(&config::VIVALDI_CONFIG)?;
-            chromium_based(&config::VIVALDI_CONFIG, db_path, domains)
+            load_with_secrets(domains, options, &OsSecrets)
         }
     }
 }
 
-/// Returns cookies from opera
+/// Same as [`load_with`] with the keyring/keychain passwords taken from `secrets`
+/// instead of the OS keystores, e.g. a mock in tests. `max_prompts` still caps how
+/// many keystore items are asked for
 ///
 /// # Arguments
 ///
 /// * `domains` - A optional list that for getting specific domains only
+/// * `options` - Limits on how the browsers are read
+/// * `secrets` - Source of the passwords the cookie keys are derived from
 ///
 /// # Examples
 ///
 /// ```
+/// use rookie::common::secrets::OsSecrets;
+/// use rookie::LoadOptions;
 ///
 /// fn main() {
 ///     let domains = vec!["google.com"];
-///     let cookies = rookie::opera(Some(domains));
+///     let outcome = rookie::load_with_secrets(Some(domains), &LoadOptions::default(), &OsSecrets);
+/// }
+/// ```
+#[cfg(unix)]
+pub fn load_with_secrets(
+    domains: impl Into<Domains>,
+    options: &LoadOptions,
+    secrets: &dyn SecretsProvider
+) -> Result<LoadOutcome> {
+    load_filtered_with(domains, &NameFilter::default(), options, secrets)
+}
+
+/// Returns the cookies matching `names` from all browsers, with the options set by
+/// [`set_global_defaults`]. The chromium and firefox DBs are filtered in the query,
+/// so the values of the other cookies are never decrypted
+///
+/// # Arguments
+///
+/// * `domains` - A optional list that for getting specific domains only
+/// * `names` - Cookie names to get, exact by default, see [`NameFilter::prefix`]
+///
+/// # Examples
+///
+/// ```
+/// use rookie::NameFilter;
+///
+/// fn main() {
+///     let domains = vec!["example.com"];
+///     let cookies = rookie::load_filtered(Some(domains), &NameFilter::exact(["SESSIONID"]));
 /// }
 /// ```
-pub fn opera(domains: Option<Vec<&str>>) -> Result<Vec<Cookie>> {
+pub fn load_filtered(
+    domains: impl Into<Domains>,
+    names: &NameFilter
+) -> Result<Vec<Cookie>> {
+    let options = defaults::global_defaults().load;
     cfg_if::cfg_if! {
         if #[cfg(target_os = "windows")] {
-            let (key, db_path) = paths::find_chrome_based_paths(&config::OPERA_CONFIG)?;
-            chromium_based(PathBuf::from(key), db_path, domains)
+            Ok(load_filtered_with(domains, names, &options)?.cookies)
         } else {
-            let (_, db_path) = paths::find_chrome_based_paths(&config::OPERA_CONFIG)?;
-            chromium_based(&config::OPERA_CONFIG, db_path, domains)
+            Ok(load_filtered_with(domains, names, &options, &OsSecrets)?.cookies)
         }
     }
 }
 
-/// Returns cookies from opera gx
+/// Returns the cookies matching `filter` from all browsers, with the options set by
+/// [`set_global_defaults`]. `domains` narrows down the read first, the patterns are
+/// applied to the cookies read
 ///
 /// # Arguments
 ///
 /// * `domains` - A optional list that for getting specific domains only
+/// * `filter` - Regex patterns on the domain and name of the cookies
 ///
 /// # Examples
 ///
 /// ```
+/// use regex::Regex;
+/// use rookie::CookieFilter;
 ///
 /// fn main() {
-///     let domains = vec!["google.com"];
-///     let cookies = rookie::opera_gx(Some(domains));
+///     let filter = CookieFilter { name: Some(Regex::new("^_ga").unwrap()), ..Default::default() };
+///     let cookies = rookie::load_matching(None, &filter);
 /// }
 /// ```
-pub fn opera_gx(domains: Option<Vec<&str>>) -> Result<Vec<Cookie>> {
+pub fn load_matching(
+    domains: impl Into<Domains>,
+    filter: &CookieFilter
+) -> Result<Vec<Cookie>> {
+    Ok(filter.apply(load(domains)?))
+}
+
+fn load_filtered_with(
+    domains: impl Into<Domains>,
+    names: &NameFilter,
+    options: &LoadOptions,
+    #[cfg(unix)] secrets: &dyn SecretsProvider
+) -> Result<LoadOutcome> {
+    let domains: Domains = domains.into();
+    let mut outcome = LoadOutcome::default();
+
+    let read_options = options.read_options();
+    for (name, config) in mozilla_browsers() {
+        for db_path in mozilla_db_paths(config) {
+            record_read(&mut outcome, name, firefox_read_filtered(db_path, &domains, names, read_options.clone()));
+        }
+    }
+
+    let found: Vec<_> = chromium_browsers()
+        .into_iter()
+        .filter_map(|(name, config)| {
+            let (key_path, db_path) = paths::find_chrome_based_paths(config).ok()?;
+            Some((name, config, key_path, db_path))
+        })
+        .collect();
+
     cfg_if::cfg_if! {
         if #[cfg(target_os = "windows")] {
-            let (key, db_path) = paths::find_chrome_based_paths(&config::OPERA_GX_CONFIG)?;
-            chromium_based(PathBuf::from(key), db_path, domains)
+            let _ = options;
         } else {
-            let (_, db_path) = paths::find_chrome_based_paths(&config::OPERA_GX_CONFIG)?;
-            chromium_based(&config::OPERA_GX_CONFIG, db_path, domains)
+            let secrets = CachedSecrets::new(secrets, options.max_prompts);
+            // ask for every key before reading so the prompts come together
+            for (_, config, _, _) in &found {
+                let _ = secrets.passwords(config);
+            }
+        }
+    }
+    for (name, config, key_path, db_path) in found {
+        cfg_if::cfg_if! {
+            if #[cfg(target_os = "windows")] {
+                let _ = (name, config);
+                let read = chromium::chromium_read_filtered(key_path, db_path, &domains, names, read_options.clone());
+            } else {
+                let _ = key_path;
+                let read = chromium_read_filtered(config, db_path, &domains, names, &secrets, read_options.clone());
+            }
+        }
+        record_read(&mut outcome, name, read);
+    }
+
+    // neither store records the port
+    #[cfg(target_os = "windows")]
+    if let Ok(cookies) = internet_explorer(&domains) {
+        outcome.cookies.extend(cookies);
+        if utils::has_port(domains.as_deref()) {
+            outcome.ports_ignored.push("internet_explorer".to_string());
         }
     }
+
+    #[cfg(target_os = "macos")]
+    if let Ok(db_path) = paths::find_safari_based_paths(&config::SAFARI_CONFIG) {
+        record_read(&mut outcome, "safari", safari_read(db_path, &domains, read_options.clone()));
+    }
+
+    if !options.include_expired {
+        // the other stores can't be filtered while reading
+        let now = date::now();
+        outcome.cookies.retain(|cookie| cookie.expires.map_or(true, |expires| expires > now));
+    }
+    let cutoff = date::expiry_cutoff(options.expires_before, options.expires_within);
+    outcome.cookies.retain(|cookie| {
+        names.matches(&cookie.name) &&
+            options.persistence.matches(cookie) &&
+            request::path_in_scope(&cookie.path, options.path.as_deref()) &&
+            cookie.has_flags(options.secure_only, options.http_only) &&
+            cookie.expires_by(cutoff)
+    });
+    utils::sort_and_page(&mut outcome.cookies, options.sort_by, options.offset, options.limit);
+    Ok(outcome)
 }
 
-/// Returns cookies from octo browser
+/// Returns cookies from all browsers of another user, with the options set by
+/// [`set_global_defaults`]. Browsers are looked up under `user_profile_dir` as with
+/// [`with_user_profile_root`], e.g. when running elevated for incident response.
+/// Chromium browsers whose key only opens in that user's session are listed in
+/// [`LoadOutcome::needs_user_context`]
 ///
 /// # Arguments
 ///
+/// * `user_profile_dir` - Profile directory of the user, e.g. `C:\Users\alice` or `/home/alice`
 /// * `domains` - A optional list that for getting specific domains only
 ///
 /// # Examples
 ///
 /// ```
+/// use std::path::Path;
 ///
 /// fn main() {
 ///     let domains = vec!["google.com"];
-///     let cookies = rookie::octo(Some(domains));
+///     let outcome = rookie::load_for_user(Path::new(r"C:\Users\alice"), Some(domains));
 /// }
 /// ```
-#[cfg(target_os = "windows")]
-pub fn octo_browser(domains: Option<Vec<&str>>) -> Result<Vec<Cookie>> {
-    let (key, db_path) = paths::find_chrome_based_paths(&config::OPERA_GX_CONFIG)?;
-    chromium_based(PathBuf::from(key), db_path, domains)
+pub fn load_for_user(
+    user_profile_dir: &std::path::Path,
+    domains: impl Into<Domains>
+) -> Result<LoadOutcome> {
+    let domains: Domains = domains.into();
+    // a profile that can't be listed would only come back without cookies
+    std::fs
+        ::read_dir(user_profile_dir)
+        .with_context(|| format!("cant read user profile dir {}", user_profile_dir.display()))?;
+    with_user_profile_root(user_profile_dir, || {
+        load_with(&domains, &defaults::global_defaults().load)
+    })
 }
 
-/// Returns cookies from safari (MacOS only)
+/// Returns cookies from all browsers of each of the given users, see [`load_for_user`].
+/// Users that can't be read are listed in `failures` instead of failing the others
 ///
 /// # Arguments
 ///
+/// * `user_profile_dirs` - Profile directories of the users, e.g. `C:\Users\alice`
 /// * `domains` - A optional list that for getting specific domains only
 ///
 /// # Examples
 ///
 /// ```
+/// use std::path::PathBuf;
 ///
 /// fn main() {
 ///     let domains = vec!["google.com"];
-///     let cookies = rookie::safari(Some(domains));
+///     let users = vec![PathBuf::from("/home/alice"), PathBuf::from("/home/bob")];
+///     let read = rookie::load_users(users, Some(domains));
+///     for (user_profile_dir, err) in &read.failures {
+///         eprintln!("{}: {}", user_profile_dir.display(), err);
+///     }
 /// }
 /// ```
-#[cfg(target_os = "macos")]
-pub fn safari(domains: Option<Vec<&str>>) -> Result<Vec<Cookie>> {
-    let db_path = paths::find_safari_based_paths(&config::SAFARI_CONFIG)?;
-    safari_based(db_path, domains)
+pub fn load_users(
+    user_profile_dirs: impl IntoIterator<Item = std::path::PathBuf>,
+    domains: impl Into<Domains>
+) -> UsersRead {
+    let domains: Domains = domains.into();
+    let mut read = UsersRead::default();
+    for user_profile_dir in user_profile_dirs {
+        match load_for_user(&user_profile_dir, &domains) {
+            Ok(outcome) => read.users.push((user_profile_dir, outcome)),
+            Err(err) => {
+                warn!("Skipping user {}: {}", user_profile_dir.display(), err);
+                read.failures.push((user_profile_dir, err));
+            }
+        }
+    }
+    read
 }
 
-/// Returns cookies from internet explorer (Windows only)
+/// Returns cookies from all browsers of every local user, see [`load_users`].
+/// Needs to run elevated to read the other profiles
 ///
 /// # Arguments
 ///
@@ -368,54 +2031,51 @@ pub fn safari(domains: Option<Vec<&str>>) -> Result<Vec<Cookie>> {
 ///
 /// fn main() {
 ///     let domains = vec!["google.com"];
-///     let cookies = rookie::internet_explorer(Some(domains));
+///     if let Ok(read) = rookie::load_all_users(Some(domains)) {
+///         for (user_profile_dir, outcome) in read.users {
+///             println!("{}: {} cookies", user_profile_dir.display(), outcome.cookies.len());
+///         }
+///     }
 /// }
 /// ```
 #[cfg(target_os = "windows")]
-pub fn internet_explorer(domains: Option<Vec<&str>>) -> Result<Vec<Cookie>> {
-    let db_path = paths::find_ie_based_paths(&config::IE_CONFIG)?;
-    internet_explorer_based(db_path, domains)
+pub fn load_all_users(domains: impl Into<Domains>) -> Result<UsersRead> {
+    let Some(users_dir) = common::winapi::user_profiles_folder() else {
+        bail!("cant find the user profiles folder");
+    };
+    Ok(load_users(paths::user_profile_dirs(&users_dir)?, domains))
 }
 
-/// Returns cookies from all browsers
+/// Returns cookies for a site from all browsers, including the auxiliary and CDN
+/// domains its login spans (see [`sites::SITE_PROFILES`]), with duplicates removed.
+/// Each domain is matched the same way as the `domains` argument of the other readers.
 ///
 /// # Arguments
 ///
-/// * `domains` - A optional list that for getting specific domains only
+/// * `site` - Site profile name, e.g. "github"
+/// * `domains_override` - Optional list that replaces the domains of the site profile
 ///
 /// # Examples
 ///
 /// ```
 ///
 /// fn main() {
-///     let domains = vec!["google.com"];
-///     let cookies = rookie::load(Some(domains));
+///     let cookies = rookie::site_cookies("github", None);
 /// }
 /// ```
-pub fn load(domains: Option<Vec<&str>>) -> Result<Vec<Cookie>> {
-    let mut cookies = Vec::new();
-
-    let mut browser_types = vec![firefox, libre_wolf, opera, edge, chromium, brave, vivaldi];
-    cfg_if::cfg_if! {
-        if #[cfg(target_os = "windows")] {
-            browser_types.push(chrome);
-            browser_types.push(opera_gx);
-            browser_types.push(internet_explorer);
-        } else if #[cfg(target_os = "linux")] {
-            browser_types.push(chrome);
-        } else if #[cfg(target_os = "macos")] {
-            browser_types.push(opera_gx);
-            browser_types.push(chrome);
-            browser_types.push(safari);
-        }
-    }
-
-    for browser_fn in browser_types.iter() {
-        let browser_cookies = browser_fn(domains.clone()).unwrap_or(vec![]);
-        cookies.extend(browser_cookies);
-    }
-
-    Ok(cookies)
+pub fn site_cookies(
+    site: &str,
+    domains_override: impl Into<Domains>
+) -> Result<Vec<Cookie>> {
+    let domains = match domains_override.into().as_deref() {
+        Some(domains) => domains.to_vec(),
+        None =>
+            match sites::site_domains(site) {
+                Some(domains) => domains,
+                None => bail!("unknown site profile {}", site),
+            }
+    };
+    Ok(utils::dedup_cookies(load(domains)?))
 }
 
 /// Returns cookies from specific browser
@@ -424,11 +2084,11 @@ pub fn load(domains: Option<Vec<&str>>) -> Result<Vec<Cookie>> {
 ///
 /// * `cookies_path` - Absolute path for cookies file
 /// * `domains` - Optional list that for getting specific domains only
-/// * `key_path` - Optional absolute path for key required to decrypt the cookies (required for chrome)
+/// * `key_path` - Optional absolute path for key required to decrypt the cookies (Windows only, looked up next to `cookies_path` when missing)
 ///
 /// # Examples
 ///
-/// ```
+/// ```no_run
 ///
 /// fn main() {
 ///     let domains = vec!["google.com"];
@@ -439,44 +2099,54 @@ pub fn load(domains: Option<Vec<&str>>) -> Result<Vec<Cookie>> {
 /// ```
 pub fn any_browser(
     cookies_path: &str,
-    domains: Option<Vec<&str>>,
+    domains: impl Into<Domains>,
     key_path: Option<&str>
 ) -> Result<Vec<Cookie>> {
+    let domains: Domains = domains.into();
     // chromium based
     cfg_if::cfg_if! {
         // Linux Chromium
         if #[cfg(unix)] {
             use crate::config;
+            // keys come from the OS keyring on unix
+            let _ = key_path;
             let chrome_configs = &[
                 &config::CHROME_CONFIG,
                 &config::BRAVE_CONFIG,
                 &config::CHROMIUM_CONFIG,
                 &config::EDGE_CONFIG,
                 &config::OPERA_CONFIG,
+                &config::OPERA_BETA_CONFIG,
+                &config::OPERA_DEVELOPER_CONFIG,
                 &config::OPERA_GX_CONFIG,
                 &config::VIVALDI_CONFIG,
             ];
             for browser_config in chrome_configs {
-                if
-                    let Ok(cookies) = chromium_based(
-                        &browser_config,
-                        cookies_path.into(),
-                        domains.clone()
-                    )
-                {
-                    return Ok(cookies);
+                match chromium_based(browser_config, cookies_path.into(), &domains) {
+                    Ok(cookies) => {
+                        return Ok(cookies);
+                    }
+                    Err(err) if err.is::<chromium::AdditionalPasswordRequired>() => {
+                        return Err(err);
+                    }
+                    Err(_) => {}
                 }
             }
         } else {
+            // probe next to the cookies file when no key is given
+            // (covers Maxthon, UC Browser and other forks with a displaced Local State)
+            let key_path = key_path
+                .map(PathBuf::from)
+                .or_else(|| paths::find_key_path(&PathBuf::from(cookies_path)));
             if let Some(key_path) = key_path {
-                if
-                    let Ok(cookies) = chromium_based(
-                        PathBuf::from(key_path),
-                        cookies_path.into(),
-                        domains.clone()
-                    )
-                {
-                    return Ok(cookies);
+                match chromium_based(key_path, cookies_path.into(), &domains) {
+                    Ok(cookies) => {
+                        return Ok(cookies);
+                    }
+                    Err(err) if err.is::<chromium::AdditionalPasswordRequired>() => {
+                        return Err(err);
+                    }
+                    Err(_) => {}
                 }
             }
         }
@@ -484,23 +2154,22 @@ pub fn any_browser(
     }
 
     // Firefox
-    if let Ok(cookies) = firefox_based(cookies_path.into(), domains.clone()) {
+    if let Ok(cookies) = firefox_based(cookies_path.into(), &domains) {
         return Ok(cookies);
     }
 
     cfg_if::cfg_if! {
         if #[cfg(target_os = "windows")] {
             // Internet Explorer
-            if let Ok(cookies) = internet_explorer_based(cookies_path.into(), domains.clone()) {
-                return Ok(cookies);
-            }
-        } else if #[cfg(target_os = "macos")] {
-            if let Ok(cookies) = safari_based(cookies_path.into(), domains) {
+            if let Ok(cookies) = internet_explorer_based(cookies_path.into(), &domains) {
                 return Ok(cookies);
             }
         }
-        // Safari
-        // try safari based
+    }
+
+    // Safari and iOS apps
+    if let Ok(cookies) = binarycookies_based(cookies_path.into(), &domains) {
+        return Ok(cookies);
     }
     bail!("cant find any cookies");
 }
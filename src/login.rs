@@ -0,0 +1,192 @@
+use crate::browser::{ chromium, mozilla };
+use crate::common::enums::{ BrowserConfig, DomainStats, EnumerationOptions };
+use crate::common::{ paths, request };
+use crate::sites;
+use anyhow::Result;
+use std::path::Path;
+use url::Url;
+
+#[cfg(target_os = "macos")]
+use crate::config;
+
+/// Whether one browser profile holds cookies for a site, without any cookie value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoginStatus {
+    pub browser: String,
+    /// Profile directory, e.g. "Default" or "xxxxxxxx.default-release"
+    pub profile: String,
+    /// Unexpired cookies of the site's registrable domains, session cookies included
+    pub cookies: usize,
+    /// Newest last access of those cookies in unix seconds, `None` when unknown
+    pub last_access: Option<u64>,
+}
+
+impl LoginStatus {
+    /// Whether the profile looks signed in, i.e. has any unexpired cookie for the site
+    pub fn logged_in(&self) -> bool {
+        self.cookies > 0
+    }
+}
+
+/// Hosts of a site profile name, a host or a URL
+fn site_hosts(site: &str) -> Vec<String> {
+    sites::site_domains(site).unwrap_or_else(|| {
+        let host = Url::parse(site)
+            .ok()
+            .and_then(|url| url.host_str().map(String::from))
+            .unwrap_or_else(|| site.to_string());
+        vec![host]
+    })
+}
+
+fn group_domains(site: &str, registrable_domain: impl Fn(&str) -> Option<String>) -> Vec<String> {
+    let mut domains: Vec<String> = site_hosts(site)
+        .iter()
+        .filter_map(|host| registrable_domain(host))
+        .collect();
+    domains.sort();
+    domains.dedup();
+    domains
+}
+
+/// Registrable domains to look for, from a site profile name, a host or a URL.
+/// They're approximated by [`request::registrable_domain`].
+#[cfg_attr(feature = "publicsuffix", doc = "See [`site_domains_psl`] for the public suffix list.")]
+pub fn site_domains(site: &str) -> Vec<String> {
+    group_domains(site, request::registrable_domain)
+}
+
+/// Same as [`site_domains`] with the registrable domains taken from the public
+/// suffix list, e.g. `user.github.io` instead of `github.io`
+#[cfg(feature = "publicsuffix")]
+pub fn site_domains_psl(site: &str, list: &publicsuffix::List) -> Vec<String> {
+    group_domains(site, |host| request::registrable_domain_psl(host, list))
+}
+
+fn stats(
+    db_path: &Path,
+    domains: &[String],
+    domain_stats: fn(&Path, &str) -> Result<DomainStats>
+) -> Result<DomainStats> {
+    let mut total = DomainStats::default();
+    for domain in domains {
+        let stats = domain_stats(db_path, domain)?;
+        total.cookies += stats.cookies;
+        total.last_access = total.last_access.max(stats.last_access);
+    }
+    Ok(total)
+}
+
+fn status(browser: &str, profile: String, stats: DomainStats) -> LoginStatus {
+    LoginStatus {
+        browser: browser.to_string(),
+        profile,
+        cookies: stats.cookies,
+        last_access: stats.last_access,
+    }
+}
+
+/// [`login_status`] of the default profile of a mozilla based browser, every profile for Zen
+pub fn mozilla_login_status(browser: &str, config: &BrowserConfig, site: &str) -> Vec<LoginStatus> {
+    mozilla_domains_status(browser, config, &site_domains(site))
+}
+
+fn mozilla_domains_status(browser: &str, config: &BrowserConfig, domains: &[String]) -> Vec<LoginStatus> {
+    crate
+        ::mozilla_db_paths(config)
+        .into_iter()
+        .filter_map(|db_path| {
+            let profile = db_path
+                .parent()
+                .and_then(|dir| dir.file_name())
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            stats(&db_path, domains, mozilla::domain_stats)
+                .map(|stats| status(browser, profile, stats))
+                .ok()
+        })
+        .collect()
+}
+
+/// [`login_status`] of every profile of a chromium based browser
+pub fn chromium_login_status(browser: &str, config: &BrowserConfig, site: &str) -> Vec<LoginStatus> {
+    chromium_domains_status(browser, config, &site_domains(site))
+}
+
+fn chromium_domains_status(browser: &str, config: &BrowserConfig, domains: &[String]) -> Vec<LoginStatus> {
+    let profiles = paths
+        ::find_chrome_based_paths_v2(config, &EnumerationOptions::default())
+        .unwrap_or_default();
+    profiles
+        .into_iter()
+        .filter_map(|(_, db_path)| {
+            let profile = paths::chrome_profile_dir(&db_path).unwrap_or_default();
+            let stats = stats(&db_path, domains, chromium::domain_stats).ok()?;
+            Some(status(browser, profile, stats))
+        })
+        .collect()
+}
+
+/// Reports for every installed browser profile whether it has cookies for `site`,
+/// how many and when they were last used. Values are never read, so no key is
+/// needed and the keyring/keychain is never asked. Profiles that can't be read
+/// are left out.
+///
+/// # Arguments
+///
+/// * `site` - Site profile name (see [`sites::SITE_PROFILES`]), host or URL
+///
+/// # Examples
+///
+/// ```
+/// fn main() {
+///     for status in rookie::login_status("github") {
+///         if status.logged_in() {
+///             println!("found a github login in {} ({})", status.browser, status.profile);
+///         }
+///     }
+/// }
+/// ```
+pub fn login_status(site: &str) -> Vec<LoginStatus> {
+    domains_status(&site_domains(site))
+}
+
+/// Same as [`login_status`] grouping the site's hosts by the public suffix list,
+/// see [`site_domains_psl`]
+///
+/// # Examples
+///
+/// ```no_run
+/// fn main() {
+///     let list: publicsuffix::List = std::fs
+///         ::read_to_string("/usr/share/publicsuffix/public_suffix_list.dat")
+///         .unwrap()
+///         .parse()
+///         .unwrap();
+///     let statuses = rookie::login::login_status_psl("https://user.github.io/", &list);
+///     println!("{:?}", statuses);
+/// }
+/// ```
+#[cfg(feature = "publicsuffix")]
+pub fn login_status_psl(site: &str, list: &publicsuffix::List) -> Vec<LoginStatus> {
+    domains_status(&site_domains_psl(site, list))
+}
+
+fn domains_status(domains: &[String]) -> Vec<LoginStatus> {
+    let mut statuses = vec![];
+    for (browser, config) in crate::mozilla_browsers() {
+        statuses.extend(mozilla_domains_status(browser, config, domains));
+    }
+    for (browser, config) in crate::chromium_browsers() {
+        statuses.extend(chromium_domains_status(browser, config, domains));
+    }
+
+    #[cfg(target_os = "macos")]
+    if let Ok(db_path) = paths::find_safari_based_paths(&config::SAFARI_CONFIG) {
+        if let Ok(stats) = stats(&db_path, domains, crate::browser::binarycookies::domain_stats) {
+            statuses.push(status("safari", String::new(), stats));
+        }
+    }
+
+    statuses
+}
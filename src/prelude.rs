@@ -0,0 +1,48 @@
+//! Versioned import surfaces
+//!
+//! `use rookie::prelude::v1::*;` brings in the stable set. What `v1` names keeps
+//! its signature and meaning for all of `0.x` with the same minor version and, once
+//! released, all of `1.x`: nothing is removed or renamed. The structs that can grow
+//! ([`v1::Cookie`], [`v1::ReadOptions`], [`v1::LoadOptions`], [`v1::LoadOutcome`],
+//! [`v1::RequestContext`]) are `#[non_exhaustive]`, so outside the crate they're
+//! built with their constructor or `Default::default()` and a new field isn't a
+//! breaking change. [`v1::MinimalCookie`] keeps its fields as they are. Anything
+//! outside the prelude (browser modules, `common::*`, paths and secrets helpers) may
+//! still change in a minor release.
+//!
+//! Superseded items are marked `#[deprecated]` with a note naming the replacement
+//! and are kept for at least one minor release before removal. A breaking change
+//! to the stable set gets a new `v2` module, `v1` stays as it was.
+
+pub mod v1 {
+    pub use crate::common::enums::{ Cookie, Domains, LoadOptions, LoadOutcome, MinimalCookie, ReadOptions };
+    pub use crate::common::request::{ header_for_request, RequestContext };
+    pub use crate::common::sqlite::UnsupportedSchema;
+    pub use crate::browser::chromium::AdditionalPasswordRequired;
+    pub use crate::browser::mozilla::ShareUnreachable;
+    pub use crate::{
+        any_browser,
+        brave,
+        chrome,
+        chromium,
+        chromium_profiles,
+        edge,
+        firefox,
+        libre_wolf,
+        load,
+        load_with,
+        mullvad,
+        opera,
+        opera_gx,
+        vivaldi,
+        waterfox,
+        zen,
+    };
+
+    #[cfg(unix)]
+    pub use crate::common::secrets::InteractionRequired;
+    #[cfg(target_os = "macos")]
+    pub use crate::safari;
+    #[cfg(target_os = "windows")]
+    pub use crate::internet_explorer;
+}
@@ -0,0 +1,211 @@
+use crate::browser::{ chromium, mozilla };
+use crate::common::enums::{ BrowserConfig, Cookie, Domains, EnumerationOptions };
+use crate::common::paths;
+use anyhow::{ bail, Result };
+use std::path::{ Path, PathBuf };
+use std::time::UNIX_EPOCH;
+
+#[cfg(target_os = "macos")]
+use crate::config;
+
+/// A browser profile found on disk, see [`list_profiles`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileInfo {
+    /// Browser name as in [`crate::load`], e.g. "chrome" or "firefox"
+    pub browser: String,
+    /// Profile directory, e.g. "Default" or "xxxxxxxx.default-release"
+    pub profile_dir: String,
+    /// Name shown by the browser's profile picker, `None` when it doesn't give one
+    pub name: Option<String>,
+    /// Cookies DB of the profile
+    pub db_path: PathBuf,
+    /// Last write to the cookies DB in unix seconds, `None` when unknown
+    pub last_modified: Option<u64>,
+}
+
+fn info(browser: &str, profile_dir: String, name: Option<String>, db_path: PathBuf) -> ProfileInfo {
+    let last_modified = paths
+        ::last_modified(&db_path)
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs());
+    ProfileInfo {
+        browser: browser.to_string(),
+        profile_dir,
+        name,
+        db_path,
+        last_modified,
+    }
+}
+
+/// Every profile of a chromium based browser, named from `Local State` or `Preferences`
+pub fn chromium_profile_infos(browser: &str, config: &BrowserConfig) -> Vec<ProfileInfo> {
+    paths
+        ::find_chrome_based_paths_v2(config, &EnumerationOptions::default())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(_, db_path)| {
+            let profile_dir = paths::chrome_profile_dir(&db_path).unwrap_or_default();
+            let name = paths::chrome_profile_path(&db_path).and_then(chromium::profile_name);
+            info(browser, profile_dir, name, db_path)
+        })
+        .collect()
+}
+
+/// `Name` of a profile in the profiles.ini of its root, which is the parent directory
+/// or the one above a `Profiles` directory
+fn mozilla_profile_name(profile_dir: &Path) -> Option<String> {
+    profile_dir
+        .ancestors()
+        .skip(1)
+        .take(2)
+        .filter_map(|root| mozilla::named_profile_dirs(root).ok())
+        .flatten()
+        .find(|(_, dir)| dir == profile_dir)
+        .and_then(|(name, _)| name)
+}
+
+/// Every profile of a mozilla based browser holding a `cookies.sqlite`, named from profiles.ini
+pub fn mozilla_profile_infos(browser: &str, config: &BrowserConfig) -> Vec<ProfileInfo> {
+    paths
+        ::find_mozilla_profile_paths(config)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|db_path| {
+            let profile_path = db_path.parent().unwrap_or(Path::new(""));
+            let profile_dir = profile_path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let name = mozilla_profile_name(profile_path);
+            info(browser, profile_dir, name, db_path)
+        })
+        .collect()
+}
+
+/// Lists the profiles of a browser without reading any cookie, so one can be picked
+/// and read with [`read_profile`]. A browser that isn't installed has none.
+///
+/// # Arguments
+///
+/// * `browser` - Browser name as in [`crate::load`], e.g. "chrome", "edge" or "firefox"
+///
+/// # Examples
+///
+/// ```
+/// fn main() {
+///     for profile in rookie::list_profiles("chrome").unwrap() {
+///         println!("{} ({:?}) {}", profile.profile_dir, profile.name, profile.db_path.display());
+///     }
+/// }
+/// ```
+pub fn list_profiles(browser: &str) -> Result<Vec<ProfileInfo>> {
+    if let Some((_, config)) = crate::mozilla_browsers().into_iter().find(|(name, _)| *name == browser) {
+        return Ok(mozilla_profile_infos(browser, config));
+    }
+    if let Some((_, config)) = crate::chromium_browsers().into_iter().find(|(name, _)| *name == browser) {
+        return Ok(chromium_profile_infos(browser, config));
+    }
+    #[cfg(target_os = "macos")]
+    if browser == "safari" {
+        let profiles = paths
+            ::find_safari_based_paths(&config::SAFARI_CONFIG)
+            .map(|db_path| info(browser, String::new(), None, db_path));
+        return Ok(profiles.into_iter().collect());
+    }
+    bail!("unknown browser {}", browser)
+}
+
+/// Returned when no profile of a browser has the requested directory or display name
+#[derive(Debug)]
+pub struct ProfileNotFound {
+    pub browser: String,
+    pub name: String,
+    /// Profiles that exist, as "directory (display name)"
+    pub available: Vec<String>,
+}
+
+impl std::fmt::Display for ProfileNotFound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "no {} profile named {:?}", self.browser, self.name)?;
+        if self.available.is_empty() {
+            write!(f, ", no profile was found")
+        } else {
+            write!(f, ", available: {}", self.available.join(", "))
+        }
+    }
+}
+
+impl std::error::Error for ProfileNotFound {}
+
+/// Finds a profile of a browser by its directory ("Profile 2") or by the name the
+/// browser shows for it, the directory taking precedence. Fails with [`ProfileNotFound`].
+pub fn find_profile(browser: &str, name: &str) -> Result<ProfileInfo> {
+    let mut profiles = list_profiles(browser)?;
+    let found = profiles
+        .iter()
+        .position(|profile| profile.profile_dir == name)
+        .or_else(|| profiles.iter().position(|profile| profile.name.as_deref() == Some(name)));
+    if let Some(index) = found {
+        return Ok(profiles.swap_remove(index));
+    }
+    let available = profiles
+        .iter()
+        .map(|profile| {
+            match &profile.name {
+                Some(display_name) => format!("{} ({})", profile.profile_dir, display_name),
+                None => profile.profile_dir.clone(),
+            }
+        })
+        .collect();
+    bail!(ProfileNotFound { browser: browser.to_string(), name: name.to_string(), available })
+}
+
+/// Returns cookies from a profile found by [`list_profiles`]
+///
+/// # Arguments
+///
+/// * `profile` - Profile to read
+/// * `domains` - A optional list that for getting specific domains only
+///
+/// # Examples
+///
+/// ```
+/// fn main() {
+///     let profiles = rookie::list_profiles("firefox").unwrap();
+///     if let Some(profile) = profiles.iter().max_by_key(|profile| profile.last_modified) {
+///         let cookies = rookie::read_profile(profile, Some(vec!["google.com"]));
+///     }
+/// }
+/// ```
+pub fn read_profile(
+    profile: &ProfileInfo,
+    domains: impl Into<Domains>
+) -> Result<Vec<Cookie>> {
+    Ok(crate::with_browser(&profile.browser, read_cookies(profile, domains)?))
+}
+
+fn read_cookies(
+    profile: &ProfileInfo,
+    domains: impl Into<Domains>
+) -> Result<Vec<Cookie>> {
+    let db_path = profile.db_path.clone();
+    if crate::mozilla_browsers().iter().any(|(name, _)| *name == profile.browser) {
+        return mozilla::firefox_based(db_path, domains);
+    }
+    #[cfg(target_os = "macos")]
+    if profile.browser == "safari" {
+        return crate::browser::safari::safari_based(db_path, domains);
+    }
+    let Some((_, config)) = crate::chromium_browsers().into_iter().find(|(name, _)| *name == profile.browser) else {
+        bail!("unknown browser {}", profile.browser);
+    };
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let _ = config;
+            let key_path = paths::find_key_path(&db_path).unwrap_or_default();
+            chromium::chromium_based(key_path, db_path, domains)
+        } else {
+            chromium::chromium_based(config, db_path, domains)
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use crate::common::{ date, enums::Cookie };
+use ::reqwest::cookie::Jar;
+use url::Url;
+
+/// Cookie jar for a reqwest client, with each cookie set from its own site so
+/// reqwest sends it following the usual domain, path and Secure rules. Domain cookies
+/// go to subdomains too while host-only ones only go to their host.
+///
+/// Expired cookies are left out, and so are partitioned (CHIPS) cookies since they
+/// only belong to pages embedded in their top level site.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// fn main() {
+///     let cookies = rookie::firefox(Some(vec!["github.com"])).unwrap_or_default();
+///     let jar = rookie::reqwest::to_cookie_jar(&cookies);
+///     let client = reqwest::Client::builder().cookie_provider(Arc::new(jar)).build();
+///     assert!(client.is_ok());
+/// }
+/// ```
+pub fn to_cookie_jar(cookies: &[Cookie]) -> Jar {
+    let jar = Jar::default();
+    for cookie in cookies {
+        if cookie.is_expired() || cookie.partition_key.is_some() {
+            continue;
+        }
+        let host = cookie.domain.trim_start_matches('.');
+        let path = if cookie.path.starts_with('/') { cookie.path.as_str() } else { "/" };
+        let Ok(url) = Url::parse(&format!("https://{}{}", host, path)) else {
+            continue;
+        };
+        jar.add_cookie_str(&set_cookie(cookie, host, path), &url);
+    }
+    jar
+}
+
+/// `Set-Cookie` value restoring `cookie` when received from `host`
+fn set_cookie(cookie: &Cookie, host: &str, path: &str) -> String {
+    let mut attributes = vec![format!("{}={}", cookie.name, cookie.value), format!("Path={}", path)];
+    if !cookie.host_only {
+        attributes.push(format!("Domain={}", host));
+    }
+    if let Some(expires) = cookie.expires {
+        attributes.push(format!("Expires={}", date::http_date(expires)));
+    }
+    if cookie.secure {
+        attributes.push("Secure".to_string());
+    }
+    if cookie.http_only {
+        attributes.push("HttpOnly".to_string());
+    }
+    attributes.join("; ")
+}
@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+use std::sync::{ OnceLock, RwLock };
+
+/// Domains a login spans for common services, adding a site is a single entry here
+pub static SITE_PROFILES: &[(&str, &[&str])] = &[
+    ("github", &["github.com", "githubusercontent.com", "githubassets.com"]),
+    ("gitlab", &["gitlab.com"]),
+    ("google", &["google.com", "gstatic.com", "googleapis.com", "youtube.com"]),
+    ("youtube", &["youtube.com", "google.com", "ytimg.com"]),
+    ("microsoft", &["microsoft.com", "live.com", "microsoftonline.com", "office.com"]),
+    ("facebook", &["facebook.com", "fbcdn.net", "messenger.com"]),
+    ("twitter", &["twitter.com", "x.com", "twimg.com"]),
+    ("reddit", &["reddit.com", "redditmedia.com", "redd.it"]),
+    ("atlassian", &["atlassian.com", "atlassian.net"]),
+];
+
+fn registered() -> &'static RwLock<HashMap<String, Vec<String>>> {
+    static REGISTERED: OnceLock<RwLock<HashMap<String, Vec<String>>>> = OnceLock::new();
+    REGISTERED.get_or_init(Default::default)
+}
+
+/// Adds or replaces a site profile at runtime, taking precedence over the built-in one
+pub fn register_site_profile(site: &str, domains: impl IntoIterator<Item = impl AsRef<str>>) {
+    let domains = domains
+        .into_iter()
+        .map(|domain| domain.as_ref().to_string())
+        .collect();
+    let mut registered = registered().write().unwrap_or_else(|err| err.into_inner());
+    registered.insert(site.to_lowercase(), domains);
+}
+
+/// Returns the domains of a site profile, `None` for unknown sites
+pub fn site_domains(site: &str) -> Option<Vec<String>> {
+    let site = site.to_lowercase();
+    let registered = registered().read().unwrap_or_else(|err| err.into_inner());
+    if let Some(domains) = registered.get(&site) {
+        return Some(domains.clone());
+    }
+    SITE_PROFILES.iter()
+        .find(|(name, _)| *name == site)
+        .map(|(_, domains)| {
+            domains
+                .iter()
+                .map(|domain| domain.to_string())
+                .collect()
+        })
+}
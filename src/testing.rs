@@ -0,0 +1,627 @@
+//! Fixture generators used by the integration tests.
+//!
+//! Every generator writes a minimal but realistic on-disk layout under the
+//! given root, so the real discovery and parsing code can run against it
+//! without touching the user's browsers or OS keystores.
+
+use anyhow::Result;
+use rusqlite::{ params, Connection };
+use std::fs;
+use std::path::{ Path, PathBuf };
+
+/// Seconds between 1601-01-01 (Chromium epoch) and 1970-01-01
+const CHROMIUM_EPOCH_OFFSET: u64 = 11_644_473_600;
+
+/// Seconds between 1970-01-01 and 2001-01-01 (Mac absolute time)
+const MAC_EPOCH_OFFSET: u64 = 978_307_200;
+
+/// AES key used for fixture profiles on Windows, wrapped with DPAPI in `Local State`
+#[cfg(target_os = "windows")]
+const FIXTURE_KEY: [u8; 32] = [0x42; 32];
+
+#[derive(Debug, Clone)]
+pub struct FixtureCookie {
+    pub host: String,
+    pub path: String,
+    pub name: String,
+    pub value: String,
+    /// Unix seconds, `None` for session cookies
+    pub expires: Option<u64>,
+    pub secure: bool,
+    pub http_only: bool,
+    pub same_site: i64,
+    /// `source_port`, `None` for the default port of the scheme (443 or 80)
+    pub port: Option<u16>,
+}
+
+impl FixtureCookie {
+    pub fn new(host: &str, name: &str, value: &str) -> Self {
+        FixtureCookie {
+            host: host.to_string(),
+            path: "/".to_string(),
+            name: name.to_string(),
+            value: value.to_string(),
+            expires: Some(4_102_444_800), // 2100-01-01
+            secure: false,
+            http_only: false,
+            same_site: 0,
+            port: None,
+        }
+    }
+}
+
+/// A small set of cookies spread over a few domains,
+/// including a nameless (`=value`) and a valueless (`name=`) cookie
+pub fn sample_cookies() -> Vec<FixtureCookie> {
+    let mut session = FixtureCookie::new("example.com", "session", "s3ss10n");
+    session.expires = None;
+    session.http_only = true;
+
+    let mut secure = FixtureCookie::new(".google.com", "SID", "g00gle");
+    secure.secure = true;
+    secure.same_site = 1;
+
+    let mut api = FixtureCookie::new("api.example.com", "token", "t0k3n");
+    api.path = "/api".to_string();
+
+    vec![
+        session,
+        secure,
+        api,
+        FixtureCookie::new("notgoogle.com", "id", "n0t"),
+        FixtureCookie::new("example.org", "", "n4meless"),
+        FixtureCookie::new("example.org", "empty", "")
+    ]
+}
+
+/// Cookie DB layouts written by different Chromium releases
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChromiumSchema {
+    /// Chrome 80 era, no partition key
+    V12,
+    /// Chrome 110 era, adds `top_frame_site_key` and `source_port`
+    V18,
+    /// Chrome 120 era, adds `source_type` and `has_cross_site_ancestor`
+    V21,
+    /// Chrome 130 era, the table of V21 with the SHA256 of `host_key` in front of
+    /// every encrypted plaintext
+    V24,
+}
+
+impl ChromiumSchema {
+    pub fn all() -> [ChromiumSchema; 4] {
+        [ChromiumSchema::V12, ChromiumSchema::V18, ChromiumSchema::V21, ChromiumSchema::V24]
+    }
+
+    fn version(&self) -> i64 {
+        match self {
+            ChromiumSchema::V12 => 12,
+            ChromiumSchema::V18 => 18,
+            ChromiumSchema::V21 => 21,
+            ChromiumSchema::V24 => 24,
+        }
+    }
+
+    /// Whether the plaintext of a value starts with the hash of its host
+    fn hashes_host(&self) -> bool {
+        self.version() >= 24
+    }
+
+    fn create_table(&self) -> &'static str {
+        match self {
+            ChromiumSchema::V12 =>
+                "CREATE TABLE cookies(creation_utc INTEGER NOT NULL,host_key TEXT NOT NULL,name TEXT NOT NULL,value TEXT NOT NULL,path TEXT NOT NULL,expires_utc INTEGER NOT NULL,is_secure INTEGER NOT NULL,is_httponly INTEGER NOT NULL,last_access_utc INTEGER NOT NULL,has_expires INTEGER NOT NULL DEFAULT 1,is_persistent INTEGER NOT NULL DEFAULT 1,priority INTEGER NOT NULL DEFAULT 1,encrypted_value BLOB DEFAULT '',samesite INTEGER NOT NULL DEFAULT -1,source_scheme INTEGER NOT NULL DEFAULT 0,UNIQUE (host_key, name, path))",
+            ChromiumSchema::V18 =>
+                "CREATE TABLE cookies(creation_utc INTEGER NOT NULL,top_frame_site_key TEXT NOT NULL,host_key TEXT NOT NULL,name TEXT NOT NULL,value TEXT NOT NULL,encrypted_value BLOB DEFAULT '',path TEXT NOT NULL,expires_utc INTEGER NOT NULL,is_secure INTEGER NOT NULL,is_httponly INTEGER NOT NULL,last_access_utc INTEGER NOT NULL,has_expires INTEGER NOT NULL DEFAULT 1,is_persistent INTEGER NOT NULL DEFAULT 1,priority INTEGER NOT NULL DEFAULT 1,samesite INTEGER NOT NULL DEFAULT -1,source_scheme INTEGER NOT NULL DEFAULT 0,source_port INTEGER NOT NULL DEFAULT -1,is_same_party INTEGER NOT NULL DEFAULT 0,last_update_utc INTEGER NOT NULL DEFAULT 0,UNIQUE (top_frame_site_key, host_key, name, path))",
+            ChromiumSchema::V21 | ChromiumSchema::V24 =>
+                "CREATE TABLE cookies(creation_utc INTEGER NOT NULL,host_key TEXT NOT NULL,top_frame_site_key TEXT NOT NULL,name TEXT NOT NULL,value TEXT NOT NULL,encrypted_value BLOB NOT NULL,path TEXT NOT NULL,expires_utc INTEGER NOT NULL,is_secure INTEGER NOT NULL,is_httponly INTEGER NOT NULL,last_access_utc INTEGER NOT NULL,has_expires INTEGER NOT NULL,is_persistent INTEGER NOT NULL,priority INTEGER NOT NULL,samesite INTEGER NOT NULL,source_scheme INTEGER NOT NULL,source_port INTEGER NOT NULL,last_update_utc INTEGER NOT NULL,source_type INTEGER NOT NULL,has_cross_site_ancestor INTEGER NOT NULL,UNIQUE (host_key, top_frame_site_key, has_cross_site_ancestor, name, path, source_scheme, source_port))",
+        }
+    }
+}
+
+/// Paths of a generated Chromium profile
+#[derive(Debug, Clone)]
+pub struct ChromiumFixture {
+    pub key_path: PathBuf,
+    pub db_path: PathBuf,
+}
+
+/// Writes `<root>/User Data/Local State` and `<root>/User Data/Default/Network/Cookies`
+/// with every value v10-encrypted the way the platform's reader expects.
+pub fn write_chromium_profile(
+    root: &Path,
+    schema: ChromiumSchema,
+    cookies: &[FixtureCookie]
+) -> Result<ChromiumFixture> {
+    write_chromium_profile_in(root, "Default", schema, cookies)
+}
+
+/// Same as [`write_chromium_profile`] for any profile directory,
+/// sharing one `Local State` between all profiles under `root`.
+pub fn write_chromium_profile_in(
+    root: &Path,
+    profile: &str,
+    schema: ChromiumSchema,
+    cookies: &[FixtureCookie]
+) -> Result<ChromiumFixture> {
+    let user_data = root.join("User Data");
+    let network = user_data.join(profile).join("Network");
+    fs::create_dir_all(&network)?;
+
+    let key_path = user_data.join("Local State");
+    if !key_path.exists() {
+        fs::write(&key_path, local_state()?)?;
+    }
+
+    let db_path = network.join("Cookies");
+    let connection = Connection::open(&db_path)?;
+    connection.execute_batch(
+        "CREATE TABLE meta(key LONGVARCHAR NOT NULL UNIQUE PRIMARY KEY, value LONGVARCHAR);"
+    )?;
+    connection.execute(
+        "INSERT INTO meta (key, value) VALUES ('version', ?1), ('last_compatible_version', ?1)",
+        params![schema.version().to_string()]
+    )?;
+    connection.execute_batch(schema.create_table())?;
+
+    for cookie in cookies {
+        let expires_utc = cookie.expires.map_or(0, chromium_time);
+        let now = chromium_time(1_700_000_000);
+        let host = schema.hashes_host().then_some(cookie.host.as_str());
+        let encrypted_value = encrypt_value(&cookie.value, host)?;
+        let has_expires = cookie.expires.is_some();
+        let mut columns = vec![
+            "creation_utc",
+            "host_key",
+            "name",
+            "value",
+            "encrypted_value",
+            "path",
+            "expires_utc",
+            "is_secure",
+            "is_httponly",
+            "last_access_utc",
+            "has_expires",
+            "is_persistent",
+            "priority",
+            "samesite",
+            "source_scheme"
+        ];
+        let mut values: Vec<Box<dyn rusqlite::ToSql>> = vec![
+            Box::new(now),
+            Box::new(cookie.host.clone()),
+            Box::new(cookie.name.clone()),
+            Box::new(""),
+            Box::new(encrypted_value),
+            Box::new(cookie.path.clone()),
+            Box::new(expires_utc),
+            Box::new(cookie.secure),
+            Box::new(cookie.http_only),
+            Box::new(now),
+            Box::new(has_expires),
+            Box::new(has_expires),
+            Box::new(1),
+            Box::new(cookie.same_site),
+            Box::new(if cookie.secure { 2 } else { 1 })
+        ];
+        if schema != ChromiumSchema::V12 {
+            columns.extend(["top_frame_site_key", "source_port", "last_update_utc"]);
+            values.push(Box::new(""));
+            values.push(Box::new(cookie.port.unwrap_or(if cookie.secure { 443 } else { 80 })));
+            values.push(Box::new(now));
+        }
+        if matches!(schema, ChromiumSchema::V21 | ChromiumSchema::V24) {
+            columns.extend(["source_type", "has_cross_site_ancestor"]);
+            values.push(Box::new(0));
+            values.push(Box::new(0));
+        }
+        let placeholders: Vec<String> = (1..=columns.len()).map(|i| format!("?{}", i)).collect();
+        let query = format!(
+            "INSERT INTO cookies ({}) VALUES ({})",
+            columns.join(", "),
+            placeholders.join(", ")
+        );
+        connection.execute(&query, rusqlite::params_from_iter(values.iter()))?;
+    }
+
+    Ok(ChromiumFixture { key_path, db_path })
+}
+
+/// Adds another row for the cookie `name` holding `value`, updated at `updated_at`
+/// (unix seconds), dropping the UNIQUE index first like a crashed browser can leave it
+pub fn insert_duplicate(db_path: &Path, name: &str, value: &str, updated_at: u64) -> Result<()> {
+    let connection = Connection::open(db_path)?;
+    // CREATE TABLE .. AS SELECT copies the rows without the constraints
+    connection.execute_batch(
+        "CREATE TABLE cookies_copy AS SELECT * FROM cookies;
+         DROP TABLE cookies;
+         ALTER TABLE cookies_copy RENAME TO cookies;"
+    )?;
+    connection.execute(
+        "CREATE TEMP TABLE duplicate AS SELECT * FROM cookies WHERE name = ?1 LIMIT 1",
+        params![name]
+    )?;
+    let updated_at = chromium_time(updated_at);
+    let version: i64 = connection.query_row(
+        "SELECT CAST(value AS INTEGER) FROM meta WHERE key = 'version'",
+        [],
+        |row| row.get(0)
+    )?;
+    let host: String = connection.query_row("SELECT host_key FROM duplicate", [], |row| row.get(0))?;
+    let host = (version >= 24).then_some(host.as_str());
+    connection.execute(
+        "UPDATE duplicate SET encrypted_value = ?1, creation_utc = ?2",
+        params![encrypt_value(value, host)?, updated_at]
+    )?;
+    // V12 has no last_update_utc
+    let _ = connection.execute(
+        "UPDATE duplicate SET last_update_utc = ?1",
+        params![updated_at]
+    );
+    connection.execute_batch("INSERT INTO cookies SELECT * FROM duplicate;")?;
+    Ok(())
+}
+
+/// Sets `profile.info_cache.<profile>` in the shared `Local State` under `root`
+pub fn set_profile_info(root: &Path, profile: &str, info: serde_json::Value) -> Result<()> {
+    set_local_state(root, &["profile", "info_cache", profile], info)
+}
+
+/// Sets the value at `keys` in the shared `Local State` under `root`
+pub fn set_local_state(root: &Path, keys: &[&str], value: serde_json::Value) -> Result<()> {
+    let key_path = root.join("User Data").join("Local State");
+    let mut local_state: serde_json::Value = serde_json::from_str(&fs::read_to_string(&key_path)?)?;
+    let target = keys.iter().fold(&mut local_state, |json, key| &mut json[*key]);
+    *target = value;
+    fs::write(&key_path, local_state.to_string())?;
+    Ok(())
+}
+
+/// Writes the `Preferences` file of a profile under `root`
+pub fn write_preferences(root: &Path, profile: &str, preferences: serde_json::Value) -> Result<()> {
+    let profile_dir = root.join("User Data").join(profile);
+    fs::create_dir_all(&profile_dir)?;
+    fs::write(profile_dir.join("Preferences"), preferences.to_string())?;
+    Ok(())
+}
+
+/// A config whose only data paths point into a fixture `User Data` tree
+pub fn chromium_data_paths(root: &Path) -> Vec<String> {
+    let user_data = root.join("User Data");
+    ["Default/Network/Cookies", "Profile */Network/Cookies"]
+        .iter()
+        .map(|p| format!("{}/{}", user_data.display(), p))
+        .collect()
+}
+
+/// Writes `<root>/profiles.ini` and a single default profile with `cookies.sqlite`,
+/// returning the path of the database.
+pub fn write_mozilla_profile(root: &Path, cookies: &[FixtureCookie]) -> Result<PathBuf> {
+    let profile = "fixture.default-release";
+    fs::create_dir_all(root.join(profile))?;
+    fs::write(
+        root.join("profiles.ini"),
+        format!(
+            "[Profile0]\nName=default-release\nIsRelative=1\nPath={}\nDefault=1\n\n[General]\nStartWithLastProfile=1\nVersion=2\n",
+            profile
+        )
+    )?;
+
+    let db_path = root.join(profile).join("cookies.sqlite");
+    let connection = Connection::open(&db_path)?;
+    connection.execute_batch(
+        "CREATE TABLE moz_cookies (id INTEGER PRIMARY KEY, originAttributes TEXT NOT NULL DEFAULT '', name TEXT, value TEXT, host TEXT, path TEXT, expiry INTEGER, lastAccessed INTEGER, creationTime INTEGER, isSecure INTEGER, isHttpOnly INTEGER, inBrowserElement INTEGER DEFAULT 0, sameSite INTEGER DEFAULT 0, rawSameSite INTEGER DEFAULT 0, schemeMap INTEGER DEFAULT 0, CONSTRAINT moz_uniqueid UNIQUE (name, host, path, originAttributes));
+        PRAGMA user_version = 12;"
+    )?;
+
+    for cookie in cookies {
+        let now: u64 = 1_700_000_000_000_000;
+        connection.execute(
+            "INSERT INTO moz_cookies (name, value, host, path, expiry, lastAccessed, creationTime, isSecure, isHttpOnly, sameSite, rawSameSite) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6, ?7, ?8, ?9, ?9)",
+            params![
+                cookie.name,
+                cookie.value,
+                cookie.host,
+                cookie.path,
+                cookie.expires.unwrap_or(0),
+                now,
+                cookie.secure,
+                cookie.http_only,
+                cookie.same_site
+            ]
+        )?;
+    }
+    Ok(db_path)
+}
+
+/// Writes a libsoup `cookies.sqlite` as Epiphany (GNOME Web) keeps it, `same_site`
+/// leaves out the `sameSite` column like libsoup before 2.70 did.
+pub fn write_epiphany_cookies(db_path: &Path, cookies: &[FixtureCookie], same_site: bool) -> Result<()> {
+    if let Some(parent) = db_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let connection = Connection::open(db_path)?;
+    connection.execute_batch(
+        "CREATE TABLE moz_cookies (id INTEGER PRIMARY KEY, name TEXT, value TEXT, host TEXT, path TEXT, expiry INTEGER, lastAccessed INTEGER, isSecure INTEGER, isHttpOnly INTEGER);"
+    )?;
+    if same_site {
+        connection.execute_batch("ALTER TABLE moz_cookies ADD COLUMN sameSite INTEGER DEFAULT 0;")?;
+    }
+
+    for cookie in cookies {
+        connection.execute(
+            "INSERT INTO moz_cookies (name, value, host, path, expiry, lastAccessed, isSecure, isHttpOnly) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                cookie.name,
+                cookie.value,
+                cookie.host,
+                cookie.path,
+                cookie.expires.unwrap_or(0),
+                1_700_000_000u64,
+                cookie.secure,
+                cookie.http_only
+            ]
+        )?;
+        if same_site {
+            connection.execute(
+                "UPDATE moz_cookies SET sameSite = ?1 WHERE id = last_insert_rowid()",
+                params![cookie.same_site]
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Data key of the Yandex fixture profiles
+pub const YANDEX_DATA_KEY: [u8; 32] = [0x59; 32];
+
+/// Same as [`write_chromium_profile`] for Yandex: `Default/Ya Passman Data` holds
+/// [`YANDEX_DATA_KEY`] wrapped in `local_encryptor_data` and every value is encrypted
+/// with it instead of the os_crypt key. `locked` adds the `sealed_key` a master password sets.
+pub fn write_yandex_profile(root: &Path, cookies: &[FixtureCookie], locked: bool) -> Result<ChromiumFixture> {
+    let fixture = write_chromium_profile(root, ChromiumSchema::V21, cookies)?;
+    let connection = Connection::open(&fixture.db_path)?;
+    for cookie in cookies {
+        connection.execute(
+            "UPDATE cookies SET encrypted_value = ?1 WHERE host_key = ?2 AND name = ?3",
+            params![encrypt_with(&YANDEX_DATA_KEY, cookie.value.as_bytes())?, cookie.host, cookie.name]
+        )?;
+    }
+
+    let mut unwrapped = vec![0x08, 0x01, 0x12, 0x20];
+    unwrapped.extend(YANDEX_DATA_KEY);
+    unwrapped.extend([0u8; 32]);
+    // protobuf framing around the wrapped key
+    let mut local_encryptor_data = vec![0x0a, 0x63];
+    local_encryptor_data.extend(encrypt_with(&fixture_key(), &unwrapped)?);
+
+    let passman = Connection::open(root.join("User Data/Default/Ya Passman Data"))?;
+    passman.execute_batch(
+        "CREATE TABLE meta(key LONGVARCHAR NOT NULL UNIQUE PRIMARY KEY, value LONGVARCHAR);"
+    )?;
+    passman.execute(
+        "INSERT INTO meta (key, value) VALUES ('local_encryptor_data', ?1)",
+        params![local_encryptor_data]
+    )?;
+    if locked {
+        passman.execute(
+            "INSERT INTO meta (key, value) VALUES ('sealed_key', ?1)",
+            params![br#"{"encrypted_private_key":"AAAA","unlock_key_salt":"AAAA"}"#.to_vec()]
+        )?;
+    }
+    Ok(fixture)
+}
+
+/// Writes a Firefox profile where discovery looks for it on this OS when `root` is the
+/// user profile, see [`crate::with_user_profile_root`]. Returns the path of the database.
+pub fn write_firefox_home(root: &Path, cookies: &[FixtureCookie]) -> Result<PathBuf> {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let firefox_dir = root.join("AppData/Roaming/Mozilla/Firefox");
+        } else if #[cfg(target_os = "macos")] {
+            let firefox_dir = root.join("Library/Application Support/Firefox");
+        } else {
+            let firefox_dir = root.join(".mozilla/firefox");
+        }
+    }
+    write_mozilla_profile(&firefox_dir, cookies)
+}
+
+/// Writes a Safari `Cookies.binarycookies` file with every cookie on a single page.
+pub fn write_binarycookies(path: &Path, cookies: &[FixtureCookie]) -> Result<()> {
+    let mut records: Vec<Vec<u8>> = vec![];
+    for cookie in cookies {
+        let mut strings: Vec<u8> = vec![];
+        let header_len = 0x38;
+        let mut offsets: Vec<u32> = vec![];
+        for field in [&cookie.host, &cookie.name, &cookie.path, &cookie.value] {
+            offsets.push((header_len + strings.len()) as u32);
+            strings.extend_from_slice(field.as_bytes());
+            strings.push(0);
+        }
+        let mut flags = 0u32;
+        if cookie.secure {
+            flags |= 0x01;
+        }
+        if cookie.http_only {
+            flags |= 0x04;
+        }
+        let expires = cookie.expires.map_or(0.0, |e| e.saturating_sub(MAC_EPOCH_OFFSET) as f64);
+
+        let mut record: Vec<u8> = vec![];
+        record.extend(((header_len + strings.len()) as u32).to_le_bytes());
+        record.extend(0u32.to_le_bytes());
+        record.extend(flags.to_le_bytes());
+        record.extend(0u32.to_le_bytes());
+        for offset in offsets {
+            record.extend(offset.to_le_bytes());
+        }
+        record.extend([0u8; 8]);
+        record.extend(expires.to_le_bytes());
+        // creation
+        record.extend(((1_700_000_000 - MAC_EPOCH_OFFSET) as f64).to_le_bytes());
+        record.extend(strings);
+        records.push(record);
+    }
+
+    let mut page: Vec<u8> = vec![0x00, 0x00, 0x01, 0x00];
+    page.extend((records.len() as u32).to_le_bytes());
+    let mut offset = 8 + records.len() * 4 + 4;
+    for record in &records {
+        page.extend((offset as u32).to_le_bytes());
+        offset += record.len();
+    }
+    page.extend([0u8; 4]);
+    for record in records {
+        page.extend(record);
+    }
+
+    let mut content: Vec<u8> = b"cook".to_vec();
+    content.extend(1u32.to_be_bytes());
+    content.extend((page.len() as u32).to_be_bytes());
+    content.extend(page);
+    content.extend([0u8; 4]); // checksum, not verified by readers
+    content.extend([0x07, 0x17, 0x20, 0x05, 0x00, 0x00, 0x00, 0x4b]);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)?;
+    Ok(())
+}
+
+/// Writes an unencrypted iOS backup holding `cookies` as the app's
+/// `Library/Cookies/Cookies.binarycookies`, listed in a minimal `Manifest.db`.
+/// Returns the path of the hashed file.
+pub fn write_ios_backup(root: &Path, bundle_id: &str, cookies: &[FixtureCookie]) -> Result<PathBuf> {
+    let domain = format!("AppDomain-{}", bundle_id);
+    let relative_path = "Library/Cookies/Cookies.binarycookies";
+    // iOS names files by the SHA-1 of "domain-relativePath", any 40 hex digits do here
+    let digest = crate::common::provenance::sha256_hex(format!("{}-{}", domain, relative_path).as_bytes());
+    let file_id = digest[..40].to_string();
+    let path = root.join(&file_id[..2]).join(&file_id);
+    write_binarycookies(&path, cookies)?;
+
+    let connection = Connection::open(root.join("Manifest.db"))?;
+    connection.execute_batch(
+        "CREATE TABLE Files (fileID TEXT PRIMARY KEY, domain TEXT, relativePath TEXT, flags INTEGER, file BLOB);
+        CREATE INDEX FilesDomainIdx ON Files(domain);
+        CREATE INDEX FilesRelativePathIdx ON Files(relativePath);"
+    )?;
+    connection.execute(
+        "INSERT INTO Files (fileID, domain, relativePath, flags, file) VALUES (?1, ?2, ?3, 1, NULL), (?4, ?2, 'Library/Cookies', 2, NULL)",
+        params![file_id, domain, relative_path, format!("{:0>40}", 1)]
+    )?;
+    Ok(path)
+}
+
+fn chromium_time(unix_seconds: u64) -> u64 {
+    (unix_seconds + CHROMIUM_EPOCH_OFFSET) * 1_000_000
+}
+
+#[cfg(unix)]
+fn local_state() -> Result<String> {
+    Ok(serde_json::json!({ "os_crypt": {} }).to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn local_state() -> Result<String> {
+    use base64::{ Engine as _, engine::general_purpose };
+    use crate::common::winapi;
+
+    let mut key = FIXTURE_KEY.to_vec();
+    let mut wrapped = b"DPAPI".to_vec();
+    wrapped.extend(winapi::encrypt(&mut key)?);
+    let encrypted_key = general_purpose::STANDARD.encode(wrapped);
+    Ok(serde_json::json!({ "os_crypt": { "encrypted_key": encrypted_key } }).to_string())
+}
+
+/// Keystore stand-in counting how often it's asked, so reads never reach the OS
+/// keystores. Its password doesn't open the fixtures, they decrypt with the
+/// "peanuts" fallback like a profile without a keystore entry
+#[cfg(unix)]
+#[derive(Debug, Default)]
+pub struct CountingSecrets {
+    pub calls: std::sync::atomic::AtomicUsize,
+}
+
+#[cfg(unix)]
+impl crate::common::secrets::SecretsProvider for CountingSecrets {
+    fn passwords(&self, _config: &crate::common::enums::BrowserConfig) -> Result<Vec<String>> {
+        self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        Ok(vec!["not the fixture password".to_string()])
+    }
+}
+
+/// The built-in "peanuts" password, which every unix reader falls back to
+#[cfg(unix)]
+fn fixture_key() -> Vec<u8> {
+    use crate::browser::chromium::{ create_pbkdf2_key, PBKDF2_ITERATIONS };
+    create_pbkdf2_key("peanuts", b"saltysalt", PBKDF2_ITERATIONS)
+}
+
+/// The key stored in `Local State`
+#[cfg(target_os = "windows")]
+fn fixture_key() -> Vec<u8> {
+    FIXTURE_KEY.to_vec()
+}
+
+/// Encrypts `value` with the fixture key, behind the SHA256 of `host` when given
+/// like chromium does from `meta.version` 24 on
+pub fn encrypt_value(value: &str, host: Option<&str>) -> Result<Vec<u8>> {
+    use sha2::{ Digest, Sha256 };
+    let mut plaintext = host.map(|host| Sha256::digest(host.as_bytes()).to_vec()).unwrap_or_default();
+    plaintext.extend_from_slice(value.as_bytes());
+    encrypt_with(&fixture_key(), &plaintext)
+}
+
+/// v10 AES-128-CBC with the first 16 bytes of `key`
+#[cfg(unix)]
+fn encrypt_with(key: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+    use aes::cipher::{ block_padding::Pkcs7, BlockEncryptMut, KeyIvInit };
+
+    type Aes128CbcEnc = cbc::Encryptor<aes::Aes128>;
+
+    let mut key_array: [u8; 16] = [0; 16];
+    key_array.copy_from_slice(&key[..16]);
+    let iv: [u8; 16] = [b' '; 16];
+    let cipher = Aes128CbcEnc::new(&key_array.into(), &iv.into());
+    let mut buffer = plaintext.to_vec();
+    buffer.resize(plaintext.len() + 16, 0);
+    let ciphertext = cipher
+        .encrypt_padded_mut::<Pkcs7>(&mut buffer, plaintext.len())
+        .or(Err(anyhow::anyhow!("cant encrypt fixture value")))?;
+
+    let mut encrypted = b"v10".to_vec();
+    encrypted.extend_from_slice(ciphertext);
+    Ok(encrypted)
+}
+
+/// v11 AES-128-CBC value as written with a keyring `password` (libsecret/kwallet)
+#[cfg(unix)]
+pub fn encrypt_v11(password: &str, value: &str) -> Result<Vec<u8>> {
+    use crate::browser::chromium::{ create_pbkdf2_key, PBKDF2_ITERATIONS };
+    let key = create_pbkdf2_key(password, b"saltysalt", PBKDF2_ITERATIONS);
+    let mut encrypted = encrypt_with(&key, value.as_bytes())?;
+    encrypted[..3].copy_from_slice(b"v11");
+    Ok(encrypted)
+}
+
+/// v10 AES-256-GCM
+#[cfg(target_os = "windows")]
+fn encrypt_with(key: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+    use aes_gcm::{ Aes256Gcm, Key, aead::{ Aead, KeyInit, generic_array::GenericArray } };
+    use anyhow::anyhow;
+
+    let nonce: [u8; 12] = [0x24; 12];
+    let key = Key::<Aes256Gcm>::from_slice(key);
+    let cipher = Aes256Gcm::new(key);
+    let ciphertext = cipher
+        .encrypt(GenericArray::from_slice(&nonce), plaintext)
+        .or(Err(anyhow!("cant encrypt fixture value")))?;
+
+    let mut encrypted = b"v10".to_vec();
+    encrypted.extend(nonce);
+    encrypted.extend(ciphertext);
+    Ok(encrypted)
+}
@@ -0,0 +1,238 @@
+use crate::browser::{ chromium, mozilla };
+use crate::common::enums::{ BrowserConfig, Cookie, EnumerationOptions };
+use crate::common::paths;
+use crate::{ config, export };
+use anyhow::{ anyhow, bail, Result };
+use std::path::{ Path, PathBuf };
+use std::str::FromStr;
+
+/// Browser names accepted in a spec, yt-dlp's plus the other ones rookie reads
+pub const BROWSERS: &[&str] = &[
+    "brave",
+    "chrome",
+    "chromium",
+    "edge",
+    "firefox",
+    "librewolf",
+    "opera",
+    "opera_gx",
+    "safari",
+    "vivaldi",
+];
+
+/// Linux keyring named after `+` in a spec
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Keyring {
+    BasicText,
+    GnomeKeyring,
+    KWallet,
+    KWallet5,
+    KWallet6,
+}
+
+impl FromStr for Keyring {
+    type Err = anyhow::Error;
+
+    fn from_str(keyring: &str) -> Result<Self> {
+        match keyring.to_uppercase().as_str() {
+            "BASICTEXT" => Ok(Keyring::BasicText),
+            "GNOMEKEYRING" => Ok(Keyring::GnomeKeyring),
+            "KWALLET" => Ok(Keyring::KWallet),
+            "KWALLET5" => Ok(Keyring::KWallet5),
+            "KWALLET6" => Ok(Keyring::KWallet6),
+            _ =>
+                bail!(
+                    "unknown keyring {:?}, expected one of BASICTEXT, GNOMEKEYRING, KWALLET, KWALLET5, KWALLET6",
+                    keyring
+                ),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Keyring {
+    /// The backends asked for the password, kwalletd6 for `KWALLET6` as in yt-dlp
+    pub fn secrets(self) -> crate::common::secrets::LinuxSecrets {
+        use crate::common::secrets::LinuxSecrets;
+        match self {
+            Keyring::BasicText => LinuxSecrets::default(),
+            Keyring::GnomeKeyring => LinuxSecrets { libsecret: true, ..Default::default() },
+            Keyring::KWallet | Keyring::KWallet5 => LinuxSecrets { kwallet: true, ..Default::default() },
+            Keyring::KWallet6 => LinuxSecrets { kwallet6: true, ..Default::default() },
+        }
+    }
+}
+
+/// yt-dlp's `--cookies-from-browser` argument, `BROWSER[+KEYRING][:PROFILE][::CONTAINER]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrowserSpec {
+    pub browser: String,
+    pub keyring: Option<Keyring>,
+    /// Profile directory name or path
+    pub profile: Option<String>,
+    /// Firefox container name, `"none"` for the cookies outside of every container
+    pub container: Option<String>,
+}
+
+fn non_empty<'a>(part: &'a str, what: &str, spec: &str) -> Result<&'a str> {
+    let part = part.trim();
+    if part.is_empty() {
+        bail!("invalid browser spec {:?}: empty {}", spec, what);
+    }
+    Ok(part)
+}
+
+impl FromStr for BrowserSpec {
+    type Err = anyhow::Error;
+
+    fn from_str(spec: &str) -> Result<Self> {
+        let (rest, container) = match spec.split_once("::") {
+            Some((rest, container)) => (rest, Some(non_empty(container, "container", spec)?)),
+            None => (spec, None),
+        };
+        let (rest, profile) = match rest.split_once(':') {
+            Some((rest, profile)) => (rest, Some(non_empty(profile, "profile", spec)?)),
+            None => (rest, None),
+        };
+        let (browser, keyring) = match rest.split_once('+') {
+            Some((browser, keyring)) => (browser, Some(non_empty(keyring, "keyring", spec)?)),
+            None => (rest, None),
+        };
+
+        let browser = non_empty(browser, "browser name", spec)?.to_lowercase();
+        if !BROWSERS.contains(&browser.as_str()) {
+            bail!("unknown browser {:?}, expected one of {}", browser, BROWSERS.join(", "));
+        }
+        if container.is_some() && browser != "firefox" && browser != "librewolf" {
+            bail!("invalid browser spec {:?}: containers are only supported for firefox", spec);
+        }
+        Ok(BrowserSpec {
+            browser,
+            keyring: keyring.map(Keyring::from_str).transpose()?,
+            profile: profile.map(String::from),
+            container: container.map(String::from),
+        })
+    }
+}
+
+fn chromium_config(browser: &str) -> Option<&'static BrowserConfig<'static>> {
+    match browser {
+        "brave" => Some(&config::BRAVE_CONFIG),
+        "chrome" => Some(&config::CHROME_CONFIG),
+        "chromium" => Some(&config::CHROMIUM_CONFIG),
+        "edge" => Some(&config::EDGE_CONFIG),
+        "opera" => Some(&config::OPERA_CONFIG),
+        "opera_gx" => Some(&config::OPERA_GX_CONFIG),
+        "vivaldi" => Some(&config::VIVALDI_CONFIG),
+        _ => None,
+    }
+}
+
+/// Key and cookies DB of a chromium profile given as a directory path or name
+fn chromium_profile_paths(
+    browser: &str,
+    config: &BrowserConfig,
+    profile: &str
+) -> Result<(PathBuf, PathBuf)> {
+    let profile_path = Path::new(profile);
+    if profile_path.is_dir() {
+        let db_path = [profile_path.join("Network/Cookies"), profile_path.join("Cookies")]
+            .into_iter()
+            .find(|db_path| db_path.exists())
+            .ok_or(anyhow!("no cookies DB in {}", profile_path.display()))?;
+        let key_path = paths::find_key_path(&db_path).unwrap_or_default();
+        return Ok((key_path, db_path));
+    }
+    let options = EnumerationOptions { include_guest: true, ..Default::default() };
+    paths
+        ::find_chrome_based_paths_v2(config, &options)?
+        .into_iter()
+        .find(|(_, db_path)| {
+            paths::chrome_component(db_path).is_none() &&
+                paths::chrome_profile_dir(db_path).as_deref() == Some(profile)
+        })
+        .ok_or(anyhow!("cant find {} profile {}", browser, profile))
+}
+
+fn chromium_cookies(spec: &BrowserSpec, config: &BrowserConfig) -> Result<Vec<Cookie>> {
+    let (key_path, db_path) = match &spec.profile {
+        Some(profile) => chromium_profile_paths(&spec.browser, config, profile)?,
+        None => paths::find_chrome_based_paths(config)?,
+    };
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            chromium::chromium_based(key_path, db_path, None)
+        } else if #[cfg(target_os = "linux")] {
+            use crate::common::secrets::LinuxSecrets;
+            let _ = key_path;
+            let secrets = match spec.keyring {
+                None => LinuxSecrets { libsecret: true, kwallet: true, kwallet6: true },
+                Some(keyring) => keyring.secrets(),
+            };
+            chromium::chromium_based_with(config, db_path, None::<Vec<&str>>, &secrets)
+        } else {
+            // the keyring only applies on Linux, like in yt-dlp
+            let _ = key_path;
+            chromium::chromium_based(config, db_path, None)
+        }
+    }
+}
+
+fn mozilla_cookies(spec: &BrowserSpec, config: &BrowserConfig) -> Result<Vec<Cookie>> {
+    let db_path = match &spec.profile {
+        Some(profile) => paths::find_mozilla_profile_path(config, profile)?,
+        None => paths::find_mozilla_based_paths(config)?,
+    };
+    match &spec.container {
+        Some(container) => {
+            let profile_dir = db_path.parent().unwrap_or(Path::new(""));
+            let container = mozilla::find_container(profile_dir, container)?;
+            mozilla::firefox_based_in_container(db_path, None::<Vec<&str>>, container)
+        }
+        None => mozilla::firefox_based(db_path, None),
+    }
+}
+
+/// Reads the cookies selected by a parsed spec
+pub fn cookies(spec: &BrowserSpec) -> Result<Vec<Cookie>> {
+    match spec.browser.as_str() {
+        "firefox" => mozilla_cookies(spec, &config::FIREFOX_CONFIG),
+        "librewolf" => mozilla_cookies(spec, &config::LIBRE_WOLF_CONFIG),
+        "safari" => {
+            if spec.profile.is_some() {
+                bail!("profiles aren't supported for safari");
+            }
+            cfg_if::cfg_if! {
+                if #[cfg(target_os = "macos")] {
+                    crate::safari(None)
+                } else {
+                    bail!("safari is only supported on macOS")
+                }
+            }
+        }
+        browser => {
+            let config = chromium_config(browser).ok_or(anyhow!("unknown browser {}", browser))?;
+            chromium_cookies(spec, config)
+        }
+    }
+}
+
+/// Returns a Netscape cookies.txt for yt-dlp's `--cookies-from-browser` syntax
+///
+/// # Arguments
+///
+/// * `spec` - `BROWSER[+KEYRING][:PROFILE][::CONTAINER]`, e.g. `firefox:default::Personal`
+///
+/// # Examples
+///
+/// ```no_run
+/// fn main() {
+///     let cookies_txt = rookie::ytdlp::cookies_txt("chrome:Profile 2");
+/// }
+/// ```
+pub fn cookies_txt(spec: &str) -> Result<String> {
+    let spec: BrowserSpec = spec.parse()?;
+    let mut txt = vec![];
+    export::to_netscape(&cookies(&spec)?, &mut txt)?;
+    Ok(String::from_utf8(txt)?)
+}
@@ -0,0 +1,101 @@
+#![cfg(feature = "cookie")]
+
+use rookie::common::enums::{ Cookie, SameSite };
+use rookie::cookie::ConversionError;
+
+fn cookie(domain: &str, name: &str, value: &str) -> Cookie {
+    let mut cookie = Cookie::new(domain, name, value);
+    cookie.path = "/app".to_string();
+    cookie.secure = true;
+    cookie.expires = Some(4_102_444_800);
+    cookie.http_only = true;
+    cookie.same_site = SameSite::Lax;
+    cookie
+}
+
+#[test]
+fn cookie_crate_round_trip() {
+    // a quote and a space, which cookie::Cookie::parse would reject
+    let original = cookie(".example.com", "prefs", r#"{"a": 1}"#);
+    let converted = cookie::Cookie::try_from(&original).unwrap();
+    assert_eq!(converted.value(), r#"{"a": 1}"#);
+    assert_eq!(converted.domain(), Some("example.com"));
+    assert_eq!(converted.path(), Some("/app"));
+    assert_eq!((converted.secure(), converted.http_only()), (Some(true), Some(true)));
+    assert_eq!(converted.same_site(), Some(cookie::SameSite::Lax));
+    assert_eq!(converted.expires_datetime().unwrap().unix_timestamp(), 4_102_444_800);
+    let back = Cookie::try_from(&converted).unwrap();
+    assert_eq!(back.debug_full(), original.debug_full());
+
+    let mut session = cookie("example.com", "session", "s");
+    session.expires = None;
+    session.same_site = SameSite::Unspecified;
+    let converted = cookie::Cookie::try_from(&session).unwrap();
+    assert_eq!(converted.expires(), Some(cookie::Expiration::Session));
+    assert_eq!(converted.same_site(), None);
+    let back = Cookie::try_from(&converted).unwrap();
+    // the cookie crate has no host-only flag
+    assert_eq!((back.domain.as_str(), back.host_only), (".example.com", false));
+    assert_eq!((back.expires, back.same_site), (None, SameSite::Unspecified));
+}
+
+#[test]
+fn cookie_crate_conversion_errors() {
+    let mut far = cookie(".example.com", "far", "v");
+    far.expires = Some(u64::MAX);
+    let err: ConversionError = cookie::Cookie::try_from(&far).unwrap_err();
+    assert_eq!((err.name.as_str(), err.domain.as_str()), ("far", ".example.com"));
+    assert!(err.to_string().contains("out of range"), "{}", err);
+
+    let err = Cookie::try_from(&cookie::Cookie::new("orphan", "v")).unwrap_err();
+    assert_eq!(err.to_string(), r#"Can't convert cookie "orphan" of "": it has no domain"#);
+}
+
+#[test]
+fn cookies_compare_by_identity() {
+    let mut chrome = cookie(".example.com", "session", "from chrome");
+    chrome.browser = "chrome".to_string();
+    chrome.expires = Some(1_800_000_000);
+    let mut firefox = cookie(".example.com", "session", "from firefox");
+    firefox.browser = "firefox".to_string();
+    firefox.secure = false;
+    firefox.creation = Some(1_700_000_000);
+    assert_eq!(chrome, firefox);
+    assert_eq!(chrome.cmp(&firefox), std::cmp::Ordering::Equal);
+
+    let mut partitioned = cookie(".example.com", "session", "embedded");
+    partitioned.partition_key = Some("https://other.test".to_string());
+    assert_ne!(chrome, partitioned);
+
+    let set: std::collections::HashSet<_> = [chrome, firefox, partitioned].into_iter().collect();
+    assert_eq!(set.len(), 2);
+
+    let mut sorted = vec![
+        cookie("b.com", "a", "1"),
+        cookie("a.com", "z", "2"),
+        cookie("a.com", "a", "3"),
+        cookie("b.com", "a", "4"),
+        cookie("a.com", "a", "5")
+    ];
+    sorted[0].path = "/b".to_string();
+    sorted.sort();
+    sorted.dedup();
+    let values: Vec<&str> = sorted
+        .iter()
+        .map(|cookie| cookie.value.as_str())
+        .collect();
+    // the sort is stable and dedup keeps the first of equal cookies
+    assert_eq!(values, ["3", "2", "4", "1"]);
+}
+
+#[test]
+fn debug_output_hides_the_value() {
+    let cookie = Cookie::new(".example.com", "session", "s3cr3t-t0k3n");
+    let debug = format!("{:?}", cookie);
+    assert!(!debug.contains("s3cr3t"), "{}", debug);
+    assert!(debug.contains(r#"name: "session", value: <redacted, 12 bytes>, http_only: false"#), "{}", debug);
+    assert!(debug.contains(r#"domain: ".example.com""#));
+
+    assert_eq!(cookie.value(), "s3cr3t-t0k3n");
+    assert_eq!(cookie.debug_full(), debug.replace("<redacted, 12 bytes>", r#""s3cr3t-t0k3n""#));
+}
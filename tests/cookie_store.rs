@@ -0,0 +1,55 @@
+#![cfg(feature = "cookie_store")]
+
+use rookie::common::enums::Cookie;
+use url::Url;
+
+fn cookie(domain: &str, name: &str) -> Cookie {
+    let mut cookie = Cookie::new(domain, name, "v");
+    cookie.expires = Some(4_102_444_800);
+    cookie
+}
+
+fn sent(store: &cookie_store::CookieStore, url: &str) -> Vec<String> {
+    let mut names: Vec<String> = store
+        .get_request_values(&Url::parse(url).unwrap())
+        .map(|(name, _)| name.to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn cookie_store_keeps_the_cookie_scope() {
+    let mut secure = cookie(".example.com", "secure");
+    secure.secure = true;
+    let mut api = cookie("example.com", "api");
+    api.path = "/api".to_string();
+    let mut expired = cookie(".example.com", "expired");
+    expired.expires = Some(1_000_000_000);
+    let cookies = vec![cookie("example.com", "host"), cookie(".example.com", "domain"), secure, api, expired];
+
+    let outcome = rookie::to_cookie_store(&cookies);
+    assert_eq!(sent(&outcome.store, "http://example.com/"), ["domain", "host"]);
+    assert_eq!(sent(&outcome.store, "https://example.com/api/v1"), ["api", "domain", "host", "secure"]);
+    assert_eq!(sent(&outcome.store, "https://www.example.com/"), ["domain", "secure"]);
+    let skipped: Vec<String> = outcome.skipped
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(skipped, [r#"Can't convert cookie "expired" of ".example.com": it expired"#]);
+}
+
+#[test]
+fn public_suffix_rejections_are_reported() {
+    let list: publicsuffix::List = "// ===BEGIN ICANN DOMAINS===\ncom\nco.uk\n// ===END ICANN DOMAINS===\n".parse().unwrap();
+    let mut store = cookie_store::CookieStore::new(Some(list));
+    let cookies = vec![cookie(".com", "tracker"), cookie(".co.uk", "uk"), cookie(".example.com", "site")];
+    let skipped = rookie::cookie_store::insert_cookies(&mut store, &cookies);
+    let names: Vec<&str> = skipped
+        .iter()
+        .map(|skipped| skipped.name.as_str())
+        .collect();
+    assert_eq!(names, ["tracker", "uk"]);
+    assert!(skipped.iter().all(|skipped| skipped.reason == "its domain is a public suffix"));
+    assert_eq!(sent(&store, "https://www.example.com/"), ["site"]);
+}
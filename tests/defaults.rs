@@ -0,0 +1,54 @@
+#![cfg(unix)]
+
+use rookie::testing::{ self, FixtureCookie };
+use rookie::{ GlobalDefaults, LoadOptions, ReadOptions };
+
+// a single test since the globals and HOME are shared by the whole process
+#[test]
+fn global_defaults_precedence() {
+    let home = tempfile::tempdir().unwrap();
+    std::env::set_var("HOME", home.path());
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "macos")] {
+            let firefox_dir = home.path().join("Library/Application Support/Firefox");
+        } else {
+            let firefox_dir = home.path().join(".mozilla/firefox");
+        }
+    }
+    let mut old = FixtureCookie::new("example.com", "old", "v");
+    old.expires = Some(1_000_000_000);
+    let fixture_cookies = vec![FixtureCookie::new("example.com", "live", "v"), old];
+    let db_path = testing::write_mozilla_profile(&firefox_dir, &fixture_cookies).unwrap();
+
+    let names = |cookies: Vec<rookie::common::enums::Cookie>| {
+        let mut names: Vec<String> = cookies.into_iter().map(|cookie| cookie.name).collect();
+        names.sort();
+        names
+    };
+    let domains = Some(vec!["example.com"]);
+
+    // built-in
+    assert_eq!(names(rookie::load(domains.clone()).unwrap()), ["live", "old"]);
+    assert_eq!(names(rookie::firefox(domains.clone()).unwrap()), ["live", "old"]);
+
+    // global
+    let mut defaults = GlobalDefaults::default();
+    defaults.read.include_expired = false;
+    defaults.load.include_expired = false;
+    rookie::set_global_defaults(defaults);
+    assert_eq!(names(rookie::load(domains.clone()).unwrap()), ["live"]);
+    assert_eq!(names(rookie::firefox(domains.clone()).unwrap()), ["live"]);
+    assert_eq!(names(rookie::browser::mozilla::firefox_based(db_path.clone(), domains.clone()).unwrap()), ["live"]);
+
+    // per-call wins over global
+    let outcome = rookie::load_with(domains.clone(), &LoadOptions::default()).unwrap();
+    assert_eq!(names(outcome.cookies), ["live", "old"]);
+    let read = rookie::browser::mozilla
+        ::firefox_read(db_path, domains.clone(), ReadOptions::default())
+        .unwrap();
+    assert_eq!(names(read.cookies), ["live", "old"]);
+
+    rookie::reset_global_defaults();
+    assert_eq!(names(rookie::load(domains).unwrap()), ["live", "old"]);
+    assert!(rookie::defaults::global_defaults().load.include_expired);
+}
@@ -0,0 +1,170 @@
+use rookie::common::enums::BrowserConfig;
+use rookie::dry_run::{ self, Stage, StageStatus };
+use rookie::testing::{ self, ChromiumSchema };
+
+#[cfg(unix)]
+use rookie::common::secrets::SecretsProvider;
+
+fn fixture_config<'a>(data_paths: &'a [&'a str]) -> BrowserConfig<'a> {
+    BrowserConfig {
+        data_paths,
+        channels: None,
+        os_crypt_name: Some("chromium"),
+        osx_key_service: None,
+        osx_key_user: None,
+    }
+}
+
+fn statuses(run: &dry_run::BrowserDryRun) -> Vec<(Stage, &str)> {
+    run.stages
+        .iter()
+        .map(|(stage, status)| {
+            let status = match status {
+                StageStatus::Passed => "passed",
+                StageStatus::Failed(_) => "failed",
+                StageStatus::NotVerified(_) => "not verified",
+                StageStatus::Skipped => "skipped",
+            };
+            (*stage, status)
+        })
+        .collect()
+}
+
+#[cfg(unix)]
+fn check_chromium(config: &BrowserConfig) -> dry_run::BrowserDryRun {
+    dry_run::check_chromium_with_secrets("fixture", config, &testing::CountingSecrets::default())
+}
+
+#[cfg(target_os = "windows")]
+fn check_chromium(config: &BrowserConfig) -> dry_run::BrowserDryRun {
+    dry_run::check_chromium("fixture", config)
+}
+
+#[test]
+fn chromium_stages_pass_without_values() {
+    let dir = tempfile::tempdir().unwrap();
+    let cookies = testing::sample_cookies();
+    testing::write_chromium_profile(dir.path(), ChromiumSchema::V21, &cookies).unwrap();
+    let data_paths = testing::chromium_data_paths(dir.path());
+    let data_paths: Vec<&str> = data_paths.iter().map(|p| p.as_str()).collect();
+
+    let run = check_chromium(&fixture_config(&data_paths));
+    assert_eq!(statuses(&run), [
+        (Stage::Paths, "passed"),
+        (Stage::Key, "passed"),
+        (Stage::Database, "passed"),
+        (Stage::Decrypt, "passed"),
+    ]);
+    assert_eq!(run.rows, Some(cookies.len()));
+    assert_eq!(run.version, None);
+
+    std::fs::write(dir.path().join("User Data/Last Version"), "127.0.6533.73").unwrap();
+    let run = check_chromium(&fixture_config(&data_paths));
+    assert_eq!(run.version.map(|version| version.major), Some(127));
+
+    let report = dry_run::DryRunReport { browsers: vec![run] };
+    assert!(report.passed());
+    let printed = report.to_string();
+    assert!(printed.starts_with("fixture 127.0.6533.73 (LastVersionFile)\n"), "{}", printed);
+    assert!(printed.contains(&format!("database: ok ({} rows, schema 21)\n", cookies.len())), "{}", printed);
+    for cookie in &cookies {
+        assert!(cookie.value.is_empty() || !printed.contains(&cookie.value), "{}", printed);
+    }
+}
+
+#[test]
+fn chromium_wrong_key_fails_decrypt() {
+    let dir = tempfile::tempdir().unwrap();
+    let fixture = testing
+        ::write_chromium_profile(dir.path(), ChromiumSchema::V21, &testing::sample_cookies())
+        .unwrap();
+    let connection = rusqlite::Connection::open(&fixture.db_path).unwrap();
+    let garbage = [b"v10".to_vec(), vec![0x41; 44]].concat();
+    connection.execute("UPDATE cookies SET encrypted_value = ?1", [garbage]).unwrap();
+    drop(connection);
+
+    let data_paths = testing::chromium_data_paths(dir.path());
+    let data_paths: Vec<&str> = data_paths.iter().map(|p| p.as_str()).collect();
+    let run = check_chromium(&fixture_config(&data_paths));
+    assert_eq!(run.stages[3].0, Stage::Decrypt);
+    assert!(matches!(run.stages[3].1, StageStatus::Failed(_)));
+    assert!(!run.passed());
+}
+
+/// A keystore without a password for the browser
+#[cfg(unix)]
+#[derive(Debug)]
+struct NoPasswords;
+
+#[cfg(unix)]
+impl SecretsProvider for NoPasswords {
+    fn passwords(&self, _config: &BrowserConfig) -> anyhow::Result<Vec<String>> {
+        Ok(vec![])
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn fallback_key_is_not_verified() {
+    let dir = tempfile::tempdir().unwrap();
+    testing::write_chromium_profile(dir.path(), ChromiumSchema::V21, &testing::sample_cookies()).unwrap();
+    let data_paths = testing::chromium_data_paths(dir.path());
+    let data_paths: Vec<&str> = data_paths.iter().map(|p| p.as_str()).collect();
+
+    let run = dry_run::check_chromium_with_secrets("fixture", &fixture_config(&data_paths), &NoPasswords);
+    assert_eq!(statuses(&run), [
+        (Stage::Paths, "passed"),
+        (Stage::Key, "not verified"),
+        (Stage::Database, "passed"),
+        (Stage::Decrypt, "passed"),
+    ]);
+    assert!(run.passed());
+    let report = dry_run::DryRunReport { browsers: vec![run] };
+    assert!(report.to_string().contains("  key: not verified, "), "{}", report);
+}
+
+#[test]
+fn missing_browser_skips_later_stages() {
+    let dir = tempfile::tempdir().unwrap();
+    let data_paths = testing::chromium_data_paths(dir.path());
+    let data_paths: Vec<&str> = data_paths.iter().map(|p| p.as_str()).collect();
+    let run = check_chromium(&fixture_config(&data_paths));
+    assert_eq!(statuses(&run), [
+        (Stage::Paths, "failed"),
+        (Stage::Key, "skipped"),
+        (Stage::Database, "skipped"),
+        (Stage::Decrypt, "skipped"),
+    ]);
+    assert!(!run.found());
+
+    // a missing browser fails on its own, next to a found one it's only listed
+    let report = dry_run::DryRunReport { browsers: vec![run] };
+    assert!(!report.passed());
+    let mut browsers = report.browsers;
+    testing::write_chromium_profile(dir.path(), ChromiumSchema::V21, &testing::sample_cookies()).unwrap();
+    browsers.push(check_chromium(&fixture_config(&data_paths)));
+    let report = dry_run::DryRunReport { browsers };
+    assert!(report.passed());
+    assert!(report.to_string().contains("  paths: failed, "), "{}", report);
+}
+
+#[test]
+fn mozilla_has_no_key_stages() {
+    let dir = tempfile::tempdir().unwrap();
+    testing::write_mozilla_profile(dir.path(), &testing::sample_cookies()).unwrap();
+    let data_path = dir.path().to_string_lossy();
+    let data_paths = [data_path.as_ref()];
+    let run = dry_run::check_mozilla("fixture", &fixture_config(&data_paths));
+    assert_eq!(statuses(&run), [
+        (Stage::Paths, "passed"),
+        (Stage::Key, "skipped"),
+        (Stage::Database, "passed"),
+        (Stage::Decrypt, "skipped"),
+    ]);
+    assert_eq!(run.rows, Some(testing::sample_cookies().len()));
+}
+
+#[test]
+fn unknown_browser() {
+    assert!(rookie::dry_run("netscape").is_err());
+}
@@ -0,0 +1,80 @@
+//! Runs the programs under `examples/` against a generated Firefox profile and
+//! compares what they print with `tests/golden/examples`. Firefox needs no key,
+//! so the output is the same on every OS and no keyring or keychain is asked.
+
+use std::io::Write;
+use std::path::Path;
+
+#[path = "../examples/dump_all.rs"]
+#[allow(dead_code)]
+mod dump_all;
+#[path = "../examples/export_netscape.rs"]
+#[allow(dead_code)]
+mod export_netscape;
+#[path = "../examples/session_header.rs"]
+#[allow(dead_code)]
+mod session_header;
+#[path = "../examples/watch.rs"]
+#[allow(dead_code)]
+mod watch;
+
+type Example = fn(&[String], &mut dyn Write) -> anyhow::Result<()>;
+
+fn run(example: Example, fixtures: &Path, args: &[&str]) -> String {
+    let mut all_args = vec!["--fixtures".to_string(), fixtures.to_string_lossy().to_string()];
+    all_args.extend(args.iter().map(|arg| arg.to_string()));
+    let mut out = vec![];
+    example(&all_args, &mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+fn golden(name: &str, actual: &str) {
+    let path = format!("{}/tests/golden/examples/{}", env!("CARGO_MANIFEST_DIR"), name);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, actual).unwrap();
+    }
+    let expected = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(actual, expected.replace("\r\n", "\n"), "{} is out of date", name);
+}
+
+fn fixtures() -> tempfile::TempDir {
+    let root = tempfile::tempdir().unwrap();
+    rookie::testing::write_firefox_home(root.path(), &rookie::testing::sample_cookies()).unwrap();
+    root
+}
+
+#[test]
+fn dump_all() {
+    let root = fixtures();
+    golden("dump_all.txt", &run(dump_all::run, root.path(), &[]));
+    golden("dump_all-example.com.txt", &run(dump_all::run, root.path(), &["example.com"]));
+}
+
+#[test]
+fn session_header() {
+    let root = fixtures();
+    golden("session_header.txt", &run(session_header::run, root.path(), &["http://api.example.com/api/v1"]));
+    assert_eq!(run(session_header::run, root.path(), &["https://example.net/"]), "no cookies for https://example.net/\n");
+}
+
+#[test]
+fn export_netscape() {
+    let root = fixtures();
+    golden("export_netscape.txt", &run(export_netscape::run, root.path(), &[]));
+}
+
+#[test]
+fn watch() {
+    let root = fixtures();
+    let first_round = run(watch::run, root.path(), &["--rounds", "1"]);
+    golden("watch.txt", &first_round);
+    // nothing changed between the rounds
+    let two_rounds = run(watch::run, root.path(), &["--rounds", "2", "--interval", "0"]);
+    assert_eq!(two_rounds, first_round);
+}
+
+#[test]
+fn fixtures_flag_needs_a_directory() {
+    let err = dump_all::run(&["--fixtures".to_string()], &mut vec![]).unwrap_err();
+    assert_eq!(err.to_string(), "--fixtures needs a directory");
+}
@@ -0,0 +1,263 @@
+use rookie::common::enums::{ Cookie, CookieFilter, CookieToString, MinimalCookie, SameSite, SourceScheme };
+use rookie::export;
+
+fn cookie(domain: &str, name: &str, value: &str) -> Cookie {
+    let mut cookie = Cookie::new(domain, name, value);
+    cookie.expires = Some(4_102_444_800);
+    cookie
+}
+
+fn cookies() -> Vec<Cookie> {
+    let mut session = cookie("example.com", "session", "s3ss10n");
+    session.http_only = true;
+    session.expires = None;
+
+    let mut prefs = cookie(".example.com", "prefs", r#"{"theme":"dark"}"#);
+    prefs.secure = true;
+    prefs.same_site = SameSite::None;
+
+    let mut tricky = cookie("www.example.com", "list", r"a;b\c");
+    tricky.path = "/app".to_string();
+    tricky.same_site = SameSite::Lax;
+    tricky.expires = Some(1_700_000_000);
+
+    vec![session, prefs, tricky, cookie("other.org", "id", "n0t")]
+}
+
+fn golden(name: &str, actual: &str) {
+    let path = format!("{}/tests/golden/{}", env!("CARGO_MANIFEST_DIR"), name);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, actual).unwrap();
+    }
+    let expected = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(actual, expected.replace("\r\n", "\n"), "{} is out of date", name);
+}
+
+#[test]
+fn js_setter_script() {
+    let export = export::to_js(&cookies(), "https://www.example.com/app").unwrap();
+    assert_eq!(export.skipped_http_only, 1);
+    assert_eq!(export.skipped_other_domain, 1);
+    golden("cookies.js", &export.script);
+}
+
+#[test]
+fn cookie_editor_json() {
+    golden("cookie-editor.json", &export::to_cookie_editor_json(&cookies()).unwrap());
+}
+
+#[test]
+fn json_and_ndjson() {
+    let json: serde_json::Value = serde_json::from_str(&export::to_json(&cookies()).unwrap()).unwrap();
+    assert_eq!(json[1]["expires"], 4_102_444_800u64);
+    assert_eq!(json[1]["expiresIso"], "2100-01-01T00:00:00.000Z");
+    assert!(json[0].get("expiresIso").is_none());
+    let read: Vec<Cookie> = serde_json::from_value(json).unwrap();
+    let full = |cookies: &[Cookie]| cookies.iter().map(Cookie::debug_full).collect::<Vec<_>>();
+    assert_eq!(full(&read), full(&cookies()));
+
+    let mut ndjson = vec![];
+    export::to_ndjson(cookies().iter(), &mut ndjson).unwrap();
+    let ndjson = String::from_utf8(ndjson).unwrap();
+    let lines: Vec<serde_json::Value> = ndjson
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let json: serde_json::Value = serde_json::from_str(&export::to_json(&cookies()).unwrap()).unwrap();
+    assert_eq!(serde_json::Value::from(lines), json);
+}
+
+#[test]
+fn har_cookies() {
+    let har = export::to_har(&cookies());
+    golden("cookies.har.json", &serde_json::to_string_pretty(&har).unwrap());
+    let session = &har[0];
+    assert!(session.get("expires").is_none() && session.get("sameSite").is_none());
+    assert_eq!(har[2]["expires"], "2023-11-14T22:13:20.000Z");
+    // as stored, no percent-encoding
+    assert_eq!(har[2]["value"], r"a;b\c");
+}
+
+#[test]
+fn netscape_cookies_txt() {
+    let mut written = vec![];
+    export::to_netscape(&cookies(), &mut written).unwrap();
+    golden("cookies.txt", &String::from_utf8(written).unwrap());
+}
+
+#[test]
+fn netscape_skips_tabs_and_line_breaks() {
+    let mut cookies = cookies();
+    cookies.push(cookie("example.com", "tab", "a\tb"));
+    cookies.push(cookie("example.com", "line\nbreak", "v"));
+    cookies.push(cookie("example.com", "return", "a\r\nSet-Cookie: x=y"));
+    let mut text = vec![];
+    export::to_netscape(&cookies, &mut text).unwrap();
+    let text = String::from_utf8(text).unwrap();
+    assert_eq!(text.lines().count(), 5);
+    assert!(text.lines().skip(1).all(|line| line.split('\t').count() == 7));
+}
+
+#[test]
+fn minimal_projection_ignores_newer_fields() {
+    let mut cookies = cookies();
+    let header = cookies.to_string();
+    for cookie in &mut cookies {
+        cookie.same_site = SameSite::Strict;
+    }
+    let minimal: Vec<MinimalCookie> = cookies.iter().map(|cookie| cookie.to_minimal()).collect();
+    assert_eq!(minimal.to_string(), header);
+    assert_eq!(minimal[0], MinimalCookie::from(&cookies[0]));
+
+    let json = serde_json::to_string(&minimal[0]).unwrap();
+    assert_eq!(serde_json::from_str::<MinimalCookie>(&json).unwrap(), minimal[0]);
+}
+
+fn many_cookies(count: usize) -> impl Iterator<Item = Cookie> {
+    (0..count).map(|i| cookie(&format!("d{:03}.example.com", i % 40), &format!("c{}", i), "v"))
+}
+
+#[test]
+fn chunked_manifest_matches_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let manifest = export::write_netscape_chunked(many_cookies(103), dir.path(), 25).unwrap();
+    assert_eq!(manifest.cookies, 103);
+    let files: Vec<&str> = manifest.chunks.iter().map(|chunk| chunk.file.as_str()).collect();
+    assert_eq!(
+        files,
+        ["cookies-0001.txt", "cookies-0002.txt", "cookies-0003.txt", "cookies-0004.txt", "cookies-0005.txt"]
+    );
+
+    let written: export::ChunkManifest = serde_json
+        ::from_str(&std::fs::read_to_string(dir.path().join("manifest.json")).unwrap())
+        .unwrap();
+    assert_eq!(written, manifest);
+
+    let mut names = vec![];
+    for chunk in &manifest.chunks {
+        let text = std::fs::read_to_string(dir.path().join(&chunk.file)).unwrap();
+        let records: Vec<Vec<&str>> = text
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .map(|line| line.split('\t').collect())
+            .collect();
+        assert_eq!(records.len(), chunk.cookies);
+        // every line is a whole cookie
+        assert!(records.iter().all(|record| record.len() == 7));
+        let domains = records.iter().map(|record| record[0]);
+        assert_eq!(domains.clone().min().unwrap(), chunk.first_domain);
+        assert_eq!(domains.max().unwrap(), chunk.last_domain);
+        names.extend(records.iter().map(|record| record[5].to_string()));
+    }
+    let expected: Vec<String> = (0..103).map(|i| format!("c{}", i)).collect();
+    assert_eq!(names, expected);
+    assert_eq!(manifest.chunks[4].cookies, 3);
+}
+
+#[test]
+fn chunked_jsonl() {
+    let dir = tempfile::tempdir().unwrap();
+    let manifest = export::write_jsonl_chunked(many_cookies(10), dir.path(), 4).unwrap();
+    assert_eq!(manifest.chunks.iter().map(|chunk| chunk.cookies).collect::<Vec<_>>(), [4, 4, 2]);
+    for chunk in &manifest.chunks {
+        assert!(chunk.file.ends_with(".jsonl"));
+        let text = std::fs::read_to_string(dir.path().join(&chunk.file)).unwrap();
+        let cookies: Vec<Cookie> = text
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(cookies.len(), chunk.cookies);
+        assert_eq!(
+            cookies.iter().map(|cookie| cookie.domain.as_str()).min().unwrap(),
+            chunk.first_domain
+        );
+    }
+
+    let empty = tempfile::tempdir().unwrap();
+    let manifest = export::write_jsonl_chunked(many_cookies(0), empty.path(), 4).unwrap();
+    assert!(manifest.chunks.is_empty());
+    assert!(empty.path().join("manifest.json").exists());
+    assert!(export::write_jsonl_chunked(many_cookies(1), empty.path(), 0).is_err());
+}
+
+#[test]
+fn cookie_json_shape_round_trips() {
+    let mut cookie = cookie(".example.com", "session", "s3ss10n");
+    cookie.port = Some(8443);
+    cookie.source_scheme = SourceScheme::Secure;
+    cookie.partition_key = Some("https://top.example".to_string());
+    cookie.browser = "chrome".to_string();
+    cookie.profile = Some("Profile 1".to_string());
+    let json = serde_json::to_value(&cookie).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "domain": ".example.com",
+            "hostOnly": false,
+            "path": "/",
+            "secure": false,
+            "expires": 4_102_444_800_u64,
+            "partitionKey": "https://top.example",
+            "name": "session",
+            "value": "s3ss10n",
+            "httpOnly": false,
+            "sameSite": -1,
+            "port": 8443,
+            "sourceScheme": 2,
+            "browser": "chrome",
+            "profile": "Profile 1",
+        })
+    );
+
+    let parsed: Cookie = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(parsed.expires, Some(4_102_444_800));
+    assert_eq!(parsed.debug_full(), cookie.debug_full());
+
+    // the JSON export writes the same fields without the feature
+    let mut exported: serde_json::Value = serde_json::from_str(&export::to_json(&[cookie]).unwrap()).unwrap();
+    exported[0].as_object_mut().unwrap().remove("expiresIso");
+    assert_eq!(exported[0], json);
+
+    // the optional fields are left out when unset, older snake_case JSON still parses
+    let minimal: Cookie = serde_json::from_str(
+        r#"{"domain":"a.com","path":"/","secure":true,"expires":null,"name":"n","value":"v","http_only":true,"same_site":1}"#
+    ).unwrap();
+    assert!(minimal.http_only && minimal.same_site == SameSite::Lax);
+    assert!(minimal.browser.is_empty() && minimal.profile.is_none() && minimal.port.is_none());
+}
+
+#[test]
+fn cookie_filter_patterns() {
+    use regex::Regex;
+    let cookies = || {
+        vec![
+            cookie("git.internal.corp", "session", "1"),
+            cookie(".wiki.internal.corp", "_ga", "2"),
+            cookie("git.staging.internal.corp", "session", "3"),
+            cookie("internal.corp.example.com", "session", "4")
+        ]
+    };
+    let values = |cookies: Vec<Cookie>| {
+        cookies
+            .into_iter()
+            .map(|cookie| cookie.value)
+            .collect::<Vec<_>>()
+    };
+    let internal = CookieFilter {
+        domain: Some(Regex::new(r"\.internal\.corp$").unwrap()),
+        exclude_domain: Some(Regex::new(r"(^|\.)staging\.").unwrap()),
+        ..Default::default()
+    };
+    assert_eq!(values(internal.apply(cookies())), ["1", "2"]);
+    let analytics = CookieFilter { name: Some(Regex::new("^_ga").unwrap()), ..internal };
+    assert_eq!(values(analytics.apply(cookies())), ["2"]);
+    assert_eq!(values(CookieFilter::default().apply(cookies())).len(), 4);
+}
+
+#[test]
+fn domain_unicode_decodes_punycode() {
+    assert_eq!(cookie(".xn--bcher-kva.de", "a", "1").domain_unicode(), ".bücher.de");
+    assert_eq!(cookie("shop.xn--bcher-kva.de", "a", "1").domain_unicode(), "shop.bücher.de");
+    assert_eq!(cookie("example.com", "a", "1").domain_unicode(), "example.com");
+    assert_eq!(cookie("127.0.0.1", "a", "1").domain_unicode(), "127.0.0.1");
+}
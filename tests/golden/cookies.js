@@ -0,0 +1,3 @@
+// https://www.example.com/app
+document.cookie = "prefs={\"theme\":\"dark\"}; domain=.example.com; path=/; expires=Fri, 01 Jan 2100 00:00:00 GMT; secure; samesite=none";
+document.cookie = "list=a%3Bb\\c; path=/app; expires=Tue, 14 Nov 2023 22:13:20 GMT; samesite=lax";
@@ -0,0 +1,117 @@
+use rookie::common::enums::Cookie;
+use rookie::{ export, import };
+
+fn golden(name: &str) -> String {
+    let path = format!("{}/tests/golden/{}", env!("CARGO_MANIFEST_DIR"), name);
+    std::fs::read_to_string(path).unwrap().replace("\r\n", "\n")
+}
+
+fn to_netscape(cookies: &[Cookie]) -> String {
+    let mut text = vec![];
+    export::to_netscape(cookies, &mut text).unwrap();
+    String::from_utf8(text).unwrap()
+}
+
+#[test]
+fn netscape_round_trip() {
+    let text = golden("cookies.txt");
+    let cookies = import::from_netscape(text.as_bytes()).unwrap();
+    assert_eq!(cookies.len(), text.lines().filter(|line| line.contains('\t')).count());
+    let records = |text: &str| {
+        let mut lines: Vec<String> = text
+            .lines()
+            .filter(|line| line.contains('\t'))
+            .map(String::from)
+            .collect();
+        lines.sort();
+        lines
+    };
+    assert_eq!(records(&to_netscape(&cookies)), records(&text));
+
+    let session = &cookies[0];
+    assert!(session.http_only && session.host_only && session.expires.is_none());
+    assert_eq!(
+        rookie::to_cookie_header(&cookies, "https://example.com/").unwrap(),
+        "session=s3ss10n; prefs={\"theme\":\"dark\"}"
+    );
+}
+
+/// Whether yt-dlp's cookie jar loads a record line: 7 tab separated fields after
+/// the `#HttpOnly_` prefix, `TRUE`/`FALSE` flags and a numeric or empty expiry
+fn yt_dlp_accepts(line: &str) -> bool {
+    let fields: Vec<&str> = line.trim_start_matches("#HttpOnly_").split('\t').collect();
+    let flag = |field: &str| field == "TRUE" || field == "FALSE";
+    fields.len() == 7 &&
+        flag(fields[1]) &&
+        flag(fields[3]) &&
+        fields[4].chars().all(|c| c.is_ascii_digit())
+}
+
+#[test]
+fn yt_dlp_cookies_txt_round_trip() {
+    // laid out the way yt-dlp saves its cookie jar, session cookies have an empty expiry
+    let text = golden("yt-dlp-cookies.txt");
+    let cookies = import::from_netscape(text.as_bytes()).unwrap();
+    let names: Vec<&str> = cookies.iter().map(|cookie| cookie.name.as_str()).collect();
+    assert_eq!(names, ["PREF", "YSC", "__Secure-3PSID", ""]);
+    assert!(cookies[1].expires.is_none());
+    assert!(cookies[2].http_only && cookies[2].secure && !cookies[2].host_only);
+    assert_eq!((cookies[3].value.as_str(), cookies[3].host_only), ("nameless", true));
+
+    let written = to_netscape(&cookies);
+    let records: Vec<&str> = written
+        .lines()
+        .filter(|line| !line.is_empty() && (!line.starts_with('#') || line.starts_with("#HttpOnly_")))
+        .collect();
+    assert_eq!(records.len(), cookies.len());
+    for line in &records {
+        assert!(yt_dlp_accepts(line), "{:?}", line);
+    }
+    // the only difference is the 0 expiry of session cookies, which yt-dlp reads as a session too
+    let expected: Vec<String> = text
+        .lines()
+        .filter(|line| line.contains('\t'))
+        .map(|line| {
+            let mut fields: Vec<&str> = line.split('\t').collect();
+            if fields[4].is_empty() {
+                fields[4] = "0";
+            }
+            fields.join("\t")
+        })
+        .collect();
+    assert_eq!(records, expected);
+
+    let again = import::from_netscape(written.as_bytes()).unwrap();
+    let full = |cookies: &[Cookie]| cookies.iter().map(Cookie::debug_full).collect::<Vec<_>>();
+    assert_eq!(full(&again), full(&cookies));
+}
+
+#[test]
+fn netscape_lenient_lines() {
+    let text = [
+        "# Netscape HTTP Cookie File",
+        "",
+        "example.com\ttrue\t/\tfalse\t4102444800\tdomain\tv\r",
+        "#HttpOnly_.example.com\tTrue\t/\tTRUE\t0\tsecret\ttab\tin value",
+        "example.com\tFALSE\t/\tFALSE\t0\tempty",
+        "example.com\tmaybe\t/\tFALSE\t0\tbad_flag\tv",
+        "example.com\tFALSE\t/\tFALSE\tsoon\tbad_expiry\tv",
+        "example.com\tFALSE\t/",
+        "\tFALSE\t/\tFALSE\t0\tno_domain\tv",
+    ].join("\n");
+    let mut bytes = text.into_bytes();
+    bytes.extend(b"\nexample.com\tFALSE\t/\tFALSE\t0\tlatin1\t\xe9\n");
+    let cookies = import::from_netscape(bytes.as_slice()).unwrap();
+
+    let names: Vec<&str> = cookies
+        .iter()
+        .map(|cookie| cookie.name.as_str())
+        .collect();
+    assert_eq!(names, ["domain", "secret", "empty"]);
+    // TRUE include subdomains means a domain cookie, stored with the leading dot
+    assert_eq!((cookies[0].domain.as_str(), cookies[0].host_only), (".example.com", false));
+    assert_eq!(cookies[0].value, "v");
+    assert!(cookies[1].http_only && cookies[1].secure);
+    assert_eq!(cookies[1].value, "tab\tin value");
+    assert_eq!(cookies[2].value, "");
+}
@@ -0,0 +1,857 @@
+use rookie::browser::mozilla;
+use rookie::common::enums::{ BrowserConfig, EnumerationOptions };
+use rookie::common::paths;
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn paths_at_filesystem_root() {
+    assert_eq!(paths::find_key_path(Path::new("/")), None);
+    assert_eq!(paths::chrome_profile_dir(Path::new("/")), None);
+    assert!(rookie::any_browser("/", None, Some("/Local State")).is_err());
+}
+
+#[test]
+fn empty_profile_dir() {
+    let dir = tempfile::tempdir().unwrap();
+    let profile = dir.path().join("User Data/Default");
+    fs::create_dir_all(&profile).unwrap();
+
+    let data_path = format!("{}/Network/Cookies", profile.display());
+    let data_paths = [data_path.as_str()];
+    let config = BrowserConfig {
+        data_paths: &data_paths,
+        channels: None,
+        os_crypt_name: Some("chromium"),
+        osx_key_service: None,
+        osx_key_user: None,
+    };
+    let options = EnumerationOptions::default();
+    assert!(rookie::chromium_profiles(&config, None, &options).is_err());
+    assert!(rookie::any_browser(profile.to_str().unwrap(), None, None).is_err());
+}
+
+#[test]
+fn truncated_session_store() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("sessionstore-backups")).unwrap();
+    fs::write(dir.path().join("sessionstore-backups/recovery.jsonlz4"), b"mozL").unwrap();
+    assert!(mozilla::get_session_cookies_lz4(None, dir.path().to_path_buf(), Default::default()).is_err());
+}
+
+#[cfg(target_os = "windows")]
+#[test]
+fn extended_length_paths() {
+    use std::path::PathBuf;
+
+    let plain = Path::new(r"C:\Users\user\AppData\Local\Google\Chrome\User Data\Local State");
+    assert_eq!(paths::extended_length_path(plain), plain);
+
+    let cases = [
+        (r"C:\Users\user. \AppData\Local State", r"\\?\C:\Users\user. \AppData\Local State"),
+        (r"C:\Users\CON\AppData/Local State", r"\\?\C:\Users\CON\AppData\Local State"),
+        (r"C:\Users\aux.home\Default\Network\..\..\Local State", r"\\?\C:\Users\aux.home\Local State"),
+        (r"\\server\share\Users\name \Cookies", r"\\?\UNC\server\share\Users\name \Cookies"),
+    ];
+    for (path, expected) in cases {
+        assert_eq!(paths::extended_length_path(Path::new(path)), PathBuf::from(expected));
+    }
+
+    let long = format!(r"C:\{}\Cookies", "a".repeat(300));
+    let extended = paths::extended_length_path(Path::new(&long));
+    assert_eq!(extended, PathBuf::from(format!(r"\\?\{}", long)));
+
+    let verbatim = Path::new(r"\\?\C:\Users\user \Cookies");
+    assert_eq!(paths::extended_length_path(verbatim), verbatim);
+}
+
+#[cfg(target_os = "windows")]
+#[test]
+fn profile_under_trailing_space_dir() {
+    use rookie::browser::chromium;
+    use rookie::testing::{ self, ChromiumSchema };
+
+    let dir = tempfile::tempdir().unwrap();
+    let root = paths::extended_length_path(&dir.path().join("user "));
+    let fixture = testing
+        ::write_chromium_profile(&root, ChromiumSchema::V21, &testing::sample_cookies())
+        .unwrap();
+    let cookies = chromium::chromium_based(fixture.key_path, fixture.db_path, None);
+    assert_eq!(cookies.unwrap().len(), testing::sample_cookies().len());
+}
+
+fn install_profiles_ini(dir: &Path, fixture: &str, root: &Path) {
+    let ini = fs::read_to_string(Path::new("tests/fixtures/profiles-ini").join(fixture)).unwrap();
+    let ini = ini.replace("{root}", &root.to_string_lossy());
+    fs::write(dir.join("profiles.ini"), ini).unwrap();
+}
+
+fn mozilla_config<'a>(data_paths: &'a [&'a str]) -> BrowserConfig<'a> {
+    BrowserConfig {
+        data_paths,
+        channels: None,
+        os_crypt_name: None,
+        osx_key_service: None,
+        osx_key_user: None,
+    }
+}
+
+#[test]
+fn profiles_ini_relative_entry() {
+    let dir = tempfile::tempdir().unwrap();
+    install_profiles_ini(dir.path(), "relative.ini", dir.path());
+    assert_eq!(
+        mozilla::default_profile_dir(dir.path()).unwrap(),
+        dir.path().join("Profiles/abcd1234.default-release")
+    );
+}
+
+#[test]
+fn profiles_ini_absolute_entry() {
+    let dir = tempfile::tempdir().unwrap();
+    let roaming = tempfile::tempdir().unwrap();
+    install_profiles_ini(dir.path(), "absolute.ini", roaming.path());
+    assert_eq!(mozilla::default_profile_dir(dir.path()).unwrap(), roaming.path());
+
+    fs::write(roaming.path().join("cookies.sqlite"), b"").unwrap();
+    let data_path = dir.path().to_string_lossy();
+    let data_paths = [data_path.as_ref()];
+    assert_eq!(
+        paths::find_mozilla_based_paths(&mozilla_config(&data_paths)).unwrap(),
+        roaming.path().join("cookies.sqlite")
+    );
+}
+
+#[test]
+fn profiles_ini_unreachable_unc_entry() {
+    let dir = tempfile::tempdir().unwrap();
+    install_profiles_ini(dir.path(), "unc.ini", dir.path());
+    let err = mozilla::default_profile_dir(dir.path()).unwrap_err();
+    let unreachable = err.downcast_ref::<mozilla::ShareUnreachable>().unwrap();
+    assert_eq!(unreachable.0, Path::new(r"\\rookie-test.invalid\profiles"));
+
+    let data_path = dir.path().to_string_lossy();
+    let data_paths = [data_path.as_ref()];
+    let err = paths::find_mozilla_based_paths(&mozilla_config(&data_paths)).unwrap_err();
+    assert!(err.is::<mozilla::ShareUnreachable>());
+}
+
+#[test]
+fn profiles_ini_default_flag_over_file_order() {
+    let dir = tempfile::tempdir().unwrap();
+    install_profiles_ini(dir.path(), "renamed.ini", dir.path());
+    assert_eq!(mozilla::default_profile_dir(dir.path()).unwrap(), dir.path().join("Profiles/work"));
+
+    // installs.ini has the last word over the Default=1 flag
+    fs::write(
+        dir.path().join("installs.ini"),
+        "[308046B0AF4A39CB]\nDefault=Profiles/abcd1234.default-release\nLocked=1\n"
+    ).unwrap();
+    assert_eq!(
+        mozilla::default_profile_dir(dir.path()).unwrap(),
+        dir.path().join("Profiles/abcd1234.default-release")
+    );
+}
+
+#[test]
+fn profiles_ini_most_recent_of_several_defaults() {
+    for (fixture, used) in [
+        ("two-installs.ini", "Profiles/efgh5678.default-esr"),
+        ("no-default.ini", "Profiles/current"),
+    ] {
+        let dir = tempfile::tempdir().unwrap();
+        install_profiles_ini(dir.path(), fixture, dir.path());
+        fs::create_dir_all(dir.path().join(used)).unwrap();
+        fs::write(dir.path().join(used).join("cookies.sqlite"), b"").unwrap();
+        assert_eq!(mozilla::default_profile_dir(dir.path()).unwrap(), dir.path().join(used), "{}", fixture);
+    }
+}
+
+#[test]
+fn moved_windows_profile_composition() {
+    let root = r"D:\Users\alice";
+    let expand = |path: &str| {
+        paths::expand_placeholders(path, |placeholder| paths::rebased_folder(root, placeholder)).unwrap()
+    };
+    assert_eq!(
+        expand("%LOCALAPPDATA%/Google/Chrome/User Data/Default/Network/Cookies"),
+        r"D:\Users\alice\AppData\Local/Google/Chrome/User Data/Default/Network/Cookies"
+    );
+    assert_eq!(expand("%APPDATA%/Mozilla/Firefox"), r"D:\Users\alice\AppData\Roaming/Mozilla/Firefox");
+    assert_eq!(expand("%USERPROFILE%/x"), r"D:\Users\alice/x");
+    // unknown variables are left for the environment
+    assert_eq!(expand("%PROGRAMDATA%/x"), "%PROGRAMDATA%/x");
+    assert_eq!(
+        paths::rebased_folder(r"E:\Profiles\bob\", "localappdata").unwrap(),
+        r"E:\Profiles\bob\AppData\Local"
+    );
+}
+
+#[cfg(target_os = "windows")]
+#[test]
+fn windows_configs_follow_profile_root() {
+    let root = Path::new(r"D:\Users\alice");
+    let configs = [
+        &rookie::config::CHROME_CONFIG,
+        &rookie::config::EDGE_CONFIG,
+        &rookie::config::BRAVE_CONFIG,
+        &rookie::config::FIREFOX_CONFIG,
+        &rookie::config::OPERA_CONFIG,
+        &rookie::config::VIVALDI_CONFIG,
+    ];
+    rookie::with_user_profile_root(root, || {
+        for config in configs {
+            for data_path in config.data_paths {
+                let expanded = paths::expand_path(data_path).unwrap();
+                assert!(expanded.starts_with(root), "{} -> {}", data_path, expanded.display());
+            }
+        }
+    });
+    assert_eq!(paths::user_profile_root(), None);
+}
+
+#[cfg(unix)]
+#[test]
+fn discovery_under_another_profile_root() {
+    let root = tempfile::tempdir().unwrap();
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "macos")] {
+            let firefox_dir = root.path().join("Library/Application Support/Firefox");
+        } else {
+            let firefox_dir = root.path().join(".mozilla/firefox");
+        }
+    }
+    let fixture_cookies = rookie::testing::sample_cookies();
+    rookie::testing::write_mozilla_profile(&firefox_dir, &fixture_cookies).unwrap();
+
+    let cookies = rookie
+        ::with_user_profile_root(root.path(), || rookie::firefox(None))
+        .unwrap();
+    assert_eq!(cookies.len(), fixture_cookies.len());
+    assert_eq!(paths::user_profile_root(), None);
+}
+
+#[test]
+fn waterfox_current_and_classic() {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let dirs = ["AppData/Roaming/Waterfox", "AppData/Roaming/Waterfox Classic"];
+        } else if #[cfg(target_os = "macos")] {
+            let dirs = ["Library/Application Support/Waterfox", "Library/Application Support/Waterfox Classic"];
+        } else {
+            let dirs = [".waterfox", ".waterfox-classic"];
+        }
+    }
+    let fixture_cookies = rookie::testing::sample_cookies();
+    for dir in dirs {
+        let root = tempfile::tempdir().unwrap();
+        rookie::testing::write_mozilla_profile(&root.path().join(dir), &fixture_cookies).unwrap();
+
+        let (cookies, loaded) = rookie
+            ::with_user_profile_root(root.path(), || {
+                (rookie::waterfox(None), rookie::load(None))
+            });
+        assert_eq!(cookies.unwrap().len(), fixture_cookies.len(), "{}", dir);
+        assert_eq!(loaded.unwrap().len(), fixture_cookies.len(), "{}", dir);
+    }
+}
+
+#[test]
+fn mullvad_profile_without_profiles_ini() {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let profiles_dir = "AppData/Roaming/Mullvad/MullvadBrowser/Profiles";
+        } else if #[cfg(target_os = "macos")] {
+            let profiles_dir = "Library/Application Support/MullvadBrowser-Data/Browser";
+        } else {
+            let profiles_dir = ".mullvad-browser";
+        }
+    }
+    let root = tempfile::tempdir().unwrap();
+    let profiles_dir = root.path().join(profiles_dir);
+    let fixture_cookies = rookie::testing::sample_cookies();
+    rookie::testing::write_mozilla_profile(&profiles_dir, &fixture_cookies).unwrap();
+    // a profile that isn't named *.default-release, and nothing pointing at it
+    fs::rename(profiles_dir.join("fixture.default-release"), profiles_dir.join("j3kx0y1a.default")).unwrap();
+    fs::remove_file(profiles_dir.join("profiles.ini")).unwrap();
+    fs::create_dir_all(profiles_dir.join("empty.default-release")).unwrap();
+
+    let (cookies, loaded) = rookie
+        ::with_user_profile_root(root.path(), || {
+            (rookie::mullvad(None), rookie::load(None))
+        });
+    assert_eq!(cookies.unwrap().len(), fixture_cookies.len());
+    assert_eq!(loaded.unwrap().len(), fixture_cookies.len());
+}
+
+#[test]
+fn firefox_channel_profiles() {
+    use rookie::common::enums::FirefoxChannel;
+
+    let root = tempfile::tempdir().unwrap();
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let firefox_dir = root.path().join("AppData/Roaming/Mozilla/Firefox");
+        } else if #[cfg(target_os = "macos")] {
+            let firefox_dir = root.path().join("Library/Application Support/Firefox");
+        } else {
+            let firefox_dir = root.path().join(".mozilla/firefox");
+        }
+    }
+    let fixture_cookies = rookie::testing::sample_cookies();
+    let profiles = ["a1.default-release", "b2.dev-edition-default", "c3.default-nightly", "d4.default"];
+    for profile in profiles {
+        let staging = root.path().join("staging");
+        rookie::testing::write_mozilla_profile(&staging, &fixture_cookies).unwrap();
+        fs::create_dir_all(firefox_dir.join("Profiles")).unwrap();
+        fs::rename(staging.join("fixture.default-release"), firefox_dir.join("Profiles").join(profile)).unwrap();
+        fs::remove_dir_all(staging).unwrap();
+    }
+    // nightly has no install section left, the legacy profile isn't a channel's
+    fs::write(
+        firefox_dir.join("profiles.ini"),
+        "[Install308046B0AF4A39CB]\nDefault=Profiles/a1.default-release\nLocked=1\n\n\
+         [Install46F492E0ACFF84D4]\nDefault=Profiles/b2.dev-edition-default\nLocked=1\n\n\
+         [Profile0]\nName=default-release\nIsRelative=1\nPath=Profiles/a1.default-release\n\n\
+         [Profile1]\nName=dev-edition-default\nIsRelative=1\nPath=Profiles/b2.dev-edition-default\n\n\
+         [Profile2]\nName=default-nightly\nIsRelative=1\nPath=Profiles/c3.default-nightly\n\n\
+         [Profile3]\nName=default\nIsRelative=1\nPath=Profiles/d4.default\nDefault=1\n\n\
+         [General]\nStartWithLastProfile=1\nVersion=2\n"
+    ).unwrap();
+
+    let read = rookie
+        ::with_user_profile_root(root.path(), || rookie::firefox_channels(None))
+        .unwrap();
+    assert!(read.failures.is_empty());
+    let channels: Vec<_> = read.channels
+        .iter()
+        .map(|channel| (channel.channel, channel.profile_dir.as_str(), channel.cookies.len()))
+        .collect();
+    assert_eq!(
+        channels,
+        [
+            (Some(FirefoxChannel::Release), "a1.default-release", fixture_cookies.len()),
+            (Some(FirefoxChannel::DeveloperEdition), "b2.dev-edition-default", fixture_cookies.len()),
+            (Some(FirefoxChannel::Nightly), "c3.default-nightly", fixture_cookies.len()),
+        ]
+    );
+    assert_eq!(mozilla::profile_channel("Profiles/x9.default-esr"), Some(FirefoxChannel::Esr));
+    assert_eq!(mozilla::profile_channel("x9.default-beta"), Some(FirefoxChannel::Beta));
+}
+
+/// Installs each channel alone and checks only its own config finds it
+fn assert_channels_told_apart(channels: &[(&BrowserConfig, &str)]) {
+    for &(config, user_data) in channels {
+        // only this channel is installed
+        let root = tempfile::tempdir().unwrap();
+        let user_data = root.path().join(user_data);
+        fs::create_dir_all(user_data.join("Default")).unwrap();
+        fs::write(user_data.join("Default/Cookies"), b"").unwrap();
+        fs::write(user_data.join("Local State"), b"{}").unwrap();
+
+        rookie::with_user_profile_root(root.path(), || {
+            for (other, _) in channels.iter().copied() {
+                let found = paths::find_chrome_based_paths(other);
+                if std::ptr::eq(other, config) {
+                    let (_, db_path) = found.unwrap();
+                    assert_eq!(db_path, user_data.join("Default/Cookies"));
+                } else {
+                    assert!(found.is_err(), "{} found by another channel", user_data.display());
+                }
+            }
+        });
+    }
+}
+
+#[test]
+fn chrome_channels_are_told_apart() {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let channels = [
+                (&rookie::config::CHROME_CONFIG, "AppData/Local/Google/Chrome/User Data"),
+                (&rookie::config::CHROME_BETA_CONFIG, "AppData/Local/Google/Chrome Beta/User Data"),
+                (&rookie::config::CHROME_DEV_CONFIG, "AppData/Local/Google/Chrome Dev/User Data"),
+                (&rookie::config::CHROME_CANARY_CONFIG, "AppData/Local/Google/Chrome SxS/User Data"),
+            ];
+        } else if #[cfg(target_os = "macos")] {
+            let channels = [
+                (&rookie::config::CHROME_CONFIG, "Library/Application Support/Google/Chrome"),
+                (&rookie::config::CHROME_BETA_CONFIG, "Library/Application Support/Google/Chrome Beta"),
+                (&rookie::config::CHROME_DEV_CONFIG, "Library/Application Support/Google/Chrome Dev"),
+                (&rookie::config::CHROME_CANARY_CONFIG, "Library/Application Support/Google/Chrome Canary"),
+            ];
+        } else {
+            let channels = [
+                (&rookie::config::CHROME_CONFIG, ".config/google-chrome"),
+                (&rookie::config::CHROME_BETA_CONFIG, ".config/google-chrome-beta"),
+                (&rookie::config::CHROME_DEV_CONFIG, ".config/google-chrome-unstable"),
+                (&rookie::config::CHROME_CANARY_CONFIG, ".config/google-chrome-canary"),
+            ];
+        }
+    }
+    assert_channels_told_apart(&channels);
+}
+
+#[test]
+fn edge_channels_are_told_apart() {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let channels = [
+                (&rookie::config::EDGE_CONFIG, "AppData/Local/Microsoft/Edge/User Data"),
+                (&rookie::config::EDGE_BETA_CONFIG, "AppData/Local/Microsoft/Edge Beta/User Data"),
+                (&rookie::config::EDGE_DEV_CONFIG, "AppData/Local/Microsoft/Edge Dev/User Data"),
+                (&rookie::config::EDGE_CANARY_CONFIG, "AppData/Local/Microsoft/Edge SxS/User Data"),
+            ];
+        } else if #[cfg(target_os = "macos")] {
+            let channels = [
+                (&rookie::config::EDGE_CONFIG, "Library/Application Support/Microsoft Edge"),
+                (&rookie::config::EDGE_BETA_CONFIG, "Library/Application Support/Microsoft Edge Beta"),
+                (&rookie::config::EDGE_DEV_CONFIG, "Library/Application Support/Microsoft Edge Dev"),
+                (&rookie::config::EDGE_CANARY_CONFIG, "Library/Application Support/Microsoft Edge Canary"),
+            ];
+        } else {
+            let channels = [
+                (&rookie::config::EDGE_CONFIG, ".config/microsoft-edge"),
+                (&rookie::config::EDGE_BETA_CONFIG, ".config/microsoft-edge-beta"),
+                (&rookie::config::EDGE_DEV_CONFIG, ".config/microsoft-edge-dev"),
+            ];
+        }
+    }
+    assert_channels_told_apart(&channels);
+}
+
+#[test]
+fn brave_channels_are_told_apart() {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let root = "AppData/Local/BraveSoftware";
+            let user_data = "/User Data";
+        } else if #[cfg(target_os = "macos")] {
+            let root = "Library/Application Support/BraveSoftware";
+            let user_data = "";
+        } else {
+            let root = ".config/BraveSoftware";
+            let user_data = "";
+        }
+    }
+    let stable = format!("{}/Brave-Browser{}", root, user_data);
+    let beta = format!("{}/Brave-Browser-Beta{}", root, user_data);
+    let nightly = format!("{}/Brave-Browser-Nightly{}", root, user_data);
+    assert_channels_told_apart(
+        &[
+            (&rookie::config::BRAVE_CONFIG, &stable),
+            (&rookie::config::BRAVE_BETA_CONFIG, &beta),
+            (&rookie::config::BRAVE_NIGHTLY_CONFIG, &nightly),
+        ]
+    );
+}
+
+#[test]
+fn vivaldi_channels_are_told_apart() {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let (stable, snapshot) = ("AppData/Local/Vivaldi/User Data", "AppData/Local/Vivaldi Snapshot/User Data");
+        } else if #[cfg(target_os = "macos")] {
+            let (stable, snapshot) = ("Library/Application Support/Vivaldi", "Library/Application Support/Vivaldi Snapshot");
+        } else {
+            let (stable, snapshot) = (".config/vivaldi", ".config/vivaldi-snapshot");
+        }
+    }
+    assert_channels_told_apart(
+        &[
+            (&rookie::config::VIVALDI_CONFIG, stable),
+            (&rookie::config::VIVALDI_SNAPSHOT_CONFIG, snapshot),
+        ]
+    );
+}
+
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+#[test]
+fn whale_discovery() {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let user_data = "AppData/Local/Naver/Naver Whale/User Data";
+        } else {
+            let user_data = "Library/Application Support/Naver/Whale";
+        }
+    }
+    assert_channels_told_apart(&[(&rookie::config::WHALE_CONFIG, user_data)]);
+}
+
+#[cfg(target_os = "windows")]
+#[test]
+fn qq_browser_discovery() {
+    assert_channels_told_apart(&[(&rookie::config::QQ_BROWSER_CONFIG, "AppData/Local/Tencent/QQBrowser/User Data")]);
+}
+
+#[cfg(target_os = "windows")]
+#[test]
+fn epic_after_exit() {
+    let root = tempfile::tempdir().unwrap();
+    let epic_dir = root.path().join("AppData/Local/Epic Privacy Browser");
+    rookie::testing::write_chromium_profile(&epic_dir, rookie::testing::ChromiumSchema::V21, &[]).unwrap();
+    let cookies = rookie::with_user_profile_root(root.path(), || rookie::epic(None)).unwrap();
+    assert!(cookies.is_empty());
+}
+
+#[cfg(not(target_os = "macos"))]
+#[test]
+fn iridium_discovery() {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let user_data = "AppData/Local/Iridium/User Data";
+        } else {
+            let user_data = ".config/iridium";
+        }
+    }
+    assert_channels_told_apart(&[(&rookie::config::IRIDIUM_CONFIG, user_data)]);
+}
+
+#[test]
+fn thunderbird_profiles() {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let thunderbird_dir = "AppData/Roaming/Thunderbird";
+        } else if #[cfg(target_os = "macos")] {
+            let thunderbird_dir = "Library/Thunderbird";
+        } else {
+            let thunderbird_dir = ".thunderbird";
+        }
+    }
+    let fixture_cookies = rookie::testing::sample_cookies();
+    let root = tempfile::tempdir().unwrap();
+    let thunderbird_dir = root.path().join(thunderbird_dir);
+    rookie::testing::write_mozilla_profile(&thunderbird_dir, &fixture_cookies).unwrap();
+    let read = || rookie::with_user_profile_root(root.path(), || rookie::thunderbird(None));
+    assert_eq!(read().unwrap().len(), fixture_cookies.len());
+    // not part of load()
+    assert!(rookie::with_user_profile_root(root.path(), || rookie::load(None)).unwrap().is_empty());
+
+    // no profiles.ini, a *.default profile under Profiles next to an older one
+    fs::remove_file(thunderbird_dir.join("profiles.ini")).unwrap();
+    fs::create_dir_all(thunderbird_dir.join("Profiles")).unwrap();
+    fs::rename(thunderbird_dir.join("fixture.default-release"), thunderbird_dir.join("Profiles/x8k2m1.default")).unwrap();
+    fs::create_dir_all(thunderbird_dir.join("Profiles/a0aaaa.old")).unwrap();
+    fs::write(thunderbird_dir.join("Profiles/a0aaaa.old/cookies.sqlite"), b"").unwrap();
+    assert_eq!(read().unwrap().len(), fixture_cookies.len());
+}
+
+#[test]
+fn zen_profiles_are_merged() {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let zen_dir = "AppData/Roaming/zen";
+        } else if #[cfg(target_os = "macos")] {
+            let zen_dir = "Library/Application Support/zen";
+        } else {
+            let zen_dir = ".zen";
+        }
+    }
+    let root = tempfile::tempdir().unwrap();
+    let zen_dir = root.path().join(zen_dir);
+    let work = vec![rookie::testing::FixtureCookie::new("work.example", "sid", "w0rk")];
+    let personal = rookie::testing::sample_cookies();
+    rookie::testing::write_mozilla_profile(&zen_dir, &personal).unwrap();
+    // a second profile profiles.ini doesn't list
+    let staging = root.path().join("staging");
+    rookie::testing::write_mozilla_profile(&staging, &work).unwrap();
+    fs::create_dir_all(zen_dir.join("Profiles")).unwrap();
+    fs::rename(staging.join("fixture.default-release"), zen_dir.join("Profiles/k2v9.Work")).unwrap();
+
+    let (cookies, loaded) = rookie
+        ::with_user_profile_root(root.path(), || {
+            (rookie::zen(None), rookie::load(None))
+        });
+    let cookies = cookies.unwrap();
+    assert_eq!(cookies.len(), personal.len() + work.len());
+    assert!(cookies.iter().any(|cookie| cookie.value == "w0rk"));
+    assert_eq!(loaded.unwrap().len(), cookies.len());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn epiphany_flatpak() {
+    let root = tempfile::tempdir().unwrap();
+    let fixture_cookies = rookie::testing::sample_cookies();
+    let db_path = root.path().join(".var/app/org.gnome.Epiphany/data/epiphany/cookies.sqlite");
+    rookie::testing::write_epiphany_cookies(&db_path, &fixture_cookies, true).unwrap();
+
+    let cookies = rookie
+        ::with_user_profile_root(root.path(), || rookie::epiphany(None))
+        .unwrap();
+    assert_eq!(cookies.len(), fixture_cookies.len());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn falkon_profiles() {
+    use rookie::testing::{ write_chromium_profile_in, ChromiumSchema, FixtureCookie };
+
+    let root = tempfile::tempdir().unwrap();
+    let falkon_dir = root.path().join(".config/falkon");
+    let fixture_cookies = rookie::testing::sample_cookies();
+    write_chromium_profile_in(&falkon_dir, "default", ChromiumSchema::V21, &fixture_cookies).unwrap();
+    let work = [FixtureCookie::new("work.example", "sid", "w0rk")];
+    let fixture = write_chromium_profile_in(&falkon_dir, "work", ChromiumSchema::V21, &work).unwrap();
+    // QtWebEngine without os_crypt leaves the value in plaintext
+    rusqlite::Connection
+        ::open(&fixture.db_path)
+        .unwrap()
+        .execute("UPDATE cookies SET value = 'w0rk', encrypted_value = X''", [])
+        .unwrap();
+    fs::rename(falkon_dir.join("User Data"), falkon_dir.join("profiles")).unwrap();
+    // there's no Local State next to the profiles
+    fs::remove_file(falkon_dir.join("profiles/Local State")).unwrap();
+
+    let (cookies, loaded) = rookie::with_user_profile_root(root.path(), || {
+        (rookie::falkon(None), rookie::load(None))
+    });
+    let cookies = cookies.unwrap();
+    assert_eq!(cookies.len(), fixture_cookies.len() + work.len());
+    assert!(cookies.iter().any(|cookie| cookie.name == "sid" && cookie.value == "w0rk"));
+    assert!(cookies.iter().any(|cookie| cookie.name == "SID" && cookie.value == "g00gle"));
+    assert!(!loaded.unwrap().is_empty());
+}
+
+#[cfg(target_os = "windows")]
+#[test]
+fn octo_profiles_are_kept_apart() {
+    use rookie::testing::{ write_chromium_profile, ChromiumSchema, FixtureCookie };
+
+    let root = tempfile::tempdir().unwrap();
+    let profiles = root.path().join("AppData/Roaming/Octo Browser/tmp");
+    for (profile, value) in [("a1f3", "first"), ("c7d9", "second")] {
+        // every profile has its own Local State and key
+        let staging = root.path().join("staging").join(profile);
+        write_chromium_profile(&staging, ChromiumSchema::V21, &[FixtureCookie::new("example.com", "sid", value)]).unwrap();
+        fs::create_dir_all(&profiles).unwrap();
+        fs::rename(staging.join("User Data"), profiles.join(profile)).unwrap();
+    }
+
+    let mut read = rookie
+        ::with_user_profile_root(root.path(), || rookie::octo_browser(None))
+        .unwrap();
+    read.sort_by(|a, b| a.0.cmp(&b.0));
+    let values: Vec<(&str, &str)> = read
+        .iter()
+        .map(|(profile, cookies)| (profile.as_str(), cookies[0].value.as_str()))
+        .collect();
+    assert_eq!(values, [("a1f3", "first"), ("c7d9", "second")]);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn chromium_snap_is_a_fallback() {
+    use rookie::testing::{ write_chromium_profile, ChromiumSchema, FixtureCookie };
+
+    let root = tempfile::tempdir().unwrap();
+    // without the password-manager-service plug the snap falls back to the v10 "peanuts" key, like the fixtures
+    for (user_data, value) in [(".config/chromium", "deb"), ("snap/chromium/common/chromium", "snap")] {
+        let staging = root.path().join("staging").join(value);
+        let fixture = write_chromium_profile(&staging, ChromiumSchema::V21, &[FixtureCookie::new("example.com", "sid", value)]).unwrap();
+        let profile = root.path().join(user_data).join("Default");
+        fs::create_dir_all(&profile).unwrap();
+        fs::rename(fixture.db_path, profile.join("Cookies")).unwrap();
+    }
+    let read = || {
+        rookie::with_user_profile_root(root.path(), || rookie::chromium(None)).unwrap()[0].value.clone()
+    };
+    assert_eq!(read(), "deb");
+
+    fs::remove_dir_all(root.path().join(".config/chromium")).unwrap();
+    assert_eq!(read(), "snap");
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn firefox_most_recent_install_wins() {
+    use rookie::testing::FixtureCookie;
+
+    let root = tempfile::tempdir().unwrap();
+    let mut db_paths = vec![];
+    for (firefox_dir, value) in [(".mozilla/firefox", "deb"), ("snap/firefox/common/.mozilla/firefox", "snap")] {
+        let cookies = [FixtureCookie::new("example.com", "sid", value)];
+        db_paths.push(rookie::testing::write_mozilla_profile(&root.path().join(firefox_dir), &cookies).unwrap());
+    }
+    let touch = |db_path: &Path, date: &str| {
+        let status = std::process::Command::new("touch").arg("-d").arg(date).arg(db_path).status().unwrap();
+        assert!(status.success());
+    };
+    let read = || {
+        rookie::with_user_profile_root(root.path(), || rookie::firefox(None)).unwrap()[0].value.clone()
+    };
+
+    touch(&db_paths[0], "2024-01-01");
+    touch(&db_paths[1], "2025-01-01");
+    assert_eq!(read(), "snap");
+
+    touch(&db_paths[0], "2025-06-01");
+    assert_eq!(read(), "deb");
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn flatpak_only_installs() {
+    use rookie::config;
+    use rookie::testing::{ write_chromium_profile, ChromiumSchema, FixtureCookie };
+
+    assert_channels_told_apart(
+        &[
+            (&config::CHROME_CONFIG, ".var/app/com.google.Chrome/config/google-chrome"),
+            (&config::CHROMIUM_CONFIG, ".var/app/org.chromium.Chromium/config/chromium"),
+            (&config::BRAVE_CONFIG, ".var/app/com.brave.Browser/config/BraveSoftware/Brave-Browser"),
+            (&config::EDGE_CONFIG, ".var/app/com.microsoft.Edge/config/microsoft-edge"),
+            (&config::VIVALDI_CONFIG, ".var/app/com.vivaldi.Vivaldi/config/vivaldi"),
+        ]
+    );
+
+    // the sandboxed browser uses the host keyring entries, the fixture's "peanuts" key is tried after them
+    let root = tempfile::tempdir().unwrap();
+    let fixture = write_chromium_profile(
+        &root.path().join("staging"),
+        ChromiumSchema::V21,
+        &[FixtureCookie::new("example.com", "sid", "flatpak")]
+    ).unwrap();
+    let profile = root.path().join(".var/app/com.brave.Browser/config/BraveSoftware/Brave-Browser/Default");
+    fs::create_dir_all(&profile).unwrap();
+    fs::rename(fixture.db_path, profile.join("Cookies")).unwrap();
+    let cookies = rookie::with_user_profile_root(root.path(), || rookie::load(None)).unwrap();
+    assert!(cookies.iter().any(|cookie| cookie.value == "flatpak"));
+}
+
+#[test]
+fn libre_wolf_custom_profile_dirs() {
+    use rookie::testing::FixtureCookie;
+
+    let root = tempfile::tempdir().unwrap();
+    let profiles = root.path().join("LibreWolf-Portable/Profiles");
+    let db_path = rookie::testing
+        ::write_mozilla_profile(&profiles, &[FixtureCookie::new("example.com", "sid", "portable")])
+        .unwrap();
+    for dir in [db_path.parent().unwrap(), profiles.as_path()] {
+        let cookies = rookie::libre_wolf_from_dir(dir, None).unwrap();
+        assert_eq!(cookies[0].value, "portable");
+    }
+    assert!(rookie::libre_wolf_from_dir(root.path(), None).is_err());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn libre_wolf_flatpak_without_suffixes() {
+    use rookie::testing::FixtureCookie;
+
+    let root = tempfile::tempdir().unwrap();
+    let librewolf_dir = root.path().join(".var/app/io.gitlab.librewolf-community/.librewolf");
+    for (profile, value, date) in [("k3j2", "old", "2024-01-01"), ("q9w8", "current", "2025-01-01")] {
+        let staging = root.path().join("staging").join(profile);
+        let db_path = rookie::testing
+            ::write_mozilla_profile(&staging, &[FixtureCookie::new("example.com", "sid", value)])
+            .unwrap();
+        fs::create_dir_all(&librewolf_dir).unwrap();
+        fs::rename(db_path.parent().unwrap(), librewolf_dir.join(profile)).unwrap();
+        let status = std::process::Command
+            ::new("touch")
+            .arg("-d")
+            .arg(date)
+            .arg(librewolf_dir.join(profile).join("cookies.sqlite"))
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    // no profiles.ini and no default-release profile, the most recently written one is used
+    let cookies = rookie
+        ::with_user_profile_root(root.path(), || rookie::libre_wolf(None))
+        .unwrap();
+    assert_eq!(cookies[0].value, "current");
+}
+
+#[test]
+fn opera_channels_are_told_apart() {
+    use rookie::config::{ OPERA_BETA_CONFIG, OPERA_CONFIG, OPERA_DEVELOPER_CONFIG };
+
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let channels = [
+                (&OPERA_CONFIG, "AppData/Roaming/Opera Software/Opera Stable"),
+                (&OPERA_BETA_CONFIG, "AppData/Roaming/Opera Software/Opera Next"),
+                (&OPERA_DEVELOPER_CONFIG, "AppData/Roaming/Opera Software/Opera Developer"),
+            ];
+        } else if #[cfg(target_os = "macos")] {
+            let channels = [
+                (&OPERA_CONFIG, "Library/Application Support/com.operasoftware.Opera"),
+                (&OPERA_BETA_CONFIG, "Library/Application Support/com.operasoftware.OperaNext"),
+                (&OPERA_DEVELOPER_CONFIG, "Library/Application Support/com.operasoftware.OperaDeveloper"),
+            ];
+        } else {
+            let channels = [
+                (&OPERA_CONFIG, ".config/opera"),
+                (&OPERA_BETA_CONFIG, ".config/opera-beta"),
+                (&OPERA_DEVELOPER_CONFIG, ".config/opera-developer"),
+            ];
+        }
+    }
+    // opera keeps the cookies DB in the root of its data directory, without a Default profile
+    for (config, data_dir) in channels {
+        let root = tempfile::tempdir().unwrap();
+        let data_dir = root.path().join(data_dir);
+        fs::create_dir_all(&data_dir).unwrap();
+        fs::write(data_dir.join("Cookies"), b"").unwrap();
+
+        rookie::with_user_profile_root(root.path(), || {
+            for (other, _) in channels {
+                let found = paths::find_chrome_based_paths(other);
+                if std::ptr::eq(other, config) {
+                    assert_eq!(found.unwrap().1, data_dir.join("Cookies"));
+                } else {
+                    assert!(found.is_err(), "{} found by another channel", data_dir.display());
+                }
+            }
+        });
+    }
+}
+
+#[test]
+fn every_user_profile_is_loaded() {
+    let users = tempfile::tempdir().unwrap();
+    let cookies = rookie::testing::sample_cookies();
+    for user in ["alice", "Public"] {
+        rookie::testing::write_firefox_home(&users.path().join(user), &cookies).unwrap();
+    }
+    for user in ["bob", "Default"] {
+        fs::create_dir_all(users.path().join(user)).unwrap();
+    }
+    fs::write(users.path().join("desktop.ini"), b"").unwrap();
+
+    let user_dirs = paths::user_profile_dirs(users.path()).unwrap();
+    assert_eq!(user_dirs, [users.path().join("alice"), users.path().join("bob")]);
+    let loaded: Vec<_> = user_dirs
+        .iter()
+        .map(|user_dir| rookie::load_for_user(user_dir, None).unwrap().cookies.len())
+        .collect();
+    assert_eq!(loaded, [cookies.len(), 0]);
+    assert_eq!(paths::user_profile_root(), None);
+}
+
+#[test]
+fn unreadable_user_is_recorded() {
+    let users = tempfile::tempdir().unwrap();
+    let cookies = rookie::testing::sample_cookies();
+    for user in ["alice", "carol"] {
+        rookie::testing::write_firefox_home(&users.path().join(user), &cookies).unwrap();
+    }
+    // listed, then gone by the time it's read, which fails like a dir without read access
+    let bob = users.path().join("bob");
+    fs::create_dir_all(&bob).unwrap();
+    let user_dirs = paths::user_profile_dirs(users.path()).unwrap();
+    fs::remove_dir(&bob).unwrap();
+
+    let read = rookie::load_users(user_dirs, None);
+    let loaded: Vec<_> = read.users
+        .iter()
+        .map(|(user_dir, outcome)| (user_dir.clone(), outcome.cookies.len()))
+        .collect();
+    assert_eq!(loaded, [(users.path().join("alice"), cookies.len()), (users.path().join("carol"), cookies.len())]);
+    assert_eq!(read.failures.len(), 1);
+    assert_eq!(read.failures[0].0, bob);
+}
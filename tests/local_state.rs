@@ -0,0 +1,46 @@
+use rookie::browser::chromium::{ browser_version, local_state_encrypted_key };
+use rookie::common::utils::parse_json_lenient;
+use std::path::{ Path, PathBuf };
+
+const ENCRYPTED_KEY: &str = "RFBBUEkBAAAA0Iyd3wEV0RGMegDAT8KX6wEAAAA=";
+
+/// `Local State` variants written by forks and crash recovery that all parse
+const PARSEABLE: &[&str] = &[
+    "utf8-bom.json",
+    "utf16le-bom.json",
+    "utf16be-bom.json",
+    "utf16le.json",
+    "trailing-garbage.json",
+    "comments.json",
+];
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/local-state").join(name)
+}
+
+#[test]
+fn malformed_variants_parse() {
+    for name in PARSEABLE {
+        let content = std::fs::read(fixture(name)).unwrap();
+        let json = parse_json_lenient(&content).unwrap_or_else(|err| panic!("{}: {}", name, err));
+        assert_eq!(json["profile"]["info_cache"]["Default"]["is_ephemeral"], false, "{}", name);
+        assert_eq!(local_state_encrypted_key(&content).unwrap().as_deref(), Some(ENCRYPTED_KEY), "{}", name);
+        let version = browser_version(&fixture(name), None).unwrap();
+        assert_eq!(version.to_string(), "120.0.6099.130", "{}", name);
+    }
+
+    // `//` inside a string isn't a comment
+    let commented = parse_json_lenient(&std::fs::read(fixture("comments.json")).unwrap()).unwrap();
+    assert_eq!(commented["profile"]["info_cache"]["Default"]["name"], "http://not/a/comment");
+}
+
+#[test]
+fn truncated_local_state_falls_back_to_a_scan() {
+    let content = std::fs::read(fixture("truncated.json")).unwrap();
+    assert!(parse_json_lenient(&content).is_err());
+    assert_eq!(local_state_encrypted_key(&content).unwrap().as_deref(), Some(ENCRYPTED_KEY));
+
+    // parsed fine, the key just isn't there (bare DPAPI forks)
+    assert_eq!(local_state_encrypted_key(br#"{"os_crypt":{}}"#).unwrap(), None);
+    assert!(local_state_encrypted_key(b"\x00garbage").is_err());
+}
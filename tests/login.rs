@@ -0,0 +1,105 @@
+use rookie::browser::{ binarycookies, chromium };
+use rookie::common::enums::{ BrowserConfig, DomainStats };
+use rookie::login::{ self, LoginStatus };
+use rookie::testing::{ self, ChromiumSchema, FixtureCookie };
+
+fn github_cookies() -> Vec<FixtureCookie> {
+    let mut session = FixtureCookie::new("github.com", "_gh_sess", "s3ss10n");
+    session.expires = None;
+    let mut expired = FixtureCookie::new(".github.com", "logged_in", "no");
+    expired.expires = Some(1_000_000_000);
+    vec![
+        session,
+        expired,
+        FixtureCookie::new(".github.com", "user_session", "t0k3n"),
+        FixtureCookie::new("gist.github.com", "gist", "g1st"),
+        FixtureCookie::new("notgithub.com", "id", "n0t")
+    ]
+}
+
+fn config<'a>(data_paths: &'a [&'a str]) -> BrowserConfig<'a> {
+    BrowserConfig {
+        data_paths,
+        channels: None,
+        os_crypt_name: Some("chromium"),
+        osx_key_service: None,
+        osx_key_user: None,
+    }
+}
+
+#[test]
+fn site_registrable_domains() {
+    assert_eq!(login::site_domains("github"), ["github.com", "githubassets.com", "githubusercontent.com"]);
+    assert_eq!(login::site_domains("https://gist.github.com/user/1"), ["github.com"]);
+    assert_eq!(login::site_domains("shop.example.co.uk"), ["example.co.uk"]);
+}
+
+#[test]
+fn site_domains_by_public_suffix_list() {
+    let list: publicsuffix::List = "// ===BEGIN ICANN DOMAINS===\ncom\nuk\nco.uk\n// ===END ICANN DOMAINS===\n// ===BEGIN PRIVATE DOMAINS===\ngithub.io\n// ===END PRIVATE DOMAINS===\n"
+        .parse()
+        .unwrap();
+    // the approximation groups every github.io site together
+    assert_eq!(login::site_domains("https://user.github.io/"), ["github.io"]);
+    assert_eq!(login::site_domains_psl("https://user.github.io/", &list), ["user.github.io"]);
+    assert_eq!(login::site_domains_psl("shop.example.co.uk", &list), ["example.co.uk"]);
+    assert_eq!(login::site_domains_psl("github", &list), login::site_domains("github"));
+    assert_eq!(login::site_domains_psl("http://localhost:3000/", &list), ["localhost"]);
+    assert_eq!(login::site_domains_psl("github.io", &list), ["github.io"]);
+}
+
+#[test]
+fn chromium_profiles_without_values() {
+    let dir = tempfile::tempdir().unwrap();
+    let fixture = testing
+        ::write_chromium_profile_in(dir.path(), "Default", ChromiumSchema::V21, &github_cookies())
+        .unwrap();
+    testing
+        ::write_chromium_profile_in(dir.path(), "Profile 1", ChromiumSchema::V12, &testing::sample_cookies())
+        .unwrap();
+    let count = |domain| chromium::domain_stats(&fixture.db_path, domain).unwrap().cookies;
+    assert_eq!(count("GitHub.com."), 3);
+    assert_eq!(count("git_ub.com"), 0);
+    assert_eq!(count("hub.com"), 0);
+    let data_paths = testing::chromium_data_paths(dir.path());
+    let data_paths: Vec<&str> = data_paths.iter().map(|p| p.as_str()).collect();
+
+    let mut statuses = login::chromium_login_status("fixture", &config(&data_paths), "github");
+    statuses.sort_by(|a, b| a.profile.cmp(&b.profile));
+    assert_eq!(statuses, [
+        LoginStatus {
+            browser: "fixture".into(),
+            profile: "Default".into(),
+            cookies: 3,
+            last_access: Some(1_700_000_000),
+        },
+        LoginStatus { browser: "fixture".into(), profile: "Profile 1".into(), cookies: 0, last_access: None },
+    ]);
+    assert!(statuses[0].logged_in());
+    assert!(!statuses[1].logged_in());
+}
+
+#[test]
+fn mozilla_and_binarycookies_stats() {
+    let dir = tempfile::tempdir().unwrap();
+    let db_path = testing::write_mozilla_profile(dir.path(), &github_cookies()).unwrap();
+    let stats = rookie::browser::mozilla::domain_stats(&db_path, "github.com").unwrap();
+    assert_eq!(stats, DomainStats { cookies: 3, last_access: Some(1_700_000_000) });
+    // matched like the domains filter: normalized, on label boundaries, `_` taken literally
+    let count = |domain| rookie::browser::mozilla::domain_stats(&db_path, domain).unwrap().cookies;
+    assert_eq!(count("GitHub.com."), 3);
+    assert_eq!(count("git_ub.com"), 0);
+    assert_eq!(count("hub.com"), 0);
+
+    let data_path = dir.path().to_string_lossy();
+    let data_paths = [data_path.as_ref()];
+    let statuses = login::mozilla_login_status("firefox", &config(&data_paths), "https://github.com/");
+    assert_eq!(statuses.len(), 1);
+    assert_eq!(statuses[0].profile, "fixture.default-release");
+    assert_eq!(statuses[0].cookies, 3);
+
+    let path = dir.path().join("Cookies.binarycookies");
+    testing::write_binarycookies(&path, &github_cookies()).unwrap();
+    let stats = binarycookies::domain_stats(&path, "github.com").unwrap();
+    assert_eq!(stats, DomainStats { cookies: 3, last_access: None });
+}
@@ -0,0 +1,1205 @@
+use rookie::common::enums::{
+    BrowserConfig,
+    Cookie,
+    CookiesRead,
+    CookieToString,
+    DomainMatch,
+    EnumerationOptions,
+    NameFilter,
+    Persistence,
+    ReadOptions,
+    SameSite,
+    SortBy,
+    SourceScheme,
+};
+use rookie::common::sqlite::UnsupportedSchema;
+use rookie::testing::{ self, ChromiumSchema, FixtureCookie };
+use std::time::{ Duration, SystemTime, UNIX_EPOCH };
+
+fn find<'a>(cookies: &'a [Cookie], name: &str) -> &'a Cookie {
+    cookies
+        .iter()
+        .find(|c| c.name == name)
+        .unwrap_or_else(|| panic!("missing cookie {}", name))
+}
+
+fn assert_matches_fixture(cookies: &[Cookie], fixture: &[FixtureCookie]) {
+    assert_eq!(cookies.len(), fixture.len());
+    for expected in fixture {
+        let cookie = find(cookies, &expected.name);
+        assert_eq!(cookie.domain, expected.host);
+        assert_eq!(cookie.path, expected.path);
+        assert_eq!(cookie.value, expected.value);
+        assert_eq!(cookie.secure, expected.secure);
+        assert_eq!(cookie.http_only, expected.http_only);
+    }
+}
+
+fn read_chromium(fixture: &testing::ChromiumFixture, domains: Option<Vec<&str>>) -> Vec<Cookie> {
+    let db_path = fixture.db_path.to_str().unwrap();
+    let key_path = fixture.key_path.to_str().unwrap();
+    rookie::any_browser(db_path, domains, Some(key_path)).unwrap()
+}
+
+fn read_chromium_with(
+    fixture: &testing::ChromiumFixture,
+    domains: Option<Vec<&str>>,
+    options: ReadOptions
+) -> anyhow::Result<CookiesRead> {
+    read_chromium_filtered(fixture, domains, &NameFilter::default(), options)
+}
+
+fn read_chromium_filtered(
+    fixture: &testing::ChromiumFixture,
+    domains: Option<Vec<&str>>,
+    names: &NameFilter,
+    options: ReadOptions
+) -> anyhow::Result<CookiesRead> {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            rookie::browser::chromium::chromium_read_filtered(
+                fixture.key_path.clone(),
+                fixture.db_path.clone(),
+                domains,
+                names,
+                options
+            )
+        } else {
+            let config = BrowserConfig {
+                data_paths: &[],
+                channels: None,
+                os_crypt_name: Some("chromium"),
+                osx_key_service: None,
+                osx_key_user: None,
+            };
+            rookie::browser::chromium::chromium_read_filtered(
+                &config,
+                fixture.db_path.clone(),
+                domains,
+                names,
+                &testing::CountingSecrets::default(),
+                options
+            )
+        }
+    }
+}
+
+#[test]
+fn chromium_schemas_decrypt() {
+    let fixture_cookies = testing::sample_cookies();
+    for schema in ChromiumSchema::all() {
+        let dir = tempfile::tempdir().unwrap();
+        let fixture = testing::write_chromium_profile(dir.path(), schema, &fixture_cookies).unwrap();
+        let cookies = read_chromium(&fixture, None);
+        assert_matches_fixture(&cookies, &fixture_cookies);
+
+        let session = find(&cookies, "session");
+        assert_eq!(session.expires, None);
+        assert_eq!(find(&cookies, "id").expires, Some(4_102_444_800));
+    }
+}
+
+#[test]
+fn empty_names_and_values_survive() {
+    let dir = tempfile::tempdir().unwrap();
+    let fixture = testing
+        ::write_chromium_profile(dir.path(), ChromiumSchema::V21, &testing::sample_cookies())
+        .unwrap();
+    let cookies = read_chromium(&fixture, Some(vec!["example.org"]));
+    assert_eq!(find(&cookies, "").value, "n4meless");
+    assert_eq!(find(&cookies, "empty").value, "");
+
+    let mut header: Vec<_> = cookies.to_string().split(';').map(String::from).collect();
+    header.sort();
+    assert_eq!(header, ["empty=", "n4meless"]);
+}
+
+#[test]
+fn chromium_domain_filter() {
+    let dir = tempfile::tempdir().unwrap();
+    let fixture = testing
+        ::write_chromium_profile(dir.path(), ChromiumSchema::V21, &testing::sample_cookies())
+        .unwrap();
+    let cookies = read_chromium(&fixture, Some(vec!["example.com"]));
+    let mut names: Vec<_> = cookies.iter().map(|c| c.name.as_str()).collect();
+    names.sort();
+    assert_eq!(names, ["session", "token"]);
+}
+
+#[test]
+fn mozilla_profile() {
+    let fixture_cookies = testing::sample_cookies();
+    let dir = tempfile::tempdir().unwrap();
+    let db_path = testing::write_mozilla_profile(dir.path(), &fixture_cookies).unwrap();
+    let cookies = rookie::any_browser(db_path.to_str().unwrap(), None, None).unwrap();
+    assert_matches_fixture(&cookies, &fixture_cookies);
+    assert_eq!(find(&cookies, "session").expires, None);
+    assert_eq!(find(&cookies, "id").expires, Some(4_102_444_800));
+}
+
+#[test]
+fn mozilla_domain_filter() {
+    let dir = tempfile::tempdir().unwrap();
+    let db_path = testing::write_mozilla_profile(dir.path(), &testing::sample_cookies()).unwrap();
+    let domains = vec!["google.com".to_string()];
+    let cookies = rookie::any_browser(db_path.to_str().unwrap(), &domains, None).unwrap();
+    assert!(cookies.iter().all(|c| c.domain.contains("google.com")));
+}
+
+#[test]
+fn binarycookies_file() {
+    let fixture_cookies = testing::sample_cookies();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("Cookies.binarycookies");
+    testing::write_binarycookies(&path, &fixture_cookies).unwrap();
+    let cookies = rookie::any_browser(path.to_str().unwrap(), None, None).unwrap();
+    assert_matches_fixture(&cookies, &fixture_cookies);
+    assert_eq!(find(&cookies, "session").expires, None);
+    assert_eq!(find(&cookies, "id").expires, Some(4_102_444_800));
+
+    let cookies = rookie::binarycookies(&path, Some(vec![".google.com"])).unwrap();
+    assert_eq!(names(&cookies), ["SID"]);
+}
+
+#[test]
+fn read_options_record_provenance() {
+    use rookie::common::provenance;
+
+    let dir = tempfile::tempdir().unwrap();
+    let fixture = testing::write_chromium_profile(dir.path(), ChromiumSchema::V21, &testing::sample_cookies()).unwrap();
+    let firefox_db = testing::write_mozilla_profile(&dir.path().join("firefox"), &testing::sample_cookies()).unwrap();
+    let safari_file = dir.path().join("Cookies.binarycookies");
+    testing::write_binarycookies(&safari_file, &testing::sample_cookies()).unwrap();
+
+    let mut options = ReadOptions::default();
+    options.provenance = true;
+    let reads = [
+        (fixture.db_path.clone(), read_chromium_with(&fixture, None, options.clone()).unwrap()),
+        (
+            firefox_db.clone(),
+            rookie::browser::mozilla::firefox_read(firefox_db.clone(), None::<Vec<&str>>, options.clone()).unwrap(),
+        ),
+        (
+            safari_file.clone(),
+            rookie::browser::binarycookies::binarycookies_read(safari_file.clone(), None::<Vec<&str>>, options.clone()).unwrap(),
+        ),
+    ];
+    for (path, read) in reads {
+        let bytes = std::fs::read(&path).unwrap();
+        let recorded = read.provenance.unwrap();
+        assert_eq!(recorded.source_path, path);
+        assert_eq!(recorded.size, bytes.len() as u64);
+        assert_eq!(recorded.sha256, provenance::sha256_hex(&bytes));
+        assert!(!read.cookies.is_empty());
+    }
+    // the profile is the one of the live DB, not of the copy
+    let chromium = read_chromium_with(&fixture, None, options.clone()).unwrap();
+    assert!(chromium.cookies.iter().all(|cookie| cookie.profile.as_deref() == Some("Default")));
+
+    let read = read_chromium_with(&fixture, None, ReadOptions::default()).unwrap();
+    assert!(read.provenance.is_none());
+}
+
+#[test]
+fn ios_backup_manifest() {
+    let fixture_cookies = testing::sample_cookies();
+    let dir = tempfile::tempdir().unwrap();
+    let hashed = testing::write_ios_backup(dir.path(), "com.example.app", &fixture_cookies).unwrap();
+
+    let found = rookie::common::paths::find_ios_backup_cookies(dir.path(), "com.example.app").unwrap();
+    assert_eq!(found, vec![hashed.clone()]);
+    let cookies = rookie::from_ios_backup(dir.path(), "com.example.app").unwrap();
+    assert_matches_fixture(&cookies, &fixture_cookies);
+    // the backup domain works as well as the bundle id
+    assert_eq!(rookie::from_ios_backup(dir.path(), "AppDomain-com.example.app").unwrap().len(), fixture_cookies.len());
+
+    let err = rookie::from_ios_backup(dir.path(), "com.example.other").unwrap_err();
+    assert!(err.to_string().contains("no binarycookies for com.example.other"), "{}", err);
+    std::fs::remove_file(&hashed).unwrap();
+    assert!(rookie::from_ios_backup(dir.path(), "com.example.app").is_err());
+
+    let empty = tempfile::tempdir().unwrap();
+    let err = rookie::from_ios_backup(empty.path(), "com.example.app").unwrap_err();
+    assert!(err.to_string().contains("no Manifest.db"), "{}", err);
+    // encrypted backups keep an encrypted Manifest.db
+    std::fs::write(empty.path().join("Manifest.db"), [0x8c; 4096]).unwrap();
+    let err = rookie::from_ios_backup(empty.path(), "com.example.app").unwrap_err();
+    assert!(err.to_string().contains("encrypted backups aren't supported"), "{}", err);
+}
+
+#[test]
+fn firefox_android_layouts() {
+    let fixture_cookies = testing::sample_cookies();
+    for layout in ["files/mozilla", "apps/org.mozilla.firefox/f/mozilla"] {
+        let dir = tempfile::tempdir().unwrap();
+        let app_root = dir.path().join("org.mozilla.firefox");
+        testing::write_mozilla_profile(&app_root.join(layout), &fixture_cookies).unwrap();
+        let cookies = rookie::firefox_android(&app_root, None).unwrap();
+        assert_matches_fixture(&cookies, &fixture_cookies);
+
+        // without profiles.ini the profile is globbed
+        std::fs::remove_file(app_root.join(layout).join("profiles.ini")).unwrap();
+        let cookies = rookie::firefox_android(&app_root, ["google.com"]).unwrap();
+        assert!(!cookies.is_empty());
+        assert!(cookies.iter().all(|c| c.domain.contains("google.com")));
+    }
+}
+
+#[test]
+fn duplicate_rows_keep_the_newest() {
+    let fixture_cookies = testing::sample_cookies();
+    for schema in ChromiumSchema::all() {
+        let dir = tempfile::tempdir().unwrap();
+        let fixture = testing::write_chromium_profile(dir.path(), schema, &fixture_cookies).unwrap();
+        testing::insert_duplicate(&fixture.db_path, "id", "newer", 1_800_000_000).unwrap();
+        testing::insert_duplicate(&fixture.db_path, "id", "older", 1_600_000_000).unwrap();
+
+        let cookies = read_chromium(&fixture, None);
+        assert_eq!(cookies.len(), fixture_cookies.len());
+        assert_eq!(find(&cookies, "id").value, "newer");
+
+        let data_paths = testing::chromium_data_paths(dir.path());
+        let data_paths: Vec<&str> = data_paths.iter().map(|p| p.as_str()).collect();
+        let config = BrowserConfig {
+            data_paths: &data_paths,
+            channels: None,
+            os_crypt_name: Some("chromium"),
+            osx_key_service: None,
+            osx_key_user: None,
+        };
+        let options = EnumerationOptions::default();
+        let read = rookie::chromium_profiles(&config, None, &options).unwrap();
+        assert_eq!(read.profiles[0].duplicates, 2);
+        assert_eq!(find(&read.profiles[0].cookies, "id").value, "newer");
+    }
+}
+
+fn with_expired() -> Vec<FixtureCookie> {
+    let mut cookies = testing::sample_cookies();
+    for name in ["old", "older"] {
+        let mut expired = FixtureCookie::new("example.com", name, "expired");
+        expired.expires = Some(1_000_000_000);
+        cookies.push(expired);
+    }
+    cookies
+}
+
+fn names(cookies: &[Cookie]) -> Vec<&str> {
+    let mut names: Vec<&str> = cookies.iter().map(|c| c.name.as_str()).collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn expired_rows_are_skipped_in_the_query() {
+    let fixture_cookies = with_expired();
+    let live = fixture_cookies.len() - 2;
+    for schema in ChromiumSchema::all() {
+        let dir = tempfile::tempdir().unwrap();
+        let fixture = testing::write_chromium_profile(dir.path(), schema, &fixture_cookies).unwrap();
+        let read = |include_expired| {
+            let mut options = ReadOptions::default();
+            options.include_expired = include_expired;
+            read_chromium_with(&fixture, None, options).unwrap().cookies
+        };
+
+        assert_eq!(read(true).len(), fixture_cookies.len());
+        let cookies = read(false);
+        assert_eq!(cookies.len(), live, "{:?}", names(&cookies));
+        // session cookies have no expiry at all and must survive the filter
+        assert_eq!(find(&cookies, "session").expires, None);
+        assert!(!names(&cookies).contains(&"old"));
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    let db_path = testing::write_mozilla_profile(dir.path(), &fixture_cookies).unwrap();
+    let firefox = |include_expired| {
+        let mut options = ReadOptions::default();
+        options.include_expired = include_expired;
+        rookie::browser::mozilla::firefox_read(db_path.clone(), None::<Vec<&str>>, options).unwrap().cookies
+    };
+    assert_eq!(firefox(true).len(), fixture_cookies.len());
+    let cookies = firefox(false);
+    assert_eq!(cookies.len(), live);
+    assert_eq!(find(&cookies, "session").expires, None);
+}
+
+#[test]
+fn future_schema_versions() {
+    let fixture_cookies = testing::sample_cookies();
+    let mut strict = ReadOptions::default();
+    strict.strict_schema = true;
+    let dir = tempfile::tempdir().unwrap();
+    let fixture = testing::write_chromium_profile(dir.path(), ChromiumSchema::V24, &fixture_cookies).unwrap();
+    assert_eq!(read_chromium_with(&fixture, None, strict.clone()).unwrap().schema_version, Some(24));
+
+    let connection = rusqlite::Connection::open(&fixture.db_path).unwrap();
+    connection.execute("UPDATE meta SET value = '99' WHERE key = 'version'", []).unwrap();
+    drop(connection);
+    let lenient = read_chromium_with(&fixture, None, ReadOptions::default()).unwrap();
+    assert_eq!(lenient.schema_version, Some(99));
+    assert_eq!(lenient.cookies.len(), fixture_cookies.len());
+    let err = read_chromium_with(&fixture, None, strict.clone()).unwrap_err();
+    let unsupported = err.downcast_ref::<UnsupportedSchema>().unwrap();
+    assert_eq!((unsupported.version, unsupported.max), (99, rookie::browser::chromium::MAX_TESTED_SCHEMA));
+
+    let db_path = testing::write_mozilla_profile(dir.path(), &fixture_cookies).unwrap();
+    let firefox = |options| rookie::browser::mozilla::firefox_read(db_path.clone(), None::<Vec<&str>>, options);
+    assert_eq!(firefox(strict.clone()).unwrap().schema_version, Some(12));
+    rusqlite::Connection::open(&db_path).unwrap().execute_batch("PRAGMA user_version = 99;").unwrap();
+    let lenient = firefox(ReadOptions::default()).unwrap();
+    assert_eq!(lenient.schema_version, Some(99));
+    assert_eq!(lenient.cookies.len(), fixture_cookies.len());
+    assert!(firefox(strict).unwrap_err().is::<UnsupportedSchema>());
+}
+
+#[test]
+fn host_hash_prefix_is_checked() {
+    let fixture_cookies = testing::sample_cookies();
+    let dir = tempfile::tempdir().unwrap();
+    let fixture = testing::write_chromium_profile(dir.path(), ChromiumSchema::V24, &fixture_cookies).unwrap();
+    let read = read_chromium_with(&fixture, None, ReadOptions::default()).unwrap();
+    assert_eq!(read.undecodable, 0);
+    assert_matches_fixture(&read.cookies, &fixture_cookies);
+
+    // a value moved over from another host
+    let connection = rusqlite::Connection::open(&fixture.db_path).unwrap();
+    let moved = testing::encrypt_value("m0ved", Some("evil.com")).unwrap();
+    connection.execute("UPDATE cookies SET encrypted_value = ?1 WHERE name = 'id'", [moved]).unwrap();
+    let read = read_chromium_with(&fixture, None, ReadOptions::default()).unwrap();
+    assert_eq!(read.undecodable, 1);
+    assert_eq!(read.cookies.len(), fixture_cookies.len() - 1);
+    assert!(read.cookies.iter().all(|cookie| cookie.name != "id"));
+
+    // read as an older schema the hash is left in front, those values are skipped
+    // rather than returned empty
+    connection.execute("UPDATE meta SET value = '23' WHERE key = 'version'", []).unwrap();
+    drop(connection);
+    let read = read_chromium_with(&fixture, None, ReadOptions::default()).unwrap();
+    assert_eq!(read.undecodable, fixture_cookies.len());
+    assert!(read.cookies.is_empty());
+}
+
+#[test]
+fn host_port_domain_filters() {
+    let cookie = |host: &str, name: &str, port: u16| {
+        let mut cookie = FixtureCookie::new(host, name, "v");
+        cookie.port = Some(port);
+        cookie
+    };
+    // row order follows the UNIQUE index, which differs between schemas
+    let sorted = |cookies: &[Cookie]| {
+        let mut names: Vec<String> = cookies.iter().map(|c| c.name.clone()).collect();
+        names.sort();
+        names
+    };
+    let fixture_cookies = vec![
+        cookie("localhost", "dev", 3000),
+        cookie("localhost", "api", 8080),
+        cookie("127.0.0.1", "loop", 3000),
+        cookie("[::1]", "six", 3000),
+        cookie("[::1]", "six_api", 8080)
+    ];
+    for schema in ChromiumSchema::all() {
+        let dir = tempfile::tempdir().unwrap();
+        let fixture = testing::write_chromium_profile(dir.path(), schema, &fixture_cookies).unwrap();
+        let read = |domain: &str| {
+            read_chromium_with(&fixture, Some(vec![domain]), ReadOptions::default()).unwrap()
+        };
+
+        let local = read("localhost");
+        assert_eq!(sorted(&local.cookies), ["api", "dev"]);
+        assert!(!local.ports_ignored);
+        let dev = read("localhost:3000");
+        if schema == ChromiumSchema::V12 {
+            // no source_port column, matched on the host only
+            assert!(dev.ports_ignored);
+            assert_eq!(sorted(&dev.cookies), ["api", "dev"]);
+            assert!(dev.cookies.iter().all(|cookie| cookie.port.is_none()));
+            continue;
+        }
+        assert!(!dev.ports_ignored);
+        assert_eq!(names(&dev.cookies), ["dev"]);
+        assert_eq!(dev.cookies[0].port, Some(3000));
+        assert_eq!(names(&read("localhost:8080").cookies), ["api"]);
+        assert_eq!(names(&read("127.0.0.1:3000").cookies), ["loop"]);
+        assert!(read("127.0.0.1:8080").cookies.is_empty());
+        assert_eq!(names(&read("[::1]:3000").cookies), ["six"]);
+        assert_eq!(names(&read("[::1]:8080").cookies), ["six_api"]);
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    let db_path = testing::write_mozilla_profile(dir.path(), &fixture_cookies).unwrap();
+    let firefox = rookie::browser::mozilla
+        ::firefox_read(db_path, Some(vec!["localhost:3000"]), ReadOptions::default())
+        .unwrap();
+    assert!(firefox.ports_ignored);
+    assert_eq!(sorted(&firefox.cookies), ["api", "dev"]);
+}
+
+#[test]
+fn private_browsing_leftovers_are_excluded() {
+    use rookie::browser::mozilla::{ firefox_read, is_private_origin };
+
+    assert!(is_private_origin("^privateBrowsingId=1"));
+    assert!(is_private_origin("^privateBrowsingId=1&userContextId=2"));
+    assert!(!is_private_origin("^privateBrowsingId=0&userContextId=12"));
+    assert!(!is_private_origin("^userContextId=1"));
+    assert!(!is_private_origin(""));
+
+    let dir = tempfile::tempdir().unwrap();
+    let db_path = testing::write_mozilla_profile(dir.path(), &testing::sample_cookies()).unwrap();
+    let connection = rusqlite::Connection::open(&db_path).unwrap();
+    for (name, origin_attributes) in [
+        ("token", "^privateBrowsingId=1"),
+        ("id", "^privateBrowsingId=1&userContextId=2"),
+        ("empty", "^privateBrowsingId=0&userContextId=3"),
+    ] {
+        connection
+            .execute("UPDATE moz_cookies SET originAttributes = ?1 WHERE name = ?2", [origin_attributes, name])
+            .unwrap();
+    }
+    let session_store = serde_json::json!({
+        "windows": [{
+            "cookies": [
+                { "host": "example.net", "name": "restored", "value": "r", "path": "/" },
+                {
+                    "host": "example.net",
+                    "name": "private",
+                    "value": "p",
+                    "path": "/",
+                    "originAttributes": { "privateBrowsingId": 1 }
+                }
+            ]
+        }]
+    });
+    std::fs::write(db_path.parent().unwrap().join("sessionstore.js"), session_store.to_string()).unwrap();
+
+    let read = |include_private_leftovers| {
+        let mut options = ReadOptions::default();
+        options.include_private_leftovers = include_private_leftovers;
+        firefox_read(db_path.clone(), None::<Vec<&str>>, options).unwrap()
+    };
+    let excluded = read(false);
+    assert_eq!(excluded.private_excluded, 3);
+    let mut names = names(&excluded.cookies);
+    names.sort();
+    assert_eq!(names, ["", "SID", "empty", "restored", "session"]);
+
+    let forensic = read(true);
+    assert_eq!(forensic.private_excluded, 0);
+    assert_eq!(forensic.cookies.len(), 8);
+}
+
+fn copy_dir(from: &std::path::Path, to: &std::path::Path) {
+    std::fs::create_dir_all(to).unwrap();
+    for entry in std::fs::read_dir(from).unwrap() {
+        let path = entry.unwrap().path();
+        let target = to.join(path.file_name().unwrap());
+        if path.is_dir() {
+            copy_dir(&path, &target);
+        } else {
+            std::fs::copy(&path, &target).unwrap();
+        }
+    }
+}
+
+#[test]
+fn extension_cookies_are_attributed() {
+    const VPN: &str = "bihmplhobchoageeokmgbdihknkjbknd";
+    const PASSWORDS: &str = "nngceckbapebfimnlniiiahkandclblb";
+    const REMOVED: &str = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+
+    let dir = tempfile::tempdir().unwrap();
+    let vpn_host = format!("chrome-extension://{}", VPN);
+    let removed_host = format!("chrome-extension://{}", REMOVED);
+    let fixture_cookies = vec![
+        FixtureCookie::new("example.com", "site", "s"),
+        FixtureCookie::new(&vpn_host, "vpn_session", "v"),
+        FixtureCookie::new(".example.com", "vault", "p"),
+        FixtureCookie::new(&removed_host, "leftover", "l")
+    ];
+    let fixture = testing::write_chromium_profile(dir.path(), ChromiumSchema::V21, &fixture_cookies).unwrap();
+    // set by the password manager while embedded in its own extension page
+    rusqlite::Connection
+        ::open(&fixture.db_path)
+        .unwrap()
+        .execute(
+            "UPDATE cookies SET top_frame_site_key = ?1 WHERE name = 'vault'",
+            [format!("chrome-extension://{}", PASSWORDS)]
+        )
+        .unwrap();
+    let profile_dir = dir.path().join("User Data/Default");
+    copy_dir(std::path::Path::new("tests/fixtures/chromium-extensions"), &profile_dir);
+
+    let names_by_id = rookie::browser::chromium::extension_names(&profile_dir);
+    assert_eq!(names_by_id.len(), 2);
+    assert_eq!(names_by_id[VPN], "Touch VPN - Secure and unlimited VPN proxy");
+    // localized through _locales/en/messages.json
+    assert_eq!(names_by_id[PASSWORDS], "Bitwarden Password Manager");
+
+    let read = read_chromium_with(&fixture, None, ReadOptions::default()).unwrap();
+    assert_eq!(names(&read.cookies), ["site"]);
+
+    let mut options = ReadOptions::default();
+    options.include_extensions = true;
+    let read = read_chromium_with(&fixture, None, options).unwrap();
+    let extension = |name| find(&read.cookies, name).extension.clone();
+    assert_eq!(extension("site"), None);
+    let vpn = extension("vpn_session").unwrap();
+    assert_eq!((vpn.id.as_str(), vpn.name.as_deref()), (VPN, Some("Touch VPN - Secure and unlimited VPN proxy")));
+    let vault = extension("vault").unwrap();
+    assert_eq!((vault.id.as_str(), vault.name.as_deref()), (PASSWORDS, Some("Bitwarden Password Manager")));
+    let leftover = extension("leftover").unwrap();
+    assert_eq!((leftover.id.as_str(), leftover.name), (REMOVED, None));
+}
+
+fn read_yandex(fixture: &testing::ChromiumFixture) -> anyhow::Result<Vec<Cookie>> {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            rookie::browser::yandex::yandex_based(fixture.key_path.clone(), fixture.db_path.clone(), None::<Vec<&str>>)
+        } else {
+            let config = BrowserConfig {
+                data_paths: &[],
+                channels: None,
+                os_crypt_name: Some("yandex"),
+                osx_key_service: None,
+                osx_key_user: None,
+            };
+            rookie::browser::yandex::yandex_based_with(
+                &config,
+                fixture.db_path.clone(),
+                None::<Vec<&str>>,
+                &testing::CountingSecrets::default()
+            )
+        }
+    }
+}
+
+#[test]
+fn yandex_data_key() {
+    use rookie::browser::yandex::MasterPasswordRequired;
+
+    let dir = tempfile::tempdir().unwrap();
+    let fixture_cookies = testing::sample_cookies();
+    let fixture = testing::write_yandex_profile(dir.path(), &fixture_cookies, false).unwrap();
+    assert_matches_fixture(&read_yandex(&fixture).unwrap(), &fixture_cookies);
+    // the os_crypt key alone doesn't open the values
+    assert!(read_chromium_with(&fixture, None, ReadOptions::default()).is_err());
+
+    let locked_dir = tempfile::tempdir().unwrap();
+    let locked = testing::write_yandex_profile(locked_dir.path(), &fixture_cookies, true).unwrap();
+    let err = read_yandex(&locked).unwrap_err();
+    assert!(err.is::<MasterPasswordRequired>(), "{:#}", err);
+    assert!(err.to_string().contains("master password"));
+}
+
+#[test]
+fn unprefixed_values_pass_through() {
+    let dir = tempfile::tempdir().unwrap();
+    let fixture_cookies = testing::sample_cookies();
+    let fixture = testing::write_chromium_profile(dir.path(), ChromiumSchema::V21, &fixture_cookies).unwrap();
+    // 360 Secure Browser keeps some values as bare text in encrypted_value
+    let connection = rusqlite::Connection::open(&fixture.db_path).unwrap();
+    connection
+        .execute("UPDATE cookies SET value = '', encrypted_value = CAST('pl41n' AS BLOB) WHERE name = 'token'", [])
+        .unwrap();
+    drop(connection);
+
+    let cookies = read_chromium_with(&fixture, None, ReadOptions::default()).unwrap().cookies;
+    assert_eq!(find(&cookies, "token").value, "pl41n");
+    assert_eq!(cookies.len(), fixture_cookies.len());
+}
+
+#[test]
+fn cleared_cookies_db_reads_as_empty() {
+    // Epic clears the table on exit, the DB stays
+    let dir = tempfile::tempdir().unwrap();
+    let fixture = testing::write_chromium_profile(dir.path(), ChromiumSchema::V21, &[]).unwrap();
+    let read = read_chromium_with(&fixture, None, ReadOptions::default()).unwrap();
+    assert!(read.cookies.is_empty());
+    assert!(read_chromium(&fixture, Some(vec!["example.com"])).is_empty());
+
+    std::fs::write(&fixture.db_path, b"").unwrap();
+    let read = read_chromium_with(&fixture, None, ReadOptions::default()).unwrap();
+    assert!(read.cookies.is_empty());
+}
+
+#[test]
+fn epiphany_cookies() {
+    let fixture_cookies = testing::sample_cookies();
+    for same_site in [true, false] {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("cookies.sqlite");
+        testing::write_epiphany_cookies(&db_path, &fixture_cookies, same_site).unwrap();
+        let cookies = rookie::browser::epiphany
+            ::epiphany_based(db_path.clone(), None::<Vec<&str>>)
+            .unwrap();
+        assert_matches_fixture(&cookies, &fixture_cookies);
+        assert_eq!(find(&cookies, "session").expires, None);
+        assert_eq!(find(&cookies, "id").expires, Some(4_102_444_800));
+
+        let cookies = rookie::browser::epiphany
+            ::epiphany_based(db_path, Some(vec!["google.com"]))
+            .unwrap();
+        assert!(cookies.iter().all(|c| c.domain.contains("google.com")));
+    }
+}
+
+fn same_site_cookies(raw_values: &[i64]) -> Vec<FixtureCookie> {
+    raw_values
+        .iter()
+        .map(|raw| {
+            let mut cookie = FixtureCookie::new("example.com", &format!("c{}", raw), "v");
+            cookie.same_site = *raw;
+            cookie
+        })
+        .collect()
+}
+
+fn same_site_of(cookies: &[Cookie], raw: i64) -> (SameSite, Option<i64>) {
+    let cookie = find(cookies, &format!("c{}", raw));
+    (cookie.same_site, cookie.same_site_raw)
+}
+
+#[test]
+fn same_site_is_decoded_per_backend() {
+    let dir = tempfile::tempdir().unwrap();
+    let fixture = testing
+        ::write_chromium_profile(dir.path(), ChromiumSchema::V21, &same_site_cookies(&[-1, 0, 1, 2]))
+        .unwrap();
+    let cookies = read_chromium(&fixture, None);
+    assert_eq!(same_site_of(&cookies, -1), (SameSite::Unspecified, Some(-1)));
+    assert_eq!(same_site_of(&cookies, 0), (SameSite::None, Some(0)));
+    assert_eq!(same_site_of(&cookies, 1), (SameSite::Lax, Some(1)));
+    assert_eq!(same_site_of(&cookies, 2), (SameSite::Strict, Some(2)));
+
+    let dir = tempfile::tempdir().unwrap();
+    let db_path = testing::write_mozilla_profile(dir.path(), &same_site_cookies(&[0, 1, 2])).unwrap();
+    let cookies = rookie::browser::mozilla::firefox_based(db_path, None).unwrap();
+    assert_eq!(same_site_of(&cookies, 0), (SameSite::None, Some(0)));
+    assert_eq!(same_site_of(&cookies, 1), (SameSite::Lax, Some(1)));
+    assert_eq!(same_site_of(&cookies, 2), (SameSite::Strict, Some(2)));
+
+    // libsoup before 2.70 and safari don't record it at all
+    let db_path = dir.path().join("epiphany.sqlite");
+    testing::write_epiphany_cookies(&db_path, &same_site_cookies(&[1]), false).unwrap();
+    let cookies = rookie::browser::epiphany::epiphany_based(db_path, None::<Vec<&str>>).unwrap();
+    assert_eq!(same_site_of(&cookies, 1), (SameSite::Unspecified, None));
+    let path = dir.path().join("Cookies.binarycookies");
+    testing::write_binarycookies(&path, &same_site_cookies(&[1])).unwrap();
+    let cookies = rookie::binarycookies(&path, None).unwrap();
+    assert_eq!(same_site_of(&cookies, 1), (SameSite::Unspecified, None));
+
+    assert_eq!(i64::from(SameSite::from(-1)), -1);
+    assert_eq!(SameSite::from_mozilla(256), SameSite::Unspecified);
+}
+
+#[test]
+fn creation_and_last_access_are_unix_seconds() {
+    let timestamps = |cookies: &[Cookie]| {
+        cookies
+            .iter()
+            .map(|cookie| (cookie.creation, cookie.last_accessed))
+            .collect::<Vec<_>>()
+    };
+    let dir = tempfile::tempdir().unwrap();
+    let fixture = testing
+        ::write_chromium_profile(dir.path(), ChromiumSchema::V21, &testing::sample_cookies())
+        .unwrap();
+    let cookies = read_chromium(&fixture, None);
+    assert!(!cookies.is_empty());
+    assert!(timestamps(&cookies).iter().all(|times| *times == (Some(1_700_000_000), Some(1_700_000_000))));
+
+    let dir = tempfile::tempdir().unwrap();
+    let db_path = testing::write_mozilla_profile(dir.path(), &testing::sample_cookies()).unwrap();
+    let cookies = rookie::browser::mozilla::firefox_based(db_path, None).unwrap();
+    assert!(timestamps(&cookies).iter().all(|times| *times == (Some(1_700_000_000), Some(1_700_000_000))));
+
+    let path = dir.path().join("Cookies.binarycookies");
+    testing::write_binarycookies(&path, &testing::sample_cookies()).unwrap();
+    let cookies = rookie::binarycookies(&path, None).unwrap();
+    assert!(timestamps(&cookies).iter().all(|times| *times == (Some(1_700_000_000), None)));
+
+    let db_path = dir.path().join("epiphany.sqlite");
+    testing::write_epiphany_cookies(&db_path, &testing::sample_cookies(), true).unwrap();
+    let cookies = rookie::browser::epiphany::epiphany_based(db_path, None::<Vec<&str>>).unwrap();
+    assert!(timestamps(&cookies).iter().all(|times| *times == (None, None)));
+}
+
+#[test]
+fn partitioned_cookies_carry_their_top_level_site() {
+    let fixture_cookies = vec![FixtureCookie::new(".embed.com", "plain", "p"), FixtureCookie::new(".embed.com", "chips", "c")];
+    let partition_keys = |cookies: &[Cookie]| {
+        cookies
+            .iter()
+            .map(|cookie| (cookie.name.clone(), cookie.partition_key.clone()))
+            .collect::<Vec<_>>()
+    };
+    let expected = [("plain".to_string(), None), ("chips".to_string(), Some("https://example.com".to_string()))];
+    let mut excluded = ReadOptions::default();
+    excluded.include_partitioned = false;
+
+    let dir = tempfile::tempdir().unwrap();
+    let fixture = testing::write_chromium_profile(dir.path(), ChromiumSchema::V21, &fixture_cookies).unwrap();
+    rusqlite::Connection
+        ::open(&fixture.db_path)
+        .unwrap()
+        .execute("UPDATE cookies SET top_frame_site_key = 'https://example.com' WHERE name = 'chips'", [])
+        .unwrap();
+    let read = read_chromium_with(&fixture, None, ReadOptions::default()).unwrap();
+    assert_eq!(partition_keys(&read.cookies), expected);
+    let read = read_chromium_with(&fixture, None, excluded.clone()).unwrap();
+    assert_eq!(names(&read.cookies), ["plain"]);
+
+    let db_path = testing::write_mozilla_profile(dir.path(), &fixture_cookies).unwrap();
+    rusqlite::Connection
+        ::open(&db_path)
+        .unwrap()
+        .execute(
+            "UPDATE moz_cookies SET originAttributes = '^partitionKey=%28https%2Cexample.com%29' WHERE name = 'chips'",
+            []
+        )
+        .unwrap();
+    let firefox = |options| rookie::browser::mozilla::firefox_read(db_path.clone(), None::<Vec<&str>>, options).unwrap();
+    assert_eq!(partition_keys(&firefox(ReadOptions::default()).cookies), expected);
+    assert_eq!(names(&firefox(excluded).cookies), ["plain"]);
+
+    let origin_partition_key = rookie::browser::mozilla::origin_partition_key;
+    assert_eq!(
+        origin_partition_key("^partitionKey=%28http%2Clocalhost%2C8080%2Cf%29&userContextId=2").as_deref(),
+        Some("http://localhost:8080")
+    );
+    assert_eq!(origin_partition_key("^userContextId=2"), None);
+}
+
+#[test]
+fn expiry_is_normalized_to_unix_seconds() {
+    use rookie::common::date;
+    // 2023-11-14 22:13:20 UTC in each browser's own format
+    assert_eq!(date::chromium_timestamp(13_344_473_600_000_000), Some(1_700_000_000));
+    assert_eq!(date::mozilla_timestamp(1_700_000_000), Some(1_700_000_000));
+    assert_eq!(date::safari_timestamp(721_692_800.0), Some(1_700_000_000));
+    #[cfg(target_os = "windows")]
+    assert_eq!(date::internet_explorer_timestamp(133_444_736_000_000_000), Some(1_700_000_000));
+    // 0 is how chromium and firefox store session cookies
+    assert_eq!(date::chromium_timestamp(0), None);
+    assert_eq!(date::mozilla_timestamp(0), None);
+
+    let dir = tempfile::tempdir().unwrap();
+    let db_path = testing::write_mozilla_profile(dir.path(), &testing::sample_cookies()).unwrap();
+    let cookies = rookie::browser::mozilla::firefox_based(db_path, None).unwrap();
+    let mut cookie = cookies.into_iter().find(|cookie| cookie.name == "session").unwrap();
+    assert!(!cookie.is_expired());
+    assert_eq!(cookie.expires_datetime(), None);
+    cookie.expires = Some(1_700_000_000);
+    assert!(cookie.is_expired());
+    assert_eq!(cookie.expires_datetime(), Some(std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000)));
+    cookie.expires = Some(date::now() + 3600);
+    assert!(!cookie.is_expired());
+}
+
+#[test]
+fn host_only_is_read_from_each_backend() {
+    let host_only = |cookies: &[Cookie]| {
+        let mut flags = cookies
+            .iter()
+            .map(|cookie| (cookie.domain.clone(), cookie.host_only))
+            .collect::<Vec<_>>();
+        flags.sort();
+        flags.dedup();
+        flags
+    };
+    let expected = [
+        (".google.com".to_string(), false),
+        ("api.example.com".to_string(), true),
+        ("example.com".to_string(), true),
+        ("example.org".to_string(), true),
+        ("notgoogle.com".to_string(), true),
+    ];
+
+    let dir = tempfile::tempdir().unwrap();
+    let fixture = testing
+        ::write_chromium_profile(dir.path(), ChromiumSchema::V21, &testing::sample_cookies())
+        .unwrap();
+    assert_eq!(host_only(&read_chromium(&fixture, None)), expected);
+
+    let dir = tempfile::tempdir().unwrap();
+    let db_path = testing::write_mozilla_profile(dir.path(), &testing::sample_cookies()).unwrap();
+    let cookies = rookie::browser::mozilla::firefox_based(db_path, None).unwrap();
+    assert_eq!(host_only(&cookies), expected);
+
+    let path = dir.path().join("Cookies.binarycookies");
+    testing::write_binarycookies(&path, &testing::sample_cookies()).unwrap();
+    let cookies = rookie::binarycookies(&path, None).unwrap();
+    assert_eq!(host_only(&cookies), expected);
+}
+
+#[test]
+fn source_scheme_is_read_when_recorded() {
+    let dir = tempfile::tempdir().unwrap();
+    let fixture = testing
+        ::write_chromium_profile(dir.path(), ChromiumSchema::V21, &testing::sample_cookies())
+        .unwrap();
+    let cookies = read_chromium(&fixture, None);
+    assert_eq!(find(&cookies, "SID").source_scheme, SourceScheme::Secure);
+    assert_eq!(find(&cookies, "session").source_scheme, SourceScheme::NonSecure);
+
+    // older schemas don't have the column
+    let dir = tempfile::tempdir().unwrap();
+    let fixture = testing
+        ::write_chromium_profile(dir.path(), ChromiumSchema::V12, &testing::sample_cookies())
+        .unwrap();
+    rusqlite::Connection
+        ::open(&fixture.db_path)
+        .unwrap()
+        .execute_batch("ALTER TABLE cookies DROP COLUMN source_scheme;")
+        .unwrap();
+    let cookies = read_chromium(&fixture, None);
+    assert!(cookies.iter().all(|cookie| cookie.source_scheme == SourceScheme::Unset));
+
+    let db_path = testing::write_mozilla_profile(dir.path(), &testing::sample_cookies()).unwrap();
+    let cookies = rookie::browser::mozilla::firefox_based(db_path, None).unwrap();
+    assert!(cookies.iter().all(|cookie| cookie.source_scheme.is_unset()));
+}
+
+#[test]
+fn name_filter_is_applied_before_decrypting() {
+    let mut fixture_cookies = testing::sample_cookies();
+    fixture_cookies.push(FixtureCookie::new("example.com", "50%_off", "promo"));
+    let dir = tempfile::tempdir().unwrap();
+    let fixture = testing::write_chromium_profile(dir.path(), ChromiumSchema::V21, &fixture_cookies).unwrap();
+    // only decrypting this one would fail the read
+    rusqlite::Connection
+        ::open(&fixture.db_path)
+        .unwrap()
+        .execute("UPDATE cookies SET encrypted_value = X'7631300000' WHERE name = 'id'", [])
+        .unwrap();
+    assert!(read_chromium_with(&fixture, None, ReadOptions::default()).is_err());
+
+    let read = |names: NameFilter| read_chromium_filtered(&fixture, None, &names, ReadOptions::default()).unwrap().cookies;
+    assert_eq!(names(&read(NameFilter::exact(["SID", "token"]))), ["SID", "token"]);
+    assert!(read(NameFilter::exact(["sid"])).is_empty());
+    // % and _ are plain characters, not LIKE wildcards
+    assert_eq!(names(&read(NameFilter::prefix(["50%_"]))), ["50%_off"]);
+    assert!(read(NameFilter::prefix(["5_"])).is_empty());
+
+    let db_path = testing::write_mozilla_profile(dir.path(), &fixture_cookies).unwrap();
+    let firefox = |names: NameFilter| {
+        rookie::browser::mozilla
+            ::firefox_read_filtered(db_path.clone(), None::<Vec<&str>>, &names, ReadOptions::default())
+            .unwrap().cookies
+    };
+    assert_eq!(names(&firefox(NameFilter::prefix(["to", "se"]))), ["session", "token"]);
+    assert_eq!(firefox(NameFilter::default()).len(), fixture_cookies.len());
+}
+
+#[test]
+fn domains_match_on_label_boundaries() {
+    let fixture_cookies = vec![
+        FixtureCookie::new(".google.com", "google", "1"),
+        FixtureCookie::new("accounts.google.com", "accounts", "2"),
+        FixtureCookie::new("notgoogle.com", "notgoogle", "3"),
+        FixtureCookie::new(".example.co.uk", "example", "4"),
+        FixtureCookie::new("shop.example.co.uk", "shop", "5"),
+        FixtureCookie::new("notexample.co.uk", "notexample", "6"),
+        FixtureCookie::new("xn--bcher-kva.de", "idn", "7"),
+        FixtureCookie::new("127.0.0.10", "ip", "8")
+    ];
+    let sorted = |cookies: &[Cookie]| {
+        let mut names = names(cookies);
+        names.sort();
+        names.into_iter().map(str::to_string).collect::<Vec<_>>()
+    };
+    let cases = [
+        (vec!["google.com"], vec!["accounts", "google"]),
+        (vec!["GOOGLE.com."], vec!["accounts", "google"]),
+        (vec![".example.co.uk"], vec!["example", "shop"]),
+        (vec!["bücher.de"], vec!["idn"]),
+        (vec!["gle.com", "co.uk"], vec!["example", "notexample", "shop"]),
+        (vec!["0.0.10"], vec![]),
+        (vec!["127.0.0.10"], vec!["ip"]),
+    ];
+    let mut exact = ReadOptions::default();
+    exact.domain_match = DomainMatch::Exact;
+
+    let dir = tempfile::tempdir().unwrap();
+    let fixture = testing::write_chromium_profile(dir.path(), ChromiumSchema::V21, &fixture_cookies).unwrap();
+    for (domains, expected) in &cases {
+        assert_eq!(sorted(&read_chromium(&fixture, Some(domains.clone()))), *expected, "{:?}", domains);
+    }
+    let read = read_chromium_with(&fixture, Some(vec!["google.com", "example.co.uk"]), exact.clone()).unwrap();
+    assert_eq!(sorted(&read.cookies), ["example", "google"]);
+
+    let db_path = testing::write_mozilla_profile(dir.path(), &fixture_cookies).unwrap();
+    for (domains, expected) in &cases {
+        let cookies = rookie::browser::mozilla::firefox_based(db_path.clone(), domains).unwrap();
+        assert_eq!(sorted(&cookies), *expected, "{:?}", domains);
+    }
+    let read = rookie::browser::mozilla::firefox_read(db_path, ["accounts.google.com"], exact).unwrap();
+    assert_eq!(sorted(&read.cookies), ["accounts"]);
+
+    let path = dir.path().join("Cookies.binarycookies");
+    testing::write_binarycookies(&path, &fixture_cookies).unwrap();
+    for (domains, expected) in &cases {
+        let cookies = rookie::binarycookies(&path, domains).unwrap();
+        assert_eq!(sorted(&cookies), *expected, "{:?}", domains);
+    }
+
+    let domain_matches = rookie::common::utils::domain_matches;
+    assert!(domain_matches("[::1]", "[::1]", DomainMatch::Suffix));
+    assert!(!domain_matches("127.0.0.10", "0.0.10", DomainMatch::Suffix));
+    assert!(domain_matches(".Bücher.de", "xn--bcher-kva.de", DomainMatch::Exact));
+
+    // the pre-suffix-matching signature still compiles, with label boundaries now
+    let some_domain_in_host = rookie::common::utils::some_domain_in_host;
+    assert!(some_domain_in_host(Some(vec!["google.com"]), ".mail.google.com"));
+    assert!(!some_domain_in_host(Some(vec!["google.com"]), "notgoogle.com"));
+    assert!(!some_domain_in_host(None, "google.com"));
+    let owned = vec!["google.com".to_string()];
+    assert!(rookie::common::utils::some_domain_in_host(&owned, "google.com"));
+    assert!(rookie::common::utils::some_domain_in_host(["google.com"], "google.com"));
+    let from_args: Option<Vec<String>> = None;
+    assert!(!rookie::common::utils::some_domain_in_host(rookie::Domains::new(from_args), "google.com"));
+}
+
+#[test]
+fn persistence_filter_by_backend() {
+    let mut fixture_cookies = testing::sample_cookies();
+    let mut expired = FixtureCookie::new("example.com", "expired", "old");
+    expired.expires = Some(1_000_000_000);
+    fixture_cookies.push(expired);
+    let options = |persistence, include_expired| {
+        let mut options = ReadOptions::default();
+        options.persistence = persistence;
+        options.include_expired = include_expired;
+        options
+    };
+    let sorted = |cookies: &[Cookie]| {
+        let mut names = names(cookies);
+        names.sort();
+        names.into_iter().map(str::to_string).collect::<Vec<_>>()
+    };
+    let cases = [
+        (options(Persistence::SessionOnly, true), vec!["session"]),
+        // a session cookie is never expired
+        (options(Persistence::SessionOnly, false), vec!["session"]),
+        (options(Persistence::PersistentOnly, true), vec!["", "SID", "empty", "expired", "id", "token"]),
+        (options(Persistence::PersistentOnly, false), vec!["", "SID", "empty", "id", "token"]),
+    ];
+
+    let dir = tempfile::tempdir().unwrap();
+    let fixture = testing::write_chromium_profile(dir.path(), ChromiumSchema::V21, &fixture_cookies).unwrap();
+    for (options, expected) in &cases {
+        let read = read_chromium_with(&fixture, None, options.clone()).unwrap();
+        assert_eq!(sorted(&read.cookies), *expected, "{:?}", options);
+    }
+    let legacy_dir = tempfile::tempdir().unwrap();
+    let fixture = testing::write_chromium_profile(legacy_dir.path(), ChromiumSchema::V12, &fixture_cookies).unwrap();
+    let read = read_chromium_with(&fixture, None, options(Persistence::SessionOnly, true)).unwrap();
+    assert_eq!(sorted(&read.cookies), ["session"]);
+
+    let db_path = testing::write_mozilla_profile(dir.path(), &fixture_cookies).unwrap();
+    for (options, expected) in &cases {
+        let read = rookie::browser::mozilla::firefox_read(db_path.clone(), None::<Vec<&str>>, options.clone()).unwrap();
+        assert_eq!(sorted(&read.cookies), *expected, "{:?}", options);
+    }
+}
+
+#[test]
+fn path_filter_stops_at_slashes() {
+    let fixture_cookies: Vec<_> = ["/", "/api", "/api/", "/api/v1", "/apiv2"]
+        .iter()
+        .map(|path| {
+            let mut cookie = FixtureCookie::new("example.com", path, "v");
+            cookie.path = path.to_string();
+            cookie
+        })
+        .collect();
+    let paths = |cookies: &[Cookie]| {
+        let mut paths: Vec<_> = cookies
+            .iter()
+            .map(|cookie| cookie.path.clone())
+            .collect();
+        paths.sort();
+        paths
+    };
+    let cases = [
+        ("/api", vec!["/api", "/api/", "/api/v1"]),
+        ("/api/", vec!["/api/", "/api/v1"]),
+        ("/apiv2", vec!["/apiv2"]),
+        ("/", vec!["/", "/api", "/api/", "/api/v1", "/apiv2"]),
+    ];
+
+    let dir = tempfile::tempdir().unwrap();
+    let fixture = testing::write_chromium_profile(dir.path(), ChromiumSchema::V21, &fixture_cookies).unwrap();
+    let db_path = testing::write_mozilla_profile(dir.path(), &fixture_cookies).unwrap();
+    for (path, expected) in &cases {
+        let mut options = ReadOptions::default();
+        options.path = Some(path.to_string());
+        let read = read_chromium_with(&fixture, None, options.clone()).unwrap();
+        assert_eq!(paths(&read.cookies), *expected, "{}", path);
+        let read = rookie::browser::mozilla::firefox_read(db_path.clone(), None::<Vec<&str>>, options).unwrap();
+        assert_eq!(paths(&read.cookies), *expected, "{}", path);
+    }
+}
+
+#[test]
+fn secure_and_http_only_filters() {
+    let fixture_cookies = testing::sample_cookies();
+    let options = |secure_only, http_only| {
+        let mut options = ReadOptions::default();
+        options.secure_only = secure_only;
+        options.http_only = http_only;
+        options
+    };
+    let sorted = |cookies: &[Cookie]| {
+        let mut names = names(cookies);
+        names.sort();
+        names.into_iter().map(str::to_string).collect::<Vec<_>>()
+    };
+    let cases = [
+        (options(true, None), vec!["SID"]),
+        (options(false, Some(true)), vec!["session"]),
+        (options(false, Some(false)), vec!["", "SID", "empty", "id", "token"]),
+        (options(true, Some(true)), vec![]),
+    ];
+
+    let dir = tempfile::tempdir().unwrap();
+    let fixture = testing::write_chromium_profile(dir.path(), ChromiumSchema::V21, &fixture_cookies).unwrap();
+    let db_path = testing::write_mozilla_profile(dir.path(), &fixture_cookies).unwrap();
+    for (options, expected) in &cases {
+        let read = read_chromium_with(&fixture, None, options.clone()).unwrap();
+        assert_eq!(sorted(&read.cookies), *expected, "{:?}", options);
+        let read = rookie::browser::mozilla::firefox_read(db_path.clone(), None::<Vec<&str>>, options.clone()).unwrap();
+        assert_eq!(sorted(&read.cookies), *expected, "{:?}", options);
+    }
+}
+
+#[test]
+fn expiry_window_skips_session_cookies() {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    let expiring = |domain: &str, name: &str, expires: Option<u64>| {
+        let mut cookie = FixtureCookie::new(domain, name, "v");
+        cookie.expires = expires;
+        cookie
+    };
+    let fixture_cookies = vec![
+        expiring("example.com", "session", None),
+        expiring("example.com", "soon", Some(now + 3600)),
+        expiring("example.com", "later", Some(now + 10 * 86400)),
+        expiring("example.com", "expired", Some(1_000_000_000)),
+        expiring("example.org", "other", Some(now + 3600))
+    ];
+    let day = Duration::from_secs(86400);
+    let sorted = |cookies: &[Cookie]| {
+        let mut names = names(cookies);
+        names.sort();
+        names.into_iter().map(str::to_string).collect::<Vec<_>>()
+    };
+    let options = |expires_before, expires_within, include_expired| {
+        let mut options = ReadOptions::default();
+        options.expires_before = expires_before;
+        options.expires_within = expires_within;
+        options.include_expired = include_expired;
+        options
+    };
+    let cases = [
+        (options(None, Some(day), true), vec!["expired", "other", "soon"]),
+        (options(Some(SystemTime::now() + day), None, false), vec!["other", "soon"]),
+        // the earlier of both applies
+        (options(Some(SystemTime::now() + 30 * day), Some(day), true), vec!["expired", "other", "soon"]),
+    ];
+
+    let dir = tempfile::tempdir().unwrap();
+    let fixture = testing::write_chromium_profile(dir.path(), ChromiumSchema::V21, &fixture_cookies).unwrap();
+    let db_path = testing::write_mozilla_profile(dir.path(), &fixture_cookies).unwrap();
+    let firefox = |domains: Option<Vec<&str>>, options| rookie::browser::mozilla::firefox_read(db_path.clone(), domains, options).unwrap();
+    for (options, expected) in &cases {
+        let read = read_chromium_with(&fixture, None, options.clone()).unwrap();
+        assert_eq!(sorted(&read.cookies), *expected, "{:?}", options);
+        assert_eq!(sorted(&firefox(None, options.clone()).cookies), *expected, "{:?}", options);
+    }
+
+    let mut options = ReadOptions::default();
+    options.expires_within = Some(day);
+    options.include_expired = false;
+    let read = read_chromium_with(&fixture, Some(vec!["example.com"]), options.clone()).unwrap();
+    assert_eq!(sorted(&read.cookies), ["soon"]);
+    assert_eq!(sorted(&firefox(Some(vec!["example.com"]), options).cookies), ["soon"]);
+}
+
+#[test]
+fn pages_are_sorted_before_limiting() {
+    let expiring = |domain: &str, name: &str, expires: Option<u64>| {
+        let mut cookie = FixtureCookie::new(domain, name, "v");
+        cookie.expires = expires;
+        cookie
+    };
+    let fixture_cookies = vec![
+        expiring("d.example", "d", Some(2_000_000_000)),
+        expiring("chrome-extension://bihmplhobchoageeokmgbdihknkjbknd", "vpn", Some(1_500_000_000)),
+        expiring("a.example", "a", Some(4_000_000_000)),
+        expiring("c.example", "c", None),
+        expiring("b.example", "b2", Some(3_000_000_000)),
+        expiring("b.example", "b1", Some(3_000_000_000)),
+        expiring("e.example", "e", Some(1_000_000_000))
+    ];
+    let page = |sort_by, offset, limit| {
+        let mut options = ReadOptions::default();
+        options.sort_by = sort_by;
+        options.offset = offset;
+        options.limit = limit;
+        options
+    };
+    let pages = |read: &dyn Fn(ReadOptions) -> Vec<Cookie>, sort_by| {
+        [page(sort_by, 0, Some(2)), page(sort_by, 2, Some(2)), page(sort_by, 4, None)].map(|options| {
+            read(options)
+                .iter()
+                .map(|cookie| cookie.name.clone())
+                .collect::<Vec<_>>()
+        })
+    };
+    let by_domain = [vec!["a", "b1"], vec!["b2", "c"], vec!["d", "e"]];
+    let by_expiry = [vec!["c", "e"], vec!["d", "b1"], vec!["b2", "a"]];
+
+    let dir = tempfile::tempdir().unwrap();
+    let fixture = testing::write_chromium_profile(dir.path(), ChromiumSchema::V21, &fixture_cookies).unwrap();
+    // extension cookies are skipped by default and mustn't shorten a page
+    let chromium = |options| read_chromium_with(&fixture, None, options).unwrap().cookies;
+    assert_eq!(pages(&chromium, None), by_domain);
+    assert_eq!(pages(&chromium, Some(SortBy::Domain)), by_domain);
+    assert_eq!(pages(&chromium, Some(SortBy::Expiry)), by_expiry);
+    assert_eq!(names(&chromium(page(Some(SortBy::Expiry), 10, None))), Vec::<&str>::new());
+
+    let db_path = testing::write_mozilla_profile(dir.path(), &fixture_cookies).unwrap();
+    rusqlite::Connection
+        ::open(&db_path)
+        .unwrap()
+        .execute("UPDATE moz_cookies SET originAttributes = '^privateBrowsingId=1' WHERE name = 'vpn'", [])
+        .unwrap();
+    let firefox = |options| rookie::browser::mozilla::firefox_read(db_path.clone(), None::<Vec<&str>>, options).unwrap().cookies;
+    assert_eq!(pages(&firefox, Some(SortBy::Domain)), by_domain);
+    assert_eq!(pages(&firefox, Some(SortBy::Expiry)), by_expiry);
+
+    // session store cookies are paged together with the DB ones
+    let session_store = serde_json::json!({
+        "windows": [{ "cookies": [{ "host": "b.example", "name": "b0", "value": "r", "path": "/" }] }]
+    });
+    std::fs::write(db_path.parent().unwrap().join("sessionstore.js"), session_store.to_string()).unwrap();
+    assert_eq!(pages(&firefox, None), [vec!["a", "b0"], vec!["b1", "b2"], vec!["c", "d", "e"]]);
+}
+
+#[test]
+fn chromium_expiry_before_unix_epoch() {
+    let dir = tempfile::tempdir().unwrap();
+    let fixture = testing::write_chromium_profile(dir.path(), ChromiumSchema::V21, &testing::sample_cookies()).unwrap();
+    rusqlite::Connection
+        ::open(&fixture.db_path)
+        .unwrap()
+        .execute("UPDATE cookies SET expires_utc = 1 WHERE name = 'id'", [])
+        .unwrap();
+    // there's no unix time before 1970, it used to underflow
+    let cookies = read_chromium(&fixture, None);
+    assert_eq!(find(&cookies, "id").expires, None);
+}
@@ -0,0 +1,497 @@
+use rookie::common::enums::{
+    BrowserConfig,
+    BrowserVersion,
+    EnumerationOptions,
+    ProfileCookies,
+    ProfileIdentity,
+    VersionSource,
+};
+use rookie::browser::chromium::{ self, AdditionalPasswordRequired };
+use rookie::common::{ paths, provenance };
+use rookie::profiles::ProfileNotFound;
+use rookie::testing::{ self, ChromiumSchema };
+use std::path::Path;
+
+fn fixture_config<'a>(data_paths: &'a [&'a str]) -> BrowserConfig<'a> {
+    BrowserConfig {
+        data_paths,
+        channels: None,
+        os_crypt_name: Some("chromium"),
+        osx_key_service: None,
+        osx_key_user: None,
+    }
+}
+
+fn read_profiles(config: &BrowserConfig, options: &EnumerationOptions) -> Vec<ProfileCookies> {
+    let read = rookie::chromium_profiles(config, None, options).unwrap();
+    assert!(read.failures.is_empty(), "{:?}", read.failures);
+    read.profiles
+}
+
+fn profile_names(root: &Path, options: &EnumerationOptions) -> Vec<String> {
+    let data_paths = testing::chromium_data_paths(root);
+    let data_paths: Vec<&str> = data_paths.iter().map(|p| p.as_str()).collect();
+    let config = fixture_config(&data_paths);
+    let mut names: Vec<String> = paths
+        ::find_chrome_based_paths_v2(&config, options)
+        .unwrap()
+        .iter()
+        .map(|(_, db_path)| paths::chrome_profile_dir(db_path).unwrap())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn special_profiles_follow_enumeration_options() {
+    let dir = tempfile::tempdir().unwrap();
+    let cookies = testing::sample_cookies();
+    for profile in ["Default", "Profile 1", "Guest Profile", "System Profile", "Tor Profile"] {
+        testing::write_chromium_profile_in(dir.path(), profile, ChromiumSchema::V21, &cookies).unwrap();
+    }
+    testing
+        ::set_profile_info(dir.path(), "Profile 1", serde_json::json!({ "is_ephemeral": true }))
+        .unwrap();
+    testing
+        ::set_profile_info(dir.path(), "Default", serde_json::json!({ "is_ephemeral": false }))
+        .unwrap();
+
+    let cases = [
+        ((false, false, false, false), vec!["Default"]),
+        ((true, false, false, false), vec!["Default", "Guest Profile"]),
+        ((false, true, false, false), vec!["Default", "System Profile"]),
+        ((false, false, true, false), vec!["Default", "Profile 1"]),
+        ((false, false, false, true), vec!["Default", "Tor Profile"]),
+        (
+            (true, true, true, true),
+            vec!["Default", "Guest Profile", "Profile 1", "System Profile", "Tor Profile"],
+        ),
+    ];
+    for ((include_guest, include_system, include_ephemeral, include_tor), expected) in cases {
+        let options = EnumerationOptions {
+            include_guest,
+            include_system,
+            include_ephemeral,
+            include_tor,
+            ..Default::default()
+        };
+        assert_eq!(profile_names(dir.path(), &options), expected, "{:?}", options);
+    }
+}
+
+#[test]
+fn component_stores_are_labeled_and_excluded_by_default() {
+    let dir = tempfile::tempdir().unwrap();
+    let cookies = testing::sample_cookies();
+    testing::write_chromium_profile_in(dir.path(), "Default", ChromiumSchema::V21, &cookies).unwrap();
+    testing
+        ::write_chromium_profile_in(dir.path(), "Default/Mail", ChromiumSchema::V21, &cookies[..1])
+        .unwrap();
+
+    let data_paths = testing::chromium_data_paths(dir.path());
+    let data_paths: Vec<&str> = data_paths.iter().map(|p| p.as_str()).collect();
+    let config = fixture_config(&data_paths);
+
+    let profiles = read_profiles(&config, &EnumerationOptions::default());
+    assert_eq!(profiles.len(), 1);
+    assert_eq!(profiles[0].profile_dir, "Default");
+    assert_eq!(profiles[0].component, None);
+
+    let options = EnumerationOptions { include_components: true, ..Default::default() };
+    let profiles = read_profiles(&config, &options);
+    let mail = profiles
+        .iter()
+        .find(|p| p.component.as_deref() == Some("Mail"))
+        .expect("mail component");
+    assert_eq!(mail.profile_dir, "Default");
+    assert_eq!(mail.cookies.len(), 1);
+    assert_eq!(mail.cookies[0].value, cookies[0].value);
+}
+
+#[test]
+fn tor_profile_is_tagged() {
+    let dir = tempfile::tempdir().unwrap();
+    let cookies = testing::sample_cookies();
+    for profile in ["Default", "Tor Profile"] {
+        testing::write_chromium_profile_in(dir.path(), profile, ChromiumSchema::V21, &cookies).unwrap();
+    }
+    let data_paths = testing::chromium_data_paths(dir.path());
+    let data_paths: Vec<&str> = data_paths.iter().map(|p| p.as_str()).collect();
+    let config = fixture_config(&data_paths);
+
+    let options = EnumerationOptions { include_tor: true, ..Default::default() };
+    let profiles = read_profiles(&config, &options);
+    let tor: Vec<_> = profiles.iter().filter(|p| p.is_tor()).collect();
+    assert_eq!(tor.len(), 1);
+    assert_eq!(tor[0].cookies.len(), cookies.len());
+    assert!(profiles.iter().any(|p| !p.is_tor()));
+}
+
+#[test]
+fn profile_identity_is_opt_in() {
+    let dir = tempfile::tempdir().unwrap();
+    let cookies = testing::sample_cookies();
+    for profile in ["Default", "Profile 1"] {
+        testing::write_chromium_profile_in(dir.path(), profile, ChromiumSchema::V21, &cookies).unwrap();
+    }
+    testing
+        ::write_preferences(
+            dir.path(),
+            "Default",
+            serde_json::json!({
+                "account_info": [{ "gaia": "1234567890", "email": "user@example.com" }],
+            })
+        )
+        .unwrap();
+    // older forks only keep the last signed in account
+    testing
+        ::write_preferences(
+            dir.path(),
+            "Profile 1",
+            serde_json::json!({ "google": { "services": { "last_gaia_id": "42" } } })
+        )
+        .unwrap();
+    testing
+        ::set_local_state(
+            dir.path(),
+            &["user_experience_metrics", "client_id2"],
+            serde_json::json!("client-guid")
+        )
+        .unwrap();
+
+    let data_paths = testing::chromium_data_paths(dir.path());
+    let data_paths: Vec<&str> = data_paths.iter().map(|p| p.as_str()).collect();
+    let config = fixture_config(&data_paths);
+
+    let profiles = read_profiles(&config, &EnumerationOptions::default());
+    assert!(profiles.iter().all(|p| p.identity.is_none()));
+
+    let options = EnumerationOptions { include_identity: true, ..Default::default() };
+    let profiles = read_profiles(&config, &options);
+    let identity = |dir: &str| {
+        profiles
+            .iter()
+            .find(|p| p.profile_dir == dir)
+            .and_then(|p| p.identity.clone())
+            .unwrap()
+    };
+    assert_eq!(identity("Default"), ProfileIdentity {
+        gaia_id: Some("1234567890".into()),
+        account_email: Some("user@example.com".into()),
+        client_id: Some("client-guid".into()),
+    });
+    assert_eq!(identity("Profile 1"), ProfileIdentity {
+        gaia_id: Some("42".into()),
+        account_email: None,
+        client_id: Some("client-guid".into()),
+    });
+}
+
+#[test]
+fn broken_profile_does_not_fail_the_others() {
+    let dir = tempfile::tempdir().unwrap();
+    let cookies = testing::sample_cookies();
+    for profile in ["Default", "Profile 1"] {
+        testing::write_chromium_profile_in(dir.path(), profile, ChromiumSchema::V21, &cookies).unwrap();
+    }
+    let broken = dir.path().join("User Data/Profile 1/Network/Cookies");
+    std::fs::write(&broken, b"not a sqlite database").unwrap();
+    testing::set_profile_info(dir.path(), "Default", serde_json::json!({ "name": "Personal" })).unwrap();
+
+    let data_paths = testing::chromium_data_paths(dir.path());
+    let data_paths: Vec<&str> = data_paths.iter().map(|p| p.as_str()).collect();
+    let config = fixture_config(&data_paths);
+
+    let read = rookie
+        ::chromium_profiles(&config, None, &EnumerationOptions::default())
+        .unwrap();
+    assert_eq!(read.profiles.len(), 1);
+    assert_eq!(read.profiles[0].profile_dir, "Default");
+    assert_eq!(read.profiles[0].display_name.as_deref(), Some("Personal"));
+    assert_eq!(read.profiles[0].cookies.len(), cookies.len());
+    assert_eq!(read.failures.len(), 1);
+    assert_eq!(read.failures[0].0, broken);
+}
+
+#[test]
+fn provenance_hashes_the_parsed_copy() {
+    let dir = tempfile::tempdir().unwrap();
+    let cookies = testing::sample_cookies();
+    let fixture = testing::write_chromium_profile(dir.path(), ChromiumSchema::V21, &cookies).unwrap();
+
+    let data_paths = testing::chromium_data_paths(dir.path());
+    let data_paths: Vec<&str> = data_paths.iter().map(|p| p.as_str()).collect();
+    let config = fixture_config(&data_paths);
+
+    let profiles = read_profiles(&config, &EnumerationOptions::default());
+    assert_eq!(profiles[0].provenance, None);
+
+    let options = EnumerationOptions { provenance: true, ..Default::default() };
+    let profiles = read_profiles(&config, &options);
+    assert_eq!(profiles[0].cookies.len(), cookies.len());
+    // the copy is only what's parsed, the profile is still the live one
+    assert!(profiles[0].cookies.iter().all(|cookie| cookie.profile.as_deref() == Some("Default")));
+
+    let bytes = std::fs::read(&fixture.db_path).unwrap();
+    let provenance = profiles[0].provenance.as_ref().unwrap();
+    assert_eq!(provenance.source_path, fixture.db_path);
+    assert_eq!(provenance.size, bytes.len() as u64);
+    assert_eq!(provenance.sha256, provenance::sha256_hex(&bytes));
+    assert_eq!(provenance.sha256.len(), 64);
+    assert!(provenance.modified.is_some());
+    assert!(provenance.extracted_at >= provenance.modified.unwrap());
+
+    // the copy is private to the user and gone once dropped
+    let snapshot = provenance::snapshot(&fixture.db_path).unwrap();
+    let copy = snapshot.path().to_path_buf();
+    assert_eq!(std::fs::read(&copy).unwrap(), bytes);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(std::fs::metadata(&copy).unwrap().permissions().mode() & 0o777, 0o600);
+    }
+    drop(snapshot);
+    assert!(!copy.exists());
+
+    let json: serde_json::Value = serde_json
+        ::from_str(&rookie::export::to_json_with_provenance(&profiles[0].cookies, provenance).unwrap())
+        .unwrap();
+    let har = rookie::export::to_har_with_provenance(&profiles[0].cookies, provenance).unwrap();
+    let mut ndjson = vec![];
+    rookie::export::to_ndjson_with_provenance(&profiles[0].cookies, provenance, &mut ndjson).unwrap();
+    let line: serde_json::Value = serde_json::from_slice(ndjson.split(|b| *b == b'\n').next().unwrap()).unwrap();
+    for entry in [&json[0], &har[0], &line] {
+        assert_eq!(entry["_provenance"]["sha256"], provenance.sha256.as_str());
+        assert_eq!(entry["_provenance"]["size"], bytes.len() as u64);
+    }
+    let plain: serde_json::Value = serde_json::from_str(&rookie::export::to_json(&profiles[0].cookies).unwrap()).unwrap();
+    assert!(plain[0].get("_provenance").is_none());
+}
+
+#[test]
+fn opera_password_protection_fails_early() {
+    let dir = tempfile::tempdir().unwrap();
+    let cookies = testing::sample_cookies();
+    let fixture = testing::write_chromium_profile(dir.path(), ChromiumSchema::V21, &cookies).unwrap();
+    let data_paths = testing::chromium_data_paths(dir.path());
+    let data_paths: Vec<&str> = data_paths.iter().map(|p| p.as_str()).collect();
+    let config = fixture_config(&data_paths);
+    assert!(!chromium::password_protected(&fixture.db_path));
+
+    let protected = serde_json::json!({ "opera": { "browsing_data_protection": { "enabled": true } } });
+    testing::write_preferences(dir.path(), "Default", protected).unwrap();
+    assert!(chromium::password_protected(&fixture.db_path));
+    let read = rookie
+        ::chromium_profiles(&config, None, &EnumerationOptions::default())
+        .unwrap();
+    assert!(read.profiles.is_empty());
+    assert!(read.failures[0].1.is::<AdditionalPasswordRequired>());
+
+    let unprotected = serde_json::json!({ "opera": { "browsing_data_protection": { "enabled": false } } });
+    testing::write_preferences(dir.path(), "Default", unprotected).unwrap();
+    assert_eq!(read_profiles(&config, &EnumerationOptions::default())[0].cookies.len(), cookies.len());
+
+    testing
+        ::set_local_state(dir.path(), &["os_crypt", "password_protection_enabled"], true.into())
+        .unwrap();
+    let err = rookie
+        ::any_browser(
+            fixture.db_path.to_str().unwrap(),
+            None,
+            fixture.key_path.to_str()
+        )
+        .unwrap_err();
+    assert!(err.is::<AdditionalPasswordRequired>());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn opera_with_password() {
+    let fixture_dir = tempfile::tempdir().unwrap();
+    let cookies = testing::sample_cookies();
+    let fixture = testing::write_chromium_profile(fixture_dir.path(), ChromiumSchema::V21, &cookies).unwrap();
+    // Opera keeps the cookies and Local State right in its data dir
+    let root = tempfile::tempdir().unwrap();
+    let opera_dir = root.path().join(".config/opera");
+    std::fs::create_dir_all(&opera_dir).unwrap();
+    std::fs::copy(&fixture.db_path, opera_dir.join("Cookies")).unwrap();
+    std::fs::copy(&fixture.key_path, opera_dir.join("Local State")).unwrap();
+    let read = || rookie::with_user_profile_root(root.path(), || rookie::opera_with_password("password", None));
+
+    assert_eq!(read().unwrap().len(), cookies.len());
+
+    let protected = serde_json::json!({ "opera": { "browsing_data_protection": { "enabled": true } } });
+    std::fs::write(opera_dir.join("Preferences"), protected.to_string()).unwrap();
+    let err = read().unwrap_err();
+    let unsupported = err.downcast_ref::<chromium::PasswordUnwrapUnsupported>().unwrap();
+    assert_eq!(unsupported.0, opera_dir);
+}
+
+#[test]
+fn browser_version_falls_back_to_local_state() {
+    let dir = tempfile::tempdir().unwrap();
+    let cookies = testing::sample_cookies();
+    testing::write_chromium_profile(dir.path(), ChromiumSchema::V21, &cookies).unwrap();
+    let data_paths = testing::chromium_data_paths(dir.path());
+    let data_paths: Vec<&str> = data_paths.iter().map(|p| p.as_str()).collect();
+    let config = fixture_config(&data_paths);
+    let local_state = dir.path().join("User Data/Local State");
+
+    // per-machine install without a version anywhere
+    assert_eq!(chromium::browser_version(&local_state, None), None);
+    assert_eq!(read_profiles(&config, &EnumerationOptions::default())[0].version, None);
+
+    testing
+        ::set_local_state(dir.path(), &["browser", "last_known_version"], "119.0.6045.199".into())
+        .unwrap();
+    let version = chromium::browser_version(&local_state, None).unwrap();
+    assert_eq!(version.numbers(), (119, 0, 6045, 199));
+    assert_eq!(version.source, VersionSource::LocalState);
+    let profile = &read_profiles(&config, &EnumerationOptions::default())[0];
+    assert_eq!(profile.version, Some(version));
+    assert_eq!(profile.last_version.as_deref(), Some("119.0.6045.199"));
+
+    std::fs::write(dir.path().join("User Data/Last Version"), "127.0.6533.73\n").unwrap();
+    let version = chromium::browser_version(&local_state, None).unwrap();
+    assert_eq!(version.to_string(), "127.0.6533.73");
+    assert_eq!(version.source, VersionSource::LastVersionFile);
+
+    std::fs::write(dir.path().join("User Data/Last Version"), "garbage").unwrap();
+    assert_eq!(chromium::browser_version(&local_state, None).unwrap().source, VersionSource::LocalState);
+
+    assert_eq!(BrowserVersion::parse("120", VersionSource::LocalState).unwrap().numbers(), (120, 0, 0, 0));
+    assert_eq!(BrowserVersion::parse("1.2.3.4.5", VersionSource::LocalState), None);
+    assert_eq!(BrowserVersion::parse("", VersionSource::LocalState), None);
+}
+
+#[test]
+fn chromium_profiles_are_listed_with_names() {
+    let dir = tempfile::tempdir().unwrap();
+    let cookies = testing::sample_cookies();
+    for profile in ["Default", "Profile 1"] {
+        testing::write_chromium_profile_in(dir.path(), profile, ChromiumSchema::V21, &cookies).unwrap();
+    }
+    testing::set_profile_info(dir.path(), "Default", serde_json::json!({ "name": "Work" })).unwrap();
+    // not in the info cache, the name comes from its Preferences
+    testing
+        ::write_preferences(dir.path(), "Profile 1", serde_json::json!({ "profile": { "name": "Personal" } }))
+        .unwrap();
+
+    let data_paths = testing::chromium_data_paths(dir.path());
+    let data_paths: Vec<&str> = data_paths.iter().map(|p| p.as_str()).collect();
+    let config = fixture_config(&data_paths);
+    let mut profiles = rookie::profiles::chromium_profile_infos("chromium", &config);
+    profiles.sort_by(|a, b| a.profile_dir.cmp(&b.profile_dir));
+
+    let names: Vec<(&str, Option<&str>)> = profiles
+        .iter()
+        .map(|p| (p.profile_dir.as_str(), p.name.as_deref()))
+        .collect();
+    assert_eq!(names, [("Default", Some("Work")), ("Profile 1", Some("Personal"))]);
+    assert_eq!(profiles[0].db_path, dir.path().join("User Data/Default/Network/Cookies"));
+    assert!(profiles.iter().all(|p| p.browser == "chromium" && p.last_modified.is_some()));
+}
+
+#[test]
+fn firefox_profiles_are_listed_and_read() {
+    let root = tempfile::tempdir().unwrap();
+    let cookies = testing::sample_cookies();
+    testing::write_firefox_home(root.path(), &cookies).unwrap();
+
+    rookie::with_user_profile_root(root.path(), || {
+        let profiles = rookie::list_profiles("firefox").unwrap();
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].profile_dir, "fixture.default-release");
+        assert_eq!(profiles[0].name.as_deref(), Some("default-release"));
+        let read = rookie::read_profile(&profiles[0], None).unwrap();
+        assert_eq!(read.len(), cookies.len());
+
+        assert!(rookie::list_profiles("librewolf").unwrap().is_empty());
+    });
+    assert!(rookie::list_profiles("netscape").is_err());
+}
+
+#[test]
+fn chrome_profile_by_directory_or_display_name() {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            let user_data = "AppData/Local/Google/Chrome/User Data";
+        } else if #[cfg(target_os = "macos")] {
+            let user_data = "Library/Application Support/Google/Chrome";
+        } else {
+            let user_data = ".config/google-chrome";
+        }
+    }
+    let root = tempfile::tempdir().unwrap();
+    let user_data = root.path().join(user_data);
+    for profile in ["Default", "Profile 2"] {
+        std::fs::create_dir_all(user_data.join(profile)).unwrap();
+        std::fs::write(user_data.join(profile).join("Cookies"), b"").unwrap();
+    }
+    let local_state = serde_json::json!({ "profile": { "info_cache": { "Profile 2": { "name": "Work" } } } });
+    std::fs::write(user_data.join("Local State"), local_state.to_string()).unwrap();
+
+    rookie::with_user_profile_root(root.path(), || {
+        for name in ["Profile 2", "Work"] {
+            assert_eq!(rookie::profiles::find_profile("chrome", name).unwrap().profile_dir, "Profile 2");
+        }
+        let err = rookie::chrome_profile("Personal", None).unwrap_err();
+        let not_found = err.downcast_ref::<ProfileNotFound>().unwrap();
+        assert_eq!(not_found.available.len(), 2);
+        assert!(not_found.available.contains(&"Profile 2 (Work)".to_string()), "{}", err);
+        assert!(not_found.available.contains(&"Default".to_string()), "{}", err);
+    });
+}
+
+#[test]
+fn firefox_all_profiles_skips_unreadable_ones() {
+    let root = tempfile::tempdir().unwrap();
+    let cookies = testing::sample_cookies();
+    let db_path = testing::write_firefox_home(root.path(), &cookies).unwrap();
+    let profiles_dir = db_path.parent().unwrap().parent().unwrap();
+    std::fs::create_dir_all(profiles_dir.join("work1234.work")).unwrap();
+    std::fs::copy(&db_path, profiles_dir.join("work1234.work/cookies.sqlite")).unwrap();
+    std::fs::create_dir_all(profiles_dir.join("empty123.empty")).unwrap();
+    std::fs::write(profiles_dir.join("empty123.empty/cookies.sqlite"), b"").unwrap();
+
+    let profiles = rookie
+        ::with_user_profile_root(root.path(), || rookie::firefox_all_profiles(None))
+        .unwrap();
+    let mut read: Vec<_> = profiles
+        .iter()
+        .map(|(profile, profile_cookies)| (profile.as_str(), profile_cookies.len()))
+        .collect();
+    read.sort();
+    assert_eq!(read, [("default-release", cookies.len()), ("work1234.work", cookies.len())]);
+
+    let empty = tempfile::tempdir().unwrap();
+    rookie::with_user_profile_root(empty.path(), || {
+        assert!(rookie::firefox_all_profiles(None).is_err());
+    });
+}
+
+#[test]
+fn cookies_record_browser_and_profile() {
+    let root = tempfile::tempdir().unwrap();
+    let cookies = testing::sample_cookies();
+    testing::write_firefox_home(root.path(), &cookies).unwrap();
+
+    let loaded = rookie::load_for_user(root.path(), None).unwrap().cookies;
+    let read = rookie::with_user_profile_root(root.path(), || rookie::firefox(None)).unwrap();
+    assert!(!loaded.is_empty());
+    for cookie in loaded.iter().chain(&read) {
+        assert_eq!(cookie.browser, "firefox");
+        assert_eq!(cookie.profile.as_deref(), Some("fixture.default-release"));
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    testing::write_chromium_profile(dir.path(), ChromiumSchema::V21, &cookies).unwrap();
+    let data_paths = testing::chromium_data_paths(dir.path());
+    let data_paths: Vec<&str> = data_paths.iter().map(|p| p.as_str()).collect();
+    let read = rookie
+        ::chromium_profiles(&fixture_config(&data_paths), None, &EnumerationOptions::default())
+        .unwrap();
+    // a config of its own isn't one of the known browsers
+    assert!(read.profiles[0].cookies.iter().all(|cookie| cookie.browser.is_empty()));
+    assert!(read.profiles[0].cookies.iter().all(|cookie| cookie.profile.as_deref() == Some("Default")));
+}
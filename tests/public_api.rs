@@ -0,0 +1,524 @@
+//! Public API snapshot. Every public item of the crate as rustdoc sees it (modules,
+//! functions, structs with their fields, enum variants, traits, re-exports and
+//! trait impls) is listed in `tests/golden/public-api.txt`, so a change to the public
+//! surface shows up as a failing test and a diff in review. Rerun with
+//! `UPDATE_GOLDEN=1` after an intended change. The `testing` fixtures are left out,
+//! they carry no stability promise.
+//!
+//! The listing is built from rustdoc's JSON output by the toolchain running the tests.
+//! Stable rustdoc writes it with `RUSTC_BOOTSTRAP=1`, so the snapshot is part of a
+//! plain `cargo test`. The format changes between releases, [`FORMAT_VERSION`] is the
+//! one this file reads. `PUBLIC_API_TOOLCHAIN` picks another installed toolchain that
+//! writes the same format. It's the x86_64 Linux surface with every feature on, the
+//! items only built for Windows or macOS aren't in it.
+
+#[cfg(target_os = "linux")]
+use { serde_json::Value, std::path::Path, std::process::Command };
+
+/// Version of the rustdoc JSON format [`Api`] reads, written by Rust 1.95
+#[cfg(target_os = "linux")]
+const FORMAT_VERSION: u64 = 57;
+
+#[cfg(target_os = "linux")]
+fn rustdoc_json() -> Value {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let target_dir = manifest_dir.join("target").join("public-api");
+    let toolchain = std::env::var("PUBLIC_API_TOOLCHAIN").ok();
+    let mut command = match &toolchain {
+        Some(toolchain) => {
+            let mut command = Command::new("rustup");
+            command.args(["run", toolchain, "cargo"]);
+            command
+        }
+        None => Command::new(env!("CARGO")),
+    };
+    let output = command
+        .args(["rustdoc", "--lib", "--all-features"])
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .args(["--", "-Z", "unstable-options", "--output-format", "json"])
+        .current_dir(manifest_dir)
+        // lets stable rustdoc write JSON
+        .env("RUSTC_BOOTSTRAP", "1")
+        // with RUSTC_BOOTSTRAP set, rustix 0.37 (under zbus) probes for nightly-only
+        // attributes that current compilers reject, its libc backend doesn't use them
+        .env("RUSTFLAGS", "--cfg rustix_use_libc")
+        .output()
+        .expect("cant run cargo rustdoc");
+    assert!(output.status.success(), "cargo rustdoc failed\n{}", String::from_utf8_lossy(&output.stderr));
+    let json = std::fs::read_to_string(target_dir.join("doc").join("rookie.json")).unwrap();
+    let json: Value = serde_json::from_str(&json).unwrap();
+    let version = json["format_version"].as_u64().unwrap_or_default();
+    assert_eq!(
+        version,
+        FORMAT_VERSION,
+        "rustdoc JSON format {} isn't the one this test reads, update it for the new format or \
+         set PUBLIC_API_TOOLCHAIN to a toolchain writing format {}",
+        version,
+        FORMAT_VERSION
+    );
+    json
+}
+
+/// Renders the public items of a rustdoc JSON crate, one line each
+#[cfg(target_os = "linux")]
+struct Api<'a> {
+    json: &'a Value,
+    lines: Vec<String>,
+}
+
+#[cfg(target_os = "linux")]
+impl<'a> Api<'a> {
+    fn item(&self, id: &Value) -> &'a Value {
+        &self.json["index"][id.to_string()]
+    }
+
+    /// Full path of an item rustdoc knows about, as written when it doesn't. serde
+    /// 1.0.220 and later define the traits in `serde_core` and re-export them, they
+    /// keep their `serde` path so the snapshot doesn't depend on the lockfile.
+    fn path(&self, path: &Value) -> String {
+        let mut name = match self.json["paths"][path["id"].to_string()]["path"].as_array() {
+            Some(segments) =>
+                segments
+                    .iter()
+                    .map(|segment| segment.as_str().unwrap())
+                    .collect::<Vec<_>>()
+                    .join("::"),
+            None => path["path"].as_str().unwrap().to_string(),
+        };
+        if let Some(rest) = name.strip_prefix("serde_core::") {
+            name = format!("serde::{}", rest);
+        }
+        format!("{}{}", name, self.generic_args(&path["args"]))
+    }
+
+    fn generic_args(&self, args: &Value) -> String {
+        if let Some(args) = args.get("angle_bracketed") {
+            let mut rendered: Vec<String> = args["args"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|arg| {
+                    if let Some(ty) = arg.get("type") {
+                        self.ty(ty)
+                    } else if let Some(lifetime) = arg.get("lifetime") {
+                        lifetime.as_str().unwrap().to_string()
+                    } else if let Some(constant) = arg.get("const") {
+                        constant["expr"].as_str().unwrap().to_string()
+                    } else {
+                        "_".to_string()
+                    }
+                })
+                .collect();
+            for constraint in args["constraints"].as_array().unwrap() {
+                let name = constraint["name"].as_str().unwrap();
+                let binding = &constraint["binding"];
+                if let Some(ty) = binding["equality"].get("type") {
+                    rendered.push(format!("{} = {}", name, self.ty(ty)));
+                } else {
+                    rendered.push(format!("{}: {}", name, self.bounds(&binding["constraint"])));
+                }
+            }
+            if rendered.is_empty() { String::new() } else { format!("<{}>", rendered.join(", ")) }
+        } else if let Some(args) = args.get("parenthesized") {
+            let inputs: Vec<String> = args["inputs"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|ty| self.ty(ty))
+                .collect();
+            match args["output"].is_null() {
+                true => format!("({})", inputs.join(", ")),
+                false => format!("({}) -> {}", inputs.join(", "), self.ty(&args["output"])),
+            }
+        } else {
+            String::new()
+        }
+    }
+
+    fn bounds(&self, bounds: &Value) -> String {
+        bounds
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|bound| {
+                if let Some(bound) = bound.get("trait_bound") {
+                    let modifier = if bound["modifier"] == "maybe" { "?" } else { "" };
+                    format!("{}{}", modifier, self.path(&bound["trait"]))
+                } else if let Some(lifetime) = bound.get("outlives") {
+                    lifetime.as_str().unwrap().to_string()
+                } else {
+                    bound.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" + ")
+    }
+
+    fn ty(&self, ty: &Value) -> String {
+        let Some((kind, inner)) = ty.as_object().and_then(|ty| ty.iter().next()) else {
+            return "_".to_string();
+        };
+        match kind.as_str() {
+            "resolved_path" => self.path(inner),
+            "generic" | "primitive" => inner.as_str().unwrap().to_string(),
+            "borrowed_ref" => {
+                let lifetime = inner["lifetime"].as_str().map(|lifetime| format!("{} ", lifetime));
+                let mutable = if inner["is_mutable"] == true { "mut " } else { "" };
+                format!("&{}{}{}", lifetime.unwrap_or_default(), mutable, self.ty(&inner["type"]))
+            }
+            "raw_pointer" => {
+                let mutable = if inner["is_mutable"] == true { "mut" } else { "const" };
+                format!("*{} {}", mutable, self.ty(&inner["type"]))
+            }
+            "slice" => format!("[{}]", self.ty(inner)),
+            "array" => format!("[{}; {}]", self.ty(&inner["type"]), inner["len"].as_str().unwrap()),
+            "tuple" => {
+                let types: Vec<String> = inner
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|ty| self.ty(ty))
+                    .collect();
+                format!("({})", types.join(", "))
+            }
+            "impl_trait" => format!("impl {}", self.bounds(inner)),
+            "dyn_trait" => {
+                let mut traits: Vec<String> = inner["traits"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|bound| self.path(&bound["trait"]))
+                    .collect();
+                traits.extend(inner["lifetime"].as_str().map(String::from));
+                format!("dyn {}", traits.join(" + "))
+            }
+            "qualified_path" =>
+                format!(
+                    "<{} as {}>::{}",
+                    self.ty(&inner["self_type"]),
+                    self.path(&inner["trait"]),
+                    inner["name"].as_str().unwrap()
+                ),
+            "function_pointer" => format!("fn{}", self.signature(&inner["sig"])),
+            _ => ty.to_string(),
+        }
+    }
+
+    fn generics(&self, generics: &Value) -> String {
+        let params: Vec<String> = generics["params"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|param| param["kind"]["type"]["is_synthetic"] != true)
+            .map(|param| {
+                let name = param["name"].as_str().unwrap().to_string();
+                match param["kind"].get("type") {
+                    Some(ty) if !ty["bounds"].as_array().unwrap().is_empty() =>
+                        format!("{}: {}", name, self.bounds(&ty["bounds"])),
+                    _ => name,
+                }
+            })
+            .collect();
+        if params.is_empty() { String::new() } else { format!("<{}>", params.join(", ")) }
+    }
+
+    fn where_clause(&self, generics: &Value) -> String {
+        let predicates: Vec<String> = generics["where_predicates"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|predicate| {
+                let predicate = predicate.get("bound_predicate")?;
+                Some(format!("{}: {}", self.ty(&predicate["type"]), self.bounds(&predicate["bounds"])))
+            })
+            .collect();
+        if predicates.is_empty() { String::new() } else { format!(" where {}", predicates.join(", ")) }
+    }
+
+    fn signature(&self, sig: &Value) -> String {
+        let inputs: Vec<String> = sig["inputs"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|input| {
+                let name = input[0].as_str().unwrap();
+                let ty = self.ty(&input[1]);
+                match (name, ty.as_str()) {
+                    ("self", "Self") => "self".to_string(),
+                    ("self", "&Self") => "&self".to_string(),
+                    ("self", "&mut Self") => "&mut self".to_string(),
+                    _ => format!("{}: {}", name, ty),
+                }
+            })
+            .collect();
+        match sig["output"].is_null() {
+            true => format!("({})", inputs.join(", ")),
+            false => format!("({}) -> {}", inputs.join(", "), self.ty(&sig["output"])),
+        }
+    }
+
+    fn function(&self, name: &str, function: &Value) -> String {
+        let header = &function["header"];
+        let qualifiers: String = [("is_const", "const "), ("is_async", "async "), ("is_unsafe", "unsafe ")]
+            .iter()
+            .filter(|(flag, _)| header[flag] == true)
+            .map(|(_, qualifier)| *qualifier)
+            .collect();
+        format!(
+            "{}fn {}{}{}{}",
+            qualifiers,
+            name,
+            self.generics(&function["generics"]),
+            self.signature(&function["sig"]),
+            self.where_clause(&function["generics"])
+        )
+    }
+
+    fn push(&mut self, item: &Value, line: String) {
+        let mut attributes = String::new();
+        if !item["deprecation"].is_null() {
+            attributes.push_str("#[deprecated] ");
+        }
+        if item["attrs"].as_array().is_some_and(|attrs| attrs.iter().any(|attr| attr == "non_exhaustive")) {
+            attributes.push_str("#[non_exhaustive] ");
+        }
+        self.lines.push(format!("{}{}", attributes, line));
+    }
+
+    fn is_public(item: &Value) -> bool {
+        item["visibility"] == "public"
+    }
+
+    fn module(&mut self, path: &str, module: &Value) {
+        for id in module["items"].as_array().unwrap() {
+            let item = self.item(id);
+            if !Self::is_public(item) {
+                continue;
+            }
+            let name = item["name"].as_str().unwrap_or_default();
+            let full = format!("{}::{}", path, name);
+            let (kind, inner) = item["inner"].as_object().unwrap().iter().next().unwrap();
+            match kind.as_str() {
+                "module" => {
+                    if full == "rookie::testing" {
+                        continue;
+                    }
+                    self.push(item, format!("pub mod {}", full));
+                    self.module(&full, inner);
+                }
+                "use" => {
+                    let glob = if inner["is_glob"] == true { "::*" } else { "" };
+                    let name = inner["name"].as_str().unwrap();
+                    self.push(item, format!("pub use {}::{}{} = {}", path, name, glob, inner["source"].as_str().unwrap()));
+                }
+                "function" => {
+                    let line = format!("pub {}", self.function(&full, inner));
+                    self.push(item, line);
+                }
+                "struct" => {
+                    let generics = self.generics(&inner["generics"]);
+                    let kind = &inner["kind"];
+                    if let Some(fields) = kind.get("tuple") {
+                        let fields: Vec<String> = fields
+                            .as_array()
+                            .unwrap()
+                            .iter()
+                            .map(|field| {
+                                let field = self.item(field);
+                                match field.is_null() || !Self::is_public(field) {
+                                    true => "_".to_string(),
+                                    false => format!("pub {}", self.ty(&field["inner"]["struct_field"])),
+                                }
+                            })
+                            .collect();
+                        self.push(item, format!("pub struct {}{}({})", full, generics, fields.join(", ")));
+                    } else {
+                        self.push(item, format!("pub struct {}{}", full, generics));
+                        for field in kind["plain"]["fields"].as_array().into_iter().flatten() {
+                            let field = self.item(field);
+                            if Self::is_public(field) {
+                                let line = format!(
+                                    "pub {}::{}: {}",
+                                    full,
+                                    field["name"].as_str().unwrap(),
+                                    self.ty(&field["inner"]["struct_field"])
+                                );
+                                self.push(field, line);
+                            }
+                        }
+                    }
+                    self.impls(&full, &inner["impls"]);
+                }
+                "enum" => {
+                    let line = format!("pub enum {}{}", full, self.generics(&inner["generics"]));
+                    self.push(item, line);
+                    for variant in inner["variants"].as_array().unwrap() {
+                        let variant = self.item(variant);
+                        let name = format!("pub {}::{}", full, variant["name"].as_str().unwrap());
+                        let kind = &variant["inner"]["variant"]["kind"];
+                        let fields = |fields: &Value| -> Vec<String> {
+                            fields
+                                .as_array()
+                                .unwrap()
+                                .iter()
+                                .map(|field| {
+                                    let field = self.item(field);
+                                    let ty = self.ty(&field["inner"]["struct_field"]);
+                                    match kind.get("struct") {
+                                        Some(_) => format!("{}: {}", field["name"].as_str().unwrap(), ty),
+                                        None => ty,
+                                    }
+                                })
+                                .collect()
+                        };
+                        let line = if let Some(tuple) = kind.get("tuple") {
+                            format!("{}({})", name, fields(tuple).join(", "))
+                        } else if let Some(plain) = kind.get("struct") {
+                            format!("{} {{ {} }}", name, fields(&plain["fields"]).join(", "))
+                        } else {
+                            name
+                        };
+                        self.push(variant, line);
+                    }
+                    self.impls(&full, &inner["impls"]);
+                }
+                "trait" => {
+                    let bounds = match inner["bounds"].as_array().unwrap().is_empty() {
+                        true => String::new(),
+                        false => format!(": {}", self.bounds(&inner["bounds"])),
+                    };
+                    let line = format!("pub trait {}{}{}", full, self.generics(&inner["generics"]), bounds);
+                    self.push(item, line);
+                    for member in inner["items"].as_array().unwrap() {
+                        let member = self.item(member);
+                        if let Some(function) = member["inner"].get("function") {
+                            let line = self.function(&format!("{}::{}", full, member["name"].as_str().unwrap()), function);
+                            self.push(member, line);
+                        }
+                    }
+                }
+                "constant" => {
+                    let value = match inner["const"]["is_literal"] == true {
+                        true => format!(" = {}", inner["const"]["expr"].as_str().unwrap()),
+                        false => String::new(),
+                    };
+                    self.push(item, format!("pub const {}: {}{}", full, self.ty(&inner["type"]), value));
+                }
+                "static" => {
+                    self.push(item, format!("pub static {}: {}", full, self.ty(&inner["type"])));
+                }
+                "type_alias" => {
+                    let line = format!("pub type {}{} = {}", full, self.generics(&inner["generics"]), self.ty(&inner["type"]));
+                    self.push(item, line);
+                }
+                _ => self.push(item, format!("pub {} {}", kind, full)),
+            }
+        }
+    }
+
+    /// Inherent methods and trait impls written for a type, auto traits, blanket
+    /// impls and the compiler's `StructuralPartialEq` marker are left out
+    fn impls(&mut self, path: &str, impls: &Value) {
+        for id in impls.as_array().unwrap() {
+            let implementation = self.item(id);
+            let inner = &implementation["inner"]["impl"];
+            if
+                inner["is_synthetic"] == true ||
+                !inner["blanket_impl"].is_null() ||
+                inner["trait"]["path"] == "StructuralPartialEq"
+            {
+                continue;
+            }
+            if inner["trait"].is_null() {
+                for member in inner["items"].as_array().unwrap() {
+                    let member = self.item(member);
+                    if !Self::is_public(member) {
+                        continue;
+                    }
+                    let name = format!("{}::{}", path, member["name"].as_str().unwrap());
+                    if let Some(function) = member["inner"].get("function") {
+                        let line = format!("pub {}", self.function(&name, function));
+                        self.push(member, line);
+                    } else if let Some(constant) = member["inner"].get("assoc_const") {
+                        let line = format!("pub const {}: {}", name, self.ty(&constant["type"]));
+                        self.push(member, line);
+                    }
+                }
+            } else {
+                let line = format!(
+                    "impl{} {} for {}{}",
+                    self.generics(&inner["generics"]),
+                    self.path(&inner["trait"]),
+                    self.ty(&inner["for"]),
+                    self.where_clause(&inner["generics"])
+                );
+                self.push(implementation, line);
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn public_api_snapshot() {
+    let json = rustdoc_json();
+    let mut api = Api { json: &json, lines: vec![] };
+    let root = api.item(&json["root"]);
+    api.module("rookie", &root["inner"]["module"]);
+    let mut lines = api.lines;
+    lines.sort();
+    lines.dedup();
+    let actual: String = lines.iter().map(|line| format!("{}\n", line)).collect();
+
+    let path = format!("{}/tests/golden/public-api.txt", env!("CARGO_MANIFEST_DIR"));
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, &actual).unwrap();
+    }
+    let expected = std::fs::read_to_string(&path).unwrap().replace("\r\n", "\n");
+    assert!(
+        actual == expected,
+        "the public API changed, rerun with UPDATE_GOLDEN=1 if that's intended and bump the version accordingly\n{}",
+        diff(&expected, &actual)
+    );
+}
+
+#[cfg(target_os = "linux")]
+fn diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+    let removed = expected
+        .iter()
+        .filter(|line| !actual.contains(line))
+        .map(|line| format!("- {}", line));
+    let added = actual
+        .iter()
+        .filter(|line| !expected.contains(line))
+        .map(|line| format!("+ {}", line));
+    removed.chain(added).collect::<Vec<_>>().join("\n")
+}
+
+#[test]
+fn prelude_v1_covers_the_stable_set() {
+    use rookie::prelude::v1::*;
+
+    // only has to compile, the signatures are what's promised
+    #[allow(dead_code)]
+    fn signatures(domains: Option<Vec<&str>>, ctx: &RequestContext) -> anyhow::Result<()> {
+        let mut options = LoadOptions::default();
+        options.include_expired = false;
+        let outcome: LoadOutcome = load_with(domains.clone(), &options)?;
+        let cookies: Vec<Cookie> = load(domains.clone())?;
+        let _: Vec<Cookie> = firefox(domains.clone())?;
+        let _: Vec<Cookie> = chrome(domains)?;
+        let _: String = header_for_request(&outcome.cookies, ctx)?;
+        let _: Vec<MinimalCookie> = cookies.iter().map(MinimalCookie::from).collect();
+        let _ = ReadOptions::default();
+        Ok(())
+    }
+
+    let err = anyhow::Error::new(ShareUnreachable("//server/profile".into()));
+    assert!(err.is::<ShareUnreachable>());
+    let err = anyhow::Error::new(AdditionalPasswordRequired("Cookies".into()));
+    assert!(err.downcast_ref::<UnsupportedSchema>().is_none());
+}
@@ -0,0 +1,242 @@
+use rookie::common::enums::{ Cookie, SameSite };
+use rookie::common::request::{ path_matches, port_matches };
+use rookie::common::utils::split_port;
+use rookie::{ header_for_request, RequestContext };
+use url::Url;
+
+fn cookie(name: &str, same_site: SameSite) -> Cookie {
+    let mut cookie = Cookie::new(".example.com", name, "v");
+    cookie.secure = true;
+    cookie.expires = Some(4_102_444_800);
+    cookie.same_site = same_site;
+    cookie
+}
+
+fn context(initiator: Option<&str>, top_level_navigation: bool, method: &str) -> RequestContext {
+    let mut ctx = RequestContext::new("https://www.example.com/account").unwrap();
+    ctx.initiator = initiator.map(|url| Url::parse(url).unwrap());
+    ctx.top_level_navigation = top_level_navigation;
+    ctx.method = method.to_string();
+    ctx
+}
+
+#[test]
+fn same_site_decision_table() {
+    let same_site = Some("https://login.example.com/");
+    let cross_site = Some("https://evil.test/");
+    // (initiator, top level, method, [None, Lax, Strict, unspecified])
+    let table = [
+        (None, true, "GET", [true, true, true, true]),
+        (same_site, false, "POST", [true, true, true, true]),
+        (cross_site, true, "GET", [true, true, false, true]),
+        (cross_site, true, "HEAD", [true, true, false, true]),
+        // Lax-allowing-unsafe: only cookies without an explicit SameSite
+        (cross_site, true, "POST", [true, false, false, true]),
+        (cross_site, true, "PUT", [true, false, false, true]),
+        (cross_site, false, "GET", [true, false, false, false]),
+        (cross_site, false, "POST", [true, false, false, false]),
+        // schemeful: http initiator is cross-site to https
+        (Some("http://www.example.com/"), true, "POST", [true, false, false, true]),
+    ];
+    for (initiator, top_level, method, expected) in table {
+        let ctx = context(initiator, top_level, method);
+        let all = [SameSite::None, SameSite::Lax, SameSite::Strict, SameSite::Unspecified];
+        for (same_site, expected) in all.into_iter().zip(expected) {
+            assert_eq!(
+                cookie("c", same_site).sent_in(&ctx),
+                expected,
+                "same_site={:?} initiator={:?} top_level={} method={}",
+                same_site,
+                initiator,
+                top_level,
+                method
+            );
+        }
+    }
+}
+
+#[test]
+fn registrable_domain_approximation() {
+    let mut ctx = RequestContext::new("https://shop.example.co.uk/").unwrap();
+    ctx.initiator = Some(Url::parse("https://www.example.co.uk/").unwrap());
+    assert!(ctx.is_same_site());
+    ctx.initiator = Some(Url::parse("https://other.co.uk/").unwrap());
+    assert!(!ctx.is_same_site());
+}
+
+#[test]
+fn domain_path_secure_and_expiry() {
+    let ctx = RequestContext::new("https://www.example.com/app/page").unwrap();
+
+    let mut host_only = cookie("c", SameSite::None);
+    host_only.domain = "example.com".to_string();
+    host_only.host_only = true;
+    assert!(!host_only.sent_in(&ctx));
+    // the flag decides, not the leading dot
+    host_only.host_only = false;
+    assert!(host_only.sent_in(&ctx));
+
+    let mut path = cookie("c", SameSite::None);
+    path.path = "/app".to_string();
+    assert!(path.sent_in(&ctx));
+    path.path = "/ap".to_string();
+    assert!(!path.sent_in(&ctx));
+
+    let http = RequestContext::new("http://www.example.com/").unwrap();
+    assert!(!cookie("c", SameSite::None).sent_in(&http));
+    let localhost = RequestContext::new("http://localhost/").unwrap();
+    let mut local = cookie("c", SameSite::None);
+    local.domain = "localhost".to_string();
+    assert!(local.sent_in(&localhost));
+
+    let mut expired = cookie("c", SameSite::None);
+    expired.expires = Some(1);
+    assert!(!expired.sent_in(&ctx));
+}
+
+#[test]
+fn header_orders_longer_paths_first() {
+    let mut root = cookie("root", SameSite::Lax);
+    root.value = "1".to_string();
+    let mut app = cookie("app", SameSite::Lax);
+    app.path = "/app".to_string();
+    app.value = "2".to_string();
+    let strict = cookie("strict", SameSite::Strict);
+
+    let mut ctx = RequestContext::new("https://www.example.com/app/x").unwrap();
+    let cookies = [root, app, strict];
+    assert_eq!(header_for_request(&cookies, &ctx).unwrap(), "app=2; root=1; strict=v");
+
+    ctx.initiator = Some(Url::parse("https://evil.test/").unwrap());
+    assert_eq!(header_for_request(&cookies, &ctx).unwrap(), "app=2; root=1");
+}
+
+#[test]
+fn rfc6265_path_match() {
+    let table = [
+        // identical
+        ("/foo", "/foo", true),
+        // cookie path ends with "/"
+        ("/foo/", "/foo/bar", true),
+        ("/", "/anything", true),
+        // next request char is "/"
+        ("/foo", "/foo/", true),
+        ("/foo", "/foo/bar", true),
+        ("/foo", "/foobar", false),
+        ("/foo/", "/foo", false),
+        ("/foo", "/fo", false),
+        ("/foo", "/bar/foo", false),
+        // case sensitive
+        ("/Foo", "/foo", false),
+        // query and fragment aren't part of the path
+        ("/foo", "/foo?x=/bar", true),
+        ("/foo", "/foobar?x=/", false),
+        ("/foo", "/foo#frag", true),
+        // empty paths count as root
+        ("", "/", true),
+        ("", "/foo", true),
+        ("/", "", true),
+        ("/foo", "", false),
+    ];
+    for (cookie_path, request_path, expected) in table {
+        assert_eq!(
+            path_matches(cookie_path, request_path),
+            expected,
+            "{:?} against {:?}",
+            cookie_path,
+            request_path
+        );
+    }
+
+    let mut scoped = cookie("c", SameSite::None);
+    scoped.path = "/app".to_string();
+    assert!(scoped.path_matches("/app/settings"));
+    assert!(!scoped.path_matches("/application"));
+}
+
+#[test]
+fn host_port_filters() {
+    let table = [
+        ("localhost:3000", ("localhost", Some(3000))),
+        ("127.0.0.1:8080", ("127.0.0.1", Some(8080))),
+        ("[::1]:3000", ("[::1]", Some(3000))),
+        ("[::1]", ("[::1]", None)),
+        ("::1", ("::1", None)),
+        ("fe80::1:8080", ("fe80::1:8080", None)),
+        ("example.com", ("example.com", None)),
+        ("example.com:http", ("example.com:http", None)),
+    ];
+    for (domain, expected) in table {
+        assert_eq!(split_port(domain), expected, "{}", domain);
+    }
+
+    assert!(port_matches(Some(3000), Some(3000)));
+    assert!(!port_matches(Some(3000), Some(8080)));
+    assert!(port_matches(Some(80), Some(443)));
+    assert!(!port_matches(Some(443), Some(3000)));
+    assert!(port_matches(None, Some(3000)));
+}
+
+#[test]
+fn header_respects_ports() {
+    let local = |domain: &str, name: &str, port: Option<u16>| {
+        let mut local = cookie(name, SameSite::None);
+        local.domain = domain.to_string();
+        local.secure = false;
+        local.port = port;
+        local
+    };
+    let cookies = [
+        local("localhost", "dev", Some(3000)),
+        local("localhost", "api", Some(8080)),
+        local("localhost", "any", None),
+        local("127.0.0.1", "loop", Some(3000)),
+        local("[::1]", "six", Some(3000)),
+        local("[::1]", "six_api", Some(8080)),
+    ];
+    let header = |url: &str| header_for_request(&cookies, &RequestContext::new(url).unwrap()).unwrap();
+    assert_eq!(header("http://localhost:3000/"), "dev=v; any=v");
+    assert_eq!(header("http://localhost:8080/"), "api=v; any=v");
+    assert_eq!(header("http://localhost/"), "any=v");
+    assert_eq!(header("http://127.0.0.1:3000/"), "loop=v");
+    assert_eq!(header("http://127.0.0.1:8080/"), "");
+    assert_eq!(header("http://[::1]:3000/"), "six=v");
+    assert_eq!(header("http://[::1]:8080/"), "six_api=v");
+}
+
+#[test]
+fn cookie_header_for_url() {
+    let mut domain = cookie("domain", SameSite::Lax);
+    domain.creation = Some(1_700_000_200);
+    let mut older = cookie("older", SameSite::Lax);
+    older.creation = Some(1_700_000_100);
+    let mut host_only = cookie("host_only", SameSite::Lax);
+    host_only.domain = "example.com".to_string();
+    host_only.host_only = true;
+    let mut expired = cookie("expired", SameSite::Lax);
+    expired.expires = Some(1);
+    let mut chips = cookie("chips", SameSite::None);
+    chips.partition_key = Some("https://example.com".to_string());
+    let mut embedded = cookie("embedded", SameSite::None);
+    embedded.partition_key = Some("https://other.test".to_string());
+    let mut encoded = cookie("encoded", SameSite::Lax);
+    encoded.value = "a%20b".to_string();
+    let cookies = [domain, older, host_only, expired, chips, embedded, encoded];
+
+    assert_eq!(
+        rookie::to_cookie_header(&cookies, "https://www.example.com/").unwrap(),
+        "older=v; domain=v; chips=v; encoded=a%20b"
+    );
+    assert_eq!(
+        rookie::to_cookie_header(&cookies, "https://example.com/").unwrap(),
+        "older=v; domain=v; host_only=v; chips=v; encoded=a%20b"
+    );
+    assert!(rookie::to_cookie_header(&cookies, "not a url").is_err());
+
+    // an iframe of www.example.com embedded in other.test
+    let mut ctx = RequestContext::new("https://www.example.com/").unwrap();
+    ctx.top_frame = Some(Url::parse("https://news.other.test/").unwrap());
+    ctx.initiator = ctx.top_frame.clone();
+    ctx.top_level_navigation = false;
+    assert_eq!(header_for_request(&cookies, &ctx).unwrap(), "embedded=v");
+}
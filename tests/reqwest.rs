@@ -0,0 +1,85 @@
+#![cfg(feature = "reqwest")]
+
+use rookie::common::enums::Cookie;
+use std::io::{ BufRead, BufReader, Write };
+use std::net::{ SocketAddr, TcpListener };
+use std::sync::Arc;
+
+fn cookie(domain: &str, name: &str, path: &str) -> Cookie {
+    let mut cookie = Cookie::new(domain, name, &format!("{}-value", name));
+    cookie.path = path.to_string();
+    cookie.expires = Some(4_102_444_800);
+    cookie
+}
+
+/// Answers every request with the `Cookie` header it received
+fn echo_server() -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut header = String::new();
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 0 && line != "\r\n" {
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("cookie") {
+                        header = value.trim().to_string();
+                    }
+                }
+                line.clear();
+            }
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                header.len(),
+                header
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+    });
+    addr
+}
+
+#[test]
+fn cookie_jar_sends_cookies_to_a_server() {
+    let mut secure = cookie(".example.com", "secure", "/");
+    secure.secure = true;
+    let mut expired = cookie(".example.com", "expired", "/");
+    expired.expires = Some(1_000_000_000);
+    let mut partitioned = cookie(".example.com", "partitioned", "/");
+    partitioned.partition_key = Some("https://other.org".to_string());
+    let cookies = vec![
+        cookie("example.com", "host", "/"),
+        cookie(".example.com", "domain", "/"),
+        cookie("example.com", "api", "/api"),
+        cookie("other.org", "other", "/"),
+        secure,
+        expired,
+        partitioned
+    ];
+
+    let addr = echo_server();
+    let client = reqwest::blocking::Client
+        ::builder()
+        .cookie_provider(Arc::new(rookie::reqwest::to_cookie_jar(&cookies)))
+        .resolve("example.com", addr)
+        .resolve("www.example.com", addr)
+        .build()
+        .unwrap();
+    let sent = |host: &str, path: &str| {
+        let url = format!("http://{}:{}{}", host, addr.port(), path);
+        let header = client.get(url).send().unwrap().text().unwrap();
+        let mut names: Vec<String> = header
+            .split("; ")
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| pair.split('=').next().unwrap().to_string())
+            .collect();
+        names.sort();
+        names
+    };
+    // secure cookies stay off plain http, host-only ones off subdomains
+    assert_eq!(sent("example.com", "/"), ["domain", "host"]);
+    assert_eq!(sent("example.com", "/api/users"), ["api", "domain", "host"]);
+    assert_eq!(sent("www.example.com", "/"), ["domain"]);
+}
@@ -0,0 +1,141 @@
+#![cfg(unix)]
+
+use rookie::browser::chromium;
+use rookie::common::secrets::{ CachedSecrets, InteractionRequired, SecretsProvider };
+use rookie::config;
+use rookie::testing::{ self, ChromiumSchema, CountingSecrets };
+use rookie::LoadOptions;
+use std::sync::atomic::Ordering;
+#[cfg(target_os = "linux")]
+use { anyhow::Result, rookie::common::enums::BrowserConfig };
+
+#[test]
+fn each_item_is_asked_once() {
+    let provider = CountingSecrets::default();
+    let secrets = CachedSecrets::new(&provider, None);
+    for _ in 0..3 {
+        secrets.passwords(&config::CHROME_CONFIG).unwrap();
+    }
+    secrets.passwords(&config::BRAVE_CONFIG).unwrap();
+    assert_eq!(provider.calls.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn max_prompts_skips_remaining_items() {
+    let provider = CountingSecrets::default();
+    let secrets = CachedSecrets::new(&provider, Some(1));
+    secrets.passwords(&config::CHROME_CONFIG).unwrap();
+    let err = secrets.passwords(&config::BRAVE_CONFIG).unwrap_err();
+    assert!(err.is::<InteractionRequired>());
+    // cached items don't count against the budget
+    secrets.passwords(&config::CHROME_CONFIG).unwrap();
+    assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn profiles_share_one_lookup() {
+    let dir = tempfile::tempdir().unwrap();
+    let cookies = testing::sample_cookies();
+    for profile in ["Default", "Profile 1", "Profile 2"] {
+        testing::write_chromium_profile_in(dir.path(), profile, ChromiumSchema::V21, &cookies).unwrap();
+    }
+
+    let provider = CountingSecrets::default();
+    let secrets = CachedSecrets::new(&provider, None);
+    for profile in ["Default", "Profile 1", "Profile 2"] {
+        let db_path = dir.path().join("User Data").join(profile).join("Network/Cookies");
+        let read = chromium
+            ::chromium_based_with(&config::CHROMIUM_CONFIG, db_path, None::<Vec<&str>>, &secrets)
+            .unwrap();
+        assert_eq!(read.len(), cookies.len());
+    }
+    assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+
+    let exhausted = CachedSecrets::new(&provider, Some(0));
+    let db_path = dir.path().join("User Data/Default/Network/Cookies");
+    let err = chromium
+        ::chromium_based_with(&config::CHROMIUM_CONFIG, db_path, None::<Vec<&str>>, &exhausted)
+        .unwrap_err();
+    assert!(err.is::<InteractionRequired>());
+    assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn load_with_secrets_uses_the_given_provider() {
+    let root = tempfile::tempdir().unwrap();
+    let cookies = testing::sample_cookies();
+    testing::write_chromium_profile(root.path(), ChromiumSchema::V21, &cookies).unwrap();
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "macos")] {
+            let user_data = root.path().join("Library/Application Support/Chromium");
+        } else {
+            let user_data = root.path().join(".config/chromium");
+        }
+    }
+    std::fs::create_dir_all(user_data.parent().unwrap()).unwrap();
+    std::fs::rename(root.path().join("User Data"), &user_data).unwrap();
+    // the unix configs look for the pre-Network layout
+    std::fs::rename(user_data.join("Default/Network/Cookies"), user_data.join("Default/Cookies")).unwrap();
+
+    let provider = CountingSecrets::default();
+    let outcome = rookie
+        ::with_user_profile_root(root.path(), || {
+            rookie::load_with_secrets(None, &LoadOptions::default(), &provider)
+        })
+        .unwrap();
+    assert_eq!(outcome.cookies.len(), cookies.len());
+    assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+}
+
+/// Answers with `password` for opera's keyring entry only
+#[cfg(target_os = "linux")]
+#[derive(Debug)]
+struct OperaSecrets {
+    password: &'static str,
+}
+
+#[cfg(target_os = "linux")]
+impl SecretsProvider for OperaSecrets {
+    fn passwords(&self, config: &BrowserConfig) -> Result<Vec<String>> {
+        if config.os_crypt_name == config::OPERA_CONFIG.os_crypt_name {
+            Ok(vec![self.password.to_string()])
+        } else {
+            Ok(vec![])
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn opera_gx_v11_values() {
+    let root = tempfile::tempdir().unwrap();
+    let cookies = testing::sample_cookies();
+    let fixture = testing::write_chromium_profile(root.path(), ChromiumSchema::V21, &cookies).unwrap();
+    let connection = rusqlite::Connection::open(&fixture.db_path).unwrap();
+    for cookie in &cookies {
+        connection
+            .execute(
+                "UPDATE cookies SET encrypted_value = ?1 WHERE host_key = ?2 AND name = ?3",
+                rusqlite::params![testing::encrypt_v11("gx keyring", &cookie.value).unwrap(), cookie.host, cookie.name]
+            )
+            .unwrap();
+    }
+    drop(connection);
+    let gx_dir = root.path().join(".config/opera-gx");
+    std::fs::create_dir_all(&gx_dir).unwrap();
+    std::fs::rename(&fixture.db_path, gx_dir.join("Cookies")).unwrap();
+
+    let (_, db_path) = rookie
+        ::with_user_profile_root(root.path(), || {
+            rookie::common::paths::find_chrome_based_paths(&config::OPERA_GX_CONFIG)
+        })
+        .unwrap();
+    let secrets = OperaSecrets { password: "gx keyring" };
+    let read = chromium
+        ::chromium_based_with(&config::OPERA_GX_CONFIG, db_path, None::<Vec<&str>>, &secrets)
+        .unwrap();
+    assert_eq!(read.len(), cookies.len());
+    for cookie in &cookies {
+        assert!(read.iter().any(|read| read.name == cookie.name && read.value == cookie.value));
+    }
+}
@@ -0,0 +1,62 @@
+use rookie::common::{ enums::Cookie, utils };
+use rookie::sites;
+
+#[test]
+fn builtin_profiles() {
+    for (site, domains) in sites::SITE_PROFILES {
+        assert!(!domains.is_empty(), "{} has no domains", site);
+        assert_eq!(sites::site_domains(site).unwrap(), domains.to_vec());
+    }
+    let github = sites::site_domains("GitHub").unwrap();
+    assert!(github.contains(&"githubusercontent.com".to_string()));
+    assert_eq!(sites::site_domains("no-such-site"), None);
+}
+
+#[test]
+fn registered_profiles() {
+    rookie::register_site_profile("Intranet", ["corp.example", "cdn.corp.example"]);
+    assert_eq!(sites::site_domains("intranet").unwrap(), ["corp.example", "cdn.corp.example"]);
+
+    rookie::register_site_profile("intranet", vec!["corp.example".to_string()]);
+    assert_eq!(sites::site_domains("intranet").unwrap(), ["corp.example"]);
+
+    assert!(rookie::site_cookies("no-such-site", None).is_err());
+}
+
+#[test]
+fn merged_cookies_are_deduped() {
+    let cookie = Cookie::new;
+    let cookies = vec![
+        cookie("github.com", "user_session", "first"),
+        cookie(".github.com", "user_session", "domain"),
+        cookie("github.com", "user_session", "second"),
+        cookie("github.com", "", "nameless")
+    ];
+    let values: Vec<_> = utils
+        ::dedup_cookies(cookies)
+        .into_iter()
+        .map(|c| c.value)
+        .collect();
+    assert_eq!(values, ["first", "domain", "nameless"]);
+}
+
+#[test]
+fn partitions_are_kept_apart() {
+    let partitioned = |value: &str, partition_key: Option<&str>| {
+        let mut cookie = Cookie::new("embed.example", "__Host-id", value);
+        cookie.partition_key = partition_key.map(str::to_string);
+        cookie
+    };
+    let cookies = vec![
+        partitioned("a", Some("https://a.example")),
+        partitioned("b", Some("https://b.example")),
+        partitioned("unpartitioned", None),
+        partitioned("a again", Some("https://a.example"))
+    ];
+    let values: Vec<_> = utils
+        ::dedup_cookies(cookies)
+        .into_iter()
+        .map(|c| c.value)
+        .collect();
+    assert_eq!(values, ["a", "b", "unpartitioned"]);
+}
@@ -0,0 +1,277 @@
+use rookie::browser::binarycookies;
+use rookie::common::enums::{ Cookie, CookieToString, SameSite, SourceScheme };
+use rookie::common::request::registrable_domain;
+use rookie::common::utils::{ split_port, truncate_bytes_lossy, truncate_utf8 };
+use rookie::testing::{ self, FixtureCookie };
+use rookie::{ export, header_for_request, import, to_cookie_header, RequestContext };
+use proptest::prelude::*;
+
+const ROUNDS: usize = 500;
+
+/// One to four byte characters plus the separators the formats care about
+const ALPHABET: &[char] = &[
+    'a', 'Z', '0', '.', ':', ';', '=', '[', ']', '\t', '\n', '"', '\\', 'é', 'ß', 'д', '中', '文',
+    '한', '\u{200d}', '\u{fe0f}', '😀', '🍪', '👩', '\u{10ffff}',
+];
+
+/// xorshift64, deterministic so a failure can be replayed
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % (n as u64)) as usize
+    }
+
+    fn string(&mut self) -> String {
+        let len = self.below(24);
+        (0..len).map(|_| ALPHABET[self.below(ALPHABET.len())]).collect()
+    }
+
+    fn bytes(&mut self) -> Vec<u8> {
+        let len = self.below(64);
+        (0..len).map(|_| self.next() as u8).collect()
+    }
+
+    fn cookie(&mut self) -> Cookie {
+        let mut cookie = Cookie::new(&self.string(), &self.string(), &self.string());
+        cookie.host_only = self.below(2) == 0;
+        cookie.path = self.string();
+        cookie.secure = self.below(2) == 0;
+        cookie.expires = [None, Some(0), Some(u64::MAX), Some(self.next())][self.below(4)];
+        cookie.http_only = self.below(2) == 0;
+        cookie.same_site = SameSite::from((self.below(4) as i64) - 1);
+        cookie.port = [None, Some(3000)][self.below(2)];
+        cookie.source_scheme = SourceScheme::from(self.below(3) as i64);
+        cookie
+    }
+}
+
+#[test]
+fn truncation_keeps_char_boundaries() {
+    let mut rng = Rng(0x5eed);
+    for _ in 0..ROUNDS {
+        let s = rng.string();
+        for max in 0..=s.len() + 1 {
+            let truncated = truncate_utf8(&s, max);
+            assert!(truncated.len() <= max, "{:?} to {}", s, max);
+            assert!(s.starts_with(truncated));
+            // nothing dropped that would have fit
+            if let Some(next) = s[truncated.len()..].chars().next() {
+                assert!(truncated.len() + next.len_utf8() > max);
+            }
+        }
+
+        let bytes = rng.bytes();
+        for max in 0..=bytes.len() * 3 + 1 {
+            assert!(truncate_bytes_lossy(&bytes, max).len() <= max);
+        }
+    }
+}
+
+#[test]
+fn truncation_boundaries() {
+    // the cut falls inside a multi-byte character, which is dropped whole
+    assert_eq!(truncate_utf8("a🍪b", 1), "a");
+    assert_eq!(truncate_utf8("a🍪b", 4), "a");
+    assert_eq!(truncate_utf8("a🍪b", 5), "a🍪");
+    assert_eq!(truncate_utf8("é", 1), "");
+    assert_eq!(truncate_utf8("abc", 3), "abc");
+
+    assert_eq!(truncate_utf8("abc", 0), "");
+    assert_eq!(truncate_utf8("", 0), "");
+    assert_eq!(truncate_bytes_lossy(b"abc", 0), "");
+
+    // U+FFFD takes 3 bytes, it's kept or dropped whole too
+    assert_eq!(truncate_bytes_lossy(b"ab\xffcd", 5), "ab\u{fffd}");
+    assert_eq!(truncate_bytes_lossy(b"ab\xffcd", 4), "ab");
+    assert_eq!(truncate_bytes_lossy(b"\xff\xfe", 6), "\u{fffd}\u{fffd}");
+    assert_eq!(truncate_bytes_lossy(b"\xf0\x9f\x8d", 3), "\u{fffd}");
+}
+
+#[test]
+fn formatting_and_export_never_panic() {
+    let mut rng = Rng(0xc00c1e);
+    for _ in 0..ROUNDS {
+        let cookies: Vec<Cookie> = (0..rng.below(4)).map(|_| rng.cookie()).collect();
+        let _ = cookies.to_string();
+        export::to_netscape(&cookies, std::io::sink()).unwrap();
+        let json = export::to_cookie_editor_json(&cookies).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        for (cookie, parsed) in cookies.iter().zip(parsed.as_array().unwrap()) {
+            assert_eq!(parsed["value"], cookie.value.as_str());
+        }
+
+        let host = rng.string();
+        let _ = export::to_js(&cookies, &format!("https://{}/{}", host, rng.string()));
+        if let Ok(ctx) = RequestContext::new(&format!("http://{}/{}", host, rng.string())) {
+            let _ = header_for_request(&cookies, &ctx);
+        }
+
+        let _ = split_port(&host);
+        let _ = registrable_domain(&host);
+        let _ = rookie::login::site_domains(&host);
+        let _ = rng.string().parse::<rookie::ytdlp::BrowserSpec>();
+    }
+}
+
+#[test]
+fn binarycookies_survive_corrupted_files() {
+    let mut rng = Rng(0xb1a5);
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("Cookies.binarycookies");
+    let fixture_cookies = vec![
+        FixtureCookie::new(".例え.jp", "名前", "値🍪"),
+        FixtureCookie::new("example.com", "emoji", "👩\u{200d}💻")
+    ];
+    testing::write_binarycookies(&path, &fixture_cookies).unwrap();
+    let content = std::fs::read(&path).unwrap();
+
+    let cookies = binarycookies::parse_content(&content).unwrap();
+    assert_eq!(cookies[0].value, "値🍪");
+    assert_eq!(cookies[1].value, "👩\u{200d}💻");
+
+    for _ in 0..ROUNDS {
+        let mut corrupted = content.clone();
+        for _ in 0..1 + rng.below(8) {
+            let index = rng.below(corrupted.len());
+            corrupted[index] = rng.next() as u8;
+        }
+        corrupted.truncate(corrupted.len() - rng.below(8));
+        let _ = binarycookies::parse_content(&corrupted);
+        let _ = binarycookies::parse_content(&rng.bytes());
+    }
+}
+
+/// Any string, one made of [`ALPHABET`] or the lossy decoding of random bytes
+fn text() -> impl Strategy<Value = String> {
+    prop_oneof![
+        any::<String>(),
+        proptest::collection
+            ::vec(proptest::sample::select(ALPHABET), 0..24)
+            .prop_map(|chars| chars.into_iter().collect()),
+        proptest::collection
+            ::vec(any::<u8>(), 0..32)
+            .prop_map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+    ]
+}
+
+fn cookie() -> impl Strategy<Value = Cookie> {
+    let flags = (any::<bool>(), any::<bool>(), any::<bool>());
+    let expires = prop_oneof![Just(None), Just(Some(0)), Just(Some(u64::MAX)), any::<u64>().prop_map(Some)];
+    (text(), text(), text(), text(), flags, expires, -1i64..3).prop_map(
+        |(domain, name, value, path, (host_only, secure, http_only), expires, same_site)| {
+            let mut cookie = Cookie::new(&domain, &name, &value);
+            cookie.host_only = host_only;
+            cookie.path = path;
+            cookie.secure = secure;
+            cookie.expires = expires;
+            cookie.http_only = http_only;
+            cookie.same_site = SameSite::from(same_site);
+            cookie
+        }
+    )
+}
+
+fn cookies() -> impl Strategy<Value = Vec<Cookie>> {
+    proptest::collection::vec(cookie(), 0..6)
+}
+
+/// Whether a cookie can be written to cookies.txt and read back as the same cookie.
+/// An empty domain is skipped by the reader and one starting with `#` is read as a comment.
+fn netscape_round_trips(cookie: &Cookie) -> bool {
+    let fields = [&cookie.domain, &cookie.path, &cookie.name, &cookie.value];
+    !fields.iter().any(|field| field.contains(['\t', '\n', '\r'])) &&
+        !cookie.domain.is_empty() &&
+        !cookie.domain.starts_with('#')
+}
+
+proptest! {
+    #[test]
+    fn netscape_keeps_its_columns(cookies in cookies()) {
+        let mut text = vec![];
+        export::to_netscape(&cookies, &mut text).unwrap();
+        let text = String::from_utf8(text).unwrap();
+        let records: Vec<&str> = text.lines().skip(1).collect();
+        for record in &records {
+            prop_assert_eq!(record.split('\t').count(), 7, "{:?}", record);
+        }
+
+        let expected: Vec<&Cookie> = cookies.iter().filter(|cookie| netscape_round_trips(cookie)).collect();
+        let read = import::from_netscape(text.as_bytes()).unwrap();
+        prop_assert_eq!(read.len(), expected.len());
+        for (read, cookie) in read.iter().zip(expected) {
+            prop_assert_eq!(&read.name, &cookie.name);
+            prop_assert_eq!(&read.value, &cookie.value);
+            prop_assert_eq!(&read.path, &cookie.path);
+            prop_assert_eq!(read.domain.trim_start_matches('.'), cookie.domain.trim_start_matches('.'));
+            prop_assert_eq!(
+                (read.host_only, read.secure, read.http_only, read.expires),
+                (cookie.host_only, cookie.secure, cookie.http_only, cookie.expires.filter(|expires| *expires != 0))
+            );
+        }
+    }
+
+    #[test]
+    fn json_exports_round_trip(cookies in cookies()) {
+        let json: serde_json::Value = serde_json::from_str(&export::to_json(&cookies).unwrap()).unwrap();
+        let read: Vec<Cookie> = serde_json::from_value(json.clone()).unwrap();
+        let full = |cookies: &[Cookie]| cookies.iter().map(Cookie::debug_full).collect::<Vec<_>>();
+        prop_assert_eq!(full(&read), full(&cookies));
+
+        let mut ndjson = vec![];
+        export::to_ndjson(&cookies, &mut ndjson).unwrap();
+        let lines: Vec<serde_json::Value> = String::from_utf8(ndjson)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        prop_assert_eq!(serde_json::Value::from(lines), json);
+    }
+
+    #[test]
+    fn har_keeps_names_and_values(cookies in cookies()) {
+        let har = export::to_har(&cookies);
+        let entries = har.as_array().unwrap();
+        prop_assert_eq!(entries.len(), cookies.len());
+        for (entry, cookie) in entries.iter().zip(&cookies) {
+            prop_assert_eq!(entry["name"].as_str(), Some(cookie.name.as_str()));
+            prop_assert_eq!(entry["value"].as_str(), Some(cookie.value.as_str()));
+            prop_assert_eq!(entry["domain"].as_str(), Some(cookie.domain.as_str()));
+        }
+    }
+
+    #[test]
+    fn js_export_accounts_for_every_cookie(cookies in cookies(), host in text(), path in text()) {
+        if let Ok(export) = export::to_js(&cookies, &format!("https://{}/{}", host, path)) {
+            prop_assert!(export.skipped_http_only + export.skipped_other_domain <= cookies.len());
+        }
+    }
+
+    #[test]
+    fn headers_only_hold_whole_pairs(cookie in cookie(), host in text(), path in text()) {
+        let url = format!("https://{}/{}", host, path);
+        let pair = cookie.to_minimal().header_pair();
+        let cookies = vec![cookie];
+        prop_assert_eq!(cookies.to_string(), pair.clone());
+        if let Ok(header) = to_cookie_header(&cookies, &url) {
+            prop_assert!(header.is_empty() || header == pair, "{:?}", header);
+        }
+        if let Ok(ctx) = RequestContext::new(&url) {
+            let header = header_for_request(&cookies, &ctx).unwrap();
+            prop_assert!(header.is_empty() || header == pair, "{:?}", header);
+        }
+    }
+
+    #[test]
+    fn netscape_import_takes_any_bytes(bytes in proptest::collection::vec(any::<u8>(), 0..512)) {
+        let cookies = import::from_netscape(bytes.as_slice()).unwrap();
+        export::to_netscape(&cookies, std::io::sink()).unwrap();
+    }
+}
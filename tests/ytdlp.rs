@@ -0,0 +1,122 @@
+use rookie::browser::mozilla::{ self, Container };
+use rookie::common::enums::BrowserConfig;
+use rookie::common::paths;
+use rookie::testing;
+use rookie::ytdlp::{ self, BrowserSpec, Keyring };
+use std::fs;
+
+fn spec(browser: &str, keyring: Option<Keyring>, profile: Option<&str>, container: Option<&str>) -> BrowserSpec {
+    BrowserSpec {
+        browser: browser.to_string(),
+        keyring,
+        profile: profile.map(String::from),
+        container: container.map(String::from),
+    }
+}
+
+#[test]
+fn spec_syntax() {
+    let table = [
+        ("chrome", spec("chrome", None, None, None)),
+        ("Chrome", spec("chrome", None, None, None)),
+        ("chrome:Profile 2", spec("chrome", None, Some("Profile 2"), None)),
+        ("chrome+gnomekeyring", spec("chrome", Some(Keyring::GnomeKeyring), None, None)),
+        ("brave+KWALLET6:Default", spec("brave", Some(Keyring::KWallet6), Some("Default"), None)),
+        ("firefox::Personal", spec("firefox", None, None, Some("Personal"))),
+        ("firefox:default::Personal", spec("firefox", None, Some("default"), Some("Personal"))),
+        ("firefox : default :: none", spec("firefox", None, Some("default"), Some("none"))),
+        (r"edge:C:\Users\me\Edge\Default", spec("edge", None, Some(r"C:\Users\me\Edge\Default"), None)),
+    ];
+    for (input, expected) in table {
+        assert_eq!(input.parse::<BrowserSpec>().unwrap(), expected, "{}", input);
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn kwallet6_asks_kwalletd6() {
+    let kwallet6 = Keyring::KWallet6.secrets();
+    assert!(kwallet6.kwallet6 && !kwallet6.kwallet && !kwallet6.libsecret);
+    let kwallet5 = Keyring::KWallet5.secrets();
+    assert!(kwallet5.kwallet && !kwallet5.kwallet6);
+    let basic = Keyring::BasicText.secrets();
+    assert!(!basic.libsecret && !basic.kwallet && !basic.kwallet6);
+}
+
+#[test]
+fn spec_errors_are_precise() {
+    let table = [
+        ("", "empty browser name"),
+        (":Default", "empty browser name"),
+        ("netscape", "unknown browser \"netscape\""),
+        ("chrome+vault", "unknown keyring \"vault\""),
+        ("chrome+", "empty keyring"),
+        ("chrome:", "empty profile"),
+        ("firefox::", "empty container"),
+        ("chrome::Personal", "containers are only supported for firefox"),
+    ];
+    for (input, message) in table {
+        let err = input.parse::<BrowserSpec>().unwrap_err().to_string();
+        assert!(err.contains(message), "{:?} gave {:?}", input, err);
+    }
+    assert!(ytdlp::cookies_txt("whale").is_err());
+}
+
+#[test]
+fn firefox_profile_and_container() {
+    let dir = tempfile::tempdir().unwrap();
+    let cookies = testing::sample_cookies();
+    let db_path = testing::write_mozilla_profile(dir.path(), &cookies).unwrap();
+    let profile_dir = db_path.parent().unwrap();
+
+    let data_path = dir.path().to_string_lossy();
+    let data_paths = [data_path.as_ref()];
+    let config = BrowserConfig {
+        data_paths: &data_paths,
+        channels: None,
+        os_crypt_name: None,
+        osx_key_service: None,
+        osx_key_user: None,
+    };
+    assert_eq!(paths::find_mozilla_profile_path(&config, "fixture.default-release").unwrap(), db_path);
+    assert_eq!(paths::find_mozilla_profile_path(&config, &profile_dir.to_string_lossy()).unwrap(), db_path);
+    assert!(paths::find_mozilla_profile_path(&config, "missing.default").is_err());
+
+    fs::write(
+        profile_dir.join("containers.json"),
+        r#"{"version":4,"identities":[
+            {"userContextId":1,"public":true,"icon":"fingerprint","color":"blue","l10nID":"userContextPersonal.label","accessKey":"userContextPersonal.accesskey"},
+            {"userContextId":12,"public":true,"icon":"briefcase","color":"red","name":"Banking"}
+        ]}"#
+    ).unwrap();
+    let connection = rusqlite::Connection::open(&db_path).unwrap();
+    connection.execute("UPDATE moz_cookies SET originAttributes = '^userContextId=1' WHERE name = 'session'", []).unwrap();
+    connection
+        .execute("UPDATE moz_cookies SET originAttributes = '^privateBrowsingId=0&userContextId=12' WHERE name = 'token'", [])
+        .unwrap();
+    drop(connection);
+
+    assert_eq!(mozilla::find_container(profile_dir, "Personal").unwrap(), Container::Id(1));
+    assert_eq!(mozilla::find_container(profile_dir, "Banking").unwrap(), Container::Id(12));
+    assert_eq!(mozilla::find_container(profile_dir, "none").unwrap(), Container::None);
+    assert!(mozilla::find_container(profile_dir, "Work").is_err());
+
+    let names = |container| {
+        mozilla
+            ::firefox_based_in_container(db_path.clone(), None::<Vec<&str>>, container)
+            .unwrap()
+            .into_iter()
+            .map(|cookie| cookie.name)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(names(Container::Id(1)), ["session"]);
+    assert_eq!(names(Container::Id(12)), ["token"]);
+    assert_eq!(names(Container::Id(2)), Vec::<String>::new());
+    assert_eq!(names(Container::None).len(), cookies.len() - 2);
+
+    let spec = format!("firefox:{}::Banking", profile_dir.display());
+    let cookies_txt = ytdlp::cookies_txt(&spec).unwrap();
+    assert!(cookies_txt.starts_with("# Netscape HTTP Cookie File\n"));
+    assert_eq!(cookies_txt.lines().count(), 2);
+    assert!(cookies_txt.contains("\ttoken\tt0k3n\n"));
+}